@@ -0,0 +1,301 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
+
+use hula_types::JointsArray;
+use serde::Serialize;
+
+use crate::{service_manager::ServiceState, AlivenessState};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Thresholds {
+    /// Maximum joint temperature in °C
+    pub maximum_temperature: f32,
+    /// Minimum battery charge in [0, 1] while discharging
+    pub minimum_charge: f32,
+    pub offline_after: Duration,
+    /// Number of times the HULK service may leave the active state during the history
+    pub maximum_restarts: usize,
+    pub history: Duration,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            maximum_temperature: 70.0,
+            minimum_charge: 0.2,
+            offline_after: Duration::from_secs(5),
+            maximum_restarts: 2,
+            history: Duration::from_secs(600),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind")]
+pub enum Alert {
+    HotJoint { joint: String, temperature: f32 },
+    LowBattery { charge: f32 },
+    CrashLoop { restarts: usize },
+    Offline { seconds: f32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub time: SystemTime,
+    /// `None` if the robot did not respond to this poll
+    pub state: Option<AlivenessState>,
+}
+
+/// Time series of aliveness states of one robot, limited to the configured history
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    samples: VecDeque<Sample>,
+    first_poll: Option<SystemTime>,
+}
+
+impl History {
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    pub fn latest_state(&self) -> Option<&AlivenessState> {
+        self.samples
+            .iter()
+            .rev()
+            .find_map(|sample| sample.state.as_ref())
+    }
+
+    pub fn last_seen(&self) -> Option<SystemTime> {
+        self.samples
+            .iter()
+            .rev()
+            .find(|sample| sample.state.is_some())
+            .map(|sample| sample.time)
+    }
+
+    /// How often the HULK service left the active state
+    pub fn hulk_restarts(&self) -> usize {
+        let hulk_states: Vec<_> = self
+            .samples
+            .iter()
+            .filter_map(|sample| sample.state.as_ref())
+            .map(|state| matches!(state.system_services.hulk, ServiceState::Active))
+            .collect();
+        hulk_states
+            .windows(2)
+            .filter(|window| window[0] && !window[1])
+            .count()
+    }
+
+    /// Change of the battery charge per minute over the history
+    pub fn charge_trend(&self) -> Option<f32> {
+        let mut charges = self.samples.iter().filter_map(|sample| {
+            Some((sample.time, sample.state.as_ref()?.battery.as_ref()?.charge))
+        });
+        let (first_time, first_charge) = charges.next()?;
+        let (last_time, last_charge) = charges.next_back()?;
+        let minutes = last_time.duration_since(first_time).ok()?.as_secs_f32() / 60.0;
+        (minutes > 0.0).then(|| (last_charge - first_charge) / minutes)
+    }
+
+    fn push(&mut self, sample: Sample, history: Duration) {
+        let time = sample.time;
+        self.first_poll.get_or_insert(time);
+        self.samples.push_back(sample);
+        while self.samples.front().is_some_and(|oldest| {
+            time.duration_since(oldest.time)
+                .is_ok_and(|age| age > history)
+        }) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+/// Keeps the aliveness history of every watched robot and every robot which responded at least
+/// once
+pub struct Monitor {
+    thresholds: Thresholds,
+    robots: BTreeMap<IpAddr, History>,
+}
+
+impl Monitor {
+    pub fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            robots: BTreeMap::new(),
+        }
+    }
+
+    /// Robots which are expected to respond, they are alerted as offline even if they never do
+    pub fn watch(&mut self, ips: impl IntoIterator<Item = IpAddr>) {
+        for ip in ips {
+            self.robots.entry(ip).or_default();
+        }
+    }
+
+    pub fn update(&mut self, time: SystemTime, responses: Vec<(IpAddr, AlivenessState)>) {
+        let mut responses: BTreeMap<_, _> = responses.into_iter().collect();
+        for (ip, history) in self.robots.iter_mut() {
+            let state = responses.remove(ip);
+            history.push(Sample { time, state }, self.thresholds.history);
+        }
+        for (ip, state) in responses {
+            let mut history = History::default();
+            history.push(
+                Sample {
+                    time,
+                    state: Some(state),
+                },
+                self.thresholds.history,
+            );
+            self.robots.insert(ip, history);
+        }
+    }
+
+    pub fn robots(&self) -> &BTreeMap<IpAddr, History> {
+        &self.robots
+    }
+
+    pub fn alerts(&self, history: &History, now: SystemTime) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        if let Some(last_seen) = history.last_seen().or(history.first_poll) {
+            let offline = now.duration_since(last_seen).unwrap_or_default();
+            if offline > self.thresholds.offline_after {
+                alerts.push(Alert::Offline {
+                    seconds: offline.as_secs_f32(),
+                });
+            }
+        }
+        if let Some(state) = history.latest_state() {
+            if let Some((joint, temperature)) = state.temperature.and_then(hottest_joint) {
+                if temperature > self.thresholds.maximum_temperature {
+                    alerts.push(Alert::HotJoint { joint, temperature });
+                }
+            }
+            if let Some(battery) = state.battery {
+                if battery.charge < self.thresholds.minimum_charge && battery.current < 0.0 {
+                    alerts.push(Alert::LowBattery {
+                        charge: battery.charge,
+                    });
+                }
+            }
+        }
+        let restarts = history.hulk_restarts();
+        if restarts > self.thresholds.maximum_restarts {
+            alerts.push(Alert::CrashLoop { restarts });
+        }
+        alerts
+    }
+}
+
+fn hottest_joint(temperatures: JointsArray) -> Option<(String, f32)> {
+    JointsArray::NAMES
+        .into_iter()
+        .zip(temperatures.into_lola())
+        .max_by(|(_, left), (_, right)| left.total_cmp(right))
+        .map(|(joint, temperature)| (joint.to_string(), temperature))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::service_manager::SystemServices;
+
+    use super::*;
+
+    fn state(hulk: ServiceState, charge: f32) -> AlivenessState {
+        AlivenessState {
+            hostname: "tester".to_string(),
+            interface_name: "wlan0".to_string(),
+            system_services: SystemServices {
+                hal: ServiceState::Active,
+                hula: ServiceState::Active,
+                hulk,
+                lola: ServiceState::Active,
+            },
+            hulks_os_version: "5.1.3".to_string(),
+            body_id: None,
+            head_id: None,
+            battery: Some(crate::Battery {
+                charge,
+                current: -1.0,
+                ..Default::default()
+            }),
+            network: None,
+            temperature: None,
+        }
+    }
+
+    #[test]
+    fn crash_loops_low_batteries_and_offline_robots_are_alerted() {
+        let ip = IpAddr::from([10, 0, 24, 21]);
+        let start = SystemTime::UNIX_EPOCH;
+        let mut monitor = Monitor::new(Thresholds::default());
+        let hulk_states = [
+            ServiceState::Active,
+            ServiceState::Failed,
+            ServiceState::Active,
+            ServiceState::Activating,
+            ServiceState::Active,
+            ServiceState::Failed,
+        ];
+        for (index, hulk) in hulk_states.into_iter().enumerate() {
+            let charge = 0.375 - 0.0625 * index as f32;
+            monitor.update(
+                start + Duration::from_secs(60 * index as u64),
+                vec![(ip, state(hulk, charge))],
+            );
+        }
+        monitor.update(start + Duration::from_secs(360), vec![]);
+
+        let history = &monitor.robots()[&ip];
+        assert_eq!(history.samples().count(), 7);
+        assert_eq!(history.charge_trend(), Some(-0.0625));
+        let alerts = monitor.alerts(history, start + Duration::from_secs(360));
+        assert_eq!(
+            alerts,
+            [
+                Alert::Offline { seconds: 60.0 },
+                Alert::LowBattery { charge: 0.0625 },
+                Alert::CrashLoop { restarts: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn samples_older_than_the_history_are_dropped() {
+        let ip = IpAddr::from([10, 0, 24, 21]);
+        let mut monitor = Monitor::new(Thresholds {
+            history: Duration::from_secs(10),
+            ..Default::default()
+        });
+        for seconds in 0..20 {
+            monitor.update(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+                vec![(ip, state(ServiceState::Active, 1.0))],
+            );
+        }
+
+        assert_eq!(monitor.robots()[&ip].samples().count(), 11);
+    }
+
+    #[test]
+    fn watched_robots_which_never_respond_are_offline() {
+        let ip = IpAddr::from([10, 0, 24, 21]);
+        let start = SystemTime::UNIX_EPOCH;
+        let mut monitor = Monitor::new(Thresholds::default());
+        monitor.watch([ip]);
+        for seconds in 0..10 {
+            monitor.update(start + Duration::from_secs(seconds), vec![]);
+        }
+
+        let history = &monitor.robots()[&ip];
+        assert_eq!(history.last_seen(), None);
+        assert_eq!(
+            monitor.alerts(history, start + Duration::from_secs(9)),
+            [Alert::Offline { seconds: 9.0 }]
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use source_analyzer::{cyclers::Cyclers, struct_hierarchy::StructHierarchy, structs::Structs};
+
+#[derive(Clone, Copy)]
+enum Source {
+    MainOutputs,
+    AdditionalOutputs,
+    Parameters,
+}
+
+pub fn generate_client(cyclers: &Cyclers, structs: &Structs) -> TokenStream {
+    let instance_fields = cyclers.instances().map(|(cycler, instance)| {
+        let field_name = format_ident!("{}", instance.to_case(Case::Snake));
+        let struct_name = format_ident!("{}Outputs", cycler.name);
+        quote! { pub #field_name: #struct_name<'a> }
+    });
+    let instance_initializers = cyclers.instances().map(|(cycler, instance)| {
+        let field_name = format_ident!("{}", instance.to_case(Case::Snake));
+        let struct_name = format_ident!("{}Outputs", cycler.name);
+        let cycler_variant = format_ident!("{}", instance);
+        let main_outputs = hierarchy_to_initializer(
+            &structs.cyclers[&cycler.name].main_outputs,
+            &format_ident!("{}MainOutputs", cycler.name),
+            quote! { cycler: communication::client::Cycler::#cycler_variant, },
+        );
+        let additional_outputs = hierarchy_to_initializer(
+            &structs.cyclers[&cycler.name].additional_outputs,
+            &format_ident!("{}AdditionalOutputs", cycler.name),
+            quote! { cycler: communication::client::Cycler::#cycler_variant, },
+        );
+        quote! {
+            #field_name: #struct_name {
+                main_outputs: #main_outputs,
+                additional_outputs: #additional_outputs,
+            }
+        }
+    });
+    let cycler_structs = cyclers.cyclers.iter().map(|cycler| {
+        let struct_name = format_ident!("{}Outputs", cycler.name);
+        let main_outputs_name = format_ident!("{}MainOutputs", cycler.name);
+        let additional_outputs_name = format_ident!("{}AdditionalOutputs", cycler.name);
+        let main_outputs = hierarchy_to_handles(
+            &structs.cyclers[&cycler.name].main_outputs,
+            &main_outputs_name,
+            "",
+            false,
+            Source::MainOutputs,
+        );
+        let additional_outputs = hierarchy_to_handles(
+            &structs.cyclers[&cycler.name].additional_outputs,
+            &additional_outputs_name,
+            "",
+            false,
+            Source::AdditionalOutputs,
+        );
+        quote! {
+            pub struct #struct_name<'a> {
+                pub main_outputs: #main_outputs_name<'a>,
+                pub additional_outputs: #additional_outputs_name<'a>,
+            }
+            #main_outputs
+            #additional_outputs
+        }
+    });
+    let parameters = hierarchy_to_handles(
+        &structs.parameters,
+        &format_ident!("Parameters"),
+        "",
+        false,
+        Source::Parameters,
+    );
+    let parameters_initializer =
+        hierarchy_to_initializer(&structs.parameters, &format_ident!("Parameters"), quote! {});
+
+    quote! {
+        /// Typed handles to the outputs and parameters of all cycler instances
+        pub struct Robot<'a> {
+            #(#instance_fields,)*
+            pub parameters: Parameters<'a>,
+        }
+
+        impl<'a> Robot<'a> {
+            pub fn new(communication: &'a communication::client::Communication) -> Self {
+                Self {
+                    #(#instance_initializers,)*
+                    parameters: #parameters_initializer,
+                }
+            }
+        }
+
+        #(#cycler_structs)*
+        #parameters
+    }
+}
+
+fn hierarchy_to_initializer(
+    hierarchy: &StructHierarchy,
+    struct_name: &Ident,
+    additional_fields: TokenStream,
+) -> TokenStream {
+    let fields = match hierarchy {
+        StructHierarchy::Struct { fields } => fields,
+        StructHierarchy::Optional { child } => {
+            return hierarchy_to_initializer(child, struct_name, additional_fields)
+        }
+        StructHierarchy::Field { .. } => panic!("field instead of struct"),
+    };
+    let child_initializers = fields
+        .iter()
+        .filter_map(|(name, struct_hierarchy)| {
+            let child = match struct_hierarchy {
+                StructHierarchy::Optional { child } => child,
+                struct_hierarchy => struct_hierarchy,
+            };
+            matches!(child, StructHierarchy::Struct { .. }).then(|| {
+                let name_identifier = format_ident!("{}", name);
+                let child_struct_name =
+                    format_ident!("{}{}", struct_name, name.to_case(Case::Pascal));
+                let initializer =
+                    hierarchy_to_initializer(child, &child_struct_name, additional_fields.clone());
+                quote! { #name_identifier: #initializer, }
+            })
+        })
+        .collect::<Vec<_>>();
+    quote! {
+        #struct_name {
+            communication,
+            #additional_fields
+            #(#child_initializers)*
+        }
+    }
+}
+
+fn hierarchy_to_handles(
+    hierarchy: &StructHierarchy,
+    struct_name: &Ident,
+    path_prefix: &str,
+    inside_optional: bool,
+    source: Source,
+) -> TokenStream {
+    let fields = match hierarchy {
+        StructHierarchy::Struct { fields } => fields,
+        StructHierarchy::Optional { .. } => panic!("option instead of struct"),
+        StructHierarchy::Field { .. } => panic!("field instead of struct"),
+    };
+    let cycler_field = match source {
+        Source::MainOutputs | Source::AdditionalOutputs => {
+            quote! { cycler: communication::client::Cycler, }
+        }
+        Source::Parameters => quote! {},
+    };
+    let mut struct_fields = Vec::new();
+    let mut methods = Vec::new();
+    let mut child_structs = Vec::new();
+    for (name, struct_hierarchy) in fields {
+        let name_identifier = format_ident!("{}", name);
+        let path = format!("{path_prefix}{name}");
+        let (struct_hierarchy, child_inside_optional) = match struct_hierarchy {
+            StructHierarchy::Optional { child } => (&**child, true),
+            struct_hierarchy => (struct_hierarchy, inside_optional),
+        };
+        match struct_hierarchy {
+            StructHierarchy::Struct { .. } => {
+                let child_struct_name =
+                    format_ident!("{}{}", struct_name, name.to_case(Case::Pascal));
+                struct_fields.push(quote! { pub #name_identifier: #child_struct_name<'a> });
+                child_structs.push(hierarchy_to_handles(
+                    struct_hierarchy,
+                    &child_struct_name,
+                    &format!("{path}."),
+                    child_inside_optional,
+                    source,
+                ));
+            }
+            StructHierarchy::Optional { .. } => {
+                panic!("unexpected optional in an optional struct")
+            }
+            StructHierarchy::Field { data_type } => {
+                let data_type = if child_inside_optional {
+                    quote! { Option<#data_type> }
+                } else {
+                    quote! { #data_type }
+                };
+                methods.push(match source {
+                    Source::MainOutputs | Source::AdditionalOutputs => {
+                        let output = match source {
+                            Source::MainOutputs => quote! { Main },
+                            _ => quote! { Additional },
+                        };
+                        quote! {
+                            pub async fn #name_identifier(&self) -> communication::client::Stream<#data_type> {
+                                communication::client::Stream::output(
+                                    self.communication,
+                                    communication::client::CyclerOutput {
+                                        cycler: self.cycler,
+                                        output: communication::client::Output::#output {
+                                            path: #path.to_string(),
+                                        },
+                                    },
+                                )
+                                .await
+                            }
+                        }
+                    }
+                    Source::Parameters => quote! {
+                        pub fn #name_identifier(&self) -> communication::client::Parameter<'a, #data_type> {
+                            communication::client::Parameter::new(self.communication, #path)
+                        }
+                    },
+                });
+            }
+        }
+    }
+    let allow_dead_code = methods.is_empty().then(|| quote! { #[allow(dead_code)] });
+    quote! {
+        #allow_dead_code
+        pub struct #struct_name<'a> {
+            communication: &'a communication::client::Communication,
+            #cycler_field
+            #(#struct_fields,)*
+        }
+
+        impl<'a> #struct_name<'a> {
+            #(#methods)*
+        }
+
+        #(#child_structs)*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use source_analyzer::{
+        cyclers::{Cycler, CyclerKind},
+        structs::CyclerStructs,
+    };
+    use syn::Type;
+
+    use super::*;
+
+    fn field(data_type: TokenStream) -> StructHierarchy {
+        StructHierarchy::Field {
+            data_type: Type::Verbatim(data_type),
+        }
+    }
+
+    #[test]
+    fn client_has_typed_handles_for_outputs_and_parameters() {
+        let cyclers = Cyclers {
+            cyclers: vec![Cycler {
+                name: "Control".to_string(),
+                kind: CyclerKind::RealTime,
+                instances: vec!["Control".to_string()],
+                setup_nodes: vec![],
+                cycle_nodes: vec![],
+            }],
+        };
+        let structs = Structs {
+            parameters: StructHierarchy::Struct {
+                fields: BTreeMap::from([(
+                    "walking".to_string(),
+                    StructHierarchy::Struct {
+                        fields: BTreeMap::from([(
+                            "step_height".to_string(),
+                            field(quote! { f32 }),
+                        )]),
+                    },
+                )]),
+            },
+            cyclers: BTreeMap::from([(
+                "Control".to_string(),
+                CyclerStructs {
+                    main_outputs: StructHierarchy::Struct {
+                        fields: BTreeMap::from([(
+                            "ball_position".to_string(),
+                            StructHierarchy::Optional {
+                                child: Box::new(field(quote! { Point2<f32> })),
+                            },
+                        )]),
+                    },
+                    additional_outputs: StructHierarchy::default(),
+                    persistent_state: StructHierarchy::default(),
+                },
+            )]),
+        };
+
+        let expected = quote! {
+            /// Typed handles to the outputs and parameters of all cycler instances
+            pub struct Robot<'a> {
+                pub control: ControlOutputs<'a>,
+                pub parameters: Parameters<'a>,
+            }
+
+            impl<'a> Robot<'a> {
+                pub fn new(communication: &'a communication::client::Communication) -> Self {
+                    Self {
+                        control: ControlOutputs {
+                            main_outputs: ControlMainOutputs {
+                                communication,
+                                cycler: communication::client::Cycler::Control,
+                            },
+                            additional_outputs: ControlAdditionalOutputs {
+                                communication,
+                                cycler: communication::client::Cycler::Control,
+                            },
+                        },
+                        parameters: Parameters {
+                            communication,
+                            walking: ParametersWalking {
+                                communication,
+                            },
+                        },
+                    }
+                }
+            }
+
+            pub struct ControlOutputs<'a> {
+                pub main_outputs: ControlMainOutputs<'a>,
+                pub additional_outputs: ControlAdditionalOutputs<'a>,
+            }
+
+            pub struct ControlMainOutputs<'a> {
+                communication: &'a communication::client::Communication,
+                cycler: communication::client::Cycler,
+            }
+
+            impl<'a> ControlMainOutputs<'a> {
+                pub async fn ball_position(&self) -> communication::client::Stream<Option<Point2<f32> > > {
+                    communication::client::Stream::output(
+                        self.communication,
+                        communication::client::CyclerOutput {
+                            cycler: self.cycler,
+                            output: communication::client::Output::Main {
+                                path: "ball_position".to_string(),
+                            },
+                        },
+                    )
+                    .await
+                }
+            }
+
+            #[allow(dead_code)]
+            pub struct ControlAdditionalOutputs<'a> {
+                communication: &'a communication::client::Communication,
+                cycler: communication::client::Cycler,
+            }
+
+            impl<'a> ControlAdditionalOutputs<'a> {}
+
+            #[allow(dead_code)]
+            pub struct Parameters<'a> {
+                communication: &'a communication::client::Communication,
+                pub walking: ParametersWalking<'a>,
+            }
+
+            impl<'a> Parameters<'a> {}
+
+            pub struct ParametersWalking<'a> {
+                communication: &'a communication::client::Communication,
+            }
+
+            impl<'a> ParametersWalking<'a> {
+                pub fn step_height(&self) -> communication::client::Parameter<'a, f32> {
+                    communication::client::Parameter::new(self.communication, "walking.step_height")
+                }
+            }
+        };
+
+        assert_eq!(
+            generate_client(&cyclers, &structs).to_string(),
+            expected.to_string()
+        );
+    }
+}
@@ -91,6 +91,7 @@ fn generate_struct(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
             own_subscribed_outputs_reader: framework::Reader<std::collections::HashSet<String>>,
             parameters_reader: framework::Reader<crate::structs::Parameters>,
             persistent_state: crate::structs::#module_name::PersistentState,
+            crash_recorder: framework::crash_capture::Recorder<Database>,
             #realtime_inputs
             #input_output_fields
             #node_fields
@@ -208,6 +209,7 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 own_subscribed_outputs_reader,
                 parameters_reader,
                 persistent_state,
+                crash_recorder: framework::crash_capture::Recorder::new(&format!("{instance:?}")),
                 #input_output_identifiers
                 #(#node_identifiers,)*
             })
@@ -329,15 +331,19 @@ fn generate_start_method() -> TokenStream {
             keep_running: tokio_util::sync::CancellationToken,
         ) -> color_eyre::Result<std::thread::JoinHandle<color_eyre::Result<()>>> {
             let instance_name = format!("{:?}", self.instance);
+            framework::crash_capture::register_parameters(self.parameters_reader.clone());
             std::thread::Builder::new()
                 .name(instance_name.clone())
                 .spawn(move || {
                     while !keep_running.is_cancelled() {
                         if let Err(error) = self.cycle() {
                             keep_running.cancel();
-                            return Err(error).wrap_err_with(|| {
-                                format!("failed to execute cycle of cycler `{:?}`", self.instance)
-                            });
+                            let error = error.wrap_err(format!(
+                                "failed to execute cycle of cycler `{:?}`",
+                                self.instance
+                            ));
+                            framework::crash_capture::capture_error(&error);
+                            return Err(error);
                         }
                     }
                     Ok(())
@@ -408,6 +414,7 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 let instance = self.instance;
                 let instance_name = format!("{instance:?}");
                 let itt_domain = ittapi::Domain::new(&instance_name);
+                framework::log_context::begin_cycle(&instance_name);
 
                 let mut own_database = self.own_writer.next();
                 let own_database_reference = {
@@ -431,6 +438,8 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 }
 
                 #after_remaining_nodes
+                framework::log_context::set_node(None);
+                self.crash_recorder.record(&*own_database_reference);
             }
             self.own_changed.notify_one();
             Ok(())
@@ -466,6 +475,7 @@ fn generate_node_execution(node: &Node, cycler: &Cycler) -> TokenStream {
             if #are_required_inputs_some {
                 let main_outputs = {
                     let _task = ittapi::Task::begin(&itt_domain, #node_name);
+                    framework::log_context::set_node(Some(#node_name));
                     self.#node_member.cycle(
                         #node_module::CycleContext::new(
                             #context_initializers
@@ -7,6 +7,7 @@ use source_analyzer::{cyclers::Cyclers, structs::Structs};
 use structs::generate_structs;
 
 mod accessor;
+pub mod client;
 pub mod cyclers;
 pub mod perception_databases;
 pub mod run;
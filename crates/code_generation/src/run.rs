@@ -15,11 +15,14 @@ pub fn generate_run_function(cyclers: &Cyclers) -> TokenStream {
     let join_cyclers = generate_cycler_joins(cyclers);
 
     quote! {
-        #[allow(clippy::redundant_clone)]
+        #[allow(clippy::redundant_clone, clippy::too_many_arguments)]
         pub fn run(
             hardware_interface: std::sync::Arc<impl crate::HardwareInterface + Send + Sync + 'static>,
             addresses: Option<impl tokio::net::ToSocketAddrs + std::marker::Send + std::marker::Sync + 'static>,
             parameters_directory: impl std::convert::AsRef<std::path::Path> + std::marker::Send + std::marker::Sync + 'static,
+            watch_parameters: bool,
+            access_control: communication::server::AccessControl,
+            local_socket: Option<std::path::PathBuf>,
             body_id: String,
             head_id: String,
             keep_running: tokio_util::sync::CancellationToken,
@@ -31,7 +34,7 @@ pub fn generate_run_function(cyclers: &Cyclers) -> TokenStream {
             #construct_future_queues
 
             let communication_server = communication::server::Runtime::start(
-                addresses, parameters_directory, body_id, head_id, #number_of_parameter_slots, keep_running.clone())
+                addresses, parameters_directory, body_id, head_id, #number_of_parameter_slots, watch_parameters, access_control, local_socket, keep_running.clone())
                 .wrap_err("failed to start communication server")?;
 
             #construct_cyclers
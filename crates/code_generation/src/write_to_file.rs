@@ -32,7 +32,10 @@ impl WriteToFile for TokenStream {
             write!(file, "{self}")?;
         }
 
-        let status = Command::new("rustfmt").arg(file_path).status()?;
+        let status = Command::new("rustfmt")
+            .args(["--edition", "2021"])
+            .arg(file_path)
+            .status()?;
         if !status.success() {
             return Err(Error::RustFmt);
         }
@@ -1,6 +1,7 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde_json::Value;
+use serialize_hierarchy::FieldMetadata;
 use tokio::{
     spawn,
     sync::{
@@ -13,10 +14,13 @@ use uuid::Uuid;
 use crate::{
     client::{
         connector::{self, connector, ConnectionStatus},
-        parameter_subscription_manager::{self, parameter_subscription_manager},
+        parameter_subscription_manager::{self, parameter_subscription_manager, ProfilesRequest},
         SubscriberMessage,
     },
-    messages::{Fields, Format, Path},
+    messages::{
+        Fields, Format, ParameterChange, Path, Profiles, Reason, SubscriptionOptions,
+        TransportOptions,
+    },
 };
 
 use super::{
@@ -35,6 +39,14 @@ pub struct Communication {
 
 impl Communication {
     pub fn new(address: Option<String>, connect: bool) -> Self {
+        Self::new_with_transport_options(address, connect, TransportOptions::default())
+    }
+
+    pub fn new_with_transport_options(
+        address: Option<String>,
+        connect: bool,
+        transport_options: TransportOptions,
+    ) -> Self {
         let (connector_sender, connector_receiver) = mpsc::channel(10);
         let (output_subscription_manager_sender, output_subscription_manager_receiver) =
             mpsc::channel(10);
@@ -51,6 +63,7 @@ impl Communication {
             responder_sender.clone(),
             address,
             connect,
+            transport_options,
         ));
         spawn(output_subscription_manager(
             output_subscription_manager_receiver,
@@ -88,6 +101,14 @@ impl Communication {
             .unwrap();
     }
 
+    /// Token for the `Authorization` header of the next connection attempts
+    pub async fn set_token(&self, token: Option<String>) {
+        self.connector
+            .send(connector::Message::SetToken(token))
+            .await
+            .unwrap();
+    }
+
     pub async fn subscribe_connection_updates(&self) -> Receiver<ConnectionStatus> {
         let (subscriber_sender, subscriber_receiver) = mpsc::channel(10);
         self.connector
@@ -101,6 +122,7 @@ impl Communication {
         &self,
         output: CyclerOutput,
         format: Format,
+        options: SubscriptionOptions,
     ) -> (Uuid, Receiver<SubscriberMessage>) {
         let (subscriber_sender, subscriber_receiver) = mpsc::channel(10);
         let (response_sender, response_receiver) = oneshot::channel();
@@ -108,6 +130,7 @@ impl Communication {
             .send(output_subscription_manager::Message::Subscribe {
                 output,
                 format,
+                options,
                 subscriber: subscriber_sender,
                 response_sender,
             })
@@ -164,15 +187,117 @@ impl Communication {
         response_receiver.await.unwrap()
     }
 
+    pub async fn get_parameter_schema(&self) -> Option<BTreeMap<Path, FieldMetadata>> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::GetSchema { response_sender })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
+    pub async fn get_parameter_history(&self) -> Option<Vec<ParameterChange>> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::GetHistory { response_sender })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
+    pub async fn revert_parameter_change(&self, change_id: usize) {
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::RevertChange { change_id })
+            .await
+            .unwrap();
+    }
+
+    pub async fn get_parameter_profiles(&self) -> Option<Result<Profiles, Reason>> {
+        self.request_profiles(ProfilesRequest::Get).await
+    }
+
+    pub async fn activate_parameter_profile(&self, name: &str) -> Option<Result<Profiles, Reason>> {
+        self.request_profiles(ProfilesRequest::Activate {
+            name: name.to_owned(),
+        })
+        .await
+    }
+
+    pub async fn deactivate_parameter_profile(
+        &self,
+        name: &str,
+    ) -> Option<Result<Profiles, Reason>> {
+        self.request_profiles(ProfilesRequest::Deactivate {
+            name: name.to_owned(),
+        })
+        .await
+    }
+
+    async fn request_profiles(&self, request: ProfilesRequest) -> Option<Result<Profiles, Reason>> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::Profiles {
+                request,
+                response_sender,
+            })
+            .await
+            .unwrap();
+        response_receiver.await.unwrap()
+    }
+
     pub async fn update_parameter_value(&self, path: &str, value: Value) {
         self.parameter_subscription_manager
             .send(
                 parameter_subscription_manager::Message::UpdateParameterValue {
                     path: path.to_owned(),
                     value,
+                    response_sender: None,
                 },
             )
             .await
             .unwrap();
     }
+
+    /// Waits for the server to apply the update, returns `None` if not connected
+    pub async fn update_parameter_value_confirmed(
+        &self,
+        path: &str,
+        value: Value,
+    ) -> Option<Result<(), Reason>> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.parameter_subscription_manager
+            .send(
+                parameter_subscription_manager::Message::UpdateParameterValue {
+                    path: path.to_owned(),
+                    value,
+                    response_sender: Some(response_sender),
+                },
+            )
+            .await
+            .unwrap();
+        response_receiver.await.ok().flatten()
+    }
+}
+
+#[cfg(test)]
+impl Communication {
+    pub fn for_test() -> (
+        Self,
+        Receiver<output_subscription_manager::Message>,
+        Receiver<parameter_subscription_manager::Message>,
+    ) {
+        let (connector, _) = mpsc::channel(10);
+        let (output_subscription_manager, output_subscription_manager_receiver) = mpsc::channel(10);
+        let (parameter_subscription_manager, parameter_subscription_manager_receiver) =
+            mpsc::channel(10);
+        (
+            Self {
+                connector,
+                output_subscription_manager,
+                parameter_subscription_manager,
+            },
+            output_subscription_manager_receiver,
+            parameter_subscription_manager_receiver,
+        )
+    }
 }
@@ -10,11 +10,18 @@ use tokio::{
     task::JoinHandle,
     time::sleep,
 };
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::header::AUTHORIZATION},
+    MaybeTlsStream, WebSocketStream,
+};
 
-use crate::client::{
-    output_subscription_manager, parameter_subscription_manager,
-    receiver::receiver as receiver_task, requester::requester, responder,
+use crate::{
+    client::{
+        output_subscription_manager, parameter_subscription_manager,
+        receiver::receiver as receiver_task, requester::requester, responder,
+    },
+    messages::{Request, TransportOptions, TransportRequest},
 };
 
 #[derive(Debug)]
@@ -22,9 +29,13 @@ pub enum Message {
     SubscribeToUpdates(Sender<ConnectionStatus>),
     SetConnect(bool),
     SetAddress(String),
+    /// token sent during the handshake of future connections
+    SetToken(Option<String>),
     ReconnectTimerElapsed,
     Connected(Box<WebSocketStream<MaybeTlsStream<TcpStream>>>),
-    ConnectionFailed { info: String },
+    ConnectionFailed {
+        info: String,
+    },
 }
 
 #[derive(Debug)]
@@ -56,6 +67,7 @@ pub enum ConnectionStatus {
     },
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn connector(
     mut receiver: Receiver<Message>,
     sender: Sender<Message>,
@@ -64,10 +76,11 @@ pub async fn connector(
     responder: Sender<responder::Message>,
     initial_address: Option<String>,
     initial_connect: bool,
+    transport_options: TransportOptions,
 ) {
     let mut status = match (initial_address, initial_connect) {
         (Some(address), true) => {
-            let ongoing_connection = spawn_connect(address.clone(), sender.clone());
+            let ongoing_connection = spawn_connect(address.clone(), None, sender.clone());
             ConnectionState::Connecting {
                 address,
                 ongoing_connection,
@@ -77,6 +90,7 @@ pub async fn connector(
     };
 
     let mut subscribers = Vec::new();
+    let mut token = None;
 
     while let Some(message) = receiver.recv().await {
         status = match status {
@@ -88,6 +102,10 @@ pub async fn connector(
                     subscribers.push(sender);
                     status
                 }
+                Message::SetToken(new_token) => {
+                    token = new_token;
+                    status
+                }
                 Message::SetConnect(new_connect) => ConnectionState::Disconnected {
                     connect: new_connect,
                     address: None,
@@ -111,8 +129,16 @@ pub async fn connector(
                         connect: false,
                     }
                 }
+                Message::SetToken(new_token) => {
+                    token = new_token;
+                    ConnectionState::Disconnected {
+                        address: Some(address),
+                        connect: false,
+                    }
+                }
                 Message::SetConnect(true) => {
-                    let ongoing_connection = spawn_connect(address.clone(), sender.clone());
+                    let ongoing_connection =
+                        spawn_connect(address.clone(), token.clone(), sender.clone());
                     ConnectionState::Connecting {
                         address,
                         ongoing_connection,
@@ -147,6 +173,10 @@ pub async fn connector(
                     subscribers.push(sender);
                     status
                 }
+                Message::SetToken(new_token) => {
+                    token = new_token;
+                    status
+                }
                 Message::SetConnect(false) => ConnectionState::Disconnected {
                     connect: false,
                     address: None,
@@ -156,7 +186,8 @@ pub async fn connector(
                     address: None,
                 },
                 Message::SetAddress(address) => {
-                    let ongoing_connection = spawn_connect(address.clone(), sender.clone());
+                    let ongoing_connection =
+                        spawn_connect(address.clone(), token.clone(), sender.clone());
                     ConnectionState::Connecting {
                         address,
                         ongoing_connection,
@@ -177,6 +208,13 @@ pub async fn connector(
                         connect: true,
                     }
                 }
+                Message::SetToken(new_token) => {
+                    token = new_token;
+                    ConnectionState::Disconnected {
+                        address: Some(address),
+                        connect: true,
+                    }
+                }
                 Message::SetConnect(false) => ConnectionState::Disconnected {
                     connect: false,
                     address: Some(address),
@@ -190,7 +228,8 @@ pub async fn connector(
                     address: Some(address),
                 },
                 Message::ReconnectTimerElapsed => {
-                    let ongoing_connection = spawn_connect(address.clone(), sender.clone());
+                    let ongoing_connection =
+                        spawn_connect(address.clone(), token.clone(), sender.clone());
                     ConnectionState::Connecting {
                         address,
                         ongoing_connection,
@@ -210,6 +249,13 @@ pub async fn connector(
                         ongoing_connection,
                     }
                 }
+                Message::SetToken(new_token) => {
+                    token = new_token;
+                    ConnectionState::Connecting {
+                        address,
+                        ongoing_connection,
+                    }
+                }
                 Message::SetConnect(false) => {
                     ongoing_connection.abort();
                     ConnectionState::Disconnected {
@@ -228,13 +274,27 @@ pub async fn connector(
                             ongoing_connection,
                         }
                     } else {
-                        replace_ongoing_connection(ongoing_connection, new_address, sender.clone())
-                            .await
+                        replace_ongoing_connection(
+                            ongoing_connection,
+                            new_address,
+                            token.clone(),
+                            sender.clone(),
+                        )
+                        .await
                     }
                 }
                 Message::Connected(ws_stream) => {
                     let (writer, reader) = (*ws_stream).split();
                     let (requester_sender, requester_receiver) = channel(10);
+                    // configure the transport first, every following response is encoded with it
+                    if transport_options != TransportOptions::default() {
+                        requester_sender
+                            .send(Request::Transport(TransportRequest::Configure {
+                                options: transport_options,
+                            }))
+                            .await
+                            .unwrap();
+                    }
                     output_subscription_manager
                         .send(output_subscription_manager::Message::Connect {
                             requester: requester_sender.clone(),
@@ -276,6 +336,10 @@ pub async fn connector(
                     subscribers.push(sender);
                     ConnectionState::Connected { address }
                 }
+                Message::SetToken(new_token) => {
+                    token = new_token;
+                    ConnectionState::Connected { address }
+                }
                 Message::SetConnect(false) => {
                     output_subscription_manager
                         .send(output_subscription_manager::Message::Disconnect)
@@ -303,7 +367,8 @@ pub async fn connector(
                             .send(parameter_subscription_manager::Message::Disconnect)
                             .await
                             .unwrap();
-                        let ongoing_connection = spawn_connect(new_address.clone(), sender.clone());
+                        let ongoing_connection =
+                            spawn_connect(new_address.clone(), token.clone(), sender.clone());
                         ConnectionState::Connecting {
                             address: new_address,
                             ongoing_connection,
@@ -356,9 +421,13 @@ fn spawn_reconnect_timer(sender: Sender<Message>) {
     });
 }
 
-fn spawn_connect(address: String, sender: Sender<Message>) -> JoinHandle<()> {
+fn spawn_connect(
+    address: String,
+    token: Option<String>,
+    sender: Sender<Message>,
+) -> JoinHandle<()> {
     spawn(async move {
-        match try_connect(address).await {
+        match try_connect(address, token).await {
             Ok(ws_stream) => sender
                 .send(Message::Connected(Box::new(ws_stream)))
                 .await
@@ -373,9 +442,24 @@ fn spawn_connect(address: String, sender: Sender<Message>) -> JoinHandle<()> {
     })
 }
 
-async fn try_connect(address: String) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+async fn try_connect(
+    address: String,
+    token: Option<String>,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
     info!("Try connection to {}", address);
-    let (ws_stream, _response) = connect_async(&address)
+    let mut request = address
+        .as_str()
+        .into_client_request()
+        .wrap_err_with(|| format!("invalid address {address}"))?;
+    if let Some(token) = token {
+        request.headers_mut().insert(
+            AUTHORIZATION,
+            format!("Bearer {token}")
+                .parse()
+                .wrap_err("token is not a valid header value")?,
+        );
+    }
+    let (ws_stream, _response) = connect_async(request)
         .await
         .wrap_err_with(|| format!("cannot connect websocket to {address}"))?;
     Ok(ws_stream)
@@ -384,13 +468,14 @@ async fn try_connect(address: String) -> Result<WebSocketStream<MaybeTlsStream<T
 async fn replace_ongoing_connection(
     ongoing_connection: JoinHandle<()>,
     new_address: String,
+    token: Option<String>,
     sender: Sender<Message>,
 ) -> ConnectionState {
     ongoing_connection.abort();
     match ongoing_connection.await {
         Err(error) => {
             assert!(error.is_cancelled());
-            let ongoing_connection = spawn_connect(new_address.clone(), sender);
+            let ongoing_connection = spawn_connect(new_address.clone(), token, sender);
             ConnectionState::Connecting {
                 address: new_address,
                 ongoing_connection,
@@ -6,8 +6,10 @@ mod parameter_subscription_manager;
 mod receiver;
 mod requester;
 mod responder;
+mod typed;
 mod types;
 
 pub use crate::client::communication::Communication;
 pub use connector::ConnectionStatus;
+pub use typed::{Parameter, Stream, StreamError};
 pub use types::{Cycler, CyclerOutput, HierarchyType, Output, OutputHierarchy, SubscriberMessage};
@@ -2,6 +2,7 @@ use std::collections::{hash_map::Entry, HashMap};
 
 use color_eyre::Result;
 use log::{error, info, warn};
+use serde_json::Value;
 use tokio::{
     spawn,
     sync::{mpsc, oneshot},
@@ -14,8 +15,8 @@ use crate::{
         responder, Output, SubscriberMessage,
     },
     messages::{
-        Fields, Format, OutputsRequest, Request,
-        TextualDataOrBinaryReference::{self, BinaryReference, TextualData},
+        Fields, Format, OutputsRequest, Request, SubscriptionOptions,
+        TextualDataOrBinaryReference::{self, BinaryReference, TextualData, TextualDelta},
     },
 };
 
@@ -30,6 +31,7 @@ pub enum Message {
     Subscribe {
         output: CyclerOutput,
         format: Format,
+        options: SubscriptionOptions,
         subscriber: mpsc::Sender<SubscriberMessage>,
         response_sender: oneshot::Sender<Uuid>,
     },
@@ -52,11 +54,13 @@ pub enum Message {
 
 #[derive(Default)]
 struct SubscriptionManager {
-    ids_to_outputs: HashMap<usize, (CyclerOutput, Format)>,
+    ids_to_outputs: HashMap<usize, OutputSubscription>,
     outputs_to_subscribers:
-        HashMap<(CyclerOutput, Format), HashMap<Uuid, mpsc::Sender<SubscriberMessage>>>,
+        HashMap<OutputSubscription, HashMap<Uuid, mpsc::Sender<SubscriberMessage>>>,
 }
 
+type OutputSubscription = (CyclerOutput, Format, SubscriptionOptions);
+
 pub async fn output_subscription_manager(
     mut receiver: mpsc::Receiver<Message>,
     sender: mpsc::Sender<Message>,
@@ -67,7 +71,8 @@ pub async fn output_subscription_manager(
     let mut requester = None;
     let mut fields = None;
     let mut binary_data_waiting_for_references: HashMap<usize, Vec<u8>> = HashMap::new();
-    let mut binary_references_waiting_for_data: HashMap<usize, CyclerOutput> = HashMap::new();
+    let mut binary_references_waiting_for_data: HashMap<usize, OutputSubscription> = HashMap::new();
+    let mut last_textual_data: HashMap<usize, Value> = HashMap::new();
 
     while let Some(message) = receiver.recv().await {
         match message {
@@ -75,11 +80,12 @@ pub async fn output_subscription_manager(
                 requester: new_requester,
             } => {
                 assert!(manager.ids_to_outputs.is_empty());
-                for ((output, format), subscribers) in &manager.outputs_to_subscribers {
+                for ((output, format, options), subscribers) in &manager.outputs_to_subscribers {
                     let subscribers = subscribers.values().cloned().collect();
                     if let Some(subscription_id) = subscribe(
                         output.clone(),
                         *format,
+                        *options,
                         subscribers,
                         &id_tracker,
                         &responder,
@@ -89,7 +95,7 @@ pub async fn output_subscription_manager(
                     {
                         manager
                             .ids_to_outputs
-                            .insert(subscription_id, (output.clone(), *format));
+                            .insert(subscription_id, (output.clone(), *format, *options));
                     }
                 }
                 match query_output_fields(sender.clone(), &id_tracker, &responder, &new_requester)
@@ -104,10 +110,12 @@ pub async fn output_subscription_manager(
             Message::Disconnect => {
                 requester = None;
                 manager.ids_to_outputs.clear();
+                last_textual_data.clear();
             }
             Message::Subscribe {
                 output,
                 format,
+                options,
                 subscriber: output_sender,
                 response_sender,
             } => {
@@ -119,6 +127,7 @@ pub async fn output_subscription_manager(
                             uuid,
                             output,
                             format,
+                            options,
                             output_sender,
                             &id_tracker,
                             &responder,
@@ -155,6 +164,7 @@ pub async fn output_subscription_manager(
                 for subscription_id in subscriptions_to_remove {
                     if let Some(requester) = &requester {
                         manager.ids_to_outputs.remove(&subscription_id);
+                        last_textual_data.remove(&subscription_id);
                         unsubscribe(subscription_id, &id_tracker, &responder, requester).await;
                     }
                 }
@@ -168,14 +178,19 @@ pub async fn output_subscription_manager(
                     if let Some(senders) = manager.outputs_to_subscribers.get(output) {
                         match value_or_reference {
                             TextualData { data } => {
-                                for sender in senders.values() {
-                                    if let Err(error) = sender
-                                        .send(SubscriberMessage::Update {
-                                            value: data.clone(),
-                                        })
-                                        .await
-                                    {
-                                        error!("{error}");
+                                send_textual_update(senders, &data).await;
+                                last_textual_data.insert(subscription_id, data);
+                            }
+                            TextualDelta { delta } => {
+                                let Some(data) = last_textual_data.get_mut(&subscription_id) else {
+                                    warn!("no previous data to apply delta of subscription_id: {subscription_id}");
+                                    continue;
+                                };
+                                match delta.apply(data) {
+                                    Ok(()) => send_textual_update(senders, data).await,
+                                    Err(error) => {
+                                        error!("failed to apply delta: {error}");
+                                        last_textual_data.remove(&subscription_id);
                                     }
                                 }
                             }
@@ -195,7 +210,7 @@ pub async fn output_subscription_manager(
                                     }
                                 } else {
                                     binary_references_waiting_for_data
-                                        .insert(reference_id, output.0.clone());
+                                        .insert(reference_id, output.clone());
                                 }
                             }
                         }
@@ -213,9 +228,7 @@ pub async fn output_subscription_manager(
             Message::UpdateBinary { referenced_items } => {
                 for (reference_id, data) in referenced_items {
                     if let Some(output) = binary_references_waiting_for_data.get(&reference_id) {
-                        let subscribers = manager
-                            .outputs_to_subscribers
-                            .get(&(output.clone(), Format::Binary));
+                        let subscribers = manager.outputs_to_subscribers.get(output);
                         if let Some(senders) = subscribers {
                             for sender in senders.values() {
                                 if let Err(error) = sender
@@ -236,6 +249,22 @@ pub async fn output_subscription_manager(
     info!("Finished manager");
 }
 
+async fn send_textual_update(
+    senders: &HashMap<Uuid, mpsc::Sender<SubscriberMessage>>,
+    data: &Value,
+) {
+    for sender in senders.values() {
+        if let Err(error) = sender
+            .send(SubscriberMessage::Update {
+                value: data.clone(),
+            })
+            .await
+        {
+            error!("{error}");
+        }
+    }
+}
+
 async fn query_output_fields(
     manager: mpsc::Sender<Message>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
@@ -272,6 +301,7 @@ async fn add_subscription(
     uuid: Uuid,
     output: CyclerOutput,
     format: Format,
+    options: SubscriptionOptions,
     output_sender: mpsc::Sender<SubscriberMessage>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
     responder: &mpsc::Sender<responder::Message>,
@@ -279,7 +309,7 @@ async fn add_subscription(
 ) {
     match manager
         .outputs_to_subscribers
-        .entry((output.clone(), format))
+        .entry((output.clone(), format, options))
     {
         Entry::Occupied(mut entry) => {
             entry.get_mut().insert(uuid, output_sender);
@@ -289,6 +319,7 @@ async fn add_subscription(
                 if let Some(subscription_id) = subscribe(
                     output.clone(),
                     format,
+                    options,
                     vec![output_sender.clone()],
                     id_tracker,
                     responder,
@@ -298,7 +329,7 @@ async fn add_subscription(
                 {
                     manager
                         .ids_to_outputs
-                        .insert(subscription_id, (output, format));
+                        .insert(subscription_id, (output, format, options));
                 }
             };
             entry.insert(HashMap::new()).insert(uuid, output_sender);
@@ -309,6 +340,7 @@ async fn add_subscription(
 async fn subscribe(
     output: CyclerOutput,
     format: Format,
+    options: SubscriptionOptions,
     subscribers: Vec<mpsc::Sender<SubscriberMessage>>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
     responder: &mpsc::Sender<responder::Message>,
@@ -335,6 +367,7 @@ async fn subscribe(
         cycler_instance: output.cycler.to_string(),
         path,
         format,
+        options,
     });
     if let Err(error) = requester.send(request).await {
         error!("{error}");
@@ -1,8 +1,9 @@
-use std::collections::{hash_map::Entry, BTreeSet, HashMap};
+use std::collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap};
 
 use color_eyre::eyre::Result;
 use log::{error, info, warn};
 use serde_json::Value;
+use serialize_hierarchy::FieldMetadata;
 use tokio::{
     spawn,
     sync::{mpsc, oneshot},
@@ -14,7 +15,7 @@ use crate::{
         id_tracker::{self, get_message_id},
         responder, SubscriberMessage,
     },
-    messages::{ParametersRequest, Path, Request},
+    messages::{ParameterChange, ParametersRequest, Path, Profiles, Reason, Request},
 };
 
 use super::responder::Response;
@@ -43,12 +44,36 @@ pub enum Message {
     GetFields {
         response_sender: oneshot::Sender<Option<BTreeSet<Path>>>,
     },
+    UpdateSchema {
+        schema: BTreeMap<Path, FieldMetadata>,
+    },
+    GetSchema {
+        response_sender: oneshot::Sender<Option<BTreeMap<Path, FieldMetadata>>>,
+    },
+    GetHistory {
+        response_sender: oneshot::Sender<Option<Vec<ParameterChange>>>,
+    },
+    RevertChange {
+        change_id: usize,
+    },
+    Profiles {
+        request: ProfilesRequest,
+        response_sender: oneshot::Sender<Option<Result<Profiles, Reason>>>,
+    },
     UpdateParameterValue {
         path: String,
         value: Value,
+        response_sender: Option<oneshot::Sender<Option<Result<(), Reason>>>>,
     },
 }
 
+#[derive(Debug)]
+pub enum ProfilesRequest {
+    Get,
+    Activate { name: String },
+    Deactivate { name: String },
+}
+
 #[derive(Default)]
 struct SubscriptionManager {
     ids_to_paths: HashMap<usize, Path>,
@@ -64,6 +89,7 @@ pub async fn parameter_subscription_manager(
     let mut manager = SubscriptionManager::default();
     let mut requester = None;
     let mut fields = None;
+    let mut schema = None;
     while let Some(message) = receiver.recv().await {
         match message {
             Message::Connect {
@@ -168,11 +194,73 @@ pub async fn parameter_subscription_manager(
                     error!("{error:?}");
                 }
             }
-            Message::UpdateParameterValue { path, value } => {
+            Message::UpdateSchema { schema: new_schema } => {
+                schema = Some(new_schema);
+            }
+            Message::GetSchema { response_sender } => {
+                if let Err(error) = response_sender.send(schema.clone()) {
+                    error!("{error:?}");
+                }
+            }
+            Message::GetHistory { response_sender } => match &requester {
+                Some(some_requester) => {
+                    if let Err(error) =
+                        get_history(response_sender, &id_tracker, &responder, some_requester).await
+                    {
+                        error!("{error}");
+                        requester = None
+                    }
+                }
+                None => {
+                    if let Err(error) = response_sender.send(None) {
+                        error!("{error:?}");
+                    }
+                }
+            },
+            Message::RevertChange { change_id } => {
+                if let Some(some_requester) = &requester {
+                    if let Err(error) =
+                        revert_change(change_id, &id_tracker, &responder, some_requester).await
+                    {
+                        error!("{error}");
+                        requester = None
+                    }
+                }
+            }
+            Message::Profiles {
+                request,
+                response_sender,
+            } => match &requester {
+                Some(some_requester) => {
+                    if let Err(error) = request_profiles(
+                        request,
+                        response_sender,
+                        &id_tracker,
+                        &responder,
+                        some_requester,
+                    )
+                    .await
+                    {
+                        error!("{error}");
+                        requester = None
+                    }
+                }
+                None => {
+                    if let Err(error) = response_sender.send(None) {
+                        error!("{error:?}");
+                    }
+                }
+            },
+            Message::UpdateParameterValue {
+                path,
+                value,
+                response_sender,
+            } => {
                 if let Some(some_requester) = requester {
                     match update_parameter_value(
                         path,
                         value,
+                        response_sender,
                         &id_tracker,
                         &responder,
                         &some_requester,
@@ -185,6 +273,10 @@ pub async fn parameter_subscription_manager(
                             requester = None
                         }
                     }
+                } else if let Some(response_sender) = response_sender {
+                    if let Err(error) = response_sender.send(None) {
+                        error!("{error:?}");
+                    }
                 }
             }
         }
@@ -213,31 +305,59 @@ async fn query_parameter_hierarchy(
         }))
         .await
         .unwrap();
+    let fields_manager = manager.clone();
     spawn(async move {
         let response = response_receiver.await.unwrap();
         match response {
-            Response::ParameterFields(fields) => manager
+            Response::ParameterFields(fields) => fields_manager
                 .send(Message::UpdateFields { fields })
                 .await
                 .unwrap(),
             response => error!("unexpected response: {response:?}"),
         }
     });
+
+    let message_id = get_message_id(id_tracker).await;
+    let (response_sender, response_receiver) = oneshot::channel();
+    responder
+        .send(responder::Message::Await {
+            id: message_id,
+            response_sender,
+        })
+        .await
+        .unwrap();
+    requester
+        .send(Request::Parameters(ParametersRequest::GetSchema {
+            id: message_id,
+        }))
+        .await
+        .unwrap();
+    spawn(async move {
+        let response = response_receiver.await.unwrap();
+        match response {
+            Response::ParameterSchema(schema) => manager
+                .send(Message::UpdateSchema { schema })
+                .await
+                .unwrap(),
+            response => error!("unexpected response: {response:?}"),
+        }
+    });
 }
 
 async fn update_parameter_value(
     path: String,
     value: Value,
+    response_sender: Option<oneshot::Sender<Option<Result<(), Reason>>>>,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
     responder: &mpsc::Sender<responder::Message>,
     requester: &mpsc::Sender<Request>,
 ) -> Result<()> {
     let message_id = get_message_id(id_tracker).await;
-    let (response_sender, response_receiver) = oneshot::channel();
+    let (update_sender, update_receiver) = oneshot::channel();
     responder
         .send(responder::Message::Await {
             id: message_id,
-            response_sender,
+            response_sender: update_sender,
         })
         .await?;
     requester
@@ -247,12 +367,89 @@ async fn update_parameter_value(
             data: value,
         }))
         .await?;
+    spawn(async move {
+        let result = match update_receiver.await.unwrap() {
+            Response::Update(result) => result,
+            Response::PermissionDenied => Err("permission denied".to_string()),
+            response => {
+                error!("unexpected response: {response:?}");
+                Err(format!("unexpected response: {response:?}"))
+            }
+        };
+        if let Err(error) = &result {
+            error!("Failed to update value: {}", error)
+        }
+        if let Some(response_sender) = response_sender {
+            if let Err(error) = response_sender.send(Some(result)) {
+                error!("{error:?}");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn get_history(
+    response_sender: oneshot::Sender<Option<Vec<ParameterChange>>>,
+    id_tracker: &mpsc::Sender<id_tracker::Message>,
+    responder: &mpsc::Sender<responder::Message>,
+    requester: &mpsc::Sender<Request>,
+) -> Result<()> {
+    let message_id = get_message_id(id_tracker).await;
+    let (history_sender, history_receiver) = oneshot::channel();
+    responder
+        .send(responder::Message::Await {
+            id: message_id,
+            response_sender: history_sender,
+        })
+        .await?;
+    requester
+        .send(Request::Parameters(ParametersRequest::GetHistory {
+            id: message_id,
+        }))
+        .await?;
+    spawn(async move {
+        let changes = match history_receiver.await.unwrap() {
+            Response::ParameterHistory(changes) => Some(changes),
+            response => {
+                error!("unexpected response: {response:?}");
+                None
+            }
+        };
+        if let Err(error) = response_sender.send(changes) {
+            error!("{error:?}");
+        }
+    });
+
+    Ok(())
+}
+
+async fn revert_change(
+    change_id: usize,
+    id_tracker: &mpsc::Sender<id_tracker::Message>,
+    responder: &mpsc::Sender<responder::Message>,
+    requester: &mpsc::Sender<Request>,
+) -> Result<()> {
+    let message_id = get_message_id(id_tracker).await;
+    let (response_sender, response_receiver) = oneshot::channel();
+    responder
+        .send(responder::Message::Await {
+            id: message_id,
+            response_sender,
+        })
+        .await?;
+    requester
+        .send(Request::Parameters(ParametersRequest::Revert {
+            id: message_id,
+            change_id,
+        }))
+        .await?;
     spawn(async move {
         let response = response_receiver.await.unwrap();
         match response {
-            Response::Update(Ok(_)) => {}
-            Response::Update(Err(error)) => {
-                error!("Failed to update value: {}", error)
+            Response::Revert(Ok(_)) => {}
+            Response::Revert(Err(error)) => {
+                error!("Failed to revert change {change_id}: {}", error)
             }
             response => error!("unexpected response: {response:?}"),
         };
@@ -261,6 +458,49 @@ async fn update_parameter_value(
     Ok(())
 }
 
+async fn request_profiles(
+    request: ProfilesRequest,
+    response_sender: oneshot::Sender<Option<Result<Profiles, Reason>>>,
+    id_tracker: &mpsc::Sender<id_tracker::Message>,
+    responder: &mpsc::Sender<responder::Message>,
+    requester: &mpsc::Sender<Request>,
+) -> Result<()> {
+    let message_id = get_message_id(id_tracker).await;
+    let (profiles_sender, profiles_receiver) = oneshot::channel();
+    responder
+        .send(responder::Message::Await {
+            id: message_id,
+            response_sender: profiles_sender,
+        })
+        .await?;
+    let request = match request {
+        ProfilesRequest::Get => ParametersRequest::GetProfiles { id: message_id },
+        ProfilesRequest::Activate { name } => ParametersRequest::ActivateProfile {
+            id: message_id,
+            name,
+        },
+        ProfilesRequest::Deactivate { name } => ParametersRequest::DeactivateProfile {
+            id: message_id,
+            name,
+        },
+    };
+    requester.send(Request::Parameters(request)).await?;
+    spawn(async move {
+        let result = match profiles_receiver.await.unwrap() {
+            Response::Profiles(result) => Some(result),
+            response => {
+                error!("unexpected response: {response:?}");
+                None
+            }
+        };
+        if let Err(error) = response_sender.send(result) {
+            error!("{error:?}");
+        }
+    });
+
+    Ok(())
+}
+
 async fn add_subscription(
     manager: &mut SubscriptionManager,
     uuid: Uuid,
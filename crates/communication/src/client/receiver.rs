@@ -11,9 +11,10 @@ use crate::{
         responder::{Message, Response},
     },
     messages::{
-        BinaryOutputsResponse, BinaryResponse, ParametersResponse, TextualOutputsResponse,
-        TextualResponse,
+        BinaryOutputsResponse, BinaryResponse, Compression, ParametersResponse,
+        TextualOutputsResponse, TextualResponse, TransportResponse,
     },
+    transport::{decompress, FrameKind},
 };
 
 use super::{output_subscription_manager, responder};
@@ -25,102 +26,48 @@ pub async fn receiver(
     parameter_subscription_manager: Sender<parameter_subscription_manager::Message>,
     connector: Sender<connector::Message>,
 ) {
+    let mut compression = Compression::None;
     while let Some(message) = reader.next().await {
         debug!("Receiver got message: {message:?}");
         match message {
             Ok(message) => match message {
                 tungstenite::Message::Text(content) => {
-                    let message = match from_str::<TextualResponse>(&content) {
-                        Ok(payload) => payload,
-                        Err(error) => {
-                            error!("Failed to deserialize message content: {error:?}\nMessage was {content:#?}");
-                            continue;
-                        }
-                    };
-                    match message {
-                        TextualResponse::Outputs(outputs_message) => match outputs_message {
-                            TextualOutputsResponse::GetFields { id, fields } => {
-                                respond(&responder, id, Response::Fields(fields)).await
-                            }
-                            TextualOutputsResponse::GetNext { id: _, result: _ } => todo!(),
-                            TextualOutputsResponse::Subscribe { id, result } => {
-                                respond(&responder, id, Response::Subscribe(result)).await
-                            }
-                            TextualOutputsResponse::Unsubscribe { id, result } => {
-                                respond(&responder, id, Response::Unsubscribe(result)).await
-                            }
-                            TextualOutputsResponse::SubscribedData { items } => {
-                                if let Err(error) = output_subscription_manager
-                                    .send(output_subscription_manager::Message::Update { items })
-                                    .await
-                                {
-                                    error!("{error}");
-                                }
-                            }
-                        },
-                        TextualResponse::Parameters(parameters_message) => match parameters_message
-                        {
-                            ParametersResponse::GetFields { id, fields } => {
-                                respond(&responder, id, Response::ParameterFields(fields)).await
-                            }
-                            ParametersResponse::Subscribe { id, result } => {
-                                respond(&responder, id, Response::Subscribe(result)).await
-                            }
-                            ParametersResponse::Unsubscribe { id, result } => {
-                                respond(&responder, id, Response::Unsubscribe(result)).await
-                            }
-                            ParametersResponse::SubscribedData {
-                                subscription_id,
-                                data,
-                            } => {
-                                if let Err(error) = parameter_subscription_manager
-                                    .send(parameter_subscription_manager::Message::Update {
-                                        subscription_id,
-                                        data,
-                                    })
-                                    .await
-                                {
-                                    error!("{error}");
-                                }
-                            }
-                            ParametersResponse::Update { id, result } => {
-                                respond(&responder, id, Response::Update(result)).await
-                            }
-                            ParametersResponse::GetCurrent { id: _, result: _ } => todo!(),
-                            ParametersResponse::LoadFromDisk { id: _, result: _ } => todo!(),
-                            ParametersResponse::StoreToDisk { id: _, result: _ } => todo!(),
-                        },
-                        message => todo!("unimplemented message {message:?}"),
-                    }
+                    handle_textual_message(
+                        &content,
+                        &mut compression,
+                        &responder,
+                        &output_subscription_manager,
+                        &parameter_subscription_manager,
+                    )
+                    .await
                 }
                 tungstenite::Message::Close(close_frame) => {
                     info!("closed: {close_frame:?}");
                     break;
                 }
-                tungstenite::Message::Binary(data) => {
-                    let response = match deserialize::<BinaryResponse>(&data) {
-                        Ok(payload) => payload,
-                        Err(error) => {
-                            error!("Failed to deserialize binary message content: {error:?}");
-                            continue;
-                        }
-                    };
-                    let message = match response {
-                        BinaryResponse::Outputs(binary_output_response) => {
-                            match binary_output_response {
-                                BinaryOutputsResponse::GetNext {
-                                    reference_id: _,
-                                    data: _,
-                                } => todo!(),
-                                BinaryOutputsResponse::SubscribedData { referenced_items } => {
-                                    output_subscription_manager::Message::UpdateBinary {
-                                        referenced_items,
-                                    }
-                                }
+                tungstenite::Message::Binary(data) if compression != Compression::None => {
+                    match decompress(&data, compression) {
+                        Ok((FrameKind::Textual, payload)) => match String::from_utf8(payload) {
+                            Ok(content) => {
+                                handle_textual_message(
+                                    &content,
+                                    &mut compression,
+                                    &responder,
+                                    &output_subscription_manager,
+                                    &parameter_subscription_manager,
+                                )
+                                .await
                             }
+                            Err(error) => error!("Failed to decode textual message: {error:?}"),
+                        },
+                        Ok((FrameKind::Binary, payload)) => {
+                            handle_binary_message(&payload, &output_subscription_manager).await
                         }
-                    };
-                    output_subscription_manager.send(message).await.unwrap();
+                        Err(error) => error!("Failed to decompress message: {error:?}"),
+                    }
+                }
+                tungstenite::Message::Binary(data) => {
+                    handle_binary_message(&data, &output_subscription_manager).await
                 }
                 _ => {
                     error!("Got unsupported message type from socket");
@@ -144,6 +91,129 @@ pub async fn receiver(
     }
 }
 
+async fn handle_textual_message(
+    content: &str,
+    compression: &mut Compression,
+    responder: &Sender<Message>,
+    output_subscription_manager: &Sender<output_subscription_manager::Message>,
+    parameter_subscription_manager: &Sender<parameter_subscription_manager::Message>,
+) {
+    let message = match from_str::<TextualResponse>(content) {
+        Ok(payload) => payload,
+        Err(error) => {
+            error!("Failed to deserialize message content: {error:?}\nMessage was {content:#?}");
+            return;
+        }
+    };
+    match message {
+        TextualResponse::Outputs(outputs_message) => match outputs_message {
+            TextualOutputsResponse::GetFields { id, fields } => {
+                respond(responder, id, Response::Fields(fields)).await
+            }
+            TextualOutputsResponse::GetNext { id: _, result: _ } => todo!(),
+            TextualOutputsResponse::Subscribe { id, result } => {
+                respond(responder, id, Response::Subscribe(result)).await
+            }
+            TextualOutputsResponse::Unsubscribe { id, result } => {
+                respond(responder, id, Response::Unsubscribe(result)).await
+            }
+            TextualOutputsResponse::SubscribedData { items } => {
+                if let Err(error) = output_subscription_manager
+                    .send(output_subscription_manager::Message::Update { items })
+                    .await
+                {
+                    error!("{error}");
+                }
+            }
+        },
+        TextualResponse::Parameters(parameters_message) => match parameters_message {
+            ParametersResponse::GetFields { id, fields } => {
+                respond(responder, id, Response::ParameterFields(fields)).await
+            }
+            ParametersResponse::GetSchema { id, schema } => {
+                respond(responder, id, Response::ParameterSchema(schema)).await
+            }
+            ParametersResponse::Subscribe { id, result } => {
+                respond(responder, id, Response::Subscribe(result)).await
+            }
+            ParametersResponse::Unsubscribe { id, result } => {
+                respond(responder, id, Response::Unsubscribe(result)).await
+            }
+            ParametersResponse::SubscribedData {
+                subscription_id,
+                data,
+            } => {
+                if let Err(error) = parameter_subscription_manager
+                    .send(parameter_subscription_manager::Message::Update {
+                        subscription_id,
+                        data,
+                    })
+                    .await
+                {
+                    error!("{error}");
+                }
+            }
+            ParametersResponse::Update { id, result } => {
+                respond(responder, id, Response::Update(result)).await
+            }
+            ParametersResponse::GetCurrent { id: _, result: _ } => todo!(),
+            ParametersResponse::LoadFromDisk { id: _, result: _ } => todo!(),
+            ParametersResponse::StoreToDisk { id: _, result: _ } => todo!(),
+            ParametersResponse::GetHistory { id, changes } => {
+                respond(responder, id, Response::ParameterHistory(changes)).await
+            }
+            ParametersResponse::Revert { id, result } => {
+                respond(responder, id, Response::Revert(result)).await
+            }
+            ParametersResponse::Profiles { id, result } => {
+                respond(responder, id, Response::Profiles(result)).await
+            }
+            ParametersResponse::ReloadFromDiskFailed { reason } => {
+                error!("Parameters could not be reloaded from disk: {reason}")
+            }
+        },
+        TextualResponse::PermissionDenied { id, permission } => {
+            error!("Permission {permission:?} denied by server");
+            if let Some(id) = id {
+                respond(responder, id, Response::PermissionDenied).await
+            }
+        }
+        TextualResponse::InvalidRequest { reason } => {
+            error!("Request rejected by server: {reason}");
+        }
+        TextualResponse::Transport(TransportResponse::Configure { options }) => {
+            info!("Transport configured: {options:?}");
+            *compression = options.compression;
+        }
+        message => todo!("unimplemented message {message:?}"),
+    }
+}
+
+async fn handle_binary_message(
+    data: &[u8],
+    output_subscription_manager: &Sender<output_subscription_manager::Message>,
+) {
+    let response = match deserialize::<BinaryResponse>(data) {
+        Ok(payload) => payload,
+        Err(error) => {
+            error!("Failed to deserialize binary message content: {error:?}");
+            return;
+        }
+    };
+    let message = match response {
+        BinaryResponse::Outputs(binary_output_response) => match binary_output_response {
+            BinaryOutputsResponse::GetNext {
+                reference_id: _,
+                data: _,
+            } => todo!(),
+            BinaryOutputsResponse::SubscribedData { referenced_items } => {
+                output_subscription_manager::Message::UpdateBinary { referenced_items }
+            }
+        },
+    };
+    output_subscription_manager.send(message).await.unwrap();
+}
+
 async fn respond(responder: &Sender<responder::Message>, id: usize, response: responder::Response) {
     if let Err(error) = responder.send(Message::Respond { id, response }).await {
         error!("{error}");
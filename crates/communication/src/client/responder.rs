@@ -1,9 +1,10 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use log::{debug, error};
+use serialize_hierarchy::FieldMetadata;
 use tokio::sync::{mpsc, oneshot};
 
-use crate::messages::{Fields, Path, Reason};
+use crate::messages::{Fields, ParameterChange, Path, Profiles, Reason};
 
 #[derive(Debug)]
 pub enum Message {
@@ -21,9 +22,14 @@ pub enum Message {
 pub enum Response {
     Fields(Fields),
     ParameterFields(BTreeSet<Path>),
+    ParameterSchema(BTreeMap<Path, FieldMetadata>),
+    ParameterHistory(Vec<ParameterChange>),
+    Revert(Result<(), Reason>),
+    Profiles(Result<Profiles, Reason>),
     Subscribe(Result<(), Reason>),
     Unsubscribe(Result<(), Reason>),
     Update(Result<(), Reason>),
+    PermissionDenied,
 }
 
 pub async fn responder(mut receiver: mpsc::Receiver<Message>) {
@@ -0,0 +1,259 @@
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{from_value, to_value};
+use tokio::{runtime::Handle, sync::mpsc::Receiver};
+use uuid::Uuid;
+
+use crate::messages::{Format, SubscriptionOptions};
+
+use super::{Communication, CyclerOutput, SubscriberMessage};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StreamError {
+    #[error("subscription failed: {info}")]
+    SubscriptionFailed { info: String },
+    #[error("failed to deserialize value")]
+    NotDeserialized(#[source] serde_json::Error),
+    #[error("got binary data for textual subscription")]
+    UnexpectedBinaryData,
+}
+
+#[derive(Clone, Copy)]
+enum Subscription {
+    Output,
+    Parameter,
+}
+
+/// Updates of a subscribed output or parameter, deserialized into the type known at compile time
+///
+/// Dropping the stream unsubscribes from the output or parameter.
+pub struct Stream<T> {
+    communication: Communication,
+    subscription: Subscription,
+    uuid: Uuid,
+    receiver: Receiver<SubscriberMessage>,
+    value_type: PhantomData<T>,
+}
+
+impl<T> Stream<T>
+where
+    T: DeserializeOwned,
+{
+    pub async fn output(communication: &Communication, output: CyclerOutput) -> Self {
+        let (uuid, receiver) = communication
+            .subscribe_output(output, Format::Textual, SubscriptionOptions::default())
+            .await;
+        Self {
+            communication: communication.clone(),
+            subscription: Subscription::Output,
+            uuid,
+            receiver,
+            value_type: PhantomData,
+        }
+    }
+
+    pub async fn parameter(communication: &Communication, path: &str) -> Self {
+        let (uuid, receiver) = communication.subscribe_parameter(path.to_string()).await;
+        Self {
+            communication: communication.clone(),
+            subscription: Subscription::Parameter,
+            uuid,
+            receiver,
+            value_type: PhantomData,
+        }
+    }
+
+    /// Identifies the subscription among all subscribers of the `Communication`
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Returns `None` after the subscription ended
+    pub async fn next(&mut self) -> Option<Result<T, StreamError>> {
+        loop {
+            let result = match self.receiver.recv().await? {
+                SubscriberMessage::Update { value } => {
+                    from_value(value).map_err(StreamError::NotDeserialized)
+                }
+                SubscriberMessage::SubscriptionSuccess => continue,
+                SubscriberMessage::SubscriptionFailure { info } => {
+                    Err(StreamError::SubscriptionFailed { info })
+                }
+                SubscriberMessage::UpdateBinary { .. } => Err(StreamError::UnexpectedBinaryData),
+            };
+            return Some(result);
+        }
+    }
+}
+
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        // without a runtime, the subscription managers are gone as well
+        if let Ok(handle) = Handle::try_current() {
+            let communication = self.communication.clone();
+            let subscription = self.subscription;
+            let uuid = self.uuid;
+            handle.spawn(async move {
+                match subscription {
+                    Subscription::Output => communication.unsubscribe_output(uuid).await,
+                    Subscription::Parameter => communication.unsubscribe_parameter(uuid).await,
+                }
+            });
+        }
+    }
+}
+
+/// Parameter at a path whose type is known at compile time
+pub struct Parameter<'a, T> {
+    communication: &'a Communication,
+    path: &'static str,
+    value_type: PhantomData<T>,
+}
+
+impl<'a, T> Parameter<'a, T>
+where
+    T: DeserializeOwned + Serialize,
+{
+    pub fn new(communication: &'a Communication, path: &'static str) -> Self {
+        Self {
+            communication,
+            path,
+            value_type: PhantomData,
+        }
+    }
+
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    pub async fn subscribe(&self) -> Stream<T> {
+        Stream::parameter(self.communication, self.path).await
+    }
+
+    pub async fn update(&self, value: &T) -> serde_json::Result<()> {
+        self.communication
+            .update_parameter_value(self.path, to_value(value)?)
+            .await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use crate::client::{
+        output_subscription_manager, parameter_subscription_manager, Cycler, Output,
+    };
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Step {
+        length: f32,
+        height: f32,
+    }
+
+    #[tokio::test]
+    async fn parameters_round_trip_through_typed_handles() {
+        let (communication, _outputs, mut parameters) = Communication::for_test();
+        let parameter = Parameter::<Step>::new(&communication, "walking.step");
+        let step = Step {
+            length: 0.05,
+            height: 0.01,
+        };
+
+        parameter.update(&step).await.unwrap();
+        let value = match parameters.recv().await {
+            Some(parameter_subscription_manager::Message::UpdateParameterValue {
+                path,
+                value,
+                ..
+            }) => {
+                assert_eq!(path, "walking.step");
+                value
+            }
+            _ => panic!("expected parameter update"),
+        };
+
+        let uuid = Uuid::new_v4();
+        let (mut stream, subscriber) = tokio::join!(parameter.subscribe(), async {
+            match parameters.recv().await {
+                Some(parameter_subscription_manager::Message::Subscribe {
+                    path,
+                    subscriber,
+                    response_sender,
+                }) => {
+                    assert_eq!(path, "walking.step");
+                    response_sender.send(uuid).unwrap();
+                    subscriber
+                }
+                _ => panic!("expected parameter subscription"),
+            }
+        });
+        subscriber
+            .send(SubscriberMessage::SubscriptionSuccess)
+            .await
+            .unwrap();
+        subscriber
+            .send(SubscriberMessage::Update { value })
+            .await
+            .unwrap();
+        subscriber
+            .send(SubscriberMessage::Update {
+                value: json!({ "length": "far" }),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(stream.uuid(), uuid);
+        assert_eq!(stream.next().await.unwrap().unwrap(), step);
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(StreamError::NotDeserialized(_)))
+        ));
+
+        drop(stream);
+        assert!(matches!(
+            parameters.recv().await,
+            Some(parameter_subscription_manager::Message::Unsubscribe { uuid: unsubscribed }) if unsubscribed == uuid
+        ));
+    }
+
+    #[tokio::test]
+    async fn dropped_output_streams_unsubscribe() {
+        let (communication, mut outputs, _parameters) = Communication::for_test();
+        let output = CyclerOutput {
+            cycler: Cycler::Control,
+            output: Output::Main {
+                path: "ball_position".to_string(),
+            },
+        };
+
+        let uuid = Uuid::new_v4();
+        let (stream, _subscriber) = tokio::join!(
+            Stream::<Option<f32>>::output(&communication, output),
+            async {
+                match outputs.recv().await {
+                    Some(output_subscription_manager::Message::Subscribe {
+                        subscriber,
+                        response_sender,
+                        ..
+                    }) => {
+                        response_sender.send(uuid).unwrap();
+                        subscriber
+                    }
+                    _ => panic!("expected output subscription"),
+                }
+            }
+        );
+
+        drop(stream);
+        assert!(matches!(
+            outputs.recv().await,
+            Some(output_subscription_manager::Message::Unsubscribe { uuid: unsubscribed }) if unsubscribed == uuid
+        ));
+    }
+}
@@ -46,6 +46,8 @@ pub enum Cycler {
     Control,
     VisionTop,
     VisionBottom,
+    SplNetwork,
+    Audio,
     BehaviorSimulator,
 }
 
@@ -55,6 +57,8 @@ impl Display for Cycler {
             Cycler::Control => f.write_str("Control"),
             Cycler::VisionTop => f.write_str("VisionTop"),
             Cycler::VisionBottom => f.write_str("VisionBottom"),
+            Cycler::SplNetwork => f.write_str("SplNetwork"),
+            Cycler::Audio => f.write_str("Audio"),
             Cycler::BehaviorSimulator => f.write_str("BehaviorSimulator"),
         }
     }
@@ -68,6 +72,8 @@ impl FromStr for Cycler {
             "Control" => Cycler::Control,
             "VisionTop" => Cycler::VisionTop,
             "VisionBottom" => Cycler::VisionBottom,
+            "SplNetwork" => Cycler::SplNetwork,
+            "Audio" => Cycler::Audio,
             "BehaviorSimulator" => Cycler::BehaviorSimulator,
             _ => bail!("unknown cycler '{string}'"),
         })
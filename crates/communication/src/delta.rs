@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("expected an object to apply the delta to")]
+    ExpectedObject,
+    #[error("expected an array of length {length} to apply the delta to")]
+    ExpectedArray { length: usize },
+    #[error("index {index} is out of range of the array of length {length}")]
+    IndexOutOfRange { index: usize, length: usize },
+}
+
+/// Structural difference between two JSON values, used to transmit only the changed parts of
+/// slowly changing outputs
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Delta {
+    Unchanged,
+    Replaced(Value),
+    Object {
+        changed: BTreeMap<String, Delta>,
+        removed: Vec<String>,
+    },
+    Array {
+        length: usize,
+        changed: BTreeMap<usize, Delta>,
+    },
+}
+
+impl Delta {
+    pub fn between(old: &Value, new: &Value) -> Self {
+        if old == new {
+            return Delta::Unchanged;
+        }
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => {
+                let changed = new
+                    .iter()
+                    .filter_map(|(key, new_value)| {
+                        let delta = match old.get(key) {
+                            Some(old_value) => Delta::between(old_value, new_value),
+                            None => Delta::Replaced(new_value.clone()),
+                        };
+                        (delta != Delta::Unchanged).then(|| (key.clone(), delta))
+                    })
+                    .collect();
+                let removed = old
+                    .keys()
+                    .filter(|key| !new.contains_key(*key))
+                    .cloned()
+                    .collect();
+                Delta::Object { changed, removed }
+            }
+            (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+                let changed: BTreeMap<_, _> = old
+                    .iter()
+                    .zip(new)
+                    .enumerate()
+                    .filter_map(|(index, (old_value, new_value))| {
+                        let delta = Delta::between(old_value, new_value);
+                        (delta != Delta::Unchanged).then_some((index, delta))
+                    })
+                    .collect();
+                // mostly changed arrays (e.g. sensor data) are cheaper to transmit as a whole
+                if changed.len() * 2 > new.len() {
+                    return Delta::Replaced(Value::Array(new.clone()));
+                }
+                Delta::Array {
+                    length: new.len(),
+                    changed,
+                }
+            }
+            _ => Delta::Replaced(new.clone()),
+        }
+    }
+
+    pub fn apply(self, value: &mut Value) -> Result<(), Error> {
+        match self {
+            Delta::Unchanged => {}
+            Delta::Replaced(new_value) => *value = new_value,
+            Delta::Object { changed, removed } => {
+                let Value::Object(object) = value else {
+                    return Err(Error::ExpectedObject);
+                };
+                for key in removed {
+                    object.remove(&key);
+                }
+                for (key, delta) in changed {
+                    delta.apply(object.entry(key).or_insert(Value::Null))?;
+                }
+            }
+            Delta::Array { length, changed } => {
+                let Value::Array(array) = value else {
+                    return Err(Error::ExpectedArray { length });
+                };
+                if array.len() != length {
+                    return Err(Error::ExpectedArray { length });
+                }
+                for (index, delta) in changed {
+                    let element = array
+                        .get_mut(index)
+                        .ok_or(Error::IndexOutOfRange { index, length })?;
+                    delta.apply(element)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn applying_delta_reconstructs_new_value() {
+        let old = json!({
+            "ball": {"position": [1.0, 2.0], "age": 3},
+            "obstacles": [{"x": 1}, {"x": 2}, {"x": 3}],
+            "removed": true,
+        });
+        let new = json!({
+            "ball": {"position": [1.0, 2.5], "age": 3},
+            "obstacles": [{"x": 1}, {"x": 2}, {"x": 4}],
+            "added": "field",
+        });
+
+        let delta = Delta::between(&old, &new);
+        let mut reconstructed = old.clone();
+        delta.clone().apply(&mut reconstructed).unwrap();
+
+        assert_eq!(reconstructed, new);
+        let Delta::Object { changed, removed } = delta else {
+            panic!("expected object delta");
+        };
+        assert_eq!(removed, ["removed"]);
+        assert_eq!(
+            changed.keys().collect::<Vec<_>>(),
+            ["added", "ball", "obstacles"]
+        );
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        let mut value = json!([1, 2]);
+        let delta = Delta::Array {
+            length: 2,
+            changed: BTreeMap::from([(5, Delta::Replaced(json!(3)))]),
+        };
+
+        assert!(matches!(
+            delta.apply(&mut value),
+            Err(Error::IndexOutOfRange {
+                index: 5,
+                length: 2
+            })
+        ));
+        assert_eq!(value, json!([1, 2]));
+    }
+
+    #[test]
+    fn equal_values_are_unchanged() {
+        let value = json!({"a": [1, 2, 3]});
+        assert_eq!(Delta::between(&value, &value), Delta::Unchanged);
+    }
+}
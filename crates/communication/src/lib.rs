@@ -1,4 +1,6 @@
 pub mod client;
+pub mod delta;
 pub mod messages;
 #[cfg(feature = "server")]
 pub mod server;
+pub mod transport;
@@ -1,10 +1,18 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    net::SocketAddr,
+    num::NonZeroUsize,
+    time::{Duration, SystemTime},
+};
 
 use parameters::directory::Scope;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use serialize_hierarchy::FieldMetadata;
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 
+use crate::delta::Delta;
+
 pub type CyclerInstance = String;
 pub type Path = String;
 pub type Reason = String;
@@ -16,13 +24,97 @@ pub enum Request {
     Injections(InjectionsRequest),
     Outputs(OutputsRequest),
     Parameters(ParametersRequest),
+    Transport(TransportRequest),
+}
+
+impl Request {
+    /// Permission a client needs for this request, configuring the transport is always allowed
+    pub fn required_permission(&self) -> Option<Permission> {
+        match self {
+            // injections are not implemented and rejected regardless of permissions
+            Request::Injections(_) => None,
+            Request::Outputs(_) => Some(Permission::ReadOutputs),
+            Request::Parameters(
+                ParametersRequest::GetFields { .. }
+                | ParametersRequest::GetSchema { .. }
+                | ParametersRequest::GetCurrent { .. }
+                | ParametersRequest::Subscribe { .. }
+                | ParametersRequest::Unsubscribe { .. }
+                | ParametersRequest::UnsubscribeEverything
+                | ParametersRequest::GetHistory { .. }
+                | ParametersRequest::GetProfiles { .. },
+            ) => Some(Permission::ReadParameters),
+            Request::Parameters(
+                ParametersRequest::Update { .. }
+                | ParametersRequest::LoadFromDisk { .. }
+                | ParametersRequest::StoreToDisk { .. }
+                | ParametersRequest::Revert { .. }
+                | ParametersRequest::ActivateProfile { .. }
+                | ParametersRequest::DeactivateProfile { .. },
+            ) => Some(Permission::WriteParameters),
+            Request::Transport(_) => None,
+        }
+    }
+
+    pub fn id(&self) -> Option<usize> {
+        match self {
+            Request::Injections(
+                InjectionsRequest::Set { id, .. } | InjectionsRequest::Unset { id, .. },
+            )
+            | Request::Outputs(
+                OutputsRequest::GetFields { id }
+                | OutputsRequest::GetNext { id, .. }
+                | OutputsRequest::Subscribe { id, .. }
+                | OutputsRequest::Unsubscribe { id, .. },
+            )
+            | Request::Parameters(
+                ParametersRequest::GetFields { id }
+                | ParametersRequest::GetSchema { id }
+                | ParametersRequest::GetCurrent { id, .. }
+                | ParametersRequest::Subscribe { id, .. }
+                | ParametersRequest::Unsubscribe { id, .. }
+                | ParametersRequest::Update { id, .. }
+                | ParametersRequest::LoadFromDisk { id }
+                | ParametersRequest::StoreToDisk { id, .. }
+                | ParametersRequest::GetHistory { id }
+                | ParametersRequest::Revert { id, .. }
+                | ParametersRequest::GetProfiles { id }
+                | ParametersRequest::ActivateProfile { id, .. }
+                | ParametersRequest::DeactivateProfile { id, .. },
+            ) => Some(*id),
+            Request::Injections(InjectionsRequest::UnsetEverything)
+            | Request::Outputs(OutputsRequest::UnsubscribeEverything)
+            | Request::Parameters(ParametersRequest::UnsubscribeEverything)
+            | Request::Transport(_) => None,
+        }
+    }
+}
+
+/// Capabilities granted to a client during the WebSocket handshake
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Permission {
+    ReadOutputs,
+    ReadParameters,
+    WriteParameters,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Response {
     Textual(TextualResponse),
     Binary(BinaryResponse),
-    Close { code: CloseCode, reason: Reason },
+    Close {
+        code: CloseCode,
+        reason: Reason,
+    },
+    ConfigureTransport {
+        options: TransportOptions,
+    },
+    /// Announces an output unsubscription to the sender, which forgets the last delivered value
+    /// of the subscription once the unsubscription is acknowledged
+    UnsubscribingOutput {
+        id: usize,
+        subscription_id: usize,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -30,6 +122,15 @@ pub enum TextualResponse {
     Injections(InjectionsResponse),
     Outputs(TextualOutputsResponse),
     Parameters(ParametersResponse),
+    Transport(TransportResponse),
+    PermissionDenied {
+        id: Option<usize>,
+        permission: Permission,
+    },
+    /// Sent by transports which keep the connection open after receiving an unparsable request
+    InvalidRequest {
+        reason: Reason,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -81,6 +182,8 @@ pub enum OutputsRequest {
         cycler_instance: CyclerInstance,
         path: Path,
         format: Format,
+        #[serde(default)]
+        options: SubscriptionOptions,
     },
     Unsubscribe {
         id: usize,
@@ -89,6 +192,27 @@ pub enum OutputsRequest {
     UnsubscribeEverything,
 }
 
+/// Limits how often subscribed data is delivered, all given limits have to be satisfied
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SubscriptionOptions {
+    /// minimum time between two deliveries
+    pub minimum_interval: Option<Duration>,
+    /// only deliver every n-th cycle
+    pub every_nth_cycle: Option<NonZeroUsize>,
+    /// only deliver if the data changed since the last delivery
+    pub only_on_change: bool,
+}
+
+impl SubscriptionOptions {
+    pub fn with_maximum_rate(self, maximum_rate_in_hertz: f32) -> Self {
+        Self {
+            minimum_interval: (maximum_rate_in_hertz > 0.0)
+                .then(|| Duration::from_secs_f32(1.0 / maximum_rate_in_hertz)),
+            ..self
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum TextualOutputsResponse {
     GetFields {
@@ -115,9 +239,37 @@ pub enum TextualOutputsResponse {
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum TextualDataOrBinaryReference {
     TextualData { data: Value },
+    TextualDelta { delta: Delta },
     BinaryReference { reference_id: usize },
 }
 
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TransportRequest {
+    Configure { options: TransportOptions },
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TransportResponse {
+    Configure { options: TransportOptions },
+}
+
+/// Encoding of all responses following the acknowledgement of the transport configuration
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct TransportOptions {
+    pub compression: Compression,
+    /// send changes of subscribed textual outputs relative to their last delivery
+    pub delta_encoding: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Compression {
+    #[default]
+    None,
+    Zstd {
+        level: i32,
+    },
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum BinaryOutputsResponse {
     GetNext {
@@ -132,6 +284,7 @@ pub enum BinaryOutputsResponse {
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum ParametersRequest {
     GetFields { id: usize },
+    GetSchema { id: usize },
     GetCurrent { id: usize, path: Path },
     Subscribe { id: usize, path: Path },
     Unsubscribe { id: usize, subscription_id: usize },
@@ -139,6 +292,11 @@ pub enum ParametersRequest {
     Update { id: usize, path: Path, data: Value },
     LoadFromDisk { id: usize },
     StoreToDisk { id: usize, scope: Scope, path: Path },
+    GetHistory { id: usize },
+    Revert { id: usize, change_id: usize },
+    GetProfiles { id: usize },
+    ActivateProfile { id: usize, name: String },
+    DeactivateProfile { id: usize, name: String },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -147,6 +305,10 @@ pub enum ParametersResponse {
         id: usize,
         fields: BTreeSet<Path>,
     },
+    GetSchema {
+        id: usize,
+        schema: BTreeMap<Path, FieldMetadata>,
+    },
     GetCurrent {
         id: usize,
         result: Result<Value, Reason>,
@@ -175,6 +337,58 @@ pub enum ParametersResponse {
         id: usize,
         result: Result<(), Reason>,
     },
+    GetHistory {
+        id: usize,
+        changes: Vec<ParameterChange>,
+    },
+    Revert {
+        id: usize,
+        result: Result<(), Reason>,
+    },
+    Profiles {
+        id: usize,
+        result: Result<Profiles, Reason>,
+    },
+    /// sent to subscribed clients if changed parameter files could not be reloaded
+    ReloadFromDiskFailed {
+        reason: Reason,
+    },
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Profiles {
+    pub available: Vec<String>,
+    /// in activation order, later profiles override earlier ones
+    pub active: Vec<String>,
+    /// parameter files in the order they are merged, the first being `default.json`
+    pub merge_order: Vec<String>,
+}
+
+/// Journal entry of a parameter update
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ParameterChange {
+    pub change_id: usize,
+    pub timestamp: SystemTime,
+    pub peer_address: SocketAddr,
+    pub path: Path,
+    pub old_value: Value,
+    pub new_value: Value,
+    /// set if this change has been created by reverting another change
+    pub reverted_change_id: Option<usize>,
+    #[serde(default)]
+    pub operation: ChangeOperation,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ChangeOperation {
+    #[default]
+    Update,
+    /// Parameters have been replaced by the ones on disk, the values only contain the changed
+    /// branches below the path
+    LoadFromDisk,
+    /// Parameters have been written to disk without changing them, the values only contain the
+    /// branches below the path which differed from the parameters last loaded from disk
+    StoreToDisk { scope: Scope },
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
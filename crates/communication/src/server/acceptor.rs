@@ -1,20 +1,27 @@
 use std::{
     io,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use log::error;
 use tokio::{
     net::{TcpListener, ToSocketAddrs},
     select, spawn,
-    sync::mpsc::{unbounded_channel, Sender},
+    sync::{
+        broadcast,
+        mpsc::{unbounded_channel, Sender},
+    },
     task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::messages::ParametersRequest;
+use crate::messages::{ParametersRequest, Reason};
 
 use super::{
+    access_control::AccessControl,
     client_request::ClientRequest,
     connection::{connection, ConnectionError},
     outputs,
@@ -32,11 +39,14 @@ pub enum AcceptError {
 
 pub fn acceptor(
     addresses: impl ToSocketAddrs + Send + Sync + 'static,
+    access_control: AccessControl,
     keep_running: CancellationToken,
+    next_client_id: Arc<AtomicUsize>,
     outputs_sender: Sender<outputs::Request>,
     parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    reload_error_sender: broadcast::Sender<Reason>,
 ) -> JoinHandle<Result<(), AcceptError>> {
-    let next_client_id = AtomicUsize::default();
+    let access_control = Arc::new(access_control);
     spawn(async move {
         let (error_sender, mut error_receiver) = unbounded_channel();
 
@@ -53,10 +63,12 @@ pub fn acceptor(
             let client_id = next_client_id.fetch_add(1, Ordering::SeqCst);
             connection(
                 stream,
+                access_control.clone(),
                 keep_running.clone(),
                 error_sender.clone(),
                 outputs_sender.clone(),
                 parameters_sender.clone(),
+                reload_error_sender.subscribe(),
                 client_id,
             );
         }
@@ -0,0 +1,87 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+
+use crate::messages::Permission;
+
+/// Permissions of clients depending on the token they send during the WebSocket handshake
+/// (`Authorization: Bearer <token>`). The default grants every permission without a token.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AccessControl {
+    /// permissions of clients without token, clients are rejected if this is empty
+    pub anonymous: BTreeSet<Permission>,
+    pub tokens: HashMap<String, BTreeSet<Permission>>,
+}
+
+impl Default for AccessControl {
+    fn default() -> Self {
+        Self {
+            anonymous: all_permissions(),
+            tokens: HashMap::new(),
+        }
+    }
+}
+
+impl AccessControl {
+    /// Returns `None` if the client has to be rejected
+    pub fn permissions(&self, token: Option<&str>) -> Option<BTreeSet<Permission>> {
+        match token {
+            Some(token) => self.tokens.get(token).cloned(),
+            None => (!self.anonymous.is_empty()).then(|| self.anonymous.clone()),
+        }
+    }
+}
+
+pub fn all_permissions() -> BTreeSet<Permission> {
+    [
+        Permission::ReadOutputs,
+        Permission::ReadParameters,
+        Permission::WriteParameters,
+    ]
+    .into()
+}
+
+pub fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get("Authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_tokens_and_anonymous_clients_without_permissions_are_rejected() {
+        let access_control = AccessControl {
+            anonymous: BTreeSet::new(),
+            tokens: [("coach".to_string(), [Permission::ReadOutputs].into())].into(),
+        };
+
+        assert_eq!(
+            access_control.permissions(Some("coach")),
+            Some([Permission::ReadOutputs].into())
+        );
+        assert_eq!(access_control.permissions(Some("guess")), None);
+        assert_eq!(access_control.permissions(None), None);
+        assert!(AccessControl::default()
+            .permissions(None)
+            .unwrap()
+            .contains(&Permission::WriteParameters));
+    }
+
+    #[test]
+    fn bearer_token_is_extracted_from_authorization_header() {
+        let request = Request::builder()
+            .header("Authorization", "Bearer secret")
+            .body(())
+            .unwrap();
+
+        assert_eq!(bearer_token(&request), Some("secret"));
+        assert_eq!(bearer_token(&Request::default()), None);
+    }
+}
@@ -1,4 +1,7 @@
-use std::hash::{Hash, Hasher};
+use std::{
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+};
 
 use tokio::sync::mpsc::Sender;
 
@@ -8,6 +11,7 @@ use crate::messages::Response;
 pub struct Client {
     pub id: usize,
     pub response_sender: Sender<Response>,
+    pub peer_address: SocketAddr,
 }
 
 impl Hash for Client {
@@ -26,3 +30,14 @@ impl PartialEq for Client {
 }
 
 impl Eq for Client {}
+
+#[cfg(test)]
+impl Client {
+    pub fn for_test(id: usize, response_sender: Sender<Response>) -> Self {
+        Self {
+            id,
+            response_sender,
+            peer_address: ([127, 0, 0, 1], 1337).into(),
+        }
+    }
+}
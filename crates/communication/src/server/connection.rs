@@ -1,18 +1,33 @@
-use std::{io, net::SocketAddr};
+use std::{io, net::SocketAddr, sync::Arc};
 
 use futures_util::StreamExt;
 use log::error;
 use tokio::{
     net::TcpStream,
     select, spawn,
-    sync::mpsc::{channel, Sender, UnboundedSender},
+    sync::{
+        broadcast,
+        mpsc::{channel, Sender, UnboundedSender},
+    },
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::StatusCode,
+    },
 };
-use tokio_tungstenite::accept_async;
 use tokio_util::sync::CancellationToken;
 
-use crate::messages::ParametersRequest;
+use crate::messages::{ParametersRequest, Reason};
 
-use super::{client_request::ClientRequest, outputs, receiver::receiver, sender::sender};
+use super::{
+    access_control::{bearer_token, AccessControl},
+    client_request::ClientRequest,
+    outputs,
+    receiver::receiver,
+    sender::sender,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConnectionError {
@@ -40,18 +55,23 @@ pub enum ReceiverOrSenderError {
     JsonNotDeserialized(serde_json::Error),
     #[error("failed to serialize JSON")]
     JsonNotSerialized(serde_json::Error),
+    #[error("failed to compress message")]
+    NotCompressed(io::Error),
     #[error("failed to read WebSocket message")]
     WebSocketMessageNotRead(tokio_tungstenite::tungstenite::Error),
     #[error("failed to write WebSocket message")]
     WebSocketMessageNotWritten(tokio_tungstenite::tungstenite::Error),
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn connection(
     stream: TcpStream,
+    access_control: Arc<AccessControl>,
     keep_running: CancellationToken,
     connection_error_sender: UnboundedSender<ConnectionError>,
     outputs_sender: Sender<outputs::Request>,
     parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    reload_error_receiver: broadcast::Receiver<Reason>,
     client_id: usize,
 ) {
     spawn(async move {
@@ -65,8 +85,24 @@ pub fn connection(
             }
         };
 
+        let mut permissions = None;
+        // the callback signature is given by tungstenite
+        #[allow(clippy::result_large_err)]
+        let authenticate = |request: &Request, response: Response| match access_control
+            .permissions(bearer_token(request))
+        {
+            Some(granted_permissions) => {
+                permissions = Some(granted_permissions);
+                Ok(response)
+            }
+            None => {
+                let mut error_response = ErrorResponse::new(Some("unauthorized".to_string()));
+                *error_response.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(error_response)
+            }
+        };
         let websocket_stream = select! {
-            result = accept_async(stream) => match result {
+            result = accept_hdr_async(stream, authenticate) => match result {
                 Ok(websocket_stream) => websocket_stream,
                 Err(source) => {
                     connection_error_sender
@@ -78,6 +114,7 @@ pub fn connection(
             _ = keep_running.cancelled() => return,
         };
 
+        let permissions = permissions.expect("handshake should only succeed after authentication");
         let (writer, reader) = websocket_stream.split();
 
         let (receiver_or_sender_error_sender, mut receiver_or_sender_error_receiver) = channel(1);
@@ -90,9 +127,12 @@ pub fn connection(
             keep_running,
             keep_only_self_running.clone(),
             client_id,
+            peer_address,
+            permissions,
             response_sender,
             outputs_sender,
             parameters_sender,
+            reload_error_receiver,
         ));
 
         spawn(sender(
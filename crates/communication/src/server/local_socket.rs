@@ -0,0 +1,394 @@
+use std::{
+    fs::Permissions,
+    io,
+    net::SocketAddr,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use bincode::serialize;
+use log::{error, warn};
+use serde_json::{from_str, to_vec};
+use tokio::{
+    fs::{metadata, remove_file, set_permissions},
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{unix::OwnedWriteHalf, UnixListener, UnixStream},
+    select, spawn,
+    sync::{
+        broadcast,
+        mpsc::{channel, Receiver, Sender},
+    },
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    messages::{ParametersRequest, Reason, Request, Response, TextualResponse, TransportResponse},
+    transport::{length_delimited, FrameKind},
+};
+
+use super::{
+    access_control::all_permissions,
+    client::Client,
+    client_request::ClientRequest,
+    outputs,
+    receiver::{dispatch, forward_reload_errors, unsubscribe_everything},
+};
+
+/// Local clients have no network address, they are identified by their client ID
+const LOCAL_PEER_ADDRESS: ([u8; 4], u16) = ([127, 0, 0, 1], 0);
+
+#[derive(Debug, thiserror::Error)]
+pub enum LocalAcceptError {
+    #[error("socket is in use by another process")]
+    SocketInUse,
+    #[error("failed to remove stale socket file")]
+    StaleSocketNotRemoved(io::Error),
+    #[error("failed to bind Unix listener")]
+    UnixListenerNotBound(io::Error),
+    #[error("failed to restrict access to socket file")]
+    SocketNotRestricted(io::Error),
+    #[error("failed to accept")]
+    NotAccepted(io::Error),
+}
+
+/// Accepts processes on the same machine at a Unix domain socket, an alternative to the WebSocket
+/// endpoint without framing and handshake overhead.
+///
+/// Clients write one JSON `Request` per line. Responses are written as length delimited frames
+/// (see `transport::length_delimited`) with JSON `TextualResponse`s or bincode `BinaryResponse`s.
+/// Compression and delta encoding are not supported. Only processes of the user owning the socket
+/// file are accepted, they are granted every permission.
+pub fn local_acceptor(
+    path: PathBuf,
+    keep_running: CancellationToken,
+    next_client_id: Arc<AtomicUsize>,
+    outputs_sender: Sender<outputs::Request>,
+    parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    reload_error_sender: broadcast::Sender<Reason>,
+) -> JoinHandle<Result<(), LocalAcceptError>> {
+    spawn(async move {
+        if UnixStream::connect(&path).await.is_ok() {
+            return Err(LocalAcceptError::SocketInUse);
+        }
+        match remove_file(&path).await {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(LocalAcceptError::StaleSocketNotRemoved(error))
+            }
+            _ => {}
+        }
+        let listener = UnixListener::bind(&path).map_err(LocalAcceptError::UnixListenerNotBound)?;
+        set_permissions(&path, Permissions::from_mode(0o600))
+            .await
+            .map_err(LocalAcceptError::SocketNotRestricted)?;
+        // clients may have connected before the permissions were restricted
+        let owner = metadata(&path)
+            .await
+            .map_err(LocalAcceptError::SocketNotRestricted)?
+            .uid();
+
+        loop {
+            let (stream, _) = select! {
+                result = listener.accept() => result.map_err(LocalAcceptError::NotAccepted)?,
+                _ = keep_running.cancelled() => break,
+            };
+            if !stream
+                .peer_cred()
+                .is_ok_and(|credentials| credentials.uid() == owner)
+            {
+                warn!("rejected local client of another user");
+                continue;
+            }
+
+            let client_id = next_client_id.fetch_add(1, Ordering::SeqCst);
+            spawn(local_connection(
+                stream,
+                keep_running.clone(),
+                client_id,
+                outputs_sender.clone(),
+                parameters_sender.clone(),
+                reload_error_sender.subscribe(),
+            ));
+        }
+
+        if let Err(error) = remove_file(&path).await {
+            error!("failed to remove socket file {}: {error}", path.display());
+        }
+        Ok(())
+    })
+}
+
+async fn local_connection(
+    stream: UnixStream,
+    keep_running: CancellationToken,
+    client_id: usize,
+    outputs_sender: Sender<outputs::Request>,
+    parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    mut reload_error_receiver: broadcast::Receiver<Reason>,
+) {
+    let (reader, writer) = stream.into_split();
+    let keep_only_self_running = CancellationToken::new();
+    let (response_sender, response_receiver) = channel(1);
+    spawn(local_sender(
+        writer,
+        keep_only_self_running.clone(),
+        response_receiver,
+    ));
+
+    let client = Client {
+        id: client_id,
+        response_sender,
+        peer_address: SocketAddr::from(LOCAL_PEER_ADDRESS),
+    };
+    let permissions = all_permissions();
+    let mut lines = BufReader::new(reader).lines();
+    select! {
+        _ = async {
+            loop {
+                let line = select! {
+                    line = lines.next_line() => line,
+                    _ = keep_running.cancelled() => break,
+                    _ = keep_only_self_running.cancelled() => break,
+                };
+                let line = match line {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break,
+                    Err(error) => {
+                        error!("failed to read from local client {client_id}: {error}");
+                        break;
+                    }
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let request: Request = match from_str(&line) {
+                    Ok(request) => request,
+                    Err(error) => {
+                        client
+                            .response_sender
+                            .send(Response::Textual(TextualResponse::InvalidRequest {
+                                reason: format!("failed to parse request: {error}"),
+                            }))
+                            .await
+                            .expect("receiver should always wait for all senders");
+                        continue;
+                    }
+                };
+                dispatch(
+                    request,
+                    client.clone(),
+                    &permissions,
+                    &outputs_sender,
+                    &parameters_sender,
+                )
+                .await;
+            }
+        } => {},
+        _ = forward_reload_errors(&mut reload_error_receiver, &client.response_sender) => {},
+    }
+
+    unsubscribe_everything(client, &outputs_sender, &parameters_sender).await;
+}
+
+async fn local_sender(
+    mut writer: OwnedWriteHalf,
+    keep_only_self_running: CancellationToken,
+    mut response_receiver: Receiver<Response>,
+) {
+    // responses have to be received until every sender is dropped, even if the client is gone
+    while let Some(response) = response_receiver.recv().await {
+        if keep_only_self_running.is_cancelled() {
+            continue;
+        }
+        let frame = match response {
+            Response::Textual(textual) => to_vec(&textual)
+                .map(|payload| length_delimited(FrameKind::Textual, &payload))
+                .map_err(|error| error.to_string()),
+            Response::Binary(binary) => serialize(&binary)
+                .map(|payload| length_delimited(FrameKind::Binary, &payload))
+                .map_err(|error| error.to_string()),
+            Response::Close { .. } => {
+                keep_only_self_running.cancel();
+                continue;
+            }
+            Response::UnsubscribingOutput { .. } => continue,
+            Response::ConfigureTransport { .. } => {
+                // the local socket always uses the default options
+                let acknowledgement = TextualResponse::Transport(TransportResponse::Configure {
+                    options: Default::default(),
+                });
+                Ok(length_delimited(
+                    FrameKind::Textual,
+                    &to_vec(&acknowledgement).expect("transport response should be serializable"),
+                ))
+            }
+        };
+        let result = match frame {
+            Ok(frame) => writer
+                .write_all(&frame)
+                .await
+                .map_err(|error| error.to_string()),
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            error!("failed to send response to local client: {error}");
+            keep_only_self_running.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use crate::{
+        messages::{InjectionsRequest, InjectionsResponse, OutputsRequest, TextualOutputsResponse},
+        transport::read_length_delimited,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_are_dispatched_and_responses_are_framed() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("communication.sock");
+        let keep_running = CancellationToken::new();
+        let (outputs_sender, mut outputs_receiver) = channel(1);
+        let (parameters_sender, _parameters_receiver) = channel(1);
+        let acceptor_task = local_acceptor(
+            path.clone(),
+            keep_running.clone(),
+            Default::default(),
+            outputs_sender,
+            parameters_sender,
+            broadcast::channel(1).0,
+        );
+
+        let mut stream = connect(&path).await;
+        stream
+            .write_all(b"{\"Outputs\":{\"GetFields\":{\"id\":42}}}\n")
+            .await
+            .unwrap();
+
+        let Some(outputs::Request::ClientRequest(ClientRequest { request, client })) =
+            outputs_receiver.recv().await
+        else {
+            panic!("expected client request");
+        };
+        assert_eq!(request, OutputsRequest::GetFields { id: 42 });
+        let response = TextualResponse::Outputs(TextualOutputsResponse::GetFields {
+            id: 42,
+            fields: Default::default(),
+        });
+        client
+            .response_sender
+            .send(Response::Textual(response.clone()))
+            .await
+            .unwrap();
+
+        let (kind, payload) = read_length_delimited(&mut stream).await.unwrap().unwrap();
+        assert_eq!(kind, FrameKind::Textual);
+        assert_eq!(payload, to_vec(&response).unwrap());
+
+        keep_running.cancel();
+        acceptor_task.await.unwrap().unwrap();
+        assert!(!path.exists());
+    }
+
+    async fn connect(path: &std::path::Path) -> UnixStream {
+        loop {
+            if let Ok(stream) = UnixStream::connect(path).await {
+                break stream;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    async fn read_textual_response(stream: &mut UnixStream) -> TextualResponse {
+        let (kind, payload) = read_length_delimited(stream).await.unwrap().unwrap();
+        assert_eq!(kind, FrameKind::Textual);
+        serde_json::from_slice(&payload).unwrap()
+    }
+
+    #[tokio::test]
+    async fn invalid_and_unsupported_requests_are_answered_with_errors() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("communication.sock");
+        let keep_running = CancellationToken::new();
+        let acceptor_task = local_acceptor(
+            path.clone(),
+            keep_running.clone(),
+            Default::default(),
+            channel(1).0,
+            channel(1).0,
+            broadcast::channel(1).0,
+        );
+        let mut stream = connect(&path).await;
+
+        stream.write_all(b"{\"Outputs\":\n").await.unwrap();
+        assert!(matches!(
+            read_textual_response(&mut stream).await,
+            TextualResponse::InvalidRequest { .. }
+        ));
+        let mut request = to_vec(&Request::Injections(InjectionsRequest::Unset {
+            id: 42,
+            cycler_instance: "Control".to_string(),
+            path: "a".to_string(),
+        }))
+        .unwrap();
+        request.push(b'\n');
+        stream.write_all(&request).await.unwrap();
+        assert_eq!(
+            read_textual_response(&mut stream).await,
+            TextualResponse::Injections(InjectionsResponse::Unset {
+                id: 42,
+                result: Err("injections are not implemented".to_string()),
+            })
+        );
+
+        keep_running.cancel();
+        acceptor_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn sockets_in_use_are_not_taken_over() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("communication.sock");
+        let keep_running = CancellationToken::new();
+        let acceptor_task = local_acceptor(
+            path.clone(),
+            keep_running.clone(),
+            Default::default(),
+            channel(1).0,
+            channel(1).0,
+            broadcast::channel(1).0,
+        );
+        drop(connect(&path).await);
+
+        let second_acceptor_result = local_acceptor(
+            path.clone(),
+            keep_running.clone(),
+            Default::default(),
+            channel(1).0,
+            channel(1).0,
+            broadcast::channel(1).0,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            second_acceptor_result,
+            Err(LocalAcceptError::SocketInUse)
+        ));
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        keep_running.cancel();
+        acceptor_task.await.unwrap().unwrap();
+    }
+}
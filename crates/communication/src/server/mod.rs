@@ -1,11 +1,14 @@
 mod acceptor;
+pub mod access_control;
 mod client;
 mod client_request;
 mod connection;
+mod local_socket;
 mod outputs;
 pub mod parameters; // TODO: revert to private visibility after behavior simulator is refactored to not access private functionality anymore
 mod receiver;
 mod runtime;
 mod sender;
 
+pub use access_control::AccessControl;
 pub use runtime::Runtime;
@@ -1,8 +1,12 @@
-use std::collections::BTreeSet;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet},
+    hash::{Hash, Hasher},
+    time::Instant,
+};
 
 use tokio::sync::mpsc::Sender;
 
-use crate::messages::{Format, OutputsRequest, Path};
+use crate::messages::{Format, OutputsRequest, Path, SubscriptionOptions};
 
 use super::client_request::ClientRequest;
 
@@ -24,4 +28,118 @@ struct Subscription {
     pub path: Path,
     pub format: Format,
     pub once: bool,
+    pub options: SubscriptionOptions,
+    cycles_since_last_delivery: usize,
+    last_delivery: Option<Instant>,
+    last_data_hash: Option<u64>,
+}
+
+impl Subscription {
+    fn new(path: Path, format: Format, options: SubscriptionOptions, once: bool) -> Self {
+        Self {
+            path,
+            format,
+            once,
+            options,
+            cycles_since_last_delivery: 0,
+            last_delivery: None,
+            last_data_hash: None,
+        }
+    }
+
+    /// Counts the current cycle and returns whether the subscription should be delivered
+    fn is_due(&mut self, now: Instant) -> bool {
+        self.cycles_since_last_delivery += 1;
+        let Some(last_delivery) = self.last_delivery else {
+            return true;
+        };
+        let every_nth_cycle_reached = self
+            .options
+            .every_nth_cycle
+            .is_none_or(|every_nth_cycle| self.cycles_since_last_delivery >= every_nth_cycle.get());
+        let minimum_interval_elapsed = self
+            .options
+            .minimum_interval
+            .is_none_or(|minimum_interval| now.duration_since(last_delivery) >= minimum_interval);
+        every_nth_cycle_reached && minimum_interval_elapsed
+    }
+
+    /// Returns whether the data differs from the last delivered data
+    fn has_changed(&mut self, data: impl Hash) -> bool {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let data_hash = hasher.finish();
+        if self.last_data_hash == Some(data_hash) {
+            return false;
+        }
+        self.last_data_hash = Some(data_hash);
+        true
+    }
+
+    fn mark_delivered(&mut self, now: Instant) {
+        self.cycles_since_last_delivery = 0;
+        self.last_delivery = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn every_nth_cycle_is_delivered() {
+        let mut subscription = Subscription::new(
+            "a.b.c".to_string(),
+            Format::Textual,
+            SubscriptionOptions {
+                every_nth_cycle: NonZeroUsize::new(3),
+                ..Default::default()
+            },
+            false,
+        );
+        let now = Instant::now();
+        let deliveries: Vec<_> = (0..7)
+            .map(|_| {
+                let is_due = subscription.is_due(now);
+                if is_due {
+                    subscription.mark_delivered(now);
+                }
+                is_due
+            })
+            .collect();
+        assert_eq!(deliveries, [true, false, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn minimum_interval_is_respected() {
+        let mut subscription = Subscription::new(
+            "a.b.c".to_string(),
+            Format::Binary,
+            SubscriptionOptions::default().with_maximum_rate(10.0),
+            false,
+        );
+        let start = Instant::now();
+        assert!(subscription.is_due(start));
+        subscription.mark_delivered(start);
+        assert!(!subscription.is_due(start + Duration::from_millis(50)));
+        assert!(subscription.is_due(start + Duration::from_millis(150)));
+    }
+
+    #[test]
+    fn unchanged_data_is_detected() {
+        let mut subscription = Subscription::new(
+            "a.b.c".to_string(),
+            Format::Textual,
+            SubscriptionOptions {
+                only_on_change: true,
+                ..Default::default()
+            },
+            false,
+        );
+        assert!(subscription.has_changed(42));
+        assert!(!subscription.has_changed(42));
+        assert!(subscription.has_changed(1337));
+    }
 }
@@ -2,6 +2,7 @@ use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     num::Wrapping,
     sync::Arc,
+    time::Instant,
 };
 
 use bincode::{DefaultOptions, Options};
@@ -21,7 +22,7 @@ use tokio::{
 use crate::{
     messages::{
         BinaryOutputsResponse, BinaryResponse, Format, OutputsRequest, Response,
-        TextualDataOrBinaryReference, TextualOutputsResponse, TextualResponse,
+        SubscriptionOptions, TextualDataOrBinaryReference, TextualOutputsResponse, TextualResponse,
     },
     server::{client::Client, client_request::ClientRequest},
 };
@@ -105,71 +106,39 @@ where
             cycler_instance: received_cycler_instance,
             path,
             format,
+        } => {
+            handle_subscribe_request::<Outputs>(
+                request.client,
+                id,
+                cycler_instance,
+                received_cycler_instance,
+                path,
+                format,
+                SubscriptionOptions::default(),
+                is_get_next,
+                subscriptions,
+            )
+            .await
         }
-        | OutputsRequest::Subscribe {
+        OutputsRequest::Subscribe {
             id,
             cycler_instance: received_cycler_instance,
             path,
             format,
+            options,
         } => {
-            assert_eq!(cycler_instance, received_cycler_instance);
-            if Outputs::exists(&path) {
-                match subscriptions.entry((request.client.clone(), id)) {
-                    Entry::Occupied(_) => {
-                        let error_message = format!("already subscribed with id {id}");
-                        request
-                            .client
-                            .response_sender
-                            .send(Response::Textual(TextualResponse::Outputs(
-                                if is_get_next {
-                                    TextualOutputsResponse::GetNext {
-                                        id,
-                                        result: Err(error_message),
-                                    }
-                                } else {
-                                    TextualOutputsResponse::Subscribe {
-                                        id,
-                                        result: Err(error_message),
-                                    }
-                                },
-                            )))
-                            .await
-                            .expect("receiver should always wait for all senders");
-                        SubscriptionsState::Unchanged
-                    }
-                    Entry::Vacant(entry) => {
-                        entry.insert(Subscription {
-                            path,
-                            format,
-                            once: is_get_next,
-                        });
-                        if !is_get_next {
-                            request
-                                .client
-                                .response_sender
-                                .send(Response::Textual(TextualResponse::Outputs(
-                                    TextualOutputsResponse::Subscribe { id, result: Ok(()) },
-                                )))
-                                .await
-                                .expect("receiver should always wait for all senders");
-                        }
-                        SubscriptionsState::Changed
-                    }
-                }
-            } else {
-                request
-                    .client
-                    .response_sender
-                    .send(Response::Textual(TextualResponse::Outputs(
-                        TextualOutputsResponse::Subscribe {
-                            id,
-                            result: Err(format!("path {path:?} does not exist")),
-                        },
-                    )))
-                    .await
-                    .expect("receiver should always wait for all senders");
-                SubscriptionsState::Unchanged
-            }
+            handle_subscribe_request::<Outputs>(
+                request.client,
+                id,
+                cycler_instance,
+                received_cycler_instance,
+                path,
+                format,
+                options,
+                is_get_next,
+                subscriptions,
+            )
+            .await
         }
         OutputsRequest::Unsubscribe {
             id,
@@ -218,6 +187,74 @@ where
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+async fn handle_subscribe_request<Outputs>(
+    client: Client,
+    id: usize,
+    cycler_instance: &'static str,
+    received_cycler_instance: String,
+    path: String,
+    format: Format,
+    options: SubscriptionOptions,
+    is_get_next: bool,
+    subscriptions: &mut HashMap<(Client, usize), Subscription>,
+) -> SubscriptionsState
+where
+    Outputs: SerializeHierarchy,
+{
+    assert_eq!(cycler_instance, received_cycler_instance);
+    if Outputs::exists(&path) {
+        match subscriptions.entry((client.clone(), id)) {
+            Entry::Occupied(_) => {
+                let error_message = format!("already subscribed with id {id}");
+                client
+                    .response_sender
+                    .send(Response::Textual(TextualResponse::Outputs(
+                        if is_get_next {
+                            TextualOutputsResponse::GetNext {
+                                id,
+                                result: Err(error_message),
+                            }
+                        } else {
+                            TextualOutputsResponse::Subscribe {
+                                id,
+                                result: Err(error_message),
+                            }
+                        },
+                    )))
+                    .await
+                    .expect("receiver should always wait for all senders");
+                SubscriptionsState::Unchanged
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Subscription::new(path, format, options, is_get_next));
+                if !is_get_next {
+                    client
+                        .response_sender
+                        .send(Response::Textual(TextualResponse::Outputs(
+                            TextualOutputsResponse::Subscribe { id, result: Ok(()) },
+                        )))
+                        .await
+                        .expect("receiver should always wait for all senders");
+                }
+                SubscriptionsState::Changed
+            }
+        }
+    } else {
+        client
+            .response_sender
+            .send(Response::Textual(TextualResponse::Outputs(
+                TextualOutputsResponse::Subscribe {
+                    id,
+                    result: Err(format!("path {path:?} does not exist")),
+                },
+            )))
+            .await
+            .expect("receiver should always wait for all senders");
+        SubscriptionsState::Unchanged
+    }
+}
+
 fn write_subscribed_outputs_from_subscriptions(
     subscriptions: &mut HashMap<(Client, usize), Subscription>,
     subscribed_outputs_writer: &Writer<HashSet<String>>,
@@ -245,7 +282,11 @@ async fn handle_notified_output(
     let mut subscriptions_state = SubscriptionsState::Unchanged;
     {
         let output = outputs_reader.next();
+        let now = Instant::now();
         subscriptions.retain(|(client, subscription_id), subscription| {
+            if !subscription.once && !subscription.is_due(now) {
+                return true;
+            }
             let data = match subscription.format {
                 Format::Textual => {
                     let data = match output
@@ -257,6 +298,11 @@ async fn handle_notified_output(
                             return true;
                         }
                     };
+                    if subscription.options.only_on_change
+                        && !subscription.has_changed(data.to_string())
+                    {
+                        return true;
+                    }
                     TextualDataOrBinaryReference::TextualData { data }
                 }
                 Format::Binary => {
@@ -269,6 +315,9 @@ async fn handle_notified_output(
                         error!("failed to serialize {:?}: {error:?}", subscription.path);
                         return true;
                     }
+                    if subscription.options.only_on_change && !subscription.has_changed(&data) {
+                        return true;
+                    }
                     let reference_id = next_binary_reference_id.0;
                     *next_binary_reference_id += 1;
                     if subscription.once {
@@ -285,6 +334,7 @@ async fn handle_notified_output(
                     TextualDataOrBinaryReference::BinaryReference { reference_id }
                 }
             };
+            subscription.mark_delivered(now);
             if subscription.once {
                 textual_get_next_items.insert((client.clone(), *subscription_id), data);
                 subscriptions_state = SubscriptionsState::Changed;
@@ -364,7 +414,7 @@ mod tests {
     use serialize_hierarchy::Error;
     use tokio::{sync::mpsc::error::TryRecvError, task::yield_now, time::timeout};
 
-    use crate::messages::Format;
+    use crate::messages::{Format, SubscriptionOptions};
 
     use super::*;
 
@@ -539,11 +589,9 @@ mod tests {
                     cycler_instance: cycler_instance.clone(),
                     path: path.clone(),
                     format,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -576,11 +624,9 @@ mod tests {
                     cycler_instance,
                     path: path.clone(),
                     format,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -640,11 +686,9 @@ mod tests {
                     cycler_instance: cycler_instance.clone(),
                     path: path.clone(),
                     format,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -677,11 +721,9 @@ mod tests {
                     cycler_instance,
                     path: path.clone(),
                     format,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: 7331,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(7331, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -741,11 +783,9 @@ mod tests {
                     cycler_instance: cycler_instance.clone(),
                     path: path.clone(),
                     format,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -778,11 +818,9 @@ mod tests {
                     cycler_instance,
                     path: path.clone(),
                     format,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -836,10 +874,7 @@ mod tests {
                     id: 42,
                     subscription_id: 1337,
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -895,11 +930,9 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -931,10 +964,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -963,10 +993,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1019,11 +1046,9 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1052,10 +1077,7 @@ mod tests {
         request_sender
             .send(ClientRequest {
                 request: OutputsRequest::UnsubscribeEverything,
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1075,10 +1097,7 @@ mod tests {
                     id: 42,
                     subscription_id: 1337,
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1135,11 +1154,9 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1194,10 +1211,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1262,11 +1276,9 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Binary,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1330,10 +1342,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1397,11 +1406,9 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender0.clone(),
-                },
+                client: Client::for_test(client_id, response_sender0.clone()),
             })
             .await
             .unwrap();
@@ -1435,11 +1442,9 @@ mod tests {
                     cycler_instance: cycler_instance.to_string(),
                     path: path.clone(),
                     format: Format::Textual,
+                    options: SubscriptionOptions::default(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender1.clone(),
-                },
+                client: Client::for_test(client_id, response_sender1.clone()),
             })
             .await
             .unwrap();
@@ -1513,10 +1518,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender0.clone(),
-                },
+                client: Client::for_test(client_id, response_sender0.clone()),
             })
             .await
             .unwrap();
@@ -1548,10 +1550,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender1.clone(),
-                },
+                client: Client::for_test(client_id, response_sender1.clone()),
             })
             .await
             .unwrap();
@@ -1620,10 +1619,7 @@ mod tests {
                     path: path.clone(),
                     format: Format::Textual,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1698,10 +1694,7 @@ mod tests {
                     path: path.clone(),
                     format: Format::Binary,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -175,7 +175,7 @@ async fn handle_request(
 mod tests {
     use tokio::sync::mpsc::{channel, error::TryRecvError};
 
-    use crate::messages::Format;
+    use crate::messages::{Format, SubscriptionOptions};
 
     use super::*;
 
@@ -209,10 +209,7 @@ mod tests {
         request_sender
             .send(Request::ClientRequest(ClientRequest {
                 request: OutputsRequest::GetFields { id: 42 },
-                client: Client {
-                    id: 1337,
-                    response_sender,
-                },
+                client: Client::for_test(1337, response_sender),
             }))
             .await
             .unwrap();
@@ -249,10 +246,7 @@ mod tests {
                     path: "a.b.c".to_string(),
                     format: Format::Textual,
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender,
-                },
+                client: Client::for_test(1337, response_sender),
             }))
             .await
             .unwrap();
@@ -300,10 +294,7 @@ mod tests {
                 path: "a.b.c".to_string(),
                 format: Format::Textual,
             },
-            client: Client {
-                id: 1337,
-                response_sender,
-            },
+            client: Client::for_test(1337, response_sender),
         };
         request_sender
             .send(Request::ClientRequest(sent_client_request.clone()))
@@ -333,16 +324,14 @@ mod tests {
             .unwrap();
 
         let (response_sender, _response_receiver) = channel(1);
-        let client = Client {
-            id: 1337,
-            response_sender,
-        };
+        let client = Client::for_test(1337, response_sender);
         let sent_client_request = ClientRequest {
             request: OutputsRequest::Subscribe {
                 id: 42,
                 cycler_instance: "CyclerInstance".to_string(),
                 path: "a.b.c".to_string(),
                 format: Format::Textual,
+                options: SubscriptionOptions::default(),
             },
             client: client.clone(),
         };
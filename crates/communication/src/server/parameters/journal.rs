@@ -0,0 +1,102 @@
+use std::{collections::VecDeque, net::SocketAddr, path::PathBuf, time::SystemTime};
+
+use log::error;
+use parameters::directory::append_to_change_log;
+use serde_json::Value;
+
+use crate::messages::{ChangeOperation, ParameterChange, Path};
+
+const MAXIMUM_NUMBER_OF_CHANGES: usize = 1000;
+
+/// History of parameter updates, kept in memory for clients and appended to the change log on
+/// disk to reconstruct which values were live after a game
+pub struct Journal {
+    changes: VecDeque<ParameterChange>,
+    next_change_id: usize,
+    parameters_directory: PathBuf,
+}
+
+impl Journal {
+    pub fn new(parameters_directory: PathBuf) -> Self {
+        Self {
+            changes: VecDeque::new(),
+            next_change_id: 0,
+            parameters_directory,
+        }
+    }
+
+    pub async fn record(
+        &mut self,
+        peer_address: SocketAddr,
+        path: Path,
+        old_value: Value,
+        new_value: Value,
+        reverted_change_id: Option<usize>,
+        operation: ChangeOperation,
+    ) {
+        let change = ParameterChange {
+            change_id: self.next_change_id,
+            timestamp: SystemTime::now(),
+            peer_address,
+            path,
+            old_value,
+            new_value,
+            reverted_change_id,
+            operation,
+        };
+        self.next_change_id += 1;
+
+        if let Err(error) = append_to_change_log(&self.parameters_directory, &change).await {
+            error!("failed to append parameter change to change log: {error:?}");
+        }
+
+        if self.changes.len() >= MAXIMUM_NUMBER_OF_CHANGES {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(change);
+    }
+
+    pub fn changes(&self) -> Vec<ParameterChange> {
+        self.changes.iter().cloned().collect()
+    }
+
+    pub fn find(&self, change_id: usize) -> Option<&ParameterChange> {
+        self.changes
+            .iter()
+            .find(|change| change.change_id == change_id)
+    }
+
+    /// Returns the first later change of the live value at the path of the given change
+    pub fn find_superseding(&self, change: &ParameterChange) -> Option<&ParameterChange> {
+        self.changes.iter().find(|later_change| {
+            later_change.change_id > change.change_id
+                && !matches!(later_change.operation, ChangeOperation::StoreToDisk { .. })
+                && paths_overlap(&later_change.path, &change.path)
+        })
+    }
+}
+
+fn paths_overlap(path: &str, other_path: &str) -> bool {
+    let is_below = |path: &str, prefix: &str| {
+        prefix.is_empty()
+            || path
+                .strip_prefix(prefix)
+                .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with('.'))
+    };
+    is_below(path, other_path) || is_below(other_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_overlap_along_whole_segments() {
+        assert!(paths_overlap("a.b", "a.b"));
+        assert!(paths_overlap("a.b.c", "a.b"));
+        assert!(paths_overlap("a.b", "a.b.c"));
+        assert!(paths_overlap("", "a.b"));
+        assert!(!paths_overlap("a.bc", "a.b"));
+        assert!(!paths_overlap("a.c", "a.b"));
+    }
+}
@@ -5,8 +5,10 @@ use crate::messages::Path;
 
 use super::client::Client;
 
+mod journal;
 pub mod storage;
 pub mod subscriptions;
+pub mod watcher;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum StorageRequest {
@@ -20,10 +22,35 @@ pub enum StorageRequest {
         client: Client,
         id: usize,
     },
+    /// sent by the watcher after parameter files have changed
+    ReloadFromDisk,
     StoreToDisk {
         client: Client,
         id: usize,
         scope: Scope,
         path: Path,
     },
+    GetHistory {
+        client: Client,
+        id: usize,
+    },
+    Revert {
+        client: Client,
+        id: usize,
+        change_id: usize,
+    },
+    GetProfiles {
+        client: Client,
+        id: usize,
+    },
+    ActivateProfile {
+        client: Client,
+        id: usize,
+        name: String,
+    },
+    DeactivateProfile {
+        client: Client,
+        id: usize,
+        name: String,
+    },
 }
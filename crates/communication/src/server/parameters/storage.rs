@@ -1,26 +1,46 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    mem::replace,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use framework::Writer;
-use parameters::directory::{deserialize, serialize};
+use log::error;
+use parameters::{
+    directory::{deserialize_with_profiles, list_profiles, merge_order, serialize_with_profiles},
+    json::{merge_json, prune_equal_branches},
+};
 use serde::{de::DeserializeOwned, Serialize};
-use serialize_hierarchy::SerializeHierarchy;
+use serde_json::{from_value, to_value, Value};
+use serialize_hierarchy::{validate, FieldMetadata, SerializeHierarchy};
 use tokio::{
     spawn,
-    sync::{mpsc::Receiver, Notify},
+    sync::{broadcast, mpsc::Receiver, Notify},
     task::JoinHandle,
 };
 
 use crate::{
-    messages::{ParametersResponse, Response, TextualResponse},
+    messages::{ChangeOperation, ParametersResponse, Profiles, Reason, Response, TextualResponse},
     server::client::Client,
 };
 
-use super::StorageRequest;
+use super::{journal::Journal, StorageRequest};
+
+struct Directory {
+    path: PathBuf,
+    body_id: String,
+    head_id: String,
+    active_profiles: Vec<String>,
+    /// parameters as merged from disk, used to tell apart values changed at runtime
+    loaded_parameters: Value,
+}
 
 pub fn storage<Parameters>(
     parameters_writer: Writer<Parameters>,
     parameters_changed: Arc<Notify>,
     mut request_receiver: Receiver<StorageRequest>,
+    reload_error_sender: broadcast::Sender<Reason>,
     parameters_directory: impl AsRef<Path> + Send + Sync + 'static,
     body_id: String,
     head_id: String,
@@ -30,29 +50,42 @@ where
 {
     spawn(async move {
         let mut parameters = (*parameters_writer.next()).clone();
+        let metadata = Parameters::get_metadata();
+        let mut journal = Journal::new(parameters_directory.as_ref().to_path_buf());
+        let mut directory = Directory {
+            path: parameters_directory.as_ref().to_path_buf(),
+            body_id,
+            head_id,
+            active_profiles: vec![],
+            loaded_parameters: to_value(&parameters)
+                .expect("parameters should always be serializable"),
+        };
         while let Some(request) = request_receiver.recv().await {
             handle_request(
                 request,
                 &mut parameters,
+                &metadata,
+                &mut journal,
+                &mut directory,
                 &parameters_writer,
                 &parameters_changed,
-                &parameters_directory,
-                &body_id,
-                &head_id,
+                &reload_error_sender,
             )
             .await;
         }
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_request<Parameters>(
     request: StorageRequest,
     parameters: &mut Parameters,
+    metadata: &BTreeMap<String, FieldMetadata>,
+    journal: &mut Journal,
+    directory: &mut Directory,
     parameters_writer: &Writer<Parameters>,
     parameters_changed: &Arc<Notify>,
-    parameters_directory: impl AsRef<Path>,
-    body_id: &str,
-    head_id: &str,
+    reload_error_sender: &broadcast::Sender<Reason>,
 ) where
     Parameters: Clone + DeserializeOwned + Serialize + SerializeHierarchy,
 {
@@ -63,40 +96,36 @@ async fn handle_request<Parameters>(
             path,
             data,
         } => {
-            if !Parameters::exists(&path) {
-                respond(
-                    client,
-                    ParametersResponse::Update {
-                        id,
-                        result: Err(format!("path {path:?} does not exist")),
-                    },
-                )
-                .await;
-                return;
-            }
-
-            if let Err(error) = parameters.deserialize_path(&path, data) {
-                respond(
-                    client,
-                    ParametersResponse::Update {
-                        id,
-                        result: Err(format!("failed to deserialize: {error:?}")),
-                    },
-                )
-                .await;
-                return;
-            }
-
-            {
-                let mut slot = parameters_writer.next();
-                *slot = parameters.clone();
-            }
-            parameters_changed.notify_one();
-
-            respond(client, ParametersResponse::Update { id, result: Ok(()) }).await;
+            let result = match update(parameters, metadata, &path, data.clone()) {
+                Ok(old_value) => {
+                    publish(parameters, parameters_writer, parameters_changed);
+                    journal
+                        .record(
+                            client.peer_address,
+                            path,
+                            old_value,
+                            data,
+                            None,
+                            ChangeOperation::Update,
+                        )
+                        .await;
+                    Ok(())
+                }
+                Err(reason) => Err(reason),
+            };
+            respond(client, ParametersResponse::Update { id, result }).await;
         }
         StorageRequest::LoadFromDisk { client, id } => {
-            let parameters = match deserialize(parameters_directory, body_id, head_id).await {
+            let previous_parameters =
+                to_value(&*parameters).expect("parameters should always be serializable");
+            *parameters = match deserialize_with_profiles(
+                &directory.path,
+                &directory.body_id,
+                &directory.head_id,
+                &directory.active_profiles,
+            )
+            .await
+            {
                 Ok(parameters) => parameters,
                 Err(error) => {
                     respond(
@@ -111,11 +140,21 @@ async fn handle_request<Parameters>(
                 }
             };
 
-            {
-                let mut slot = parameters_writer.next();
-                *slot = parameters;
-            }
-            parameters_changed.notify_one();
+            directory.loaded_parameters =
+                to_value(&*parameters).expect("parameters should always be serializable");
+            publish(parameters, parameters_writer, parameters_changed);
+            let (old_value, new_value) =
+                changed_branches(&previous_parameters, &directory.loaded_parameters, "");
+            journal
+                .record(
+                    client.peer_address,
+                    String::new(),
+                    old_value,
+                    new_value,
+                    None,
+                    ChangeOperation::LoadFromDisk,
+                )
+                .await;
 
             respond(
                 client,
@@ -123,19 +162,28 @@ async fn handle_request<Parameters>(
             )
             .await;
         }
+        StorageRequest::ReloadFromDisk => match reload(parameters, metadata, directory).await {
+            Ok(()) => publish(parameters, parameters_writer, parameters_changed),
+            Err(reason) => {
+                error!("failed to reload parameters: {reason}");
+                // there is nobody to inform if no client is connected
+                reload_error_sender.send(reason).ok();
+            }
+        },
         StorageRequest::StoreToDisk {
             client,
             id,
             scope,
             path,
         } => {
-            if let Err(error) = serialize(
+            if let Err(error) = serialize_with_profiles(
                 parameters,
                 scope,
                 &path,
-                parameters_directory,
-                body_id,
-                head_id,
+                &directory.path,
+                &directory.body_id,
+                &directory.head_id,
+                &directory.active_profiles,
             )
             .await
             {
@@ -150,15 +198,307 @@ async fn handle_request<Parameters>(
                 return;
             }
 
+            let (old_value, new_value) = changed_branches(
+                &directory.loaded_parameters,
+                &to_value(&*parameters).expect("parameters should always be serializable"),
+                &path,
+            );
+            journal
+                .record(
+                    client.peer_address,
+                    path,
+                    old_value,
+                    new_value,
+                    None,
+                    ChangeOperation::StoreToDisk { scope },
+                )
+                .await;
             respond(
                 client,
                 ParametersResponse::StoreToDisk { id, result: Ok(()) },
             )
             .await;
         }
+        StorageRequest::GetHistory { client, id } => {
+            respond(
+                client,
+                ParametersResponse::GetHistory {
+                    id,
+                    changes: journal.changes(),
+                },
+            )
+            .await;
+        }
+        StorageRequest::Revert {
+            client,
+            id,
+            change_id,
+        } => {
+            let Some(change) = journal.find(change_id).cloned() else {
+                respond(
+                    client,
+                    ParametersResponse::Revert {
+                        id,
+                        result: Err(format!("change {change_id} does not exist")),
+                    },
+                )
+                .await;
+                return;
+            };
+            if change.operation != ChangeOperation::Update {
+                respond(
+                    client,
+                    ParametersResponse::Revert {
+                        id,
+                        result: Err(format!("change {change_id} is not an update")),
+                    },
+                )
+                .await;
+                return;
+            }
+            // reverting would silently discard later changes of the same values
+            if let Some(later_change) = journal.find_superseding(&change) {
+                respond(
+                    client,
+                    ParametersResponse::Revert {
+                        id,
+                        result: Err(format!(
+                            "change {change_id} has been superseded by change {}",
+                            later_change.change_id
+                        )),
+                    },
+                )
+                .await;
+                return;
+            }
+            let result = match update(parameters, metadata, &change.path, change.old_value.clone())
+            {
+                Ok(current_value) => {
+                    publish(parameters, parameters_writer, parameters_changed);
+                    journal
+                        .record(
+                            client.peer_address,
+                            change.path,
+                            current_value,
+                            change.old_value,
+                            Some(change_id),
+                            ChangeOperation::Update,
+                        )
+                        .await;
+                    Ok(())
+                }
+                Err(reason) => Err(reason),
+            };
+            respond(client, ParametersResponse::Revert { id, result }).await;
+        }
+        StorageRequest::GetProfiles { client, id } => {
+            let result = profiles(directory).await;
+            respond(client, ParametersResponse::Profiles { id, result }).await;
+        }
+        StorageRequest::ActivateProfile { client, id, name } => {
+            let result = match activate_profile(parameters, metadata, directory, name).await {
+                Ok(()) => {
+                    publish(parameters, parameters_writer, parameters_changed);
+                    profiles(directory).await
+                }
+                Err(reason) => Err(reason),
+            };
+            respond(client, ParametersResponse::Profiles { id, result }).await;
+        }
+        StorageRequest::DeactivateProfile { client, id, name } => {
+            let result = match deactivate_profile(parameters, metadata, directory, &name).await {
+                Ok(()) => {
+                    publish(parameters, parameters_writer, parameters_changed);
+                    profiles(directory).await
+                }
+                Err(reason) => Err(reason),
+            };
+            respond(client, ParametersResponse::Profiles { id, result }).await;
+        }
     }
 }
 
+async fn profiles(directory: &Directory) -> Result<Profiles, Reason> {
+    let available = list_profiles(&directory.path)
+        .await
+        .map_err(|error| format!("failed to list profiles: {error:?}"))?;
+    let merge_order = merge_order(
+        &directory.path,
+        &directory.body_id,
+        &directory.head_id,
+        &directory.active_profiles,
+    )
+    .into_iter()
+    .map(|file_path| {
+        file_path
+            .strip_prefix(&directory.path)
+            .unwrap_or(&file_path)
+            .display()
+            .to_string()
+    })
+    .collect();
+    Ok(Profiles {
+        available,
+        active: directory.active_profiles.clone(),
+        merge_order,
+    })
+}
+
+async fn activate_profile<Parameters>(
+    parameters: &mut Parameters,
+    metadata: &BTreeMap<String, FieldMetadata>,
+    directory: &mut Directory,
+    name: String,
+) -> Result<(), Reason>
+where
+    Parameters: DeserializeOwned + Serialize,
+{
+    if directory.active_profiles.contains(&name) {
+        return Err(format!("profile {name:?} is already active"));
+    }
+    let available = list_profiles(&directory.path)
+        .await
+        .map_err(|error| format!("failed to list profiles: {error:?}"))?;
+    if !available.contains(&name) {
+        return Err(format!("profile {name:?} does not exist"));
+    }
+    let mut next_profiles = directory.active_profiles.clone();
+    next_profiles.push(name);
+    apply_profiles(parameters, metadata, directory, next_profiles).await
+}
+
+async fn deactivate_profile<Parameters>(
+    parameters: &mut Parameters,
+    metadata: &BTreeMap<String, FieldMetadata>,
+    directory: &mut Directory,
+    name: &str,
+) -> Result<(), Reason>
+where
+    Parameters: DeserializeOwned + Serialize,
+{
+    if !directory
+        .active_profiles
+        .iter()
+        .any(|profile| profile == name)
+    {
+        return Err(format!("profile {name:?} is not active"));
+    }
+    let next_profiles = directory
+        .active_profiles
+        .iter()
+        .filter(|profile| *profile != name)
+        .cloned()
+        .collect();
+    apply_profiles(parameters, metadata, directory, next_profiles).await
+}
+
+/// Re-merges the parameter files with the next profiles, keeping values changed at runtime
+async fn apply_profiles<Parameters>(
+    parameters: &mut Parameters,
+    metadata: &BTreeMap<String, FieldMetadata>,
+    directory: &mut Directory,
+    next_profiles: Vec<String>,
+) -> Result<(), Reason>
+where
+    Parameters: DeserializeOwned + Serialize,
+{
+    let previous_profiles = replace(&mut directory.active_profiles, next_profiles);
+    let result = reload(parameters, metadata, directory).await;
+    if result.is_err() {
+        directory.active_profiles = previous_profiles;
+    }
+    result
+}
+
+/// Re-merges the parameter files, values changed at runtime take precedence until they are
+/// stored or overwritten by loading from disk
+async fn reload<Parameters>(
+    parameters: &mut Parameters,
+    metadata: &BTreeMap<String, FieldMetadata>,
+    directory: &mut Directory,
+) -> Result<(), Reason>
+where
+    Parameters: DeserializeOwned + Serialize,
+{
+    let loaded_parameters: Parameters = deserialize_with_profiles(
+        &directory.path,
+        &directory.body_id,
+        &directory.head_id,
+        &directory.active_profiles,
+    )
+    .await
+    .map_err(|error| format!("failed to deserialize parameters: {error:?}"))?;
+    let loaded_parameters = to_value(loaded_parameters)
+        .map_err(|error| format!("failed to serialize parameters: {error:?}"))?;
+    validate(metadata, "", &loaded_parameters)
+        .map_err(|error| format!("invalid value: {error}"))?;
+
+    let mut runtime_changes = to_value(&*parameters)
+        .map_err(|error| format!("failed to serialize parameters: {error:?}"))?;
+    prune_equal_branches(&mut runtime_changes, &directory.loaded_parameters);
+    let mut merged_parameters = loaded_parameters.clone();
+    merge_json(&mut merged_parameters, &runtime_changes);
+    *parameters = from_value(merged_parameters)
+        .map_err(|error| format!("failed to deserialize parameters: {error:?}"))?;
+    directory.loaded_parameters = loaded_parameters;
+    Ok(())
+}
+
+/// Writes the value to the path and returns the previous value
+fn update<Parameters>(
+    parameters: &mut Parameters,
+    metadata: &BTreeMap<String, FieldMetadata>,
+    path: &str,
+    data: Value,
+) -> Result<Value, Reason>
+where
+    Parameters: SerializeHierarchy,
+{
+    if !Parameters::exists(path) {
+        return Err(format!("path {path:?} does not exist"));
+    }
+    validate(metadata, path, &data).map_err(|error| format!("invalid value: {error}"))?;
+    let old_value = parameters
+        .serialize_path(path, serde_json::value::Serializer)
+        .map_err(|error| format!("failed to serialize: {error:?}"))?;
+    parameters
+        .deserialize_path(path, data)
+        .map_err(|error| format!("failed to deserialize: {error:?}"))?;
+    Ok(old_value)
+}
+
+/// Returns the branches below the path which differ between both values
+fn changed_branches(old_value: &Value, new_value: &Value, path: &str) -> (Value, Value) {
+    let nested_value = |value: &Value| {
+        let pointer = format!("/{}", path.replace('.', "/"));
+        let nested_value = if path.is_empty() {
+            Some(value)
+        } else {
+            value.pointer(&pointer)
+        };
+        nested_value.cloned().unwrap_or_default()
+    };
+    let mut changed_old_value = nested_value(old_value);
+    let mut changed_new_value = nested_value(new_value);
+    prune_equal_branches(&mut changed_old_value, &changed_new_value);
+    prune_equal_branches(&mut changed_new_value, &nested_value(old_value));
+    (changed_old_value, changed_new_value)
+}
+
+fn publish<Parameters>(
+    parameters: &Parameters,
+    parameters_writer: &Writer<Parameters>,
+    parameters_changed: &Notify,
+) where
+    Parameters: Clone,
+{
+    {
+        let mut slot = parameters_writer.next();
+        *slot = parameters.clone();
+    }
+    parameters_changed.notify_one();
+}
+
 async fn respond(client: Client, response: ParametersResponse) {
     client
         .response_sender
@@ -173,14 +513,19 @@ mod tests {
 
     use framework::multiple_buffer_with_slots;
     use serde::{Deserialize, Deserializer, Serializer};
-    use serde_json::Value;
+    use serde_json::{json, Value};
     use serialize_hierarchy::Error;
+    use tempfile::tempdir;
     use tokio::sync::mpsc::{channel, error::TryRecvError};
 
     use crate::server::client::Client;
 
     use super::*;
 
+    fn reload_error_sender() -> broadcast::Sender<Reason> {
+        broadcast::channel(1).0
+    }
+
     #[tokio::test]
     async fn terminates_on_request_sender_drop() {
         let (parameters_writer, _parameters_reader) = multiple_buffer_with_slots([42usize]);
@@ -190,6 +535,7 @@ mod tests {
             parameters_writer,
             parameters_changed,
             request_receiver,
+            reload_error_sender(),
             ".",
             Default::default(),
             Default::default(),
@@ -253,11 +599,13 @@ mod tests {
         }]);
         let parameters_changed = Arc::new(Notify::new());
         let (request_sender, request_receiver) = channel(1);
+        let parameters_directory = tempdir().unwrap();
         let subscriptions_task = storage(
             parameters_writer,
             parameters_changed.clone(),
             request_receiver,
-            ".",
+            reload_error_sender(),
+            parameters_directory.path().to_path_buf(),
             Default::default(),
             Default::default(),
         );
@@ -266,10 +614,7 @@ mod tests {
         let (response_sender, mut response_receiver) = channel(1);
         request_sender
             .send(StorageRequest::UpdateParameter {
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
                 id: 42,
                 path: path.clone(),
                 data: Value::from(value),
@@ -295,4 +640,324 @@ mod tests {
         drop(request_sender);
         subscriptions_task.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn revert_request_restores_old_value_and_is_journaled() {
+        let path = "a.b.c".to_string();
+        let (parameters_writer, parameters_reader) = multiple_buffer_with_slots([ParametersFake {
+            existing_fields: [(path.clone(), 42)].into(),
+        }]);
+        let (request_sender, request_receiver) = channel(1);
+        let parameters_directory = tempdir().unwrap();
+        let storage_task = storage(
+            parameters_writer,
+            Arc::new(Notify::new()),
+            request_receiver,
+            reload_error_sender(),
+            parameters_directory.path().to_path_buf(),
+            Default::default(),
+            Default::default(),
+        );
+        let (response_sender, mut response_receiver) = channel(1);
+        let client = Client::for_test(1337, response_sender);
+
+        request_sender
+            .send(StorageRequest::UpdateParameter {
+                client: client.clone(),
+                id: 1,
+                path: path.clone(),
+                data: Value::from(1337),
+            })
+            .await
+            .unwrap();
+        response_receiver.recv().await.unwrap();
+        request_sender
+            .send(StorageRequest::Revert {
+                client: client.clone(),
+                id: 2,
+                change_id: 0,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Revert {
+                id: 2,
+                result: Ok(()),
+            })),
+        );
+        assert_eq!(
+            parameters_reader.next().existing_fields.get(&path),
+            Some(&42)
+        );
+
+        request_sender
+            .send(StorageRequest::GetHistory { client, id: 3 })
+            .await
+            .unwrap();
+        let Response::Textual(TextualResponse::Parameters(ParametersResponse::GetHistory {
+            id: 3,
+            changes,
+        })) = response_receiver.recv().await.unwrap()
+        else {
+            panic!("expected history response");
+        };
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            (&changes[0].old_value, &changes[0].new_value),
+            (&Value::from(42), &Value::from(1337))
+        );
+        assert_eq!(
+            (&changes[1].old_value, &changes[1].new_value),
+            (&Value::from(1337), &Value::from(42))
+        );
+        assert_eq!(changes[1].reverted_change_id, Some(0));
+
+        drop(request_sender);
+        storage_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reload_request_merges_changed_files_and_reports_errors() {
+        let parameters_directory = tempdir().unwrap();
+        let default_file_path = parameters_directory.path().join("default.json");
+        std::fs::write(&default_file_path, r#"{"existing_fields":{"a.b.c":42}}"#).unwrap();
+        let (parameters_writer, parameters_reader) = multiple_buffer_with_slots([ParametersFake {
+            existing_fields: [("a.b.c".to_string(), 42)].into(),
+        }]);
+        let parameters_changed = Arc::new(Notify::new());
+        let (request_sender, request_receiver) = channel(1);
+        let reload_error_sender = reload_error_sender();
+        let mut reload_error_receiver = reload_error_sender.subscribe();
+        let storage_task = storage(
+            parameters_writer,
+            parameters_changed.clone(),
+            request_receiver,
+            reload_error_sender,
+            parameters_directory.path().to_path_buf(),
+            Default::default(),
+            Default::default(),
+        );
+
+        std::fs::write(&default_file_path, r#"{"existing_fields":{"a.b.c":1337}}"#).unwrap();
+        request_sender
+            .send(StorageRequest::ReloadFromDisk)
+            .await
+            .unwrap();
+        parameters_changed.notified().await;
+        assert_eq!(
+            parameters_reader.next().existing_fields.get("a.b.c"),
+            Some(&1337)
+        );
+
+        std::fs::write(&default_file_path, "{").unwrap();
+        request_sender
+            .send(StorageRequest::ReloadFromDisk)
+            .await
+            .unwrap();
+        let reason = reload_error_receiver.recv().await.unwrap();
+        assert!(reason.starts_with("failed to deserialize parameters"));
+        assert_eq!(
+            parameters_reader.next().existing_fields.get("a.b.c"),
+            Some(&1337)
+        );
+
+        drop(request_sender);
+        storage_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn profiles_are_activated_and_deactivated() {
+        let parameters_directory = tempdir().unwrap();
+        std::fs::create_dir_all(parameters_directory.path().join("profiles")).unwrap();
+        std::fs::write(
+            parameters_directory.path().join("default.json"),
+            r#"{"existing_fields":{"a.b.c":42}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            parameters_directory.path().join("profiles/demo.json"),
+            r#"{"existing_fields":{"a.b.c":1337}}"#,
+        )
+        .unwrap();
+        let (parameters_writer, parameters_reader) = multiple_buffer_with_slots([ParametersFake {
+            existing_fields: [("a.b.c".to_string(), 42)].into(),
+        }]);
+        let (request_sender, request_receiver) = channel(1);
+        let storage_task = storage(
+            parameters_writer,
+            Arc::new(Notify::new()),
+            request_receiver,
+            reload_error_sender(),
+            parameters_directory.path().to_path_buf(),
+            Default::default(),
+            Default::default(),
+        );
+        let (response_sender, mut response_receiver) = channel(1);
+        let client = Client::for_test(1337, response_sender);
+        let profiles = |active: &[&str], merge_order: &[&str]| Profiles {
+            available: vec!["demo".to_string()],
+            active: active.iter().map(|name| name.to_string()).collect(),
+            merge_order: merge_order.iter().map(|path| path.to_string()).collect(),
+        };
+
+        request_sender
+            .send(StorageRequest::ActivateProfile {
+                client: client.clone(),
+                id: 1,
+                name: "demo".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Profiles {
+                id: 1,
+                result: Ok(profiles(&["demo"], &["default.json", "profiles/demo.json"])),
+            })),
+        );
+        assert_eq!(
+            parameters_reader.next().existing_fields.get("a.b.c"),
+            Some(&1337)
+        );
+
+        request_sender
+            .send(StorageRequest::ActivateProfile {
+                client: client.clone(),
+                id: 2,
+                name: "missing".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Profiles {
+                id: 2,
+                result: Err(r#"profile "missing" does not exist"#.to_string()),
+            })),
+        );
+
+        request_sender
+            .send(StorageRequest::DeactivateProfile {
+                client: client.clone(),
+                id: 3,
+                name: "demo".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Profiles {
+                id: 3,
+                result: Ok(profiles(&[], &["default.json"])),
+            })),
+        );
+        assert_eq!(
+            parameters_reader.next().existing_fields.get("a.b.c"),
+            Some(&42)
+        );
+
+        request_sender
+            .send(StorageRequest::DeactivateProfile {
+                client,
+                id: 4,
+                name: "demo".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Profiles {
+                id: 4,
+                result: Err(r#"profile "demo" is not active"#.to_string()),
+            })),
+        );
+
+        drop(request_sender);
+        storage_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn load_from_disk_is_journaled_and_supersedes_earlier_updates() {
+        let parameters_directory = tempdir().unwrap();
+        std::fs::write(
+            parameters_directory.path().join("default.json"),
+            r#"{"existing_fields":{"a.b.c":42}}"#,
+        )
+        .unwrap();
+        let (parameters_writer, _parameters_reader) =
+            multiple_buffer_with_slots([ParametersFake {
+                existing_fields: [("a.b.c".to_string(), 42)].into(),
+            }]);
+        let (request_sender, request_receiver) = channel(1);
+        let storage_task = storage(
+            parameters_writer,
+            Arc::new(Notify::new()),
+            request_receiver,
+            reload_error_sender(),
+            parameters_directory.path().to_path_buf(),
+            Default::default(),
+            Default::default(),
+        );
+        let (response_sender, mut response_receiver) = channel(1);
+        let client = Client::for_test(1337, response_sender);
+
+        request_sender
+            .send(StorageRequest::UpdateParameter {
+                client: client.clone(),
+                id: 1,
+                path: "a.b.c".to_string(),
+                data: Value::from(1337),
+            })
+            .await
+            .unwrap();
+        response_receiver.recv().await.unwrap();
+        request_sender
+            .send(StorageRequest::LoadFromDisk {
+                client: client.clone(),
+                id: 2,
+            })
+            .await
+            .unwrap();
+        response_receiver.recv().await.unwrap();
+        request_sender
+            .send(StorageRequest::Revert {
+                client: client.clone(),
+                id: 3,
+                change_id: 0,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response_receiver.recv().await.unwrap(),
+            Response::Textual(TextualResponse::Parameters(ParametersResponse::Revert {
+                id: 3,
+                result: Err("change 0 has been superseded by change 1".to_string()),
+            })),
+        );
+
+        request_sender
+            .send(StorageRequest::GetHistory { client, id: 4 })
+            .await
+            .unwrap();
+        let Response::Textual(TextualResponse::Parameters(ParametersResponse::GetHistory {
+            id: 4,
+            changes,
+        })) = response_receiver.recv().await.unwrap()
+        else {
+            panic!("expected history response");
+        };
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].operation, ChangeOperation::LoadFromDisk);
+        assert_eq!(
+            (&changes[1].old_value, &changes[1].new_value),
+            (
+                &json!({"existing_fields": {"a.b.c": 1337}}),
+                &json!({"existing_fields": {"a.b.c": 42}})
+            )
+        );
+
+        drop(request_sender);
+        storage_task.await.unwrap();
+    }
 }
@@ -1,12 +1,13 @@
 use std::{
-    collections::{hash_map::Entry, BTreeSet, HashMap},
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
     sync::Arc,
 };
 
 use framework::Reader;
 use futures_util::{stream::FuturesUnordered, StreamExt};
 use log::error;
-use serialize_hierarchy::SerializeHierarchy;
+use serde_json::Value;
+use serialize_hierarchy::{FieldMetadata, SerializeHierarchy};
 use tokio::{
     select, spawn,
     sync::{
@@ -34,8 +35,10 @@ where
 {
     spawn(async move {
         let fields = Parameters::get_fields();
+        let schema = Parameters::get_metadata();
 
         let mut subscriptions = HashMap::new();
+        let mut published_values = HashMap::new();
         loop {
             select! {
                 request = request_receiver.recv() => {
@@ -48,10 +51,15 @@ where
                         &storage_request_sender,
                         &mut subscriptions,
                         &fields,
+                        &schema,
                     ).await;
                 },
                 _ = parameters_changed.notified() => {
-                    handle_changed_parameters(&parameters_reader, &subscriptions).await;
+                    handle_changed_parameters(
+                        &parameters_reader,
+                        &subscriptions,
+                        &mut published_values,
+                    ).await;
                 }
             }
         }
@@ -64,6 +72,7 @@ async fn handle_request<Parameters>(
     storage_request_sender: &Sender<StorageRequest>,
     subscriptions: &mut HashMap<(Client, usize), Path>,
     fields: &BTreeSet<String>,
+    schema: &BTreeMap<String, FieldMetadata>,
 ) where
     Parameters: SerializeHierarchy,
 {
@@ -78,6 +87,16 @@ async fn handle_request<Parameters>(
             )
             .await;
         }
+        ParametersRequest::GetSchema { id } => {
+            respond(
+                request,
+                ParametersResponse::GetSchema {
+                    id,
+                    schema: schema.clone(),
+                },
+            )
+            .await;
+        }
         ParametersRequest::GetCurrent { id, ref path } => {
             let data = {
                 let parameters = parameters_reader.next();
@@ -220,6 +239,54 @@ async fn handle_request<Parameters>(
                 .await
                 .expect("receiver should always wait for all senders");
         }
+        ParametersRequest::GetHistory { id } => {
+            storage_request_sender
+                .send(StorageRequest::GetHistory {
+                    client: request.client,
+                    id,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        ParametersRequest::Revert { id, change_id } => {
+            storage_request_sender
+                .send(StorageRequest::Revert {
+                    client: request.client,
+                    id,
+                    change_id,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        ParametersRequest::GetProfiles { id } => {
+            storage_request_sender
+                .send(StorageRequest::GetProfiles {
+                    client: request.client,
+                    id,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        ParametersRequest::ActivateProfile { id, name } => {
+            storage_request_sender
+                .send(StorageRequest::ActivateProfile {
+                    client: request.client,
+                    id,
+                    name,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        ParametersRequest::DeactivateProfile { id, name } => {
+            storage_request_sender
+                .send(StorageRequest::DeactivateProfile {
+                    client: request.client,
+                    id,
+                    name,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
     }
 }
 
@@ -232,12 +299,15 @@ async fn respond(request: ClientRequest<ParametersRequest>, response: Parameters
         .expect("receiver should always wait for all senders");
 }
 
+/// Sends the values of all subscribed paths which changed since they were last published
 async fn handle_changed_parameters<Parameters>(
     parameters_reader: &Reader<Parameters>,
     subscriptions: &HashMap<(Client, usize), Path>,
+    published_values: &mut HashMap<(Client, usize), Value>,
 ) where
     Parameters: SerializeHierarchy,
 {
+    published_values.retain(|key, _| subscriptions.contains_key(key));
     let items: HashMap<_, _> = {
         let parameters = parameters_reader.next();
         subscriptions
@@ -250,7 +320,12 @@ async fn handle_changed_parameters<Parameters>(
                         return None;
                     }
                 };
-                Some(((client.clone(), *subscription_id), data))
+                let key = (client.clone(), *subscription_id);
+                if published_values.get(&key) == Some(&data) {
+                    return None;
+                }
+                published_values.insert(key.clone(), data.clone());
+                Some((key, data))
             })
             .collect()
     };
@@ -327,10 +402,7 @@ mod tests {
         request_sender
             .send(ClientRequest {
                 request: ParametersRequest::GetFields { id: 42 },
-                client: Client {
-                    id: 1337,
-                    response_sender,
-                },
+                client: Client::for_test(1337, response_sender),
             })
             .await
             .unwrap();
@@ -418,10 +490,7 @@ mod tests {
         request_sender
             .send(ClientRequest {
                 request: ParametersRequest::GetCurrent { id: 42, path },
-                client: Client {
-                    id: 1337,
-                    response_sender,
-                },
+                client: Client::for_test(1337, response_sender),
             })
             .await
             .unwrap();
@@ -473,10 +542,7 @@ mod tests {
                     id: ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -512,10 +578,7 @@ mod tests {
                     id: ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -566,10 +629,7 @@ mod tests {
                     id: ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -605,10 +665,7 @@ mod tests {
                     id: ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: 7331,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(7331, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -659,10 +716,7 @@ mod tests {
                     id: 42,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -698,10 +752,7 @@ mod tests {
                     id: 1337,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -749,10 +800,7 @@ mod tests {
                     id: 42,
                     subscription_id: 1337,
                 },
-                client: Client {
-                    id: 1337,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(1337, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -806,10 +854,7 @@ mod tests {
                     id: SUBSCRIPTION_ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -845,10 +890,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -876,10 +918,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -933,10 +972,7 @@ mod tests {
                     id: SUBSCRIPTION_ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -970,10 +1006,7 @@ mod tests {
         request_sender
             .send(ClientRequest {
                 request: ParametersRequest::UnsubscribeEverything,
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -992,10 +1025,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1050,10 +1080,7 @@ mod tests {
                     path: path.clone(),
                     data: value.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1070,10 +1097,7 @@ mod tests {
         assert_eq!(
             storage_request,
             StorageRequest::UpdateParameter {
-                client: Client {
-                    id: client_id,
-                    response_sender,
-                },
+                client: Client::for_test(client_id, response_sender),
                 id: 42,
                 path,
                 data: value,
@@ -1103,10 +1127,7 @@ mod tests {
         request_sender
             .send(ClientRequest {
                 request: ParametersRequest::LoadFromDisk { id: 42 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1123,10 +1144,7 @@ mod tests {
         assert_eq!(
             storage_request,
             StorageRequest::LoadFromDisk {
-                client: Client {
-                    id: client_id,
-                    response_sender,
-                },
+                client: Client::for_test(client_id, response_sender),
                 id: 42,
             }
         );
@@ -1163,10 +1181,7 @@ mod tests {
                     scope,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1183,10 +1198,7 @@ mod tests {
         assert_eq!(
             storage_request,
             StorageRequest::StoreToDisk {
-                client: Client {
-                    id: client_id,
-                    response_sender,
-                },
+                client: Client::for_test(client_id, response_sender),
                 id: 42,
                 scope,
                 path
@@ -1225,10 +1237,7 @@ mod tests {
                     id: SUBSCRIPTION_ID,
                     path: path.clone(),
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -1281,10 +1290,7 @@ mod tests {
                     id: 1337,
                     subscription_id: SUBSCRIPTION_ID,
                 },
-                client: Client {
-                    id: client_id,
-                    response_sender: response_sender.clone(),
-                },
+                client: Client::for_test(client_id, response_sender.clone()),
             })
             .await
             .unwrap();
@@ -0,0 +1,71 @@
+use std::{path::PathBuf, time::Duration};
+
+use log::error;
+use notify::{recommended_watcher, Event, EventKind, RecursiveMode, Watcher};
+use tokio::{
+    select, spawn,
+    sync::mpsc::{unbounded_channel, Sender},
+    task::JoinHandle,
+    time::sleep,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::StorageRequest;
+
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
+
+/// Requests reloading the parameters whenever a JSON file in the parameters directory changes
+pub fn watcher(
+    parameters_directory: PathBuf,
+    storage_request_sender: Sender<StorageRequest>,
+    keep_running: CancellationToken,
+) -> notify::Result<JoinHandle<()>> {
+    let (event_sender, mut event_receiver) = unbounded_channel();
+    let mut file_watcher = recommended_watcher(move |event| {
+        // the receiver is only dropped on shutdown
+        let _ = event_sender.send(event);
+    })?;
+    file_watcher.watch(&parameters_directory, RecursiveMode::Recursive)?;
+
+    Ok(spawn(async move {
+        // events are only emitted as long as the watcher is alive
+        let _file_watcher = file_watcher;
+        loop {
+            select! {
+                event = event_receiver.recv() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+                    if !is_parameter_file_change(event) {
+                        continue;
+                    }
+                    // editors write files in several steps, wait until they are done
+                    sleep(DEBOUNCE_DURATION).await;
+                    while event_receiver.try_recv().is_ok() {}
+                    storage_request_sender
+                        .send(StorageRequest::ReloadFromDisk)
+                        .await
+                        .expect("receiver should always wait for all senders");
+                },
+                _ = keep_running.cancelled() => break,
+            }
+        }
+    }))
+}
+
+fn is_parameter_file_change(event: notify::Result<Event>) -> bool {
+    let event = match event {
+        Ok(event) => event,
+        Err(error) => {
+            error!("failed to watch parameters directory: {error}");
+            return false;
+        }
+    };
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|path| path.extension().and_then(|extension| extension.to_str()) == Some("json"))
+}
@@ -1,6 +1,15 @@
+use std::{collections::BTreeSet, future::pending, net::SocketAddr};
+
 use futures_util::{stream::SplitStream, StreamExt};
 use serde_json::from_str;
-use tokio::{net::TcpStream, select, sync::mpsc::Sender};
+use tokio::{
+    net::TcpStream,
+    select,
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc::Sender,
+    },
+};
 use tokio_tungstenite::{
     tungstenite::{protocol::frame::coding::CloseCode, Message},
     WebSocketStream,
@@ -8,7 +17,11 @@ use tokio_tungstenite::{
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    messages::{OutputsRequest, ParametersRequest, Request, Response},
+    messages::{
+        InjectionsRequest, InjectionsResponse, OutputsRequest, ParametersRequest,
+        ParametersResponse, Permission, Reason, Request, Response, TextualResponse,
+        TransportRequest,
+    },
     server::client_request::ClientRequest,
 };
 
@@ -21,9 +34,12 @@ pub async fn receiver(
     keep_running: CancellationToken,
     keep_only_self_running: CancellationToken,
     client_id: usize,
+    peer_address: SocketAddr,
+    permissions: BTreeSet<Permission>,
     response_sender: Sender<Response>,
     outputs_sender: Sender<outputs::Request>,
     parameters_sender: Sender<ClientRequest<ParametersRequest>>,
+    mut reload_error_receiver: broadcast::Receiver<Reason>,
 ) {
     select! {
         _ = async {
@@ -33,43 +49,39 @@ pub async fn receiver(
                     &error_sender,
                     &keep_only_self_running,
                     client_id,
+                    peer_address,
+                    &permissions,
                     &response_sender,
                     &outputs_sender,
                     &parameters_sender,
                 ).await;
             }
         } => {},
+        _ = forward_reload_errors(&mut reload_error_receiver, &response_sender) => {},
         _ = keep_running.cancelled() => {},
         _ = keep_only_self_running.cancelled() => {},
     }
 
-    outputs_sender
-        .send(outputs::Request::ClientRequest(ClientRequest {
-            request: OutputsRequest::UnsubscribeEverything,
-            client: Client {
-                id: client_id,
-                response_sender: response_sender.clone(),
-            },
-        }))
-        .await
-        .expect("receiver should always wait for all senders");
-    parameters_sender
-        .send(ClientRequest {
-            request: ParametersRequest::UnsubscribeEverything,
-            client: Client {
-                id: client_id,
-                response_sender: response_sender.clone(),
-            },
-        })
-        .await
-        .expect("receiver should always wait for all senders");
+    unsubscribe_everything(
+        Client {
+            id: client_id,
+            response_sender,
+            peer_address,
+        },
+        &outputs_sender,
+        &parameters_sender,
+    )
+    .await;
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_message(
     message: Result<Message, tokio_tungstenite::tungstenite::Error>,
     error_sender: &Sender<ReceiverOrSenderError>,
     keep_only_self_running: &CancellationToken,
     client_id: usize,
+    peer_address: SocketAddr,
+    permissions: &BTreeSet<Permission>,
     response_sender: &Sender<Response>,
     outputs_sender: &Sender<outputs::Request>,
     parameters_sender: &Sender<ClientRequest<ParametersRequest>>,
@@ -104,28 +116,18 @@ async fn handle_message(
                 }
             };
 
-            let client = Client {
-                id: client_id,
-                response_sender: response_sender.clone(),
-            };
-            match request {
-                Request::Outputs(request) => {
-                    outputs_sender
-                        .send(outputs::Request::ClientRequest(ClientRequest {
-                            request,
-                            client,
-                        }))
-                        .await
-                        .expect("receiver should always wait for all senders");
-                }
-                Request::Injections(_) => todo!(),
-                Request::Parameters(request) => {
-                    parameters_sender
-                        .send(ClientRequest { request, client })
-                        .await
-                        .expect("receiver should always wait for all senders");
-                }
-            }
+            dispatch(
+                request,
+                Client {
+                    id: client_id,
+                    response_sender: response_sender.clone(),
+                    peer_address,
+                },
+                permissions,
+                outputs_sender,
+                parameters_sender,
+            )
+            .await;
         }
         Message::Binary(_) => {
             send_error(
@@ -158,3 +160,127 @@ async fn send_error(
         .await
         .expect("receiver should always wait for all senders");
 }
+
+/// Forwards a request of a client to the tasks handling it, shared by all transports
+pub async fn dispatch(
+    request: Request,
+    client: Client,
+    permissions: &BTreeSet<Permission>,
+    outputs_sender: &Sender<outputs::Request>,
+    parameters_sender: &Sender<ClientRequest<ParametersRequest>>,
+) {
+    if let Some(permission) = request
+        .required_permission()
+        .filter(|permission| !permissions.contains(permission))
+    {
+        client
+            .response_sender
+            .send(Response::Textual(TextualResponse::PermissionDenied {
+                id: request.id(),
+                permission,
+            }))
+            .await
+            .expect("receiver should always wait for all senders");
+        return;
+    }
+
+    match request {
+        Request::Outputs(request) => {
+            if let OutputsRequest::Unsubscribe {
+                id,
+                subscription_id,
+            } = request
+            {
+                client
+                    .response_sender
+                    .send(Response::UnsubscribingOutput {
+                        id,
+                        subscription_id,
+                    })
+                    .await
+                    .expect("receiver should always wait for all senders");
+            }
+            outputs_sender
+                .send(outputs::Request::ClientRequest(ClientRequest {
+                    request,
+                    client,
+                }))
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        Request::Injections(request) => {
+            let reason = "injections are not implemented".to_string();
+            let response = match request {
+                InjectionsRequest::Set { id, .. } => InjectionsResponse::Set {
+                    id,
+                    result: Err(reason),
+                },
+                InjectionsRequest::Unset { id, .. } => InjectionsResponse::Unset {
+                    id,
+                    result: Err(reason),
+                },
+                InjectionsRequest::UnsetEverything => return,
+            };
+            client
+                .response_sender
+                .send(Response::Textual(TextualResponse::Injections(response)))
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        Request::Parameters(request) => {
+            parameters_sender
+                .send(ClientRequest { request, client })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+        Request::Transport(TransportRequest::Configure { options }) => {
+            client
+                .response_sender
+                .send(Response::ConfigureTransport { options })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
+    }
+}
+
+/// Failed reloads of the parameter files concern every client, not only the ones subscribed to
+/// parameters, never returns
+pub async fn forward_reload_errors(
+    reload_error_receiver: &mut broadcast::Receiver<Reason>,
+    response_sender: &Sender<Response>,
+) {
+    loop {
+        let reason = match reload_error_receiver.recv().await {
+            Ok(reason) => reason,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => pending().await,
+        };
+        response_sender
+            .send(Response::Textual(TextualResponse::Parameters(
+                ParametersResponse::ReloadFromDiskFailed { reason },
+            )))
+            .await
+            .expect("receiver should always wait for all senders");
+    }
+}
+
+pub async fn unsubscribe_everything(
+    client: Client,
+    outputs_sender: &Sender<outputs::Request>,
+    parameters_sender: &Sender<ClientRequest<ParametersRequest>>,
+) {
+    outputs_sender
+        .send(outputs::Request::ClientRequest(ClientRequest {
+            request: OutputsRequest::UnsubscribeEverything,
+            client: client.clone(),
+        }))
+        .await
+        .expect("receiver should always wait for all senders");
+    parameters_sender
+        .send(ClientRequest {
+            request: ParametersRequest::UnsubscribeEverything,
+            client,
+        })
+        .await
+        .expect("receiver should always wait for all senders");
+}
@@ -3,8 +3,8 @@ use std::{
     fmt::Debug,
     io,
     iter::repeat_with,
-    path::Path,
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicUsize, Arc},
     thread::{self, JoinHandle},
 };
 
@@ -16,6 +16,7 @@ use tokio::{
     net::ToSocketAddrs,
     runtime::{self, Runtime as TokioRuntime},
     sync::{
+        broadcast,
         mpsc::{channel, Sender},
         oneshot, Notify,
     },
@@ -26,14 +27,18 @@ use crate::server::outputs::router::router;
 
 use super::{
     acceptor::{acceptor, AcceptError},
+    access_control::AccessControl,
+    local_socket::{local_acceptor, LocalAcceptError},
     outputs::{provider::provider, Request},
-    parameters::{storage::storage, subscriptions::subscriptions},
+    parameters::{storage::storage, subscriptions::subscriptions, watcher::watcher},
 };
 
 #[derive(Debug, thiserror::Error)]
 pub enum StartError {
     #[error("error while accepting connections")]
     AcceptError(#[source] AcceptError),
+    #[error("error while accepting local connections")]
+    LocalAcceptError(#[source] LocalAcceptError),
     #[error("one or more tasks encountered an error: {0:?}")]
     TasksErrored(Vec<StartError>),
     #[error("thread not started")]
@@ -42,6 +47,8 @@ pub enum StartError {
     RuntimeNotStarted(#[source] io::Error),
     #[error("initial parameters not parsed")]
     InitialParametersNotParsed(#[source] DirectoryError),
+    #[error("parameter watcher not started")]
+    ParameterWatcherNotStarted(#[source] notify::Error),
 }
 
 pub struct Runtime<Parameters> {
@@ -56,12 +63,16 @@ impl<Parameters> Runtime<Parameters>
 where
     Parameters: Clone + DeserializeOwned + Send + Serialize + SerializeHierarchy + Sync + 'static,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn start(
         addresses: Option<impl ToSocketAddrs + Send + Sync + 'static>,
         parameters_directory: impl AsRef<Path> + Send + Sync + 'static,
         body_id: String,
         head_id: String,
         amount_of_parameters_slots: usize,
+        watch_parameters: bool,
+        access_control: AccessControl,
+        local_socket: Option<PathBuf>,
         keep_running: CancellationToken,
     ) -> Result<Self, StartError> {
         let (runtime_sender, runtime_receiver) = oneshot::channel();
@@ -102,6 +113,25 @@ where
 
                     let (parameters_sender, parameters_receiver) = channel(1);
                     let (parameters_storage_sender, parameters_storage_receiver) = channel(1);
+                    let (reload_error_sender, _) = broadcast::channel(1);
+
+                    let parameters_watcher_task = if watch_parameters {
+                        match watcher(
+                            parameters_directory.as_ref().to_path_buf(),
+                            parameters_storage_sender.clone(),
+                            keep_running.clone(),
+                        ) {
+                            Ok(task) => Some(task),
+                            Err(source) => {
+                                runtime_sender.send(None).ok().expect(
+                                "successful thread creation should always wait for runtime_sender",
+                            );
+                                return Err(StartError::ParameterWatcherNotStarted(source));
+                            }
+                        }
+                    } else {
+                        None
+                    };
 
                     runtime_sender
                         .send(Some((
@@ -113,13 +143,27 @@ where
                         .ok()
                         .expect("successful thread creation should always wait for runtime_sender");
 
-                    // only start acceptor if addresses is Some
+                    let next_client_id = Arc::new(AtomicUsize::default());
+                    // only start acceptors if addresses or local socket are Some
                     let acceptor_task = addresses.map(|addresses| {
                         acceptor(
                             addresses,
+                            access_control,
                             keep_running.clone(),
+                            next_client_id.clone(),
+                            outputs_sender.clone(),
+                            parameters_sender.clone(),
+                            reload_error_sender.clone(),
+                        )
+                    });
+                    let local_acceptor_task = local_socket.map(|local_socket| {
+                        local_acceptor(
+                            local_socket,
+                            keep_running.clone(),
+                            next_client_id,
                             outputs_sender,
                             parameters_sender,
+                            reload_error_sender.clone(),
                         )
                     });
                     let outputs_task = router(outputs_receiver);
@@ -133,6 +177,7 @@ where
                         parameters_writer,
                         parameters_changed.clone(),
                         parameters_storage_receiver,
+                        reload_error_sender,
                         parameters_directory,
                         body_id,
                         head_id,
@@ -144,9 +189,17 @@ where
                         Some(acceptor_task) => Some(acceptor_task.await),
                         None => None,
                     };
+                    let local_acceptor_task_result = match local_acceptor_task {
+                        Some(local_acceptor_task) => Some(local_acceptor_task.await),
+                        None => None,
+                    };
                     let outputs_task_result = outputs_task.await;
                     let parameters_subscriptions_task_result = parameters_subscriptions_task.await;
                     let parameters_storage_task_result = parameters_storage_task.await;
+                    let parameters_watcher_task_result = match parameters_watcher_task {
+                        Some(parameters_watcher_task) => Some(parameters_watcher_task.await),
+                        None => None,
+                    };
 
                     let mut task_errors = vec![];
                     if let Some(acceptor_task_result) = acceptor_task_result {
@@ -156,9 +209,19 @@ where
                             task_errors.push(StartError::AcceptError(error));
                         }
                     }
+                    if let Some(local_acceptor_task_result) = local_acceptor_task_result {
+                        if let Err(error) =
+                            local_acceptor_task_result.expect("failed to join local acceptor task")
+                        {
+                            task_errors.push(StartError::LocalAcceptError(error));
+                        }
+                    }
                     outputs_task_result.expect("failed to join outputs task");
                     parameters_subscriptions_task_result.expect("failed to join outputs task");
                     parameters_storage_task_result.expect("failed to join outputs task");
+                    if let Some(parameters_watcher_task_result) = parameters_watcher_task_result {
+                        parameters_watcher_task_result.expect("failed to join watcher task");
+                    }
 
                     if task_errors.is_empty() {
                         Ok(())
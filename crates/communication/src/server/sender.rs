@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use bincode::serialize;
 use futures_util::{stream::SplitSink, SinkExt};
-use serde_json::to_string;
+use serde_json::{to_string, Value};
 use tokio::{
     net::TcpStream,
     sync::mpsc::{Receiver, Sender},
@@ -11,7 +13,14 @@ use tokio_tungstenite::{
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::messages::Response;
+use crate::{
+    delta::Delta,
+    messages::{
+        Compression, Response, TextualDataOrBinaryReference, TextualOutputsResponse,
+        TextualResponse, TransportOptions, TransportResponse,
+    },
+    transport::{compress, supported_compression, FrameKind},
+};
 
 use super::connection::ReceiverOrSenderError;
 
@@ -21,9 +30,28 @@ pub async fn sender(
     keep_only_self_running: CancellationToken,
     mut response_receiver: Receiver<Response>,
 ) {
+    let mut transport_options = TransportOptions::default();
+    let mut last_subscribed_values = HashMap::new();
+    let mut pending_unsubscriptions = HashMap::new();
     while let Some(response) = response_receiver.recv().await {
+        let mut next_transport_options = None;
         let message = match response {
-            Response::Textual(textual) => {
+            Response::UnsubscribingOutput {
+                id,
+                subscription_id,
+            } => {
+                pending_unsubscriptions.insert(id, subscription_id);
+                continue;
+            }
+            Response::Textual(mut textual) => {
+                forget_unsubscribed_values(
+                    &textual,
+                    &mut pending_unsubscriptions,
+                    &mut last_subscribed_values,
+                );
+                if transport_options.delta_encoding {
+                    encode_deltas(&mut textual, &mut last_subscribed_values);
+                }
                 let message_string = match to_string(&textual) {
                     Ok(message_string) => message_string,
                     Err(error) => {
@@ -57,8 +85,51 @@ pub async fn sender(
                 code,
                 reason: reason.into(),
             })),
+            Response::ConfigureTransport { options } => {
+                let options = TransportOptions {
+                    compression: supported_compression(options.compression),
+                    ..options
+                };
+                // the acknowledgement is still encoded with the previous options
+                next_transport_options = Some(options);
+                let acknowledgement =
+                    TextualResponse::Transport(TransportResponse::Configure { options });
+                Message::Text(
+                    to_string(&acknowledgement).expect("transport response should be serializable"),
+                )
+            }
         };
 
+        let compressed_frame = match (transport_options.compression, &message) {
+            (Compression::None, _) => None,
+            (compression, Message::Text(message_string)) => Some(compress(
+                FrameKind::Textual,
+                message_string.as_bytes(),
+                compression,
+            )),
+            (compression, Message::Binary(message_bytes)) => {
+                Some(compress(FrameKind::Binary, message_bytes, compression))
+            }
+            _ => None,
+        };
+        let message = match compressed_frame {
+            Some(Ok(frame)) => Message::Binary(frame),
+            Some(Err(error)) => {
+                error_sender
+                    .send(ReceiverOrSenderError::NotCompressed(error))
+                    .await
+                    .expect("receiver should always wait for all senders");
+                keep_only_self_running.cancel();
+                continue;
+            }
+            None => message,
+        };
+
+        if let Some(options) = next_transport_options {
+            transport_options = options;
+            last_subscribed_values.clear();
+        }
+
         match writer.send(message).await {
             Ok(_) => {}
             Err(error) => {
@@ -71,3 +142,62 @@ pub async fn sender(
         }
     }
 }
+
+/// Outputs are no longer delivered to a subscription once its unsubscription is acknowledged
+fn forget_unsubscribed_values(
+    textual: &TextualResponse,
+    pending_unsubscriptions: &mut HashMap<usize, usize>,
+    last_subscribed_values: &mut HashMap<usize, Value>,
+) {
+    let TextualResponse::Outputs(TextualOutputsResponse::Unsubscribe { id, .. }) = textual else {
+        return;
+    };
+    if let Some(subscription_id) = pending_unsubscriptions.remove(id) {
+        last_subscribed_values.remove(&subscription_id);
+    }
+}
+
+fn encode_deltas(
+    textual: &mut TextualResponse,
+    last_subscribed_values: &mut HashMap<usize, Value>,
+) {
+    let TextualResponse::Outputs(TextualOutputsResponse::SubscribedData { items }) = textual else {
+        return;
+    };
+    for (subscription_id, item) in items.iter_mut() {
+        let TextualDataOrBinaryReference::TextualData { data } = item else {
+            continue;
+        };
+        let Some(last_value) = last_subscribed_values.insert(*subscription_id, data.clone()) else {
+            continue;
+        };
+        *item = TextualDataOrBinaryReference::TextualDelta {
+            delta: Delta::between(&last_value, data),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn acknowledged_unsubscriptions_forget_last_values() {
+        let mut pending_unsubscriptions = HashMap::from([(7, 3)]);
+        let mut last_subscribed_values = HashMap::from([(3, json!(42)), (4, json!(1337))]);
+
+        forget_unsubscribed_values(
+            &TextualResponse::Outputs(TextualOutputsResponse::Unsubscribe {
+                id: 7,
+                result: Ok(()),
+            }),
+            &mut pending_unsubscriptions,
+            &mut last_subscribed_values,
+        );
+
+        assert!(pending_unsubscriptions.is_empty());
+        assert_eq!(last_subscribed_values, HashMap::from([(4, json!(1337))]));
+    }
+}
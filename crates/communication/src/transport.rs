@@ -0,0 +1,198 @@
+use std::io::{self, Read};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::messages::Compression;
+
+const TEXTUAL_FRAME: u8 = 0;
+const BINARY_FRAME: u8 = 1;
+
+/// Higher zstd levels cost too much time of the robot for the bandwidth they save
+pub const MAXIMUM_ZSTD_LEVEL: i32 = 9;
+
+/// Length prefixes beyond this are rejected before allocating the payload
+pub const MAXIMUM_FRAME_LENGTH: usize = 64 * 1024 * 1024;
+/// Compressed payloads expanding beyond this are rejected to withstand decompression bombs
+pub const MAXIMUM_DECOMPRESSED_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Kind of a response carried in a compressed binary WebSocket message or a length delimited frame
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FrameKind {
+    Textual,
+    Binary,
+}
+
+impl FrameKind {
+    fn tag(self) -> u8 {
+        match self {
+            FrameKind::Textual => TEXTUAL_FRAME,
+            FrameKind::Binary => BINARY_FRAME,
+        }
+    }
+
+    fn from_tag(tag: u8) -> io::Result<Self> {
+        match tag {
+            TEXTUAL_FRAME => Ok(FrameKind::Textual),
+            BINARY_FRAME => Ok(FrameKind::Binary),
+            _ => Err(unknown_frame_kind()),
+        }
+    }
+}
+
+fn unknown_frame_kind() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "missing or unknown frame kind")
+}
+
+/// Limits the compression requested by a client to what the server is willing to spend
+pub fn supported_compression(compression: Compression) -> Compression {
+    match compression {
+        Compression::None => Compression::None,
+        Compression::Zstd { level } => Compression::Zstd {
+            level: level.clamp(1, MAXIMUM_ZSTD_LEVEL),
+        },
+    }
+}
+
+pub fn compress(kind: FrameKind, payload: &[u8], compression: Compression) -> io::Result<Vec<u8>> {
+    let mut frame = vec![kind.tag()];
+    match compression {
+        Compression::None => frame.extend_from_slice(payload),
+        Compression::Zstd { level } => {
+            zstd::stream::copy_encode(payload, &mut frame, level)?;
+        }
+    }
+    Ok(frame)
+}
+
+pub fn decompress(frame: &[u8], compression: Compression) -> io::Result<(FrameKind, Vec<u8>)> {
+    let (kind, payload) = match frame.split_first() {
+        Some((&tag, payload)) => (FrameKind::from_tag(tag)?, payload),
+        None => return Err(unknown_frame_kind()),
+    };
+    let payload = match compression {
+        Compression::None => payload.to_vec(),
+        Compression::Zstd { .. } => {
+            let mut decompressed = Vec::new();
+            zstd::stream::read::Decoder::new(payload)?
+                .take(MAXIMUM_DECOMPRESSED_LENGTH as u64 + 1)
+                .read_to_end(&mut decompressed)?;
+            if decompressed.len() > MAXIMUM_DECOMPRESSED_LENGTH {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("decompressed payload exceeds {MAXIMUM_DECOMPRESSED_LENGTH} bytes"),
+                ));
+            }
+            decompressed
+        }
+    };
+    Ok((kind, payload))
+}
+
+/// Frames a response for stream transports: kind tag, payload length as little endian `u32`,
+/// payload
+pub fn length_delimited(kind: FrameKind, payload: &[u8]) -> Vec<u8> {
+    let length = u32::try_from(payload.len()).expect("payload should be smaller than 4 GiB");
+    let mut frame = Vec::with_capacity(5 + payload.len());
+    frame.push(kind.tag());
+    frame.extend_from_slice(&length.to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Returns `None` if the stream ended before a new frame
+pub async fn read_length_delimited(
+    reader: &mut (impl AsyncRead + Unpin),
+) -> io::Result<Option<(FrameKind, Vec<u8>)>> {
+    let mut header = [0; 5];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let kind = FrameKind::from_tag(header[0])?;
+    let length = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if length > MAXIMUM_FRAME_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame length {length} exceeds {MAXIMUM_FRAME_LENGTH} bytes"),
+        ));
+    }
+    let mut payload = vec![0; length];
+    reader.read_exact(&mut payload).await?;
+    Ok(Some((kind, payload)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_frames_round_trip() {
+        let payload = r#"{"Outputs":{"SubscribedData":{"items":{}}}}"#.repeat(10);
+        let compression = Compression::Zstd { level: 3 };
+
+        let frame = compress(FrameKind::Textual, payload.as_bytes(), compression).unwrap();
+        let (kind, decompressed) = decompress(&frame, compression).unwrap();
+
+        assert!(frame.len() < payload.len());
+        assert_eq!(kind, FrameKind::Textual);
+        assert_eq!(decompressed, payload.as_bytes());
+    }
+
+    #[test]
+    fn payloads_decompressing_beyond_the_limit_are_rejected() {
+        let payload = vec![0; MAXIMUM_DECOMPRESSED_LENGTH + 1];
+        let compression = Compression::Zstd { level: 1 };
+
+        let frame = compress(FrameKind::Binary, &payload, compression).unwrap();
+        let error = decompress(&frame, compression).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn requested_zstd_levels_are_clamped() {
+        assert_eq!(
+            supported_compression(Compression::Zstd { level: 22 }),
+            Compression::Zstd {
+                level: MAXIMUM_ZSTD_LEVEL
+            }
+        );
+        assert_eq!(
+            supported_compression(Compression::Zstd { level: -7 }),
+            Compression::Zstd { level: 1 }
+        );
+        assert_eq!(
+            supported_compression(Compression::Zstd { level: 3 }),
+            Compression::Zstd { level: 3 }
+        );
+    }
+
+    #[tokio::test]
+    async fn length_delimited_frames_round_trip() {
+        let mut stream = length_delimited(FrameKind::Textual, b"{}");
+        stream.extend(length_delimited(FrameKind::Binary, &[1, 2, 3]));
+        let mut reader = stream.as_slice();
+
+        assert_eq!(
+            read_length_delimited(&mut reader).await.unwrap(),
+            Some((FrameKind::Textual, b"{}".to_vec()))
+        );
+        assert_eq!(
+            read_length_delimited(&mut reader).await.unwrap(),
+            Some((FrameKind::Binary, vec![1, 2, 3]))
+        );
+        assert_eq!(read_length_delimited(&mut reader).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn oversized_length_prefixes_are_rejected() {
+        let mut stream = vec![BINARY_FRAME];
+        stream.extend_from_slice(&u32::MAX.to_le_bytes());
+        let mut reader = stream.as_slice();
+
+        let error = read_length_delimited(&mut reader).await.unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
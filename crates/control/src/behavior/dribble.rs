@@ -2,8 +2,12 @@ use nalgebra::{Isometry2, Point2};
 
 use types::{
     geometry::rotate_towards,
+    kick_decision::KickKind,
     motion_command::{HeadMotion, MotionCommand, OrientationMode},
-    parameters::{DribblingParameters, InWalkKickInfoParameters, InWalkKicksParameters},
+    parameters::{
+        DribblingParameters, InWalkKickInfoParameters, InWalkKicksParameters,
+        StandingKicksParameters,
+    },
     planned_path::PathSegment,
     world_state::WorldState,
 };
@@ -15,6 +19,7 @@ pub fn execute(
     world_state: &WorldState,
     walk_path_planner: &WalkPathPlanner,
     in_walk_kicks: &InWalkKicksParameters,
+    standing_kicks: &StandingKicksParameters,
     parameters: &DribblingParameters,
     dribble_path: Option<Vec<PathSegment>>,
 ) -> Option<MotionCommand> {
@@ -29,15 +34,25 @@ pub fn execute(
         .iter()
         .chain(instant_kick_decisions.iter())
         .find(|decision| {
-            decision.visible
-                && is_kick_pose_reached(decision.kick_pose, &in_walk_kicks[decision.variant])
+            let kick_info = match decision.kind {
+                KickKind::InWalk(variant) => &in_walk_kicks[variant],
+                KickKind::Standing(variant) => &standing_kicks[variant],
+            };
+            decision.visible && is_kick_pose_reached(decision.kick_pose, kick_info)
         });
     if let Some(kick) = available_kick {
-        let command = MotionCommand::InWalkKick {
-            head,
-            kick: kick.variant,
-            kicking_side: kick.kicking_side,
-            strength: kick.strength,
+        let command = match kick.kind {
+            KickKind::InWalk(variant) => MotionCommand::InWalkKick {
+                head,
+                kick: variant,
+                kicking_side: kick.kicking_side,
+                strength: kick.strength,
+            },
+            KickKind::Standing(variant) => MotionCommand::StandingKick {
+                head,
+                kick: variant,
+                kicking_side: kick.kicking_side,
+            },
         };
         return Some(command);
     }
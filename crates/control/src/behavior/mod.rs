@@ -11,6 +11,7 @@ mod lost_ball;
 pub mod node;
 mod penalize;
 mod prepare_jump;
+mod receive_pass;
 mod search;
 mod sit_down;
 mod stand;
@@ -8,12 +8,14 @@ use spl_network_messages::{GamePhase, GameState, SubState, Team};
 use types::{
     action::Action,
     cycle_time::CycleTime,
+    fall_state::FallState,
     field_dimensions::FieldDimensions,
     filtered_game_state::FilteredGameState,
     game_controller_state::GameControllerState,
     motion_command::MotionCommand,
     parameters::{
         BehaviorParameters, InWalkKicksParameters, InterceptBallParameters, LostBallParameters,
+        StandingKicksParameters,
     },
     path_obstacles::PathObstacle,
     planned_path::PathSegment,
@@ -31,8 +33,8 @@ use super::{
     defend::Defend,
     dribble, fall_safely,
     head::LookAction,
-    initial, intercept_ball, jump, look_around, lost_ball, penalize, prepare_jump, search,
-    sit_down, stand, stand_up, support, unstiff, walk_to_kick_off, walk_to_penalty_kick,
+    initial, intercept_ball, jump, look_around, lost_ball, penalize, prepare_jump, receive_pass,
+    search, sit_down, stand, stand_up, support, unstiff, walk_to_kick_off, walk_to_penalty_kick,
     walk_to_pose::{WalkAndStand, WalkPathPlanner},
 };
 
@@ -40,6 +42,7 @@ pub struct Behavior {
     last_motion_command: MotionCommand,
     absolute_last_known_ball_position: Point2<f32>,
     active_since: Option<SystemTime>,
+    fallen_since: Option<SystemTime>,
 }
 
 #[context]
@@ -57,6 +60,7 @@ pub struct CycleContext {
 
     parameters: Parameter<BehaviorParameters, "behavior">,
     in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
+    standing_kicks: Parameter<StandingKicksParameters, "standing_kicks">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     lost_ball_parameters: Parameter<LostBallParameters, "behavior.lost_ball">,
     intercept_ball_parameters: Parameter<InterceptBallParameters, "behavior.intercept_ball">,
@@ -77,6 +81,7 @@ impl Behavior {
             last_motion_command: MotionCommand::Unstiff,
             absolute_last_known_ball_position: point![0.0, 0.0],
             active_since: None,
+            fallen_since: None,
         })
     }
 
@@ -107,6 +112,18 @@ impl Behavior {
             (Some(_), _) => self.active_since = None,
         }
 
+        match (world_state.robot.fall_state, self.fallen_since) {
+            (FallState::Fallen { .. }, None) => self.fallen_since = Some(now),
+            // the fall state may be upright in between attempts of standing up
+            (FallState::Upright, Some(_)) if *context.has_ground_contact => {
+                self.fallen_since = None
+            }
+            _ => {}
+        }
+        let fallen_duration = self
+            .fallen_since
+            .map(|fallen_since| now.duration_since(fallen_since).unwrap_or_default());
+
         let mut actions = vec![
             Action::Unstiff,
             Action::SitDown,
@@ -117,6 +134,7 @@ impl Behavior {
             Action::Stand,
             Action::InterceptBall,
             Action::Calibrate,
+            Action::ReceivePass,
         ];
 
         if let Some(active_since) = self.active_since {
@@ -218,7 +236,11 @@ impl Behavior {
                     Action::FallSafely => {
                         fall_safely::execute(world_state, *context.has_ground_contact)
                     }
-                    Action::StandUp => stand_up::execute(world_state),
+                    Action::StandUp => stand_up::execute(
+                        world_state,
+                        fallen_duration,
+                        context.parameters.maximum_stand_up_duration_when_critical,
+                    ),
                     Action::LookAround => look_around::execute(world_state),
                     Action::InterceptBall => intercept_ball::execute(
                         world_state,
@@ -226,6 +248,12 @@ impl Behavior {
                         *context.maximum_step_size,
                     ),
                     Action::Calibrate => calibrate::execute(world_state),
+                    Action::ReceivePass => receive_pass::execute(
+                        world_state,
+                        &walk_and_stand,
+                        &look_action,
+                        &mut context.path_obstacles,
+                    ),
                     Action::DefendGoal => defend.goal(&mut context.path_obstacles),
                     Action::DefendKickOff => defend.kick_off(&mut context.path_obstacles),
                     Action::DefendLeft => defend.left(&mut context.path_obstacles),
@@ -236,6 +264,7 @@ impl Behavior {
                         world_state,
                         &walk_path_planner,
                         context.in_walk_kicks,
+                        context.standing_kicks,
                         &context.parameters.dribbling,
                         dribble_path.clone(),
                     ),
@@ -0,0 +1,52 @@
+use framework::AdditionalOutput;
+use nalgebra::{Isometry2, Point2};
+use types::{
+    filtered_game_state::FilteredGameState,
+    geometry::rotate_towards,
+    motion_command::{HeadMotion, MotionCommand},
+    path_obstacles::PathObstacle,
+    roles::Role,
+    world_state::WorldState,
+};
+
+use super::{head::LookAction, walk_to_pose::WalkAndStand};
+
+pub fn execute(
+    world_state: &WorldState,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let incoming_pass = world_state.incoming_pass?;
+    if matches!(
+        world_state.robot.role,
+        Role::Striker | Role::Keeper | Role::ReplacementKeeper
+    ) {
+        return None;
+    }
+    match world_state.filtered_game_state {
+        None | Some(FilteredGameState::Playing { ball_is_free: true }) => {}
+        _ => return None,
+    }
+    let robot_to_field = world_state.robot.robot_to_field?;
+    let ball_in_field = world_state
+        .ball
+        .map(|ball| ball.ball_in_field)
+        .unwrap_or_else(Point2::origin);
+    let receiving_pose = Isometry2::new(
+        incoming_pass.target_in_field.coords,
+        rotate_towards(incoming_pass.target_in_field, ball_in_field).angle(),
+    );
+    let head = match world_state.ball {
+        Some(ball) => HeadMotion::LookAt {
+            target: ball.ball_in_ground,
+            camera: None,
+        },
+        None => look_action.execute(),
+    };
+    walk_and_stand.execute(
+        robot_to_field.inverse() * receiving_pose,
+        head,
+        path_obstacles_output,
+    )
+}
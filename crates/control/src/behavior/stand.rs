@@ -5,6 +5,7 @@ use types::{
     game_controller_state::GameControllerState,
     motion_command::{HeadMotion, MotionCommand},
     primary_state::PrimaryState,
+    robot_health::HealthState,
     roles::Role,
     world_state::WorldState,
 };
@@ -66,9 +67,64 @@ pub fn execute(
                     head: HeadMotion::Center,
                     is_energy_saving: true,
                 }),
+                // idle supporting robots are not needed to play the ball, degraded ones save energy
+                (
+                    _,
+                    Role::MidfielderLeft | Role::MidfielderRight | Role::StrikerSupporter,
+                    None,
+                ) if world_state.incoming_pass.is_none()
+                    && world_state.robot.health.state >= HealthState::Degraded =>
+                {
+                    Some(MotionCommand::Stand {
+                        head: HeadMotion::Center,
+                        is_energy_saving: true,
+                    })
+                }
                 _ => None,
             }
         }
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use nalgebra::Isometry2;
+    use types::{pass::IncomingPass, world_state::BallState};
+
+    use super::*;
+
+    #[test]
+    fn degraded_supporters_stand_only_when_idle() {
+        let mut world_state = WorldState::default();
+        world_state.robot.primary_state = PrimaryState::Playing;
+        world_state.robot.role = Role::StrikerSupporter;
+        world_state.robot.health.state = HealthState::Degraded;
+        let field_dimensions = FieldDimensions::default();
+
+        assert!(matches!(
+            execute(&world_state, &field_dimensions),
+            Some(MotionCommand::Stand {
+                is_energy_saving: true,
+                ..
+            })
+        ));
+
+        world_state.incoming_pass = Some(IncomingPass {
+            passer: Default::default(),
+            target_in_field: Point2::origin(),
+            announced_at: UNIX_EPOCH,
+        });
+        assert!(execute(&world_state, &field_dimensions).is_none());
+
+        world_state.incoming_pass = None;
+        world_state.ball = Some(BallState::new_at_center(Isometry2::identity()));
+        assert!(execute(&world_state, &field_dimensions).is_none());
+
+        world_state.ball = None;
+        world_state.robot.health.state = HealthState::Healthy;
+        assert!(execute(&world_state, &field_dimensions).is_none());
+    }
+}
@@ -1,8 +1,27 @@
-use types::{fall_state::FallState, motion_command::MotionCommand, world_state::WorldState};
+use std::time::Duration;
 
-pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
+use types::{
+    fall_state::FallState, motion_command::MotionCommand, robot_health::HealthState,
+    world_state::WorldState,
+};
+
+pub fn execute(
+    world_state: &WorldState,
+    fallen_duration: Option<Duration>,
+    maximum_stand_up_duration_when_critical: Duration,
+) -> Option<MotionCommand> {
     match world_state.robot.fall_state {
-        FallState::Fallen { facing } => Some(MotionCommand::StandUp { facing }),
+        FallState::Fallen { facing } => {
+            // retrying to stand up heats the joints even more, the robot waits for substitution
+            let stays_down = world_state.robot.health.state == HealthState::Critical
+                && fallen_duration
+                    .is_some_and(|duration| duration > maximum_stand_up_duration_when_critical);
+            if stays_down {
+                Some(MotionCommand::Unstiff)
+            } else {
+                Some(MotionCommand::StandUp { facing })
+            }
+        }
         _ => None,
     }
 }
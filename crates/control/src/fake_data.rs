@@ -16,6 +16,7 @@ use types::{
     parameters::{BallFilterParameters, CameraMatrixParameters, LookAtParameters},
     penalty_shot_direction::PenaltyShotDirection,
     primary_state::PrimaryState,
+    robot_health::RobotHealth,
     sensor_data::SensorData,
 };
 
@@ -49,6 +50,7 @@ pub struct MainOutputs {
     pub obstacles: MainOutput<Vec<Obstacle>>,
     pub penalty_shot_direction: MainOutput<Option<PenaltyShotDirection>>,
     pub primary_state: MainOutput<PrimaryState>,
+    pub robot_health: MainOutput<RobotHealth>,
     pub robot_to_field: MainOutput<Option<Isometry2<f32>>>,
     pub sensor_data: MainOutput<SensorData>,
     pub stand_up_front_estimated_remaining_duration: MainOutput<Option<Duration>>,
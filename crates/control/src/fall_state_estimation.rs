@@ -4,6 +4,7 @@ use color_eyre::Result;
 use context_attribute::context;
 use filtering::low_pass_filter::LowPassFilter;
 use framework::{AdditionalOutput, MainOutput};
+use log::info;
 use nalgebra::{vector, Isometry3, Translation3, UnitQuaternion, Vector2, Vector3};
 use types::{
     fall_state::FallState,
@@ -12,10 +13,14 @@ use types::{
     sensor_data::{InertialMeasurementUnitData, SensorData},
 };
 
+/// Prefix of the message logged when falling ends, classified by the game report
+pub const FALLEN_FACING: &str = "fallen facing ";
+
 pub struct FallStateEstimation {
     roll_pitch_filter: LowPassFilter<Vector2<f32>>,
     angular_velocity_filter: LowPassFilter<Vector3<f32>>,
     linear_acceleration_filter: LowPassFilter<Vector3<f32>>,
+    last_fall_state: FallState,
 }
 
 #[context]
@@ -61,6 +66,7 @@ impl FallStateEstimation {
                     .fall_state_estimation
                     .linear_acceleration_low_pass_factor,
             ),
+            last_fall_state: FallState::Upright,
         })
     }
 
@@ -176,6 +182,16 @@ impl FallStateEstimation {
             (None, Some(direction)) => FallState::Falling { direction },
             (None, None) => FallState::Upright,
         };
+        match (self.last_fall_state, fall_state) {
+            (FallState::Upright, FallState::Falling { direction }) => {
+                info!("falling {direction:?}")
+            }
+            (FallState::Upright | FallState::Falling { .. }, FallState::Fallen { facing }) => {
+                info!("{FALLEN_FACING}{facing:?}")
+            }
+            _ => {}
+        }
+        self.last_fall_state = fall_state;
 
         Ok(MainOutputs {
             fall_state: fall_state.into(),
@@ -43,7 +43,7 @@ impl GameControllerFilter {
             .flatten()
             .filter_map(|message| match message {
                 IncomingMessage::GameController(message) => Some(message),
-                IncomingMessage::Spl(_) => None,
+                IncomingMessage::Spl(_) | IncomingMessage::Health(_) => None,
             })
         {
             let game_state_changed = match &self.game_controller_state {
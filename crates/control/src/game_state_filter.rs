@@ -2,7 +2,7 @@ use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use context_attribute::context;
-use framework::MainOutput;
+use framework::{log_context, MainOutput};
 use nalgebra::{distance, Isometry2, Point2, Vector2};
 use spl_network_messages::{GamePhase, GameState, Team};
 use types::{
@@ -14,6 +14,7 @@ use types::{
 pub struct GameStateFilter {
     state: State,
     opponent_state: State,
+    last_logged_game_state: Option<FilteredGameState>,
 }
 
 #[context]
@@ -44,6 +45,7 @@ impl GameStateFilter {
         Ok(Self {
             state: State::Initial,
             opponent_state: State::Initial,
+            last_logged_game_state: None,
         })
     }
 
@@ -97,6 +99,12 @@ impl GameStateFilter {
                 context.config,
             );
 
+        // the log context is shared by all threads, it is only updated on changes
+        if self.last_logged_game_state != Some(filtered_game_state) {
+            log_context::set_game_state(&filtered_game_state);
+            self.last_logged_game_state = Some(filtered_game_state);
+        }
+
         Ok(MainOutputs {
             filtered_game_state: Some(filtered_game_state).into(),
             filtered_opponent_game_state: Some(filtered_opponent_game_state).into(),
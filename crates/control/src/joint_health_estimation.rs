@@ -0,0 +1,405 @@
+use std::{
+    fs::{create_dir_all, read_to_string, rename, write},
+    path::{Path, PathBuf},
+    sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+    thread,
+    time::SystemTime,
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use framework::AdditionalOutput;
+use hardware::IdInterface;
+use log::{info, warn};
+use types::{
+    cycle_time::CycleTime,
+    joint_health::{statistics_file_name, JointHealth, JointIssues},
+    joints::{Joints, JointsCommand},
+    parameters::JointHealthParameters,
+    sensor_data::SensorData,
+};
+
+pub struct JointHealthEstimation {
+    statistics: Joints<JointHealth>,
+    tracking: Joints<JointTracking>,
+    last_command: Option<JointsCommand<f32>>,
+    last_save: Option<SystemTime>,
+    statistics_path: PathBuf,
+    save_sender: SyncSender<(PathBuf, Joints<JointHealth>)>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct JointTracking {
+    settled_since: Option<SystemTime>,
+    approach: Option<Approach>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Approach {
+    FromBelow,
+    FromAbove,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct JointSample {
+    commanded_position: f32,
+    commanded_velocity: f32,
+    stiffness: f32,
+    measured_position: f32,
+}
+
+#[context]
+pub struct CreationContext {
+    hardware_interface: HardwareInterface,
+    statistics_directory: Parameter<PathBuf, "joint_health.statistics_directory">,
+}
+
+#[context]
+pub struct CycleContext {
+    joint_health: AdditionalOutput<Joints<JointHealth>, "joint_health">,
+
+    cycle_time: Input<CycleTime, "cycle_time">,
+    joints_command: Input<JointsCommand<f32>, "joints_command">,
+    sensor_data: Input<SensorData, "sensor_data">,
+
+    parameters: Parameter<JointHealthParameters, "joint_health">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl JointHealthEstimation {
+    pub fn new(context: CreationContext<impl IdInterface>) -> Result<Self> {
+        let body_id = context.hardware_interface.get_ids().body_id;
+        let statistics_path = context
+            .statistics_directory
+            .join(statistics_file_name(&body_id));
+        // statistics from previous runs are optional, estimation starts over if they are unusable
+        let statistics = match load_statistics(&statistics_path) {
+            Ok(Some(statistics)) => {
+                info!(
+                    "loaded joint health statistics from {}",
+                    statistics_path.display()
+                );
+                statistics
+            }
+            Ok(None) => Joints::default(),
+            Err(error) => {
+                warn!("starting with empty joint health statistics: {error:#}");
+                Joints::default()
+            }
+        };
+        // writing files takes too long for the control cycle
+        let (save_sender, save_receiver) = sync_channel(1);
+        thread::Builder::new()
+            .name("joint_health".to_string())
+            .spawn(move || save_statistics_until_disconnected(save_receiver))
+            .wrap_err("failed to spawn joint health statistics writer")?;
+        Ok(Self {
+            statistics,
+            tracking: Joints::default(),
+            last_command: None,
+            last_save: None,
+            statistics_path,
+            save_sender,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let cycle_duration = context.cycle_time.last_cycle_duration.as_secs_f32();
+        let parameters = context.parameters;
+
+        // joints can only follow a command in the cycles after it was sent
+        if let (Some(last_command), true) = (self.last_command, cycle_duration > 0.0) {
+            let commanded_velocities =
+                (context.joints_command.positions - last_command.positions) / cycle_duration;
+            let samples = last_command
+                .positions
+                .as_vec()
+                .into_iter()
+                .flatten()
+                .zip(commanded_velocities.as_vec().into_iter().flatten())
+                .zip(last_command.stiffnesses.as_vec().into_iter().flatten())
+                .zip(context.sensor_data.positions.as_vec().into_iter().flatten())
+                .map(
+                    |(((commanded_position, commanded_velocity), stiffness), measured_position)| {
+                        JointSample {
+                            commanded_position,
+                            commanded_velocity,
+                            stiffness,
+                            measured_position,
+                        }
+                    },
+                );
+            for ((health, tracking), sample) in self
+                .statistics
+                .as_vec_mut()
+                .into_iter()
+                .flatten()
+                .zip(self.tracking.as_vec_mut().into_iter().flatten())
+                .zip(samples)
+            {
+                update_joint(health, tracking, sample, now, parameters);
+                health.issues = evaluate_issues(health, parameters);
+            }
+        }
+        self.last_command = Some(*context.joints_command);
+
+        let last_save = *self.last_save.get_or_insert(now);
+        if now.duration_since(last_save).unwrap_or_default() >= parameters.save_interval {
+            match self
+                .save_sender
+                .try_send((self.statistics_path.clone(), self.statistics))
+            {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    warn!("skipped saving joint health statistics, the last save is still ongoing")
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    warn!("failed to save joint health statistics, the writer has stopped")
+                }
+            }
+            self.last_save = Some(now);
+        }
+
+        context.joint_health.fill_if_subscribed(|| self.statistics);
+
+        Ok(MainOutputs {})
+    }
+}
+
+fn update_joint(
+    health: &mut JointHealth,
+    tracking: &mut JointTracking,
+    sample: JointSample,
+    now: SystemTime,
+    parameters: &JointHealthParameters,
+) {
+    if sample.stiffness < parameters.minimum_stiffness {
+        *tracking = JointTracking::default();
+        return;
+    }
+    let error = sample.measured_position - sample.commanded_position;
+    let speed = sample.commanded_velocity.abs();
+
+    if speed >= parameters.minimum_moving_velocity {
+        health
+            .tracking_error
+            .update(error.abs(), parameters.maximum_number_of_samples);
+        tracking.approach = Some(if sample.commanded_velocity > 0.0 {
+            Approach::FromBelow
+        } else {
+            Approach::FromAbove
+        });
+        tracking.settled_since = None;
+    } else if speed <= parameters.maximum_settled_velocity {
+        let settled_since = *tracking.settled_since.get_or_insert(now);
+        if now.duration_since(settled_since).unwrap_or_default() < parameters.settling_duration {
+            return;
+        }
+        let settled_error = match tracking.approach {
+            Some(Approach::FromBelow) => &mut health.settled_error_from_below,
+            Some(Approach::FromAbove) => &mut health.settled_error_from_above,
+            None => return,
+        };
+        settled_error.update(error, parameters.maximum_number_of_samples);
+    } else {
+        tracking.settled_since = None;
+    }
+}
+
+fn evaluate_issues(health: &JointHealth, parameters: &JointHealthParameters) -> JointIssues {
+    let has_enough_samples = |samples: usize| samples >= parameters.minimum_number_of_samples;
+    let has_settled_from_both_directions =
+        has_enough_samples(health.settled_error_from_below.samples)
+            && has_enough_samples(health.settled_error_from_above.samples);
+    let has_settled_from_any_direction =
+        has_enough_samples(health.settled_error_from_below.samples)
+            || has_enough_samples(health.settled_error_from_above.samples);
+
+    JointIssues {
+        calibration_drift: has_settled_from_any_direction
+            && health
+                .offset()
+                .is_some_and(|offset| offset.abs() > parameters.maximum_offset),
+        backlash: has_settled_from_both_directions
+            && health
+                .backlash()
+                .is_some_and(|backlash| backlash > parameters.maximum_backlash),
+        stiffness_anomaly: has_enough_samples(health.tracking_error.samples)
+            && health.tracking_error.mean > parameters.maximum_tracking_error,
+    }
+}
+
+fn load_statistics(path: &Path) -> Result<Option<Joints<JointHealth>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        read_to_string(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .wrap_err_with(|| format!("failed to parse {}", path.display()))
+}
+
+fn save_statistics_until_disconnected(receiver: Receiver<(PathBuf, Joints<JointHealth>)>) {
+    for (path, statistics) in receiver {
+        if let Err(error) = save_statistics(&path, &statistics) {
+            warn!("failed to save joint health statistics: {error:#}");
+        }
+    }
+}
+
+fn save_statistics(path: &Path, statistics: &Joints<JointHealth>) -> Result<()> {
+    let contents =
+        serde_json::to_string_pretty(statistics).wrap_err("failed to serialize statistics")?;
+    if let Some(directory) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        create_dir_all(directory)
+            .wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+    }
+    // a crash while writing must not corrupt the statistics of previous runs
+    let temporary_path = path.with_extension("json.tmp");
+    write(&temporary_path, contents)
+        .wrap_err_with(|| format!("failed to write {}", temporary_path.display()))?;
+    rename(&temporary_path, path).wrap_err_with(|| format!("failed to replace {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn parameters() -> JointHealthParameters {
+        JointHealthParameters {
+            minimum_stiffness: 0.5,
+            maximum_settled_velocity: 0.05,
+            minimum_moving_velocity: 0.3,
+            settling_duration: Duration::from_millis(500),
+            maximum_number_of_samples: 1000,
+            minimum_number_of_samples: 10,
+            maximum_offset: 0.035,
+            maximum_backlash: 0.04,
+            maximum_tracking_error: 0.1,
+            statistics_directory: ".".into(),
+            save_interval: Duration::from_secs(60),
+        }
+    }
+
+    fn settle(
+        health: &mut JointHealth,
+        tracking: &mut JointTracking,
+        start: SystemTime,
+        commanded_velocity: f32,
+        measured_position: f32,
+    ) {
+        let parameters = parameters();
+        let mut sample = JointSample {
+            commanded_position: 0.0,
+            commanded_velocity,
+            stiffness: 1.0,
+            measured_position: 0.0,
+        };
+        update_joint(health, tracking, sample, start, &parameters);
+        sample.commanded_velocity = 0.0;
+        sample.measured_position = measured_position;
+        for cycle in 0..100 {
+            let now = start + Duration::from_millis(12 * cycle);
+            update_joint(health, tracking, sample, now, &parameters);
+        }
+    }
+
+    #[test]
+    fn play_between_directions_of_approach_is_backlash() {
+        let parameters = parameters();
+        let mut health = JointHealth::default();
+        let mut tracking = JointTracking::default();
+
+        settle(&mut health, &mut tracking, UNIX_EPOCH, 1.0, -0.04);
+        settle(
+            &mut health,
+            &mut tracking,
+            UNIX_EPOCH + Duration::from_secs(10),
+            -1.0,
+            0.04,
+        );
+
+        assert!(health.settled_error_from_below.samples > 0);
+        assert!((health.backlash().unwrap() - 0.08).abs() < 1e-6);
+        assert!(health.offset().unwrap().abs() < 1e-6);
+        let issues = evaluate_issues(&health, &parameters);
+        assert!(issues.backlash);
+        assert!(!issues.calibration_drift);
+        assert!(!issues.stiffness_anomaly);
+    }
+
+    #[test]
+    fn consistent_settled_error_is_calibration_drift() {
+        let parameters = parameters();
+        let mut health = JointHealth::default();
+        let mut tracking = JointTracking::default();
+
+        settle(&mut health, &mut tracking, UNIX_EPOCH, 1.0, 0.05);
+
+        assert!((health.offset().unwrap() - 0.05).abs() < 1e-6);
+        let issues = evaluate_issues(&health, &parameters);
+        assert!(issues.calibration_drift);
+        assert!(!issues.backlash);
+        assert!(!issues.stiffness_anomaly);
+    }
+
+    #[test]
+    fn lagging_moving_joints_are_stiffness_anomalies() {
+        let parameters = parameters();
+        let mut health = JointHealth::default();
+        let mut tracking = JointTracking::default();
+        let sample = JointSample {
+            commanded_position: 0.5,
+            commanded_velocity: 1.0,
+            stiffness: 1.0,
+            measured_position: 0.3,
+        };
+
+        for cycle in 0..parameters.minimum_number_of_samples as u64 {
+            let now = UNIX_EPOCH + Duration::from_millis(12 * cycle);
+            update_joint(&mut health, &mut tracking, sample, now, &parameters);
+        }
+
+        assert!((health.tracking_error.mean - 0.2).abs() < 1e-6);
+        let issues = evaluate_issues(&health, &parameters);
+        assert!(issues.stiffness_anomaly);
+        assert!(!issues.calibration_drift);
+        assert!(!issues.backlash);
+    }
+
+    #[test]
+    fn saved_statistics_are_loaded() {
+        let directory = tempdir().unwrap();
+        let path = directory.path().join("statistics/joint_health.json");
+        let mut statistics = Joints::<JointHealth>::default();
+        let mut tracking = JointTracking::default();
+        settle(
+            &mut statistics.left_leg.knee_pitch,
+            &mut tracking,
+            UNIX_EPOCH,
+            1.0,
+            0.05,
+        );
+
+        assert!(load_statistics(&path).unwrap().is_none());
+        save_statistics(&path, &statistics).unwrap();
+        let loaded_statistics = load_statistics(&path).unwrap().unwrap();
+
+        assert_eq!(
+            serde_json::to_value(loaded_statistics).unwrap(),
+            serde_json::to_value(statistics).unwrap()
+        );
+    }
+}
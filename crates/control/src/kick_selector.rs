@@ -1,4 +1,7 @@
-use std::{cmp::Ordering, time::Duration};
+use std::{
+    cmp::Ordering,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::Result;
 use context_attribute::context;
@@ -10,12 +13,17 @@ use types::{
     cycle_time::CycleTime,
     field_dimensions::FieldDimensions,
     geometry::{rotate_towards, Circle, LineSegment, TwoLineSegments},
-    kick_decision::KickDecision,
+    kick_decision::{KickDecision, KickKind},
     kick_target::KickTarget,
-    motion_command::KickVariant,
-    obstacles::Obstacle,
-    parameters::{FindKickTargetsParameters, InWalkKickInfoParameters, InWalkKicksParameters},
+    motion_command::{KickVariant, StandingKickVariant},
+    obstacles::{Obstacle, ObstacleKind},
+    parameters::{
+        FindKickTargetsParameters, InWalkKickInfoParameters, InWalkKicksParameters,
+        PassPlanningParameters, StandingKicksParameters,
+    },
+    pass::{PassDecision, PlannedPass},
     support_foot::Side,
+    teammate::Teammate,
     world_state::BallState,
 };
 
@@ -32,10 +40,12 @@ pub struct CycleContext {
     robot_to_field: RequiredInput<Option<Isometry2<f32>>, "robot_to_field?">,
     ball_state: RequiredInput<Option<BallState>, "ball_state?">,
     obstacles: Input<Vec<Obstacle>, "obstacles">,
+    teammates: Input<Vec<Teammate>, "teammates">,
 
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
 
     in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
+    standing_kicks: Parameter<StandingKicksParameters, "standing_kicks">,
     angle_distance_weight: Parameter<f32, "kick_selector.angle_distance_weight">,
     max_kick_around_obstacle_angle: Parameter<f32, "kick_selector.max_kick_around_obstacle_angle">,
     kick_pose_obstacle_radius: Parameter<f32, "kick_selector.kick_pose_obstacle_radius">,
@@ -46,9 +56,13 @@ pub struct CycleContext {
 
     default_kick_strength: Parameter<f32, "kick_selector.default_kick_strength">,
     corner_kick_strength: Parameter<f32, "kick_selector.corner_kick_strength">,
+    pass_planning: Parameter<PassPlanningParameters, "kick_selector.pass_planning">,
 
     kick_targets: AdditionalOutput<Vec<KickTarget>, "kick_targets">,
     instant_kick_targets: AdditionalOutput<Vec<Point2<f32>>, "instant_kick_targets">,
+    pass_candidates: AdditionalOutput<Vec<PassDecision>, "pass_candidates">,
+
+    planned_pass: PersistentState<PlannedPass, "planned_pass">,
 }
 
 #[context]
@@ -83,6 +97,20 @@ impl KickSelector {
         if context.in_walk_kicks.side.enabled {
             kick_variants.push(KickVariant::Side)
         }
+        let mut standing_kick_variants = Vec::new();
+        if context.standing_kicks.straight.enabled {
+            standing_kick_variants.push(StandingKickVariant::Straight)
+        }
+        if context.standing_kicks.outer.enabled {
+            standing_kick_variants.push(StandingKickVariant::Outer)
+        }
+        if !has_time_for_standing_kick(context.obstacles, ball_position, context.standing_kicks) {
+            standing_kick_variants.clear();
+        }
+        let maximum_in_walk_shot_distance = kick_variants
+            .iter()
+            .map(|&variant| context.in_walk_kicks[variant].shot_distance)
+            .fold(0.0, f32::max);
 
         let obstacle_circles = generate_obstacle_circles(
             context.obstacles,
@@ -117,12 +145,12 @@ impl KickSelector {
             .kick_targets
             .fill_if_subscribed(|| kick_targets.clone());
 
-        let mut kick_decisions: Vec<_> = iproduct!(sides, kick_variants)
+        let mut kick_decisions: Vec<_> = iproduct!(sides, kick_variants.iter().copied())
             .filter_map(|(side, kick_variant)| {
                 kick_decisions_from_targets(
                     &kick_targets,
-                    context.in_walk_kicks,
-                    kick_variant,
+                    KickKind::InWalk(kick_variant),
+                    &context.in_walk_kicks[kick_variant],
                     side,
                     ball_position,
                     ball_is_visible,
@@ -132,27 +160,115 @@ impl KickSelector {
             .flatten()
             .collect();
 
-        kick_decisions.sort_by(|left, right| {
-            let left_in_obstacle = is_inside_any_obstacle(
-                left.kick_pose,
-                context.obstacles,
-                *context.kick_pose_obstacle_radius,
-            );
-            let right_in_obstacle = is_inside_any_obstacle(
-                right.kick_pose,
+        sort_kick_decisions(
+            &mut kick_decisions,
+            context.obstacles,
+            *context.kick_pose_obstacle_radius,
+            *context.angle_distance_weight,
+        );
+
+        let long_kick_targets: Vec<_> = kick_targets
+            .iter()
+            .filter(|target| {
+                distance(&ball_position, &target.position) > maximum_in_walk_shot_distance
+            })
+            .copied()
+            .collect();
+        let mut standing_kick_decisions: Vec<_> =
+            iproduct!(sides, standing_kick_variants.iter().copied())
+                .filter_map(|(side, kick_variant)| {
+                    kick_decisions_from_targets(
+                        &long_kick_targets,
+                        KickKind::Standing(kick_variant),
+                        &context.standing_kicks[kick_variant],
+                        side,
+                        ball_position,
+                        ball_is_visible,
+                        *context.default_kick_strength,
+                    )
+                })
+                .flatten()
+                .collect();
+        sort_kick_decisions(
+            &mut standing_kick_decisions,
+            context.obstacles,
+            *context.kick_pose_obstacle_radius,
+            *context.angle_distance_weight,
+        );
+        kick_decisions.splice(0..0, standing_kick_decisions);
+
+        let goal_kick_score = goal_kick_score(
+            *context.robot_to_field * ball_position,
+            context.field_dimensions,
+            context.pass_planning,
+        );
+        let pass_decision = if context.pass_planning.enabled {
+            let pass_candidates: Vec<_> = context
+                .teammates
+                .iter()
+                .filter_map(|teammate| {
+                    evaluate_pass(
+                        teammate,
+                        ball_position,
+                        *context.robot_to_field,
+                        context.obstacles,
+                        context.field_dimensions,
+                        context.pass_planning,
+                        context.cycle_time.start_time,
+                    )
+                })
+                .collect();
+            context
+                .pass_candidates
+                .fill_if_subscribed(|| pass_candidates.clone());
+            pass_candidates
+                .into_iter()
+                .filter(|candidate| {
+                    candidate.score >= context.pass_planning.minimum_score
+                        && (kick_decisions.is_empty() || candidate.score > goal_kick_score)
+                })
+                .max_by(|left, right| left.score.total_cmp(&right.score))
+        } else {
+            None
+        };
+
+        if let Some(pass_decision) = pass_decision {
+            let kick_kinds = kick_variants
+                .iter()
+                .map(|&variant| KickKind::InWalk(variant))
+                .chain(
+                    standing_kick_variants
+                        .iter()
+                        .map(|&variant| KickKind::Standing(variant)),
+                );
+            let mut pass_kick_decisions: Vec<_> = iproduct!(sides, kick_kinds)
+                .filter_map(|(side, kind)| {
+                    let kick_info = match kind {
+                        KickKind::InWalk(variant) => &context.in_walk_kicks[variant],
+                        KickKind::Standing(variant) => &context.standing_kicks[variant],
+                    };
+                    kick_decision_for_pass(
+                        &pass_decision,
+                        kind,
+                        kick_info,
+                        side,
+                        ball_position,
+                        ball_is_visible,
+                        *context.default_kick_strength,
+                        context.pass_planning.minimum_strength,
+                    )
+                })
+                .collect();
+            sort_kick_decisions(
+                &mut pass_kick_decisions,
                 context.obstacles,
                 *context.kick_pose_obstacle_radius,
+                *context.angle_distance_weight,
             );
-            let distance_to_left =
-                distance_to_kick_pose(left.kick_pose, *context.angle_distance_weight);
-            let distance_to_right =
-                distance_to_kick_pose(right.kick_pose, *context.angle_distance_weight);
-            match (left_in_obstacle, right_in_obstacle) {
-                (true, false) => Ordering::Less,
-                (false, true) => Ordering::Greater,
-                _ => distance_to_left.total_cmp(&distance_to_right),
-            }
-        });
+            // only passes scoring higher than the goal kicks are planned
+            kick_decisions.splice(0..0, pass_kick_decisions);
+        }
+        context.planned_pass.decision = pass_decision;
 
         Ok(MainOutputs {
             kick_decisions: Some(kick_decisions).into(),
@@ -237,7 +353,7 @@ fn generate_decisions_for_instant_kicks(
                     .mutate_if_subscribed(|targets| targets.as_mut().unwrap().push(target));
                 let kick_pose = compute_kick_pose(ball_position, target, kick_info, kicking_side);
                 Some(KickDecision {
-                    variant,
+                    kind: KickKind::InWalk(variant),
                     kicking_side,
                     kick_pose,
                     strength: default_kick_strength,
@@ -381,8 +497,8 @@ fn generate_goal_line_kick_targets(
 
 fn kick_decisions_from_targets(
     targets_to_kick_to: &[KickTarget],
-    in_walk_kicks: &InWalkKicksParameters,
-    variant: KickVariant,
+    kind: KickKind,
+    kick_info: &InWalkKickInfoParameters,
     kicking_side: Side,
     ball_position: Point2<f32>,
     ball_is_visible: bool,
@@ -392,10 +508,9 @@ fn kick_decisions_from_targets(
         targets_to_kick_to
             .iter()
             .map(|&KickTarget { position, strength }| {
-                let kick_info = &in_walk_kicks[variant];
                 let kick_pose = compute_kick_pose(ball_position, position, kick_info, kicking_side);
                 KickDecision {
-                    variant,
+                    kind,
                     kicking_side,
                     kick_pose,
                     strength: strength.unwrap_or(default_strength),
@@ -406,6 +521,156 @@ fn kick_decisions_from_targets(
     )
 }
 
+fn sort_kick_decisions(
+    kick_decisions: &mut [KickDecision],
+    obstacles: &[Obstacle],
+    kick_pose_obstacle_radius: f32,
+    angle_distance_weight: f32,
+) {
+    kick_decisions.sort_by(|left, right| {
+        let left_in_obstacle =
+            is_inside_any_obstacle(left.kick_pose, obstacles, kick_pose_obstacle_radius);
+        let right_in_obstacle =
+            is_inside_any_obstacle(right.kick_pose, obstacles, kick_pose_obstacle_radius);
+        let distance_to_left = distance_to_kick_pose(left.kick_pose, angle_distance_weight);
+        let distance_to_right = distance_to_kick_pose(right.kick_pose, angle_distance_weight);
+        match (left_in_obstacle, right_in_obstacle) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => distance_to_left.total_cmp(&distance_to_right),
+        }
+    });
+}
+
+/// Chance of scoring with a goal kick from the ball position, comparable to pass scores
+fn goal_kick_score(
+    ball_in_field: Point2<f32>,
+    field_dimensions: &FieldDimensions,
+    parameters: &PassPlanningParameters,
+) -> f32 {
+    let opponent_goal_center = point![field_dimensions.length / 2.0, 0.0];
+    (1.0 - distance(&ball_in_field, &opponent_goal_center) / parameters.maximum_goal_kick_distance)
+        .clamp(0.0, 1.0)
+}
+
+fn evaluate_pass(
+    teammate: &Teammate,
+    ball_position: Point2<f32>,
+    robot_to_field: Isometry2<f32>,
+    obstacles: &[Obstacle],
+    field_dimensions: &FieldDimensions,
+    parameters: &PassPlanningParameters,
+    now: SystemTime,
+) -> Option<PassDecision> {
+    if teammate.fallen || teammate.requests_substitution {
+        return None;
+    }
+    let field_to_robot = robot_to_field.inverse();
+    let receiver_in_field = teammate.predicted_position_in_field(now);
+    let ball_travel_time =
+        distance(&ball_position, &(field_to_robot * receiver_in_field)) / parameters.ball_speed;
+    let target_in_field = receiver_in_field + teammate.velocity_in_field * ball_travel_time;
+    let target_in_ground = field_to_robot * target_in_field;
+
+    let pass_distance = distance(&ball_position, &target_in_ground);
+    if pass_distance < parameters.minimum_pass_distance
+        || pass_distance > parameters.maximum_pass_distance
+        || !field_dimensions.is_inside_field(target_in_field)
+    {
+        return None;
+    }
+
+    let opponent_goal_center = point![field_dimensions.length / 2.0, 0.0];
+    let forward_gain = distance(&(robot_to_field * ball_position), &opponent_goal_center)
+        - distance(&target_in_field, &opponent_goal_center);
+    if forward_gain < parameters.minimum_forward_gain {
+        return None;
+    }
+
+    let receiver_in_ground = field_to_robot * receiver_in_field;
+    let pass_line = LineSegment(ball_position, target_in_ground);
+    let interception_risk = obstacles
+        .iter()
+        .filter(|obstacle| !matches!(obstacle.kind, ObstacleKind::Ball))
+        .filter(|obstacle| {
+            distance(&obstacle.position, &receiver_in_ground) > obstacle.radius_at_foot_height
+        })
+        .map(|obstacle| {
+            let interception_point = pass_line.closest_point(obstacle.position);
+            let ball_time = distance(&ball_position, &interception_point) / parameters.ball_speed;
+            let obstacle_time = (distance(&obstacle.position, &interception_point)
+                - obstacle.radius_at_foot_height)
+                .max(0.0)
+                / parameters.opponent_speed;
+            (1.0 - (obstacle_time - ball_time) / parameters.interception_time_margin)
+                .clamp(0.0, 1.0)
+        })
+        .fold(0.0, f32::max);
+
+    let ball_time = pass_distance / parameters.ball_speed;
+    let receiver_time = distance(&receiver_in_field, &target_in_field) / parameters.receiver_speed;
+    let reachability =
+        (1.0 - (receiver_time - ball_time) / parameters.reachability_time_margin).clamp(0.0, 1.0);
+
+    Some(PassDecision {
+        receiver: teammate.player_number,
+        target_in_ground,
+        target_in_field,
+        interception_risk,
+        reachability,
+        score: (1.0 - interception_risk) * reachability,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn kick_decision_for_pass(
+    pass_decision: &PassDecision,
+    kind: KickKind,
+    kick_info: &InWalkKickInfoParameters,
+    kicking_side: Side,
+    ball_position: Point2<f32>,
+    ball_is_visible: bool,
+    default_strength: f32,
+    minimum_strength: f32,
+) -> Option<KickDecision> {
+    let pass_distance = distance(&ball_position, &pass_decision.target_in_ground);
+    if kick_info.shot_distance < pass_distance {
+        return None;
+    }
+    let strength = (default_strength * pass_distance / kick_info.shot_distance)
+        .clamp(minimum_strength, default_strength);
+    let kick_pose = compute_kick_pose(
+        ball_position,
+        pass_decision.target_in_ground,
+        kick_info,
+        kicking_side,
+    );
+    Some(KickDecision {
+        kind,
+        kicking_side,
+        kick_pose,
+        strength,
+        visible: ball_is_visible,
+    })
+}
+
+fn has_time_for_standing_kick(
+    obstacles: &[Obstacle],
+    ball_position: Point2<f32>,
+    parameters: &StandingKicksParameters,
+) -> bool {
+    let execution_time = parameters.execution_time.as_secs_f32();
+    obstacles
+        .iter()
+        .filter(|obstacle| matches!(obstacle.kind, ObstacleKind::Robot | ObstacleKind::Unknown))
+        .all(|obstacle| {
+            let distance_to_ball = (distance(&obstacle.position, &ball_position)
+                - obstacle.radius_at_foot_height)
+                .max(0.0);
+            distance_to_ball / parameters.opponent_speed > execution_time
+        })
+}
+
 fn distance_to_kick_pose(kick_pose: Isometry2<f32>, angle_distance_weight: f32) -> f32 {
     kick_pose.translation.vector.norm() + angle_distance_weight * kick_pose.rotation.angle().abs()
 }
@@ -466,3 +731,137 @@ fn is_ball_in_opponents_corners(
         distance(&global_ball, &right_opponent_corner) < parameters.distance_from_corner;
     ball_near_left_opponent_corner || ball_near_right_opponent_corner
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use spl_network_messages::PlayerNumber;
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            ..Default::default()
+        }
+    }
+
+    fn parameters() -> PassPlanningParameters {
+        PassPlanningParameters {
+            enabled: true,
+            minimum_pass_distance: 1.0,
+            maximum_pass_distance: 4.0,
+            ball_speed: 1.0,
+            opponent_speed: 0.25,
+            receiver_speed: 0.25,
+            interception_time_margin: 2.0,
+            reachability_time_margin: 2.0,
+            minimum_strength: 0.3,
+            minimum_forward_gain: 0.0,
+            minimum_score: 0.5,
+            maximum_goal_kick_distance: 6.0,
+        }
+    }
+
+    fn teammate_at(position: Point2<f32>) -> Teammate {
+        Teammate {
+            player_number: PlayerNumber::Four,
+            robot_to_field: Isometry2::translation(position.x, position.y),
+            velocity_in_field: Vector2::zeros(),
+            fallen: false,
+            requests_substitution: false,
+            last_received: UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn free_pass_to_standing_teammate_is_safe() {
+        let pass = evaluate_pass(
+            &teammate_at(point![2.0, 0.0]),
+            point![0.0, 0.0],
+            Isometry2::identity(),
+            &[],
+            &field_dimensions(),
+            &parameters(),
+            UNIX_EPOCH,
+        )
+        .expect("pass should be possible");
+
+        assert_eq!(pass.interception_risk, 0.0);
+        assert_eq!(pass.reachability, 1.0);
+        assert_eq!(pass.score, 1.0);
+    }
+
+    #[test]
+    fn obstacle_on_pass_line_increases_interception_risk() {
+        let pass = evaluate_pass(
+            &teammate_at(point![3.0, 0.0]),
+            point![0.0, 0.0],
+            Isometry2::identity(),
+            &[Obstacle::robot(point![1.5, 0.1], 0.2, 0.3)],
+            &field_dimensions(),
+            &parameters(),
+            UNIX_EPOCH,
+        )
+        .expect("pass should be evaluated");
+
+        assert_eq!(pass.interception_risk, 1.0);
+        assert_eq!(pass.score, 0.0);
+    }
+
+    #[test]
+    fn fallen_and_backward_teammates_are_not_passed_to() {
+        let mut fallen_teammate = teammate_at(point![2.0, 0.0]);
+        fallen_teammate.fallen = true;
+        let backward_teammate = teammate_at(point![-2.0, 0.0]);
+
+        for teammate in [fallen_teammate, backward_teammate] {
+            assert!(evaluate_pass(
+                &teammate,
+                point![0.0, 0.0],
+                Isometry2::identity(),
+                &[],
+                &field_dimensions(),
+                &parameters(),
+                UNIX_EPOCH,
+            )
+            .is_none());
+        }
+    }
+
+    #[test]
+    fn standing_kick_requires_opponents_to_be_far_from_ball() {
+        let parameters = StandingKicksParameters {
+            execution_time: Duration::from_secs(4),
+            opponent_speed: 0.3,
+            ..Default::default()
+        };
+        let ball_position = point![0.0, 0.0];
+
+        assert!(has_time_for_standing_kick(&[], ball_position, &parameters));
+        assert!(has_time_for_standing_kick(
+            &[Obstacle::robot(point![2.0, 0.0], 0.2, 0.3)],
+            ball_position,
+            &parameters,
+        ));
+        assert!(!has_time_for_standing_kick(
+            &[Obstacle::robot(point![1.0, 0.0], 0.2, 0.3)],
+            ball_position,
+            &parameters,
+        ));
+    }
+
+    #[test]
+    fn goal_kicks_close_to_the_goal_outscore_passes() {
+        let parameters = parameters();
+
+        let far_from_goal = goal_kick_score(point![-2.0, 0.0], &field_dimensions(), &parameters);
+        let close_to_goal = goal_kick_score(point![3.0, 0.0], &field_dimensions(), &parameters);
+
+        assert_eq!(far_from_goal, 0.0);
+        assert!(close_to_goal > parameters.minimum_score);
+        assert!(close_to_goal < 1.0);
+    }
+}
@@ -13,6 +13,7 @@ pub mod game_controller_filter;
 pub mod game_state_filter;
 pub mod ground_contact_detector;
 pub mod ground_provider;
+pub mod joint_health_estimation;
 pub mod kick_selector;
 pub mod kinematics_provider;
 pub mod led_status;
@@ -25,12 +26,14 @@ pub mod orientation_filter;
 pub mod path_planner;
 pub mod penalty_shot_direction_estimation;
 pub mod primary_state_filter;
+pub mod robot_health_estimation;
 pub mod role_assignment;
 pub mod rule_obstacle_composer;
 pub mod sensor_data_receiver;
 pub mod sole_pressure_filter;
 pub mod sonar_filter;
 pub mod support_foot_estimation;
+pub mod teammate_filter;
 pub mod time_to_reach_kick_position;
 pub mod visual_referee_filter;
 pub mod whistle_filter;
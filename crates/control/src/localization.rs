@@ -8,6 +8,7 @@ use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
 use filtering::pose_filter::PoseFilter;
 use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
+use log::info;
 use nalgebra::{
     distance, matrix, point, vector, Isometry2, Matrix, Matrix2, Matrix3, Point2, Rotation2,
     Translation2, Vector2, Vector3,
@@ -21,13 +22,21 @@ use types::{
     initial_pose::InitialPose,
     line::{Line, Line2},
     line_data::LineData,
-    localization::{ScoredPose, Update},
+    localization::{LocalizationMode, ScoredPose, Update},
     multivariate_normal_distribution::MultivariateNormalDistribution,
+    parameters::ParticleFilterParameters,
     players::Players,
     primary_state::PrimaryState,
     support_foot::Side,
 };
 
+use self::particle_filter::ParticleFilter;
+
+mod particle_filter;
+
+/// Prefix of the messages logged when hypotheses are reset, classified by the game report
+pub const HYPOTHESES_RESET: &str = "hypotheses reset";
+
 pub struct Localization {
     field_marks: Vec<FieldMark>,
     last_primary_state: PrimaryState,
@@ -35,6 +44,8 @@ pub struct Localization {
     hypotheses_when_entered_playing: Vec<ScoredPose>,
     is_penalized_with_motion_in_set: bool,
     was_picked_up_while_penalized_with_motion_in_set: bool,
+    particle_filter: ParticleFilter,
+    last_mode: LocalizationMode,
 }
 
 #[context]
@@ -77,7 +88,9 @@ pub struct CycleContext {
     maximum_amount_of_outer_iterations:
         Parameter<usize, "localization.maximum_amount_of_outer_iterations">,
     minimum_fit_error: Parameter<f32, "localization.minimum_fit_error">,
+    mode: Parameter<LocalizationMode, "localization.mode">,
     odometry_noise: Parameter<Vector3<f32>, "localization.odometry_noise">,
+    particle_filter: Parameter<ParticleFilterParameters, "localization.particle_filter">,
     player_number: Parameter<PlayerNumber, "player_number">,
     score_per_good_match: Parameter<f32, "localization.score_per_good_match">,
     use_line_measurements: Parameter<bool, "localization.use_line_measurements">,
@@ -114,6 +127,8 @@ impl Localization {
             hypotheses_when_entered_playing: vec![],
             is_penalized_with_motion_in_set: false,
             was_picked_up_while_penalized_with_motion_in_set: false,
+            particle_filter: ParticleFilter::new(),
+            last_mode: LocalizationMode::default(),
         })
     }
 
@@ -123,13 +138,15 @@ impl Localization {
         game_phase: Option<GamePhase>,
         context: &CycleContext,
         penalty: &Option<Penalty>,
-    ) {
+    ) -> bool {
+        let mut hypotheses_were_reset = false;
         match (self.last_primary_state, primary_state, game_phase) {
             (PrimaryState::Initial, PrimaryState::Ready, _) => {
                 let initial_pose = generate_initial_pose(
                     &context.initial_poses[*context.player_number],
                     context.field_dimensions,
                 );
+                hypotheses_were_reset = true;
                 self.hypotheses = vec![ScoredPose::from_isometry(
                     initial_pose,
                     *context.initial_hypothesis_covariance,
@@ -149,6 +166,7 @@ impl Localization {
                         + (context.field_dimensions.length / 2.0),
                     0.0,
                 );
+                hypotheses_were_reset = true;
                 self.hypotheses = vec![ScoredPose::from_isometry(
                     penalty_shoot_out_striker_pose,
                     *context.initial_hypothesis_covariance,
@@ -165,6 +183,7 @@ impl Localization {
             ) => {
                 let penalty_shoot_out_keeper_pose =
                     Isometry2::translation(-context.field_dimensions.length / 2.0, 0.0);
+                hypotheses_were_reset = true;
                 self.hypotheses = vec![ScoredPose::from_isometry(
                     penalty_shoot_out_keeper_pose,
                     *context.initial_hypothesis_covariance,
@@ -187,6 +206,7 @@ impl Localization {
             (PrimaryState::Penalized, _, _) if primary_state != PrimaryState::Penalized => {
                 if self.is_penalized_with_motion_in_set {
                     if self.was_picked_up_while_penalized_with_motion_in_set {
+                        hypotheses_were_reset = true;
                         self.hypotheses = take(&mut self.hypotheses_when_entered_playing);
 
                         let penalized_poses = generate_penalized_poses(context.field_dimensions);
@@ -205,6 +225,7 @@ impl Localization {
                     self.was_picked_up_while_penalized_with_motion_in_set = false;
                 } else {
                     let penalized_poses = generate_penalized_poses(context.field_dimensions);
+                    hypotheses_were_reset = true;
                     self.hypotheses = penalized_poses
                         .into_iter()
                         .map(|pose| {
@@ -220,6 +241,7 @@ impl Localization {
             }
             (PrimaryState::Unstiff, _, _) => {
                 let penalized_poses = generate_penalized_poses(context.field_dimensions);
+                hypotheses_were_reset = true;
                 self.hypotheses = penalized_poses
                     .into_iter()
                     .map(|pose| {
@@ -234,6 +256,7 @@ impl Localization {
             }
             _ => {}
         }
+        hypotheses_were_reset
     }
 
     fn update_state(&mut self, context: &mut CycleContext) -> Result<()> {
@@ -472,8 +495,30 @@ impl Localization {
             .game_controller_state
             .map(|game_controller_state| game_controller_state.game_phase);
 
-        self.reset_state(primary_state, game_phase, &context, &penalty);
+        let hypotheses_were_reset = self.reset_state(primary_state, game_phase, &context, &penalty);
+        if hypotheses_were_reset {
+            info!("{HYPOTHESES_RESET} in {primary_state:?}");
+        }
         self.last_primary_state = primary_state;
+        // the state of the previously active mode is outdated, both continue from the last estimate
+        let mode_changed = *context.mode != self.last_mode;
+        if mode_changed && !hypotheses_were_reset {
+            self.hypotheses = vec![ScoredPose::from_isometry(
+                *context.robot_to_field,
+                *context.initial_hypothesis_covariance,
+                *context.initial_hypothesis_score,
+            )];
+            info!("{HYPOTHESES_RESET} after switching to {:?}", context.mode);
+        }
+        self.last_mode = *context.mode;
+        if *context.mode == LocalizationMode::ParticleFilter
+            && (hypotheses_were_reset || mode_changed || self.particle_filter.is_empty())
+        {
+            self.particle_filter.reset(
+                &self.hypotheses,
+                context.particle_filter.number_of_particles,
+            );
+        }
 
         if self.is_penalized_with_motion_in_set && !context.has_ground_contact {
             self.was_picked_up_while_penalized_with_motion_in_set = true;
@@ -481,7 +526,10 @@ impl Localization {
 
         let robot_to_field = match primary_state {
             PrimaryState::Ready | PrimaryState::Set | PrimaryState::Playing => {
-                self.update_state(&mut context)?;
+                match context.mode {
+                    LocalizationMode::Hypotheses => self.update_state(&mut context)?,
+                    LocalizationMode::ParticleFilter => self.update_particle_filter(&mut context),
+                }
                 Some(*context.robot_to_field)
             }
             _ => None,
@@ -512,6 +560,103 @@ impl Localization {
         })
     }
 
+    fn update_particle_filter(&mut self, context: &mut CycleContext) {
+        context.measured_lines_in_field.fill_if_subscribed(Vec::new);
+        context.correspondence_lines.fill_if_subscribed(Vec::new);
+        context.updates.fill_if_subscribed(Vec::new);
+        context.fit_errors.fill_if_subscribed(Vec::new);
+
+        let line_datas = context
+            .line_data_top
+            .persistent
+            .iter()
+            .zip(context.line_data_bottom.persistent.iter());
+        for (
+            (line_data_top_timestamp, line_data_top),
+            (line_data_bottom_timestamp, line_data_bottom),
+        ) in line_datas
+        {
+            assert_eq!(line_data_top_timestamp, line_data_bottom_timestamp);
+            if let Some(current_odometry_to_last_odometry) = context
+                .current_odometry_to_last_odometry
+                .get(line_data_top_timestamp)
+            {
+                self.particle_filter.predict(
+                    current_odometry_to_last_odometry,
+                    context.particle_filter.prediction_noise,
+                );
+            }
+            if !*context.use_line_measurements {
+                continue;
+            }
+            let mut measured_lines_in_robot: Vec<_> = line_data_top
+                .iter()
+                .chain(line_data_bottom.iter())
+                .filter_map(|data| data.as_ref())
+                .flat_map(|line_data| line_data.lines_in_robot.iter().copied())
+                .collect();
+            // every line is matched against all field marks for every particle, the longest lines
+            // are the most informative ones
+            measured_lines_in_robot.sort_by(|left, right| {
+                distance(&right.0, &right.1).total_cmp(&distance(&left.0, &left.1))
+            });
+            measured_lines_in_robot.truncate(context.particle_filter.maximum_number_of_lines);
+            if measured_lines_in_robot.is_empty() {
+                continue;
+            }
+            self.particle_filter.update(
+                &measured_lines_in_robot,
+                &self.field_marks,
+                *context.line_length_acceptance_factor,
+                context.field_dimensions,
+                context.particle_filter,
+            );
+
+            let robot_to_field = self.particle_filter.estimate();
+            let measured_lines_in_field: Vec<_> = measured_lines_in_robot
+                .iter()
+                .map(|&measured_line_in_robot| robot_to_field * measured_line_in_robot)
+                .collect();
+            if context.correspondence_lines.is_subscribed() {
+                let field_mark_correspondences = get_field_mark_correspondence(
+                    &measured_lines_in_field,
+                    Isometry2::identity(),
+                    &self.field_marks,
+                    *context.line_length_acceptance_factor,
+                );
+                context
+                    .correspondence_lines
+                    .mutate_if_subscribed(|correspondence_lines| {
+                        if let Some(correspondence_lines) = correspondence_lines {
+                            correspondence_lines.extend(
+                                get_correspondence_points(field_mark_correspondences)
+                                    .into_iter()
+                                    .map(|correspondence_points| {
+                                        Line(
+                                            correspondence_points.measured,
+                                            correspondence_points.reference,
+                                        )
+                                    }),
+                            );
+                        }
+                    });
+            }
+            context
+                .measured_lines_in_field
+                .mutate_if_subscribed(|lines| {
+                    if let Some(lines) = lines {
+                        lines.extend(measured_lines_in_field);
+                    }
+                });
+        }
+
+        context.pose_hypotheses.fill_if_subscribed(|| {
+            self.particle_filter
+                .scored_poses(*context.initial_hypothesis_covariance)
+        });
+        *context.robot_to_field = self.particle_filter.estimate();
+    }
+
     fn get_best_hypothesis(&self) -> Option<&ScoredPose> {
         self.hypotheses
             .iter()
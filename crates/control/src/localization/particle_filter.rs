@@ -0,0 +1,338 @@
+use std::f32::consts::{PI, TAU};
+
+use nalgebra::{vector, Isometry2, Matrix3, Vector3};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use types::{
+    field_dimensions::FieldDimensions, field_marks::FieldMark, line::Line2,
+    localization::ScoredPose, parameters::ParticleFilterParameters,
+};
+
+use super::get_field_mark_correspondence;
+
+#[derive(Clone, Copy, Debug)]
+struct Particle {
+    robot_to_field: Isometry2<f32>,
+    weight: f32,
+}
+
+/// Monte-Carlo localization using the field mark correspondences of the measured lines as
+/// likelihood. Uniformly distributed particles are injected when the short term fit error grows
+/// beyond the long term fit error (sensor resetting), which allows recovering from kidnapping.
+pub struct ParticleFilter {
+    particles: Vec<Particle>,
+    estimate: Isometry2<f32>,
+    short_term_fit_error: Option<f32>,
+    long_term_fit_error: Option<f32>,
+    random_number_generator: StdRng,
+}
+
+impl ParticleFilter {
+    pub fn new() -> Self {
+        Self {
+            particles: vec![],
+            estimate: Isometry2::identity(),
+            short_term_fit_error: None,
+            long_term_fit_error: None,
+            random_number_generator: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn estimate(&self) -> Isometry2<f32> {
+        self.estimate
+    }
+
+    pub fn reset(&mut self, seeds: &[ScoredPose], number_of_particles: usize) {
+        let Some(best_seed) = seeds
+            .iter()
+            .max_by(|left, right| left.score.total_cmp(&right.score))
+        else {
+            return;
+        };
+        self.estimate = pose_from_state(best_seed.state.mean);
+        let weight = 1.0 / number_of_particles as f32;
+        self.particles = (0..number_of_particles)
+            .map(|index| {
+                let seed = &seeds[index % seeds.len()];
+                let standard_deviation = seed.state.covariance.diagonal().map(f32::sqrt);
+                let noise = sample_noise(&mut self.random_number_generator, standard_deviation);
+                Particle {
+                    robot_to_field: pose_from_state(seed.state.mean + noise),
+                    weight,
+                }
+            })
+            .collect();
+        self.short_term_fit_error = None;
+        self.long_term_fit_error = None;
+    }
+
+    pub fn predict(
+        &mut self,
+        current_odometry_to_last_odometry: &Isometry2<f32>,
+        prediction_noise: Vector3<f32>,
+    ) {
+        let odometry = vector![
+            current_odometry_to_last_odometry.translation.x,
+            current_odometry_to_last_odometry.translation.y,
+            current_odometry_to_last_odometry.rotation.angle()
+        ];
+        for particle in self.particles.iter_mut() {
+            let noise = sample_noise(&mut self.random_number_generator, prediction_noise);
+            particle.robot_to_field *= pose_from_state(odometry + noise);
+        }
+        self.estimate *= current_odometry_to_last_odometry;
+    }
+
+    pub fn update(
+        &mut self,
+        measured_lines_in_robot: &[Line2],
+        field_marks: &[FieldMark],
+        line_length_acceptance_factor: f32,
+        field_dimensions: &FieldDimensions,
+        parameters: &ParticleFilterParameters,
+    ) {
+        if self.particles.is_empty() || measured_lines_in_robot.is_empty() {
+            return;
+        }
+
+        let mut mean_fit_error = 0.0;
+        for particle in self.particles.iter_mut() {
+            let measured_lines_in_field: Vec<_> = measured_lines_in_robot
+                .iter()
+                .map(|&line| particle.robot_to_field * line)
+                .collect();
+            let field_mark_correspondences = get_field_mark_correspondence(
+                &measured_lines_in_field,
+                Isometry2::identity(),
+                field_marks,
+                line_length_acceptance_factor,
+            );
+            let number_of_unmatched_lines =
+                measured_lines_in_field.len() - field_mark_correspondences.len();
+            let fit_error = (field_mark_correspondences
+                .iter()
+                .map(|correspondence| {
+                    (correspondence.fit_error_sum() / 2.0).min(parameters.maximum_fit_error)
+                })
+                .sum::<f32>()
+                + number_of_unmatched_lines as f32 * parameters.maximum_fit_error)
+                / measured_lines_in_field.len() as f32;
+            mean_fit_error += particle.weight * fit_error;
+            particle.weight *= (-0.5
+                * (fit_error / parameters.measurement_standard_deviation).powi(2)
+                * measured_lines_in_field.len() as f32)
+                .exp();
+        }
+        self.normalize_weights();
+        self.estimate = self.cluster_mean(parameters);
+
+        let short_term_fit_error = low_pass(
+            self.short_term_fit_error,
+            mean_fit_error,
+            parameters.short_term_fit_error_low_pass_factor,
+        );
+        let long_term_fit_error = low_pass(
+            self.long_term_fit_error,
+            mean_fit_error,
+            parameters.long_term_fit_error_low_pass_factor,
+        );
+        self.short_term_fit_error = Some(short_term_fit_error);
+        self.long_term_fit_error = Some(long_term_fit_error);
+
+        self.resample();
+
+        let injection_fraction = if short_term_fit_error > parameters.minimum_resetting_fit_error {
+            (1.0 - long_term_fit_error / short_term_fit_error).max(0.0)
+                * parameters.maximum_injection_fraction
+        } else {
+            0.0
+        };
+        let number_of_injected_particles =
+            (injection_fraction * self.particles.len() as f32).round() as usize;
+        for _ in 0..number_of_injected_particles {
+            let index = self
+                .random_number_generator
+                .gen_range(0..self.particles.len());
+            self.particles[index].robot_to_field = Isometry2::new(
+                vector![
+                    self.random_number_generator.gen_range(-0.5..=0.5) * field_dimensions.length,
+                    self.random_number_generator.gen_range(-0.5..=0.5) * field_dimensions.width
+                ],
+                self.random_number_generator.gen_range(-PI..PI),
+            );
+        }
+    }
+
+    pub fn scored_poses(&self, covariance: Matrix3<f32>) -> Vec<ScoredPose> {
+        self.particles
+            .iter()
+            .map(|particle| {
+                ScoredPose::from_isometry(particle.robot_to_field, covariance, particle.weight)
+            })
+            .collect()
+    }
+
+    fn normalize_weights(&mut self) {
+        let weight_sum: f32 = self.particles.iter().map(|particle| particle.weight).sum();
+        let uniform_weight = 1.0 / self.particles.len() as f32;
+        for particle in self.particles.iter_mut() {
+            particle.weight = if weight_sum > 0.0 && weight_sum.is_finite() {
+                particle.weight / weight_sum
+            } else {
+                uniform_weight
+            };
+        }
+    }
+
+    fn cluster_mean(&self, parameters: &ParticleFilterParameters) -> Isometry2<f32> {
+        let best_particle = self
+            .particles
+            .iter()
+            .max_by(|left, right| left.weight.total_cmp(&right.weight))
+            .expect("expected at least one particle");
+        let best_angle = best_particle.robot_to_field.rotation.angle();
+        let (weight_sum, translation_sum, angle_offset_sum) = self
+            .particles
+            .iter()
+            .filter(|particle| {
+                (particle.robot_to_field.translation.vector
+                    - best_particle.robot_to_field.translation.vector)
+                    .norm()
+                    < parameters.estimate_cluster_distance
+                    && normalized_angle(particle.robot_to_field.rotation.angle() - best_angle).abs()
+                        < parameters.estimate_cluster_angle
+            })
+            .fold(
+                (0.0, vector![0.0, 0.0], 0.0),
+                |(weight_sum, translation_sum, angle_offset_sum), particle| {
+                    (
+                        weight_sum + particle.weight,
+                        translation_sum
+                            + particle.robot_to_field.translation.vector * particle.weight,
+                        angle_offset_sum
+                            + normalized_angle(
+                                particle.robot_to_field.rotation.angle() - best_angle,
+                            ) * particle.weight,
+                    )
+                },
+            );
+        if weight_sum <= 0.0 {
+            return best_particle.robot_to_field;
+        }
+        Isometry2::new(
+            translation_sum / weight_sum,
+            best_angle + angle_offset_sum / weight_sum,
+        )
+    }
+
+    fn resample(&mut self) {
+        let number_of_particles = self.particles.len();
+        let step = 1.0 / number_of_particles as f32;
+        let mut target = self.random_number_generator.gen_range(0.0..step);
+        let mut cumulative_weight = 0.0;
+        let mut resampled = Vec::with_capacity(number_of_particles);
+        for particle in self.particles.iter() {
+            cumulative_weight += particle.weight;
+            while target < cumulative_weight && resampled.len() < number_of_particles {
+                resampled.push(Particle {
+                    robot_to_field: particle.robot_to_field,
+                    weight: step,
+                });
+                target += step;
+            }
+        }
+        // compensate for numerical inaccuracies of the cumulative weight
+        while resampled.len() < number_of_particles {
+            let last_particle = *resampled.last().unwrap_or(&self.particles[0]);
+            resampled.push(Particle {
+                weight: step,
+                ..last_particle
+            });
+        }
+        self.particles = resampled;
+    }
+}
+
+fn pose_from_state(state: Vector3<f32>) -> Isometry2<f32> {
+    Isometry2::new(state.xy(), state.z)
+}
+
+fn sample_noise(
+    random_number_generator: &mut StdRng,
+    standard_deviation: Vector3<f32>,
+) -> Vector3<f32> {
+    standard_deviation.map(|standard_deviation| {
+        Normal::new(0.0, standard_deviation.max(0.0))
+            .map(|distribution| distribution.sample(random_number_generator))
+            .unwrap_or(0.0)
+    })
+}
+
+fn low_pass(state: Option<f32>, measurement: f32, smoothing_factor: f32) -> f32 {
+    match state {
+        Some(state) => state + smoothing_factor * (measurement - state),
+        None => measurement,
+    }
+}
+
+fn normalized_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::point;
+    use types::{field_marks::Direction, line::Line};
+
+    use super::*;
+
+    #[test]
+    fn particles_matching_the_measured_lines_dominate_the_estimate() {
+        let field_marks = [FieldMark::Line {
+            line: Line(point![0.0, -3.0], point![0.0, 3.0]),
+            direction: Direction::PositiveY,
+        }];
+        let parameters = ParticleFilterParameters {
+            number_of_particles: 20,
+            measurement_standard_deviation: 0.2,
+            maximum_fit_error: 1.0,
+            short_term_fit_error_low_pass_factor: 0.3,
+            long_term_fit_error_low_pass_factor: 0.02,
+            minimum_resetting_fit_error: 0.2,
+            maximum_injection_fraction: 0.0,
+            estimate_cluster_distance: 0.5,
+            estimate_cluster_angle: 0.5,
+            ..Default::default()
+        };
+        let matching_pose = Isometry2::translation(-1.0, 0.0);
+        let wrong_pose = Isometry2::translation(-2.0, 0.0);
+        let mut particle_filter = ParticleFilter::new();
+        particle_filter.reset(
+            &[
+                ScoredPose::from_isometry(wrong_pose, Matrix3::zeros(), 2.0),
+                ScoredPose::from_isometry(matching_pose, Matrix3::zeros(), 1.0),
+            ],
+            parameters.number_of_particles,
+        );
+        assert_eq!(particle_filter.estimate(), wrong_pose);
+
+        particle_filter.update(
+            &[Line(point![1.0, -1.0], point![1.0, 1.0])],
+            &field_marks,
+            1.5,
+            &FieldDimensions::default(),
+            &parameters,
+        );
+
+        let estimate = particle_filter.estimate();
+        assert!((estimate.translation.vector - matching_pose.translation.vector).norm() < 1e-3);
+        assert!(particle_filter
+            .scored_poses(Matrix3::zeros())
+            .iter()
+            .all(|pose| (pose.state.mean.x - -1.0).abs() < 1e-3));
+    }
+}
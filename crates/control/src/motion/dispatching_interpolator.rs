@@ -33,6 +33,7 @@ pub struct CycleContext {
     sit_down_joints_command: Input<JointsCommand<f32>, "sit_down_joints_command">,
     stand_up_back_positions: Input<Joints<f32>, "stand_up_back_positions">,
     stand_up_front_positions: Input<Joints<f32>, "stand_up_front_positions">,
+    standing_kick_joints_command: Input<JointsCommand<f32>, "standing_kick_joints_command">,
     walk_joints_command: Input<BodyJointsCommand<f32>, "walk_joints_command">,
 
     penalized_pose: Parameter<Joints<f32>, "penalized_pose">,
@@ -92,6 +93,7 @@ impl DispatchingInterpolator {
                 ),
                 MotionType::StandUpBack => *context.stand_up_back_positions,
                 MotionType::StandUpFront => *context.stand_up_front_positions,
+                MotionType::StandingKick => context.standing_kick_joints_command.positions,
                 MotionType::Unstiff => panic!("Dispatching Unstiff doesn't make sense"),
                 MotionType::Walk => Joints::from_head_and_body(
                     HeadJoints::fill(0.0),
@@ -1,6 +1,6 @@
 use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
-use framework::AdditionalOutput;
+use framework::{AdditionalOutput, MainOutput};
 use hardware::ActuatorInterface;
 use types::{
     joints::{BodyJointsCommand, HeadJointsCommand, Joints, JointsCommand},
@@ -39,6 +39,7 @@ pub struct CycleContext {
     sit_down_joints_command: Input<JointsCommand<f32>, "sit_down_joints_command">,
     stand_up_back_positions: Input<Joints<f32>, "stand_up_back_positions">,
     stand_up_front_positions: Input<Joints<f32>, "stand_up_front_positions">,
+    standing_kick_joints_command: Input<JointsCommand<f32>, "standing_kick_joints_command">,
     walk_joints_command: Input<BodyJointsCommand<f32>, "walk_joints_command">,
     hardware_interface: HardwareInterface,
     leds: Input<Leds, "leds">,
@@ -46,7 +47,9 @@ pub struct CycleContext {
 
 #[context]
 #[derive(Default)]
-pub struct MainOutputs {}
+pub struct MainOutputs {
+    pub joints_command: MainOutput<JointsCommand<f32>>,
+}
 
 impl JointCommandSender {
     pub fn new(_context: CreationContext) -> Result<Self> {
@@ -69,6 +72,7 @@ impl JointCommandSender {
         let sit_down = context.sit_down_joints_command;
         let stand_up_back_positions = context.stand_up_back_positions;
         let stand_up_front_positions = context.stand_up_front_positions;
+        let standing_kick = context.standing_kick_joints_command;
         let walk = context.walk_joints_command;
 
         let (positions, stiffnesses) = match motion_selection.current_motion {
@@ -88,6 +92,16 @@ impl JointCommandSender {
             ),
             MotionType::StandUpBack => (*stand_up_back_positions, Joints::fill(1.0)),
             MotionType::StandUpFront => (*stand_up_front_positions, Joints::fill(1.0)),
+            MotionType::StandingKick => (
+                Joints::from_head_and_body(
+                    head_joints_command.positions,
+                    standing_kick.positions.into(),
+                ),
+                Joints::from_head_and_body(
+                    head_joints_command.stiffnesses,
+                    standing_kick.stiffnesses.into(),
+                ),
+            ),
             MotionType::Unstiff => (current_positions, Joints::fill(0.0)),
             MotionType::Walk => (
                 Joints::from_head_and_body(head_joints_command.positions, walk.positions),
@@ -129,6 +143,12 @@ impl JointCommandSender {
             .motion_safe_exits_output
             .fill_if_subscribed(|| context.motion_safe_exits.clone());
 
-        Ok(MainOutputs {})
+        Ok(MainOutputs {
+            joints_command: JointsCommand {
+                positions,
+                stiffnesses,
+            }
+            .into(),
+        })
     }
 }
@@ -13,6 +13,7 @@ pub mod motion_selector;
 pub mod sit_down;
 pub mod stand_up_back;
 pub mod stand_up_front;
+pub mod standing_kick;
 pub mod step_planner;
 pub mod walk_manager;
 pub mod walking_engine;
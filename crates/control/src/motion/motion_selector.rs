@@ -99,6 +99,7 @@ fn motion_type_from_command(
         MotionCommand::Unstiff => MotionType::Unstiff,
         MotionCommand::Walk { .. } => MotionType::Walk,
         MotionCommand::InWalkKick { .. } => MotionType::Walk,
+        MotionCommand::StandingKick { .. } => MotionType::StandingKick,
     }
 }
 
@@ -116,6 +117,7 @@ fn transition_motion(
         (MotionType::StandUpBack, _, MotionType::FallProtection, _) => MotionType::StandUpBack,
         (MotionType::StandUpFront, true, MotionType::StandUpFront, _) => MotionType::Dispatching,
         (MotionType::StandUpBack, true, MotionType::StandUpBack, _) => MotionType::Dispatching,
+        (MotionType::StandingKick, true, MotionType::StandingKick, _) => MotionType::Dispatching,
         (_, _, MotionType::FallProtection, _) => MotionType::FallProtection,
         (MotionType::Dispatching, true, _, _) => to,
         (MotionType::Stand, _, MotionType::Walk, _) => MotionType::Walk,
@@ -0,0 +1,99 @@
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use hardware::PathsInterface;
+use motionfile::{MotionFile, MotionInterpolator};
+use types::{
+    condition_input::ConditionInput,
+    cycle_time::CycleTime,
+    joints::{Joints, JointsCommand},
+    motion_command::{MotionCommand, StandingKickVariant},
+    motion_selection::{MotionSafeExits, MotionSelection, MotionType},
+    support_foot::Side,
+};
+
+pub struct StandingKick {
+    left: MotionInterpolator<Joints<f32>>,
+    right: MotionInterpolator<Joints<f32>>,
+    left_outer: MotionInterpolator<Joints<f32>>,
+    right_outer: MotionInterpolator<Joints<f32>>,
+    current_kick: (StandingKickVariant, Side),
+}
+
+#[context]
+pub struct CreationContext {
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+pub struct CycleContext {
+    motion_safe_exits: PersistentState<MotionSafeExits, "motion_safe_exits">,
+
+    condition_input: Input<ConditionInput, "condition_input">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    motion_command: Input<MotionCommand, "motion_command">,
+    motion_selection: Input<MotionSelection, "motion_selection">,
+
+    stiffness: Parameter<f32, "standing_kicks.stiffness">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub standing_kick_joints_command: MainOutput<JointsCommand<f32>>,
+}
+
+impl StandingKick {
+    pub fn new(context: CreationContext<impl PathsInterface>) -> Result<Self> {
+        let paths = context.hardware_interface.get_paths();
+        Ok(Self {
+            left: MotionFile::from_path(paths.motions.join("standing_kick_left.json"))?
+                .try_into()?,
+            right: MotionFile::from_path(paths.motions.join("standing_kick_right.json"))?
+                .try_into()?,
+            left_outer: MotionFile::from_path(paths.motions.join("standing_kick_left_outer.json"))?
+                .try_into()?,
+            right_outer: MotionFile::from_path(
+                paths.motions.join("standing_kick_right_outer.json"),
+            )?
+            .try_into()?,
+            current_kick: (StandingKickVariant::Straight, Side::Left),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let last_cycle_duration = context.cycle_time.last_cycle_duration;
+        if context.motion_selection.current_motion == MotionType::StandingKick {
+            self.current_interpolator()
+                .advance_by(last_cycle_duration, context.condition_input);
+        } else {
+            if let MotionCommand::StandingKick {
+                kick, kicking_side, ..
+            } = context.motion_command
+            {
+                self.current_kick = (*kick, *kicking_side);
+            }
+            self.current_interpolator().reset();
+        }
+
+        let interpolator = self.current_interpolator();
+        context.motion_safe_exits[MotionType::StandingKick] = interpolator.is_finished();
+
+        Ok(MainOutputs {
+            standing_kick_joints_command: JointsCommand {
+                positions: interpolator.value(),
+                stiffnesses: Joints::fill(*context.stiffness),
+            }
+            .into(),
+        })
+    }
+
+    fn current_interpolator(&mut self) -> &mut MotionInterpolator<Joints<f32>> {
+        match self.current_kick {
+            (StandingKickVariant::Straight, Side::Left) => &mut self.left,
+            (StandingKickVariant::Straight, Side::Right) => &mut self.right,
+            (StandingKickVariant::Outer, Side::Left) => &mut self.left_outer,
+            (StandingKickVariant::Outer, Side::Right) => &mut self.right_outer,
+        }
+    }
+}
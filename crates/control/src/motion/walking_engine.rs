@@ -8,6 +8,7 @@ use log::warn;
 use nalgebra::{Isometry3, Point3, Vector2, Vector3};
 use serde::{Deserialize, Serialize};
 use types::{
+    balance_control::BalanceControlState,
     cycle_time::CycleTime,
     joints::{ArmJoints, BodyJoints, BodyJointsCommand, LegJoints},
     motion_command::{KickVariant, MotionCommand},
@@ -15,6 +16,7 @@ use types::{
     parameters::{KickStepsParameters, StepPlannerParameters, WalkingEngineParameters},
     robot_kinematics::RobotKinematics,
     sensor_data::{InertialMeasurementUnitData, SensorData},
+    sole_pressure::SolePressure,
     step_adjustment::StepAdjustment,
     step_plan::Step,
     support_foot::Side,
@@ -23,6 +25,7 @@ use types::{
 
 use self::{
     arms::SwingingArm,
+    balance_control::BalanceController,
     balancing::{step_adjustment, support_leg_gyro_balancing, swing_leg_foot_leveling},
     engine::{calculate_foot_to_robot, parabolic_return, parabolic_step},
     foot_offsets::FootOffsets,
@@ -31,6 +34,7 @@ use self::{
 };
 
 mod arms;
+mod balance_control;
 mod balancing;
 mod engine;
 mod foot_offsets;
@@ -89,6 +93,8 @@ pub struct WalkingEngine {
     filtered_imu_pitch: LowPassFilter<f32>,
     /// Low pass filter the robot tilt for step adjustments
     filtered_robot_tilt_shift: LowPassFilter<f32>,
+    /// Capture point and zero moment point feedback adapting step timing and placement
+    balance_controller: BalanceController,
     /// Foot offsets for the left foot the walking engine interpolation generated for the last cycle
     last_left_walk_request: FootOffsets,
     /// Foot offsets for the right foot the walking engine interpolation generated for the last cycle
@@ -126,6 +132,7 @@ pub struct CycleContext {
     t_on_last_phase_end: AdditionalOutput<Duration, "walking_engine.t_on_last_phase_end">,
     normalized_forward_speed: AdditionalOutput<f32, "walking_engine.normalized_forward_speed">,
     has_support_changed: AdditionalOutput<bool, "walking_engine.has_support_changed">,
+    balance_control: AdditionalOutput<BalanceControlState, "walking_engine.balance_control">,
     // TODO: ask hendrik how to do that
     // walking_engine: AdditionalOutput<WalkingEngine, "walking_engine">,
     config: Parameter<WalkingEngineParameters, "walking_engine">,
@@ -141,6 +148,8 @@ pub struct CycleContext {
     cycle_time: Input<CycleTime, "cycle_time">,
     has_ground_contact: Input<bool, "has_ground_contact">,
     walk_command: Input<WalkCommand, "walk_command">,
+    center_of_mass: Input<Point3<f32>, "center_of_mass">,
+    sole_pressure: Input<SolePressure, "sole_pressure">,
 }
 
 #[context]
@@ -164,6 +173,7 @@ impl WalkingEngine {
                 0.0,
                 context.config.tilt_shift_low_pass_factor,
             ),
+            balance_controller: BalanceController::new(&context.config.balance_control),
             left_arm: SwingingArm::new(Side::Left),
             right_arm: SwingingArm::new(Side::Right),
             ..Default::default()
@@ -200,6 +210,35 @@ impl WalkingEngine {
             self.walk_state = WalkState::Standing;
         }
 
+        let balance_control_state = match self.walk_state {
+            WalkState::Walking(_) if context.config.balance_control.enabled => {
+                let support_side = self.swing_side.opposite();
+                let (support_sole_to_robot, support_center_of_pressure) = match support_side {
+                    Side::Left => (
+                        context.robot_kinematics.left_sole_to_robot,
+                        context.sole_pressure.left_center_of_pressure,
+                    ),
+                    Side::Right => (
+                        context.robot_kinematics.right_sole_to_robot,
+                        context.sole_pressure.right_center_of_pressure,
+                    ),
+                };
+                Some(self.balance_controller.update(
+                    *context.center_of_mass,
+                    support_sole_to_robot,
+                    context.sensor_data.inertial_measurement_unit.roll_pitch,
+                    support_center_of_pressure,
+                    support_side,
+                    last_cycle_duration,
+                    &context.config.balance_control,
+                ))
+            }
+            _ => {
+                self.balance_controller.reset();
+                None
+            }
+        };
+
         match &self.walk_state {
             WalkState::Standing => self.reset(),
             WalkState::Starting(_) | WalkState::Walking(_) | WalkState::Stopping => {
@@ -207,6 +246,7 @@ impl WalkingEngine {
                     context.cycle_time.last_cycle_duration,
                     context.config,
                     &mut context.step_adjustment,
+                    balance_control_state.as_ref(),
                 );
             }
             WalkState::Kicking(..) => self.kick_cycle(last_cycle_duration, context.config),
@@ -319,6 +359,10 @@ impl WalkingEngine {
             );
             support_leg_adjustment = support_leg_adjustment + support_leg_gyro_balancing;
         }
+        if let Some(balance_control_state) = &balance_control_state {
+            support_leg_adjustment.ankle_pitch += balance_control_state.ankle_pitch_adjustment;
+            support_leg_adjustment.ankle_roll += balance_control_state.ankle_roll_adjustment;
+        }
 
         adjust_legs(
             &mut left_leg,
@@ -341,6 +385,9 @@ impl WalkingEngine {
         context
             .normalized_forward_speed
             .fill_if_subscribed(|| normalized_forward_step_size);
+        context
+            .balance_control
+            .fill_if_subscribed(|| balance_control_state.clone().unwrap_or_default());
 
         // TODO: refill
         // context.walking_engine.fill_on_subscription(|| self.clone());
@@ -529,6 +576,7 @@ impl WalkingEngine {
         self.filtered_gyro.reset(Vector2::default());
         self.filtered_imu_pitch.reset(0.0);
         self.filtered_robot_tilt_shift.reset(0.0);
+        self.balance_controller.reset();
         self.last_left_walk_request = FootOffsets::zero();
         self.last_right_walk_request = FootOffsets::zero();
         self.last_left_leg_adjustment = LegJoints::default();
@@ -655,15 +703,36 @@ impl WalkingEngine {
         cycle_duration: Duration,
         config: &WalkingEngineParameters,
         step_adjustment_output: &mut AdditionalOutput<StepAdjustment>,
+        balance_control_state: Option<&BalanceControlState>,
     ) {
-        self.t += cycle_duration;
+        self.t += match balance_control_state {
+            // a vanishing factor would stretch the step time beyond what a duration can represent
+            Some(balance_control_state) => cycle_duration.div_f32(
+                balance_control_state
+                    .step_duration_factor
+                    .max(config.balance_control.minimum_step_duration_factor),
+            ),
+            None => cycle_duration,
+        };
         let (
-            next_left_walk_request,
-            next_right_walk_request,
+            mut next_left_walk_request,
+            mut next_right_walk_request,
             next_turn,
             next_left_foot_lift,
             next_right_foot_lift,
         ) = self.next_foot_offsets(self.current_step, config);
+        if let Some(balance_control_state) = balance_control_state {
+            let linear_time =
+                (self.t.as_secs_f32() / self.planned_step_duration.as_secs_f32()).clamp(0.0, 1.0);
+            let placement_adjustment =
+                balance_control_state.step_placement_adjustment * linear_time;
+            let swing_foot = match self.swing_side {
+                Side::Left => &mut next_left_walk_request,
+                Side::Right => &mut next_right_walk_request,
+            };
+            swing_foot.forward += placement_adjustment.x;
+            swing_foot.left += placement_adjustment.y;
+        }
         let (
             adjusted_left_foot,
             adjusted_right_foot,
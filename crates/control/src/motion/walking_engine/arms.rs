@@ -1,6 +1,8 @@
 use std::{f32::consts::FRAC_PI_2, time::Duration};
 
 use color_eyre::Result;
+use kinematics::arm_angles;
+use nalgebra::point;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use types::{
@@ -18,6 +20,7 @@ use super::foot_offsets::FootOffsets;
 pub struct SwingingArm {
     side: Side,
     state: State,
+    pull_tight_joints: Option<ArmJoints<f32>>,
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, SerializeHierarchy)]
@@ -44,6 +47,7 @@ impl SwingingArm {
         Self {
             side,
             state: State::Swing,
+            pull_tight_joints: None,
         }
     }
 
@@ -60,10 +64,7 @@ impl SwingingArm {
             Side::Left => config.pull_back_joints,
             Side::Right => config.pull_back_joints.mirrored(),
         };
-        let pull_tight_joints = match self.side {
-            Side::Left => config.pull_tight_joints,
-            Side::Right => config.pull_tight_joints.mirrored(),
-        };
+        let pull_tight_joints = self.pull_tight_joints(pull_back_joints, config);
         let swinging_arm_joints = self.swinging_arm_joints(foot, config);
         let center_arm_joints = self.swinging_arm_joints(FootOffsets::zero(), config);
 
@@ -221,11 +222,35 @@ impl SwingingArm {
             | State::ReleasingBack { interpolator }
             | State::ReleasingTight { interpolator }
             | State::PullingTight { interpolator } => interpolator.value().shoulder_pitch,
-            State::Back => config.pull_tight_joints.shoulder_pitch,
+            State::Back => self
+                .pull_tight_joints
+                .map_or(FRAC_PI_2, |joints| joints.shoulder_pitch),
         };
         Ok((shoulder_pitch - FRAC_PI_2) * config.torso_tilt_compensation_factor)
     }
 
+    fn pull_tight_joints(
+        &mut self,
+        pull_back_joints: ArmJoints<f32>,
+        config: &SwingingArmsParameters,
+    ) -> ArmJoints<f32> {
+        let wrist_position = config.pull_tight_wrist_position;
+        let wrist_target = match self.side {
+            Side::Left => wrist_position,
+            Side::Right => point![wrist_position.x, -wrist_position.y, wrist_position.z],
+        };
+        // the last solution is already close unless the parameters changed
+        let initial_angles = self.pull_tight_joints.unwrap_or(pull_back_joints);
+        let (_, pull_tight_joints) = arm_angles(
+            self.side,
+            wrist_target,
+            initial_angles,
+            &config.arm_joint_limits,
+        );
+        self.pull_tight_joints = Some(pull_tight_joints);
+        pull_tight_joints
+    }
+
     fn arm_motion_from_motion_command(
         &self,
         motion_command: &MotionCommand,
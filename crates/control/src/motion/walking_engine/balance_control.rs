@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use filtering::low_pass_filter::LowPassFilter;
+use nalgebra::{Isometry3, Point2, Point3, UnitQuaternion, Vector2, Vector3};
+use serde::{Deserialize, Serialize};
+use types::{
+    balance_control::BalanceControlState, parameters::BalanceControlParameters, support_foot::Side,
+};
+
+const GRAVITY: f32 = 9.81;
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceController {
+    last_center_of_mass: Option<Point2<f32>>,
+    filtered_center_of_mass_velocity: LowPassFilter<Vector2<f32>>,
+}
+
+impl BalanceController {
+    pub fn new(parameters: &BalanceControlParameters) -> Self {
+        Self {
+            last_center_of_mass: None,
+            filtered_center_of_mass_velocity: LowPassFilter::with_smoothing_factor(
+                Vector2::zeros(),
+                parameters.center_of_mass_velocity_low_pass_factor,
+            ),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.last_center_of_mass = None;
+        self.filtered_center_of_mass_velocity
+            .reset(Vector2::zeros());
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &mut self,
+        center_of_mass_in_robot: Point3<f32>,
+        support_sole_to_robot: Isometry3<f32>,
+        imu_roll_pitch: Vector2<f32>,
+        support_center_of_pressure: Option<Point2<f32>>,
+        support_side: Side,
+        cycle_duration: Duration,
+        parameters: &BalanceControlParameters,
+    ) -> BalanceControlState {
+        let robot_orientation =
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), imu_roll_pitch.y)
+                * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), imu_roll_pitch.x);
+        let sole_to_center_of_mass = robot_orientation
+            * (center_of_mass_in_robot - support_sole_to_robot.translation.vector);
+        let center_of_mass = Point2::from(sole_to_center_of_mass.xy());
+        let center_of_mass_height = sole_to_center_of_mass.z.max(0.1);
+
+        let cycle_duration = cycle_duration.as_secs_f32();
+        if let Some(last_center_of_mass) = self.last_center_of_mass {
+            if cycle_duration > 0.0 {
+                self.filtered_center_of_mass_velocity
+                    .update((center_of_mass - last_center_of_mass) / cycle_duration);
+            }
+        }
+        self.last_center_of_mass = Some(center_of_mass);
+        let center_of_mass_velocity = self.filtered_center_of_mass_velocity.state();
+
+        let natural_frequency = (GRAVITY / center_of_mass_height).sqrt();
+        let capture_point = center_of_mass + center_of_mass_velocity / natural_frequency;
+
+        let (region_minimum, region_maximum) = mirrored_region(parameters, support_side);
+        let clamped_capture_point = Point2::new(
+            capture_point.x.clamp(region_minimum.x, region_maximum.x),
+            capture_point.y.clamp(region_minimum.y, region_maximum.y),
+        );
+        let capture_point_error = capture_point - clamped_capture_point;
+
+        let zero_moment_point_error = support_center_of_pressure
+            .map(|zero_moment_point| clamped_capture_point - zero_moment_point)
+            .unwrap_or_else(Vector2::zeros);
+
+        let ankle_adjustment = (capture_point_error
+            .component_mul(&parameters.capture_point_ankle_gains)
+            + zero_moment_point_error.component_mul(&parameters.zero_moment_point_ankle_gains))
+        .map(|adjustment| {
+            adjustment.clamp(
+                -parameters.maximum_ankle_adjustment,
+                parameters.maximum_ankle_adjustment,
+            )
+        });
+
+        let swing_side = support_side.opposite();
+        let step_placement_adjustment = step_placement_adjustment(
+            capture_point_error * parameters.step_placement_gain,
+            swing_side,
+            parameters.maximum_step_placement_adjustment,
+        );
+        let step_duration_factor = (1.0 - parameters.step_timing_gain * capture_point_error.norm())
+            .clamp(parameters.minimum_step_duration_factor, 1.0);
+
+        BalanceControlState {
+            center_of_mass,
+            center_of_mass_height,
+            center_of_mass_velocity,
+            capture_point,
+            capture_point_error,
+            zero_moment_point: support_center_of_pressure,
+            zero_moment_point_error,
+            ankle_pitch_adjustment: ankle_adjustment.x,
+            ankle_roll_adjustment: ankle_adjustment.y,
+            step_placement_adjustment,
+            step_duration_factor,
+            is_disturbed: capture_point_error != Vector2::zeros(),
+        }
+    }
+}
+
+fn mirrored_region(
+    parameters: &BalanceControlParameters,
+    support_side: Side,
+) -> (Point2<f32>, Point2<f32>) {
+    let minimum = parameters.capture_point_region_minimum;
+    let maximum = parameters.capture_point_region_maximum;
+    match support_side {
+        Side::Left => (Point2::from(minimum), Point2::from(maximum)),
+        Side::Right => (
+            Point2::new(minimum.x, -maximum.y),
+            Point2::new(maximum.x, -minimum.y),
+        ),
+    }
+}
+
+fn step_placement_adjustment(
+    requested_adjustment: Vector2<f32>,
+    swing_side: Side,
+    maximum_adjustment: Vector2<f32>,
+) -> Vector2<f32> {
+    // the swing foot may only be placed further outwards to avoid crossing the support foot
+    let (minimum_left, maximum_left) = match swing_side {
+        Side::Left => (0.0, maximum_adjustment.y),
+        Side::Right => (-maximum_adjustment.y, 0.0),
+    };
+    Vector2::new(
+        requested_adjustment
+            .x
+            .clamp(-maximum_adjustment.x, maximum_adjustment.x),
+        requested_adjustment.y.clamp(minimum_left, maximum_left),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    fn parameters() -> BalanceControlParameters {
+        BalanceControlParameters {
+            enabled: true,
+            center_of_mass_velocity_low_pass_factor: 1.0,
+            capture_point_region_minimum: vector![-0.02, -0.06],
+            capture_point_region_maximum: vector![0.08, 0.06],
+            capture_point_ankle_gains: vector![1.0, 1.0],
+            zero_moment_point_ankle_gains: vector![0.0, 0.0],
+            maximum_ankle_adjustment: 0.1,
+            step_placement_gain: 1.0,
+            maximum_step_placement_adjustment: vector![0.04, 0.03],
+            step_timing_gain: 5.0,
+            minimum_step_duration_factor: 0.7,
+        }
+    }
+
+    fn update(controller: &mut BalanceController, center_of_mass_x: f32) -> BalanceControlState {
+        controller.update(
+            point![center_of_mass_x, 0.0, 0.25],
+            Isometry3::identity(),
+            Vector2::zeros(),
+            None,
+            Side::Left,
+            Duration::from_millis(10),
+            &parameters(),
+        )
+    }
+
+    #[test]
+    fn resting_center_of_mass_above_support_foot_is_undisturbed() {
+        let mut controller = BalanceController::new(&parameters());
+        update(&mut controller, 0.02);
+        let state = update(&mut controller, 0.02);
+
+        assert!(!state.is_disturbed);
+        assert_eq!(state.step_duration_factor, 1.0);
+        assert_eq!(state.step_placement_adjustment, Vector2::zeros());
+    }
+
+    #[test]
+    fn falling_forward_is_countered_by_a_forward_balance_reaction() {
+        let mut controller = BalanceController::new(&parameters());
+        update(&mut controller, 0.02);
+        let state = update(&mut controller, 0.025);
+
+        assert!(state.is_disturbed);
+        assert!(state.capture_point_error.x > 0.0);
+        assert!(state.ankle_pitch_adjustment > 0.0);
+        assert!(state.step_placement_adjustment.x > 0.0);
+        assert!(state.step_duration_factor < 1.0);
+    }
+}
@@ -2,7 +2,8 @@ use color_eyre::Result;
 use context_attribute::context;
 use framework::MainOutput;
 use hardware::SpeakerInterface;
-use spl_network_messages::PlayerNumber;
+use log::info;
+use spl_network_messages::{Penalty, PlayerNumber};
 use types::{
     audio::{Sound, SpeakerRequest},
     buttons::Buttons,
@@ -11,8 +12,15 @@ use types::{
     primary_state::PrimaryState,
 };
 
+/// Messages logged on transitions, classified by the game report
+pub const PRIMARY_STATE_CHANGED: &str = "primary state changed from ";
+pub const PRIMARY_STATE_CHANGED_TO: &str = " to ";
+pub const PENALIZED: &str = "penalized with ";
+pub const UNPENALIZED: &str = "unpenalized";
+
 pub struct PrimaryStateFilter {
     last_primary_state: PrimaryState,
+    last_penalty: Option<Penalty>,
 }
 
 #[context]
@@ -39,18 +47,25 @@ impl PrimaryStateFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             last_primary_state: PrimaryState::Unstiff,
+            last_penalty: None,
         })
     }
 
     pub fn cycle(&mut self, context: CycleContext<impl SpeakerInterface>) -> Result<MainOutputs> {
-        let is_penalized = match context.game_controller_state {
-            Some(game_controller_state) => {
-                game_controller_state.penalties[*context.player_number].is_some()
-            }
-            None => false,
-        };
+        let penalty = context
+            .game_controller_state
+            .and_then(|game_controller_state| {
+                game_controller_state.penalties[*context.player_number]
+            });
+        match (self.last_penalty, penalty) {
+            (None, Some(penalty)) => info!("{PENALIZED}{penalty:?}"),
+            (Some(_), None) => info!("{UNPENALIZED}"),
+            _ => {}
+        }
+        self.last_penalty = penalty;
+        let is_penalized = penalty.is_some();
 
-        self.last_primary_state = match (
+        let primary_state = match (
             self.last_primary_state,
             context.buttons.head_buttons_touched,
             context.buttons.is_chest_button_pressed,
@@ -90,6 +105,13 @@ impl PrimaryStateFilter {
 
             (_, _, _, _, _) => self.last_primary_state,
         };
+        if primary_state != self.last_primary_state {
+            info!(
+                "{PRIMARY_STATE_CHANGED}{:?}{PRIMARY_STATE_CHANGED_TO}{primary_state:?}",
+                self.last_primary_state
+            );
+        }
+        self.last_primary_state = primary_state;
 
         Ok(MainOutputs {
             primary_state: self.last_primary_state.into(),
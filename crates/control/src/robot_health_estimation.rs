@@ -0,0 +1,242 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use log::{info, warn};
+use types::{
+    cycle_time::CycleTime,
+    joints::Joints,
+    parameters::RobotHealthParameters,
+    robot_health::{HealthState, RobotHealth},
+    sensor_data::SensorData,
+};
+
+pub struct RobotHealthEstimation {
+    last_temperatures: Option<(SystemTime, Joints<f32>)>,
+    /// Degrees Celsius per second
+    heating_rates: Joints<f32>,
+    last_state: HealthState,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    sensor_data: Input<SensorData, "sensor_data">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+
+    parameters: Parameter<RobotHealthParameters, "robot_health">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub robot_health: MainOutput<RobotHealth>,
+}
+
+impl RobotHealthEstimation {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_temperatures: None,
+            heating_rates: Joints::default(),
+            last_state: HealthState::Healthy,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let temperatures = context.sensor_data.temperature_sensors;
+        let parameters = context.parameters;
+
+        // temperatures are reported in whole degrees, rates are estimated over longer intervals
+        match self.last_temperatures {
+            Some((last_time, last_temperatures)) => {
+                let elapsed = now.duration_since(last_time).unwrap_or_default();
+                if elapsed >= parameters.heating_rate_interval {
+                    let measured_rates = (temperatures - last_temperatures) / elapsed.as_secs_f32();
+                    self.heating_rates = self.heating_rates
+                        + (measured_rates - self.heating_rates)
+                            * parameters.heating_rate_smoothing_factor;
+                    self.last_temperatures = Some((now, temperatures));
+                }
+            }
+            None => self.last_temperatures = Some((now, temperatures)),
+        }
+
+        let maximum_joint_temperature = temperatures
+            .as_vec()
+            .into_iter()
+            .flatten()
+            .fold(f32::MIN, f32::max);
+        let time_to_overheat = time_to_overheat(
+            temperatures,
+            self.heating_rates,
+            parameters.critical_temperature,
+        );
+        let battery_charge = context.sensor_data.battery.map(|battery| battery.charge);
+        let state = next_state(
+            self.last_state,
+            maximum_joint_temperature,
+            time_to_overheat,
+            battery_charge,
+            parameters,
+        );
+        if state != self.last_state {
+            let message = format!(
+                "health changed from {:?} to {state:?} at {maximum_joint_temperature:.0} °C and {}",
+                self.last_state,
+                battery_charge.map_or("no battery".to_string(), |charge| format!(
+                    "{:.0} % battery charge",
+                    charge * 100.0
+                ))
+            );
+            if state > self.last_state {
+                warn!("{message}");
+            } else {
+                info!("{message}");
+            }
+        }
+        self.last_state = state;
+
+        Ok(MainOutputs {
+            robot_health: RobotHealth {
+                state,
+                maximum_joint_temperature,
+                time_to_overheat,
+                battery_charge,
+            }
+            .into(),
+        })
+    }
+}
+
+fn time_to_overheat(
+    temperatures: Joints<f32>,
+    heating_rates: Joints<f32>,
+    critical_temperature: f32,
+) -> Option<Duration> {
+    temperatures
+        .as_vec()
+        .into_iter()
+        .flatten()
+        .zip(heating_rates.as_vec().into_iter().flatten())
+        .filter(|(_, heating_rate)| *heating_rate > 0.0)
+        // vanishing heating rates overflow the duration and never overheat
+        .filter_map(|(temperature, heating_rate)| {
+            Duration::try_from_secs_f32(
+                ((critical_temperature - temperature) / heating_rate).max(0.0),
+            )
+            .ok()
+        })
+        .min()
+}
+
+fn next_state(
+    last_state: HealthState,
+    maximum_joint_temperature: f32,
+    time_to_overheat: Option<Duration>,
+    battery_charge: Option<f32>,
+    parameters: &RobotHealthParameters,
+) -> HealthState {
+    // states are left only after recovering by the hysteresis margins
+    let temperature_threshold = |threshold: f32, state| {
+        if last_state >= state {
+            threshold - parameters.temperature_hysteresis
+        } else {
+            threshold
+        }
+    };
+    let charge_threshold = |threshold: f32, state| {
+        if last_state >= state {
+            threshold + parameters.battery_charge_hysteresis
+        } else {
+            threshold
+        }
+    };
+
+    let is_critical = maximum_joint_temperature
+        >= temperature_threshold(parameters.critical_temperature, HealthState::Critical)
+        || battery_charge.is_some_and(|charge| {
+            charge <= charge_threshold(parameters.critical_battery_charge, HealthState::Critical)
+        });
+    let is_degraded = maximum_joint_temperature
+        >= temperature_threshold(parameters.degraded_temperature, HealthState::Degraded)
+        || time_to_overheat.is_some_and(|duration| duration < parameters.minimum_time_to_overheat)
+        || battery_charge.is_some_and(|charge| {
+            charge <= charge_threshold(parameters.degraded_battery_charge, HealthState::Degraded)
+        });
+    match (is_critical, is_degraded) {
+        (true, _) => HealthState::Critical,
+        (false, true) => HealthState::Degraded,
+        (false, false) => HealthState::Healthy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> RobotHealthParameters {
+        RobotHealthParameters {
+            degraded_temperature: 70.0,
+            critical_temperature: 80.0,
+            minimum_time_to_overheat: Duration::from_secs(120),
+            degraded_battery_charge: 0.2,
+            critical_battery_charge: 0.08,
+            heating_rate_interval: Duration::from_secs(10),
+            heating_rate_smoothing_factor: 0.5,
+            temperature_hysteresis: 3.0,
+            battery_charge_hysteresis: 0.03,
+        }
+    }
+
+    #[test]
+    fn states_are_left_after_recovering_by_the_hysteresis() {
+        let parameters = parameters();
+
+        let state = next_state(HealthState::Healthy, 80.0, None, Some(0.5), &parameters);
+        assert_eq!(state, HealthState::Critical);
+        let state = next_state(state, 78.0, None, Some(0.5), &parameters);
+        assert_eq!(state, HealthState::Critical);
+        let state = next_state(state, 76.0, None, Some(0.5), &parameters);
+        assert_eq!(state, HealthState::Degraded);
+        let state = next_state(state, 60.0, None, Some(0.21), &parameters);
+        assert_eq!(state, HealthState::Degraded);
+        let state = next_state(state, 60.0, None, Some(0.25), &parameters);
+        assert_eq!(state, HealthState::Healthy);
+    }
+
+    #[test]
+    fn heating_joints_degrade_before_reaching_the_temperature() {
+        let parameters = parameters();
+        let mut temperatures = Joints::fill(50.0);
+        temperatures.left_leg.knee_pitch = 65.0;
+        let mut heating_rates = Joints::fill(0.0);
+        heating_rates.left_leg.knee_pitch = 0.25;
+
+        let time_to_overheat = time_to_overheat(temperatures, heating_rates, 80.0);
+
+        assert_eq!(time_to_overheat, Some(Duration::from_secs(60)));
+        assert_eq!(
+            next_state(
+                HealthState::Healthy,
+                65.0,
+                time_to_overheat,
+                None,
+                &parameters
+            ),
+            HealthState::Degraded
+        );
+    }
+
+    #[test]
+    fn barely_heating_joints_do_not_overheat() {
+        let temperatures = Joints::fill(50.0);
+        let mut heating_rates = Joints::fill(0.0);
+        heating_rates.left_leg.knee_pitch = f32::MIN_POSITIVE;
+
+        assert_eq!(time_to_overheat(temperatures, heating_rates, 80.0), None);
+    }
+}
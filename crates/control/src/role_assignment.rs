@@ -4,7 +4,8 @@ use framework::{MainOutput, PerceptionInput};
 use hardware::NetworkInterface;
 use nalgebra::{Isometry2, Point2, Vector2};
 use spl_network_messages::{
-    GameControllerReturnMessage, GamePhase, HulkMessage, Penalty, PlayerNumber, Team,
+    GameControllerReturnMessage, GamePhase, HealthMessage, HulkMessage, PassTarget, Penalty,
+    PlayerNumber, Team,
 };
 use std::time::{Duration, SystemTime};
 use types::{
@@ -16,8 +17,10 @@ use types::{
     initial_pose::InitialPose,
     messages::{IncomingMessage, OutgoingMessage},
     parameters::SplNetworkParameters,
+    pass::PlannedPass,
     players::Players,
     primary_state::PrimaryState,
+    robot_health::{HealthState, RobotHealth},
     roles::Role,
 };
 
@@ -31,6 +34,8 @@ pub struct RoleAssignment {
     role_initialized: bool,
     team_ball: Option<BallPosition>,
     last_time_keeper_penalized: Option<SystemTime>,
+    last_announced_pass_receiver: Option<PlayerNumber>,
+    last_announced_substitution_request: bool,
 }
 
 #[context]
@@ -42,10 +47,12 @@ pub struct CycleContext {
     fall_state: Input<FallState, "fall_state">,
     game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
     primary_state: Input<PrimaryState, "primary_state">,
+    robot_health: Input<RobotHealth, "robot_health">,
     robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
     cycle_time: Input<CycleTime, "cycle_time">,
     network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
     time_to_reach_kick_position: PersistentState<Duration, "time_to_reach_kick_position">,
+    planned_pass: PersistentState<PlannedPass, "planned_pass">,
 
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     forced_role: Parameter<Option<Role>, "role_assignment.forced_role?">,
@@ -77,6 +84,8 @@ impl RoleAssignment {
             role_initialized: false,
             team_ball: None,
             last_time_keeper_penalized: None,
+            last_announced_pass_receiver: None,
+            last_announced_substitution_request: false,
         })
     }
 
@@ -209,7 +218,7 @@ impl RoleAssignment {
             .values()
             .flatten()
             .filter_map(|message| match message {
-                IncomingMessage::GameController(_) => None,
+                IncomingMessage::GameController(_) | IncomingMessage::Health(_) => None,
                 IncomingMessage::Spl(message) => Some(message),
             })
             .peekable();
@@ -268,6 +277,20 @@ impl RoleAssignment {
             }
         }
 
+        let pass_target = context
+            .planned_pass
+            .decision
+            .filter(|_| role == Role::Striker)
+            .map(|pass_decision| PassTarget {
+                receiver: pass_decision.receiver,
+                target_in_field: pass_decision.target_in_field,
+            });
+        if pass_target.map(|pass_target| pass_target.receiver) != self.last_announced_pass_receiver
+        {
+            send_spl_striker_message = true;
+        }
+
+        let requests_substitution = context.robot_health.state == HealthState::Critical;
         if send_spl_striker_message
             && primary_state == PrimaryState::Playing
             && silence_interval_has_passed
@@ -297,10 +320,27 @@ impl RoleAssignment {
                             robot_to_field,
                             ball_position,
                             time_to_reach_kick_position: Some(*context.time_to_reach_kick_position),
+                            pass_target,
+                            requests_substitution,
                         }))?;
+                    self.last_announced_pass_receiver =
+                        pass_target.map(|pass_target| pass_target.receiver);
+                    self.last_announced_substitution_request = requests_substitution;
                 }
             }
         }
+        if let Some(health_message) = self.health_message(
+            requests_substitution,
+            primary_state,
+            context.game_controller_state,
+            context.spl_network,
+            *context.player_number,
+        ) {
+            context
+                .hardware
+                .write_to_network(OutgoingMessage::Health(health_message))
+                .wrap_err("failed to write HealthMessage to hardware")?;
+        }
 
         if let Some(forced_role) = context.forced_role {
             self.role = *forced_role;
@@ -321,6 +361,32 @@ impl RoleAssignment {
             network_robot_obstacles: network_robot_obstacles.into(),
         })
     }
+
+    /// Announces changed substitution requests of robots which do not send striker messages
+    fn health_message(
+        &mut self,
+        requests_substitution: bool,
+        primary_state: PrimaryState,
+        game_controller_state: Option<&GameControllerState>,
+        spl_network: &SplNetworkParameters,
+        player_number: PlayerNumber,
+    ) -> Option<HealthMessage> {
+        let has_remaining_messages = game_controller_state.is_some_and(|game_controller_state| {
+            game_controller_state.remaining_amount_of_messages
+                > spl_network.remaining_amount_of_messages_to_stop_sending
+        });
+        if requests_substitution == self.last_announced_substitution_request
+            || primary_state != PrimaryState::Playing
+            || !has_remaining_messages
+        {
+            return None;
+        }
+        self.last_announced_substitution_request = requests_substitution;
+        Some(HealthMessage {
+            player_number,
+            requests_substitution,
+        })
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -758,3 +824,45 @@ fn assign_keeper_or_replacement_keeper(
 
     unassigned_robots
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use spl_network_messages::GameState;
+
+    use super::*;
+
+    #[test]
+    fn defenders_request_substitution_without_claiming_striker() {
+        let mut role_assignment = RoleAssignment::new(CreationContext {}).unwrap();
+        role_assignment.role = Role::DefenderLeft;
+        let game_controller_state = GameControllerState {
+            game_state: GameState::Playing,
+            game_phase: GamePhase::Normal,
+            kicking_team: Team::Hulks,
+            last_game_state_change: UNIX_EPOCH,
+            penalties: Default::default(),
+            remaining_amount_of_messages: 1000,
+            sub_state: None,
+            hulks_team_is_home_after_coin_toss: true,
+        };
+        let spl_network = SplNetworkParameters::default();
+        let mut health_message = |requests_substitution| {
+            role_assignment.health_message(
+                requests_substitution,
+                PrimaryState::Playing,
+                Some(&game_controller_state),
+                &spl_network,
+                PlayerNumber::Three,
+            )
+        };
+
+        assert!(health_message(false).is_none());
+        assert!(health_message(true)
+            .is_some_and(|message| message.requests_substitution
+                && message.player_number == PlayerNumber::Three));
+        assert!(health_message(true).is_none());
+        assert!(health_message(false).is_some_and(|message| !message.requests_substitution));
+    }
+}
@@ -2,11 +2,14 @@ use color_eyre::Result;
 use context_attribute::context;
 use filtering::low_pass_filter::LowPassFilter;
 use framework::MainOutput;
-use types::{sensor_data::SensorData, sole_pressure::SolePressure};
+use nalgebra::{Point2, Vector2};
+use types::{sensor_data::SensorData, sole_pressure::SolePressure, support_foot::Side};
 
 pub struct SolePressureFilter {
     left_sole_pressure: LowPassFilter<f32>,
     right_sole_pressure: LowPassFilter<f32>,
+    left_center_of_pressure: LowPassFilter<Vector2<f32>>,
+    right_center_of_pressure: LowPassFilter<Vector2<f32>>,
 }
 
 #[context]
@@ -28,6 +31,8 @@ impl SolePressureFilter {
         Ok(Self {
             left_sole_pressure: LowPassFilter::with_smoothing_factor(0.0, 0.5),
             right_sole_pressure: LowPassFilter::with_smoothing_factor(0.0, 0.5),
+            left_center_of_pressure: LowPassFilter::with_smoothing_factor(Vector2::zeros(), 0.5),
+            right_center_of_pressure: LowPassFilter::with_smoothing_factor(Vector2::zeros(), 0.5),
         })
     }
 
@@ -37,12 +42,31 @@ impl SolePressureFilter {
         self.left_sole_pressure.update(left_sole_pressure);
         let right_sole_pressure = force_sensitive_resistors.right.sum();
         self.right_sole_pressure.update(right_sole_pressure);
+        let left_center_of_pressure = filter_center_of_pressure(
+            &mut self.left_center_of_pressure,
+            force_sensitive_resistors.left.center_of_pressure(Side::Left),
+        );
+        let right_center_of_pressure = filter_center_of_pressure(
+            &mut self.right_center_of_pressure,
+            force_sensitive_resistors.right.center_of_pressure(Side::Right),
+        );
         Ok(MainOutputs {
             sole_pressure: SolePressure {
                 left: self.left_sole_pressure.state(),
                 right: self.right_sole_pressure.state(),
+                left_center_of_pressure,
+                right_center_of_pressure,
             }
             .into(),
         })
     }
 }
+
+fn filter_center_of_pressure(
+    filter: &mut LowPassFilter<Vector2<f32>>,
+    measurement: Option<Point2<f32>>,
+) -> Option<Point2<f32>> {
+    let measurement = measurement?;
+    filter.update(measurement.coords);
+    Some(Point2::from(filter.state()))
+}
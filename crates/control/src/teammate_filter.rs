@@ -0,0 +1,224 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use nalgebra::Point2;
+use spl_network_messages::{HealthMessage, HulkMessage, PlayerNumber};
+use types::{
+    cycle_time::CycleTime, messages::IncomingMessage, parameters::TeammateFilterParameters,
+    pass::IncomingPass, players::Players, teammate::Teammate,
+};
+
+pub struct TeammateFilter {
+    teammates: Players<Option<Teammate>>,
+    incoming_pass: Option<IncomingPass>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    player_number: Parameter<PlayerNumber, "player_number">,
+    parameters: Parameter<TeammateFilterParameters, "teammate_filter">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub teammates: MainOutput<Vec<Teammate>>,
+    pub incoming_pass: MainOutput<Option<IncomingPass>>,
+}
+
+impl TeammateFilter {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            teammates: Default::default(),
+            incoming_pass: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let cycle_start_time = context.cycle_time.start_time;
+
+        for (&time, message) in context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(time, messages)| messages.iter().map(move |message| (time, message)))
+        {
+            let message = match message {
+                IncomingMessage::Spl(message) => message,
+                IncomingMessage::Health(message) => {
+                    self.update_substitution_request(message);
+                    continue;
+                }
+                IncomingMessage::GameController(_) => continue,
+            };
+            if message.player_number == *context.player_number {
+                continue;
+            }
+            self.update_teammate(message, time, context.parameters);
+            self.update_incoming_pass(message, time, *context.player_number);
+        }
+
+        let expired_players: Vec<_> = self
+            .teammates
+            .iter()
+            .filter(|(_, teammate)| {
+                teammate.is_some_and(|teammate| {
+                    is_expired(teammate.last_received, cycle_start_time, context.parameters)
+                })
+            })
+            .map(|(player_number, _)| player_number)
+            .collect();
+        for player_number in expired_players {
+            self.teammates[player_number] = None;
+        }
+        if self.incoming_pass.is_some_and(|pass| {
+            cycle_start_time
+                .duration_since(pass.announced_at)
+                .is_ok_and(|age| age > context.parameters.incoming_pass_timeout)
+        }) {
+            self.incoming_pass = None;
+        }
+
+        let teammates: Vec<_> = self
+            .teammates
+            .iter()
+            .filter_map(|(_, teammate)| *teammate)
+            .collect();
+
+        Ok(MainOutputs {
+            teammates: teammates.into(),
+            incoming_pass: self.incoming_pass.into(),
+        })
+    }
+
+    fn update_substitution_request(&mut self, message: &HealthMessage) {
+        if let Some(teammate) = &mut self.teammates[message.player_number] {
+            teammate.requests_substitution = message.requests_substitution;
+        }
+    }
+
+    fn update_incoming_pass(
+        &mut self,
+        message: &HulkMessage,
+        received_at: SystemTime,
+        player_number: PlayerNumber,
+    ) {
+        match message.pass_target {
+            Some(pass_target) if pass_target.receiver == player_number => {
+                self.incoming_pass = Some(IncomingPass {
+                    passer: message.player_number,
+                    target_in_field: pass_target.target_in_field,
+                    announced_at: received_at,
+                });
+            }
+            // passers stop announcing cancelled passes e.g. after changing their role
+            _ if self
+                .incoming_pass
+                .is_some_and(|pass| pass.passer == message.player_number) =>
+            {
+                self.incoming_pass = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn update_teammate(
+        &mut self,
+        message: &HulkMessage,
+        received_at: SystemTime,
+        parameters: &TeammateFilterParameters,
+    ) {
+        let position = message.robot_to_field * Point2::origin();
+        let teammate = &mut self.teammates[message.player_number];
+        let velocity_in_field = match teammate {
+            Some(previous) if received_at > previous.last_received => {
+                let elapsed = received_at
+                    .duration_since(previous.last_received)
+                    .unwrap_or_default()
+                    .as_secs_f32();
+                let previous_position = previous.robot_to_field * Point2::origin();
+                let measured_velocity = ((position - previous_position) / elapsed)
+                    .cap_magnitude(parameters.maximum_velocity);
+                previous.velocity_in_field
+                    + (measured_velocity - previous.velocity_in_field)
+                        * parameters.velocity_smoothing_factor
+            }
+            Some(previous) => previous.velocity_in_field,
+            None => Default::default(),
+        };
+        *teammate = Some(Teammate {
+            player_number: message.player_number,
+            robot_to_field: message.robot_to_field,
+            velocity_in_field,
+            fallen: message.fallen,
+            requests_substitution: message.requests_substitution,
+            last_received: received_at,
+        });
+    }
+}
+
+fn is_expired(
+    last_received: SystemTime,
+    now: SystemTime,
+    parameters: &TeammateFilterParameters,
+) -> bool {
+    now.duration_since(last_received)
+        .is_ok_and(|age| age > parameters.maximum_message_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use spl_network_messages::PassTarget;
+
+    use super::*;
+
+    #[test]
+    fn passes_are_cancelled_by_messages_of_the_passer_without_them() {
+        let mut filter = TeammateFilter::new(CreationContext {}).unwrap();
+        let pass = HulkMessage {
+            player_number: PlayerNumber::Four,
+            pass_target: Some(PassTarget {
+                receiver: PlayerNumber::Two,
+                target_in_field: Point2::new(1.0, 0.5),
+            }),
+            ..Default::default()
+        };
+        let other_teammate = HulkMessage {
+            player_number: PlayerNumber::Three,
+            ..Default::default()
+        };
+        let passer_without_pass = HulkMessage {
+            player_number: PlayerNumber::Four,
+            ..Default::default()
+        };
+
+        filter.update_incoming_pass(&pass, UNIX_EPOCH, PlayerNumber::Two);
+        assert!(filter
+            .incoming_pass
+            .is_some_and(|pass| pass.passer == PlayerNumber::Four));
+
+        filter.update_incoming_pass(
+            &other_teammate,
+            UNIX_EPOCH + Duration::from_millis(100),
+            PlayerNumber::Two,
+        );
+        assert!(filter.incoming_pass.is_some());
+
+        filter.update_incoming_pass(
+            &passer_without_pass,
+            UNIX_EPOCH + Duration::from_millis(200),
+            PlayerNumber::Two,
+        );
+        assert!(filter.incoming_pass.is_none());
+    }
+}
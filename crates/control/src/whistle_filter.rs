@@ -3,8 +3,12 @@ use std::{collections::VecDeque, time::SystemTime};
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{MainOutput, PerceptionInput};
+use log::info;
 use types::{cycle_time::CycleTime, filtered_whistle::FilteredWhistle, whistle::Whistle};
 
+/// Message logged when a whistle starts, classified by the game report
+pub const WHISTLE_DETECTED: &str = "whistle detected";
+
 pub struct WhistleFilter {
     pub detection_buffer: VecDeque<bool>,
     pub was_detected_last_cycle: bool,
@@ -59,6 +63,7 @@ impl WhistleFilter {
         let is_detected = number_of_detections > *context.minimum_detections;
         let started_this_cycle = is_detected && !self.was_detected_last_cycle;
         if started_this_cycle {
+            info!("{WHISTLE_DETECTED}");
             self.last_detection = Some(cycle_start_time);
         }
         self.was_detected_last_cycle = is_detected;
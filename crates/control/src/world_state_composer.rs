@@ -9,7 +9,9 @@ use types::{
     game_controller_state::GameControllerState,
     kick_decision::KickDecision,
     obstacles::Obstacle,
+    pass::IncomingPass,
     primary_state::PrimaryState,
+    robot_health::RobotHealth,
     roles::Role,
     rule_obstacles::RuleObstacle,
     world_state::{BallState, RobotState, WorldState},
@@ -29,6 +31,7 @@ pub struct CycleContext {
     robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
     kick_decisions: Input<Option<Vec<KickDecision>>, "kick_decisions?">,
     instant_kick_decisions: Input<Option<Vec<KickDecision>>, "instant_kick_decisions?">,
+    incoming_pass: Input<Option<IncomingPass>, "incoming_pass?">,
 
     player_number: Parameter<PlayerNumber, "player_number">,
 
@@ -37,6 +40,7 @@ pub struct CycleContext {
     obstacles: Input<Vec<Obstacle>, "obstacles">,
     rule_obstacles: Input<Vec<RuleObstacle>, "rule_obstacles">,
     primary_state: Input<PrimaryState, "primary_state">,
+    robot_health: Input<RobotHealth, "robot_health">,
     role: Input<Role, "role">,
     position_of_interest: Input<Point2<f32>, "position_of_interest">,
 }
@@ -60,6 +64,7 @@ impl WorldStateComposer {
             fall_state: *context.fall_state,
             has_ground_contact: *context.has_ground_contact,
             player_number: *context.player_number,
+            health: *context.robot_health,
         };
 
         let world_state = WorldState {
@@ -72,6 +77,7 @@ impl WorldStateComposer {
             robot,
             kick_decisions: context.kick_decisions.cloned(),
             instant_kick_decisions: context.instant_kick_decisions.cloned(),
+            incoming_pass: context.incoming_pass.copied(),
             game_controller_state: context.game_controller_state.copied(),
         };
 
@@ -0,0 +1,368 @@
+use std::{
+    backtrace::Backtrace,
+    collections::{BTreeMap, VecDeque},
+    fmt::Debug,
+    fs::{create_dir_all, write},
+    io,
+    marker::PhantomData,
+    panic,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serialize_hierarchy::serde_json::{to_value, to_vec, Value};
+
+use crate::{
+    log_context::{self, LogContext},
+    Reader,
+};
+
+pub const FILE_PREFIX: &str = "crash.";
+pub const FILE_EXTENSION: &str = "json";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Parameters {
+    pub directory: PathBuf,
+    /// Number of most recent databases kept per cycler
+    pub number_of_cycles: usize,
+    /// Overrides `number_of_cycles` per cycler instance e.g. `{"VisionTop": 2}` for cyclers with
+    /// large databases, 0 disables the recording of a cycler
+    #[serde(default)]
+    pub number_of_cycles_per_cycler: BTreeMap<String, usize>,
+}
+
+/// Written into `crash.<unix milliseconds>.json` on panics or cycler errors
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CrashBundle {
+    pub unix_milliseconds: u64,
+    pub thread: Option<String>,
+    pub cycler: Option<String>,
+    pub node: Option<String>,
+    pub cycle: Option<u64>,
+    pub reason: String,
+    pub backtrace: Option<String>,
+    pub parameters: Option<Value>,
+    /// Oldest cycle first
+    pub cyclers: BTreeMap<String, Vec<RecordedCycle>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RecordedCycle {
+    pub cycle: Option<u64>,
+    pub database: Value,
+}
+
+/// Databases are kept serialized, they are only converted to JSON when crashing
+struct SerializedCycle {
+    cycle: Option<u64>,
+    database: Vec<u8>,
+}
+
+type SerializedCycles = Arc<Mutex<VecDeque<SerializedCycle>>>;
+type CyclesDeserializer = Box<dyn Fn() -> Option<Vec<RecordedCycle>> + Send>;
+type ParametersSerializer = Box<dyn Fn() -> Value + Send>;
+
+static CAPTURE: OnceLock<CrashCapture> = OnceLock::new();
+
+/// The panicking thread may hold a lock, writing the bundle must not dead lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Installs a panic hook writing crash bundles, the previously installed hook is called
+/// afterwards. Cyclers created afterwards record their databases.
+pub fn enable(parameters: Parameters) {
+    if CAPTURE.set(CrashCapture::new(parameters)).is_err() {
+        return;
+    }
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if let Some(capture) = CAPTURE.get() {
+            let backtrace = Backtrace::force_capture().to_string();
+            report(capture.write_bundle(&info.to_string(), Some(backtrace)));
+        }
+        previous_hook(info);
+    }));
+}
+
+/// Called by the generated cycler code when a cycle fails
+pub fn capture_error(error: &impl Debug) {
+    if let Some(capture) = CAPTURE.get() {
+        report(capture.write_bundle(&format!("{error:?}"), None));
+    }
+}
+
+/// Called by the generated cycler code, parameters are serialized only when crashing
+pub fn register_parameters<Parameters>(reader: Reader<Parameters>)
+where
+    Parameters: Serialize + Send + Sync + 'static,
+{
+    if let Some(capture) = CAPTURE.get() {
+        capture.register_parameters(reader);
+    }
+}
+
+/// Collects the recordings of all cyclers and writes them into crash bundles, installed
+/// process wide by [`enable`]
+pub struct CrashCapture {
+    parameters: Parameters,
+    /// Only locked when cyclers are created and when crashing, each cycler locks its own cycles
+    recordings: Mutex<BTreeMap<String, CyclesDeserializer>>,
+    parameters_serializer: Mutex<Option<ParametersSerializer>>,
+}
+
+impl CrashCapture {
+    pub fn new(parameters: Parameters) -> Self {
+        Self {
+            parameters,
+            recordings: Default::default(),
+            parameters_serializer: Default::default(),
+        }
+    }
+
+    pub fn recorder<Database>(&self, cycler: &str) -> Recorder<Database>
+    where
+        Database: DeserializeOwned + Serialize + 'static,
+    {
+        let number_of_cycles = self
+            .parameters
+            .number_of_cycles_per_cycler
+            .get(cycler)
+            .copied()
+            .unwrap_or(self.parameters.number_of_cycles);
+        if number_of_cycles == 0 {
+            return Recorder::disabled();
+        }
+        let cycles = SerializedCycles::default();
+        let recorded_cycles = cycles.clone();
+        self.recordings.lock().insert(
+            cycler.to_string(),
+            Box::new(move || {
+                let cycles = recorded_cycles.try_lock_for(LOCK_TIMEOUT)?;
+                Some(
+                    cycles
+                        .iter()
+                        .map(|cycle| RecordedCycle {
+                            cycle: cycle.cycle,
+                            database: deserialize_database::<Database>(&cycle.database),
+                        })
+                        .collect(),
+                )
+            }),
+        );
+        Recorder {
+            number_of_cycles,
+            cycles: Some(cycles),
+            database: PhantomData,
+        }
+    }
+
+    pub fn register_parameters<Parameters>(&self, reader: Reader<Parameters>)
+    where
+        Parameters: Serialize + Send + Sync + 'static,
+    {
+        *self.parameters_serializer.lock() = Some(Box::new(move || {
+            to_value(&*reader.next()).unwrap_or_else(|error| Value::String(error.to_string()))
+        }));
+    }
+
+    pub fn write_bundle(&self, reason: &str, backtrace: Option<String>) -> io::Result<PathBuf> {
+        let LogContext {
+            cycler,
+            node,
+            cycle,
+        } = log_context::current();
+        let parameters = self
+            .parameters_serializer
+            .try_lock_for(LOCK_TIMEOUT)
+            .and_then(|serializer| serializer.as_ref().map(|serialize| serialize()));
+        let cyclers = self
+            .recordings
+            .try_lock_for(LOCK_TIMEOUT)
+            .map(|recordings| {
+                recordings
+                    .iter()
+                    .filter_map(|(name, deserialize)| Some((name.clone(), deserialize()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let unix_milliseconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let bundle = CrashBundle {
+            unix_milliseconds,
+            thread: thread::current().name().map(ToString::to_string),
+            cycler,
+            node: node.map(ToString::to_string),
+            cycle,
+            reason: reason.to_string(),
+            backtrace,
+            parameters,
+            cyclers,
+        };
+
+        create_dir_all(&self.parameters.directory)?;
+        let path = self
+            .parameters
+            .directory
+            .join(format!("{FILE_PREFIX}{unix_milliseconds}.{FILE_EXTENSION}"));
+        write(&path, to_vec(&bundle)?)?;
+        Ok(path)
+    }
+}
+
+/// Owned by the generated cycler code, keeps the most recent databases of the cycler
+pub struct Recorder<Database> {
+    number_of_cycles: usize,
+    cycles: Option<SerializedCycles>,
+    database: PhantomData<fn(&Database)>,
+}
+
+impl<Database> Recorder<Database>
+where
+    Database: DeserializeOwned + Serialize + 'static,
+{
+    /// Records nothing unless crash capture is enabled
+    pub fn new(cycler: &str) -> Self {
+        match CAPTURE.get() {
+            Some(capture) => capture.recorder(cycler),
+            None => Self::disabled(),
+        }
+    }
+
+    fn disabled() -> Self {
+        Self {
+            number_of_cycles: 0,
+            cycles: None,
+            database: PhantomData,
+        }
+    }
+
+    /// Called by the generated cycler code after each cycle
+    pub fn record(&self, database: &Database) {
+        let Some(cycles) = &self.cycles else {
+            return;
+        };
+        // the buffer of the oldest cycle is reused to avoid allocating in every cycle
+        let mut buffer = {
+            let mut cycles = cycles.lock();
+            if cycles.len() >= self.number_of_cycles {
+                cycles
+                    .pop_front()
+                    .map(|cycle| cycle.database)
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            }
+        };
+        buffer.clear();
+        if bincode::serialize_into(&mut buffer, database).is_err() {
+            return;
+        }
+        cycles.lock().push_back(SerializedCycle {
+            cycle: log_context::current().cycle,
+            database: buffer,
+        });
+    }
+}
+
+fn deserialize_database<Database>(database: &[u8]) -> Value
+where
+    Database: DeserializeOwned + Serialize,
+{
+    bincode::deserialize::<Database>(database)
+        .map_err(|error| error.to_string())
+        .and_then(|database| to_value(database).map_err(|error| error.to_string()))
+        .unwrap_or_else(Value::String)
+}
+
+/// The logger may be unusable while crashing, stderr is written directly
+fn report(result: io::Result<PathBuf>) {
+    match result {
+        Ok(path) => eprintln!("wrote crash bundle to {}", path.display()),
+        Err(error) => eprintln!("failed to write crash bundle: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use serialize_hierarchy::serde_json::{from_str, json};
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[derive(Deserialize, Serialize)]
+    struct Database {
+        ball_position: Option<f32>,
+        image: Vec<u8>,
+    }
+
+    #[test]
+    fn crash_bundles_contain_the_most_recent_databases_of_each_cycler() {
+        let directory = tempdir().unwrap();
+        let capture = CrashCapture::new(Parameters {
+            directory: directory.path().to_path_buf(),
+            number_of_cycles: 2,
+            number_of_cycles_per_cycler: BTreeMap::from([
+                ("VisionTop".to_string(), 1),
+                ("VisionBottom".to_string(), 0),
+            ]),
+        });
+        let control = capture.recorder::<Database>("Control");
+        let vision_top = capture.recorder::<Database>("VisionTop");
+        let vision_bottom = capture.recorder::<Database>("VisionBottom");
+        for ball_position in [None, Some(1.0), Some(2.0)] {
+            log_context::begin_cycle("Control");
+            let database = Database {
+                ball_position,
+                image: vec![0; 4],
+            };
+            control.record(&database);
+            vision_top.record(&database);
+            vision_bottom.record(&database);
+        }
+
+        let path = capture
+            .write_bundle("failed to execute cycle of `BallFilter`", None)
+            .unwrap();
+
+        let bundle: CrashBundle = from_str(&read_to_string(path).unwrap()).unwrap();
+        assert_eq!(bundle.reason, "failed to execute cycle of `BallFilter`");
+        assert_eq!(bundle.cycle, Some(2));
+        assert_eq!(
+            bundle.cyclers.keys().collect::<Vec<_>>(),
+            ["Control", "VisionTop"]
+        );
+        let recorded = |cycler: &str| -> Vec<_> {
+            bundle.cyclers[cycler]
+                .iter()
+                .map(|cycle| (cycle.cycle, cycle.database.clone()))
+                .collect()
+        };
+        assert_eq!(
+            recorded("Control"),
+            [
+                (
+                    Some(1),
+                    json!({"ball_position": 1.0, "image": [0, 0, 0, 0]})
+                ),
+                (
+                    Some(2),
+                    json!({"ball_position": 2.0, "image": [0, 0, 0, 0]})
+                )
+            ]
+        );
+        assert_eq!(
+            recorded("VisionTop"),
+            [(
+                Some(2),
+                json!({"ball_position": 2.0, "image": [0, 0, 0, 0]})
+            )]
+        );
+    }
+}
@@ -1,7 +1,9 @@
 mod additional_output;
+pub mod crash_capture;
 mod future_queue;
 mod historic_databases;
 mod historic_input;
+pub mod log_context;
 mod main_output;
 mod multiple_buffer;
 mod perception_databases;
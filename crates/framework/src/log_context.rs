@@ -0,0 +1,70 @@
+use std::{cell::RefCell, fmt::Debug};
+
+use parking_lot::Mutex;
+
+/// What the calling thread is currently executing, attached to structured log records
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogContext {
+    pub cycler: Option<String>,
+    pub node: Option<&'static str>,
+    pub cycle: Option<u64>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<LogContext> = RefCell::default();
+}
+
+static GAME_STATE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Called by the generated cycler code at the beginning of each cycle
+pub fn begin_cycle(cycler: &str) {
+    CONTEXT.with_borrow_mut(|context| {
+        if context.cycler.as_deref() != Some(cycler) {
+            context.cycler = Some(cycler.to_string());
+        }
+        context.cycle = Some(context.cycle.map_or(0, |cycle| cycle + 1));
+        context.node = None;
+    });
+}
+
+/// Called by the generated cycler code before executing a node, `None` after the last one
+pub fn set_node(node: Option<&'static str>) {
+    CONTEXT.with_borrow_mut(|context| context.node = node);
+}
+
+pub fn current() -> LogContext {
+    CONTEXT.with_borrow(Clone::clone)
+}
+
+/// The game state is shared by all cyclers, it is set by the node filtering it
+pub fn set_game_state(game_state: &impl Debug) {
+    *GAME_STATE.lock() = Some(format!("{game_state:?}"));
+}
+
+pub fn game_state() -> Option<String> {
+    GAME_STATE.lock().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_are_counted_per_thread() {
+        begin_cycle("Control");
+        set_node(Some("BallFilter"));
+        begin_cycle("Control");
+        assert_eq!(
+            current(),
+            LogContext {
+                cycler: Some("Control".to_string()),
+                node: None,
+                cycle: Some(1),
+            }
+        );
+
+        std::thread::spawn(|| assert_eq!(current(), LogContext::default()))
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,16 @@
+use code_generation::{client::generate_client, write_to_file::WriteToFile};
+use color_eyre::eyre::{Result, WrapErr};
+use hulk_manifest::collect_hulk_cyclers;
+use source_analyzer::structs::Structs;
+
+fn main() -> Result<()> {
+    let cyclers = collect_hulk_cyclers()?;
+    for path in cyclers.watch_paths() {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    let structs = Structs::try_from_cyclers(&cyclers)?;
+    generate_client(&cyclers, &structs)
+        .write_to_file("generated_client.rs")
+        .wrap_err("failed to write generated client to file")
+}
@@ -0,0 +1,14 @@
+//! Typed handles to the outputs and parameters of the HULK robotics stack, generated from the
+//! cycler structure to catch misspelled paths and mismatching types at compile time.
+//!
+//! ```no_run
+//! # async fn example(communication: communication::client::Communication) {
+//! let robot = hulk_client::Robot::new(&communication);
+//! let mut ball_positions = robot.control.main_outputs.ball_position().await;
+//! while let Some(Ok(ball_position)) = ball_positions.next().await {
+//!     println!("{ball_position:?}");
+//! }
+//! # }
+//! ```
+
+include!(concat!(env!("OUT_DIR"), "/generated_client.rs"));
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::SystemTime};
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
 
 use ::hardware::{
     ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
@@ -8,10 +8,13 @@ use color_eyre::{
     eyre::{eyre, Error, WrapErr},
     Result,
 };
+use communication::server::AccessControl;
+use framework::crash_capture;
 use hardware::{PathsInterface, SpeakerInterface};
 use parking_lot::Mutex;
 use serde::Deserialize;
 use spl_network::endpoint::{Endpoint, Ports};
+use structured_log::RotationParameters;
 use tokio::{
     runtime::{Builder, Runtime},
     select,
@@ -41,10 +44,20 @@ pub struct Parameters {
     pub camera_top: nao_camera::Parameters,
     pub camera_bottom: nao_camera::Parameters,
     pub communication_addresses: Option<String>,
+    #[serde(default)]
+    pub communication_access: AccessControl,
+    pub communication_socket: Option<PathBuf>,
+    /// Crash bundles with the most recent cycles, written on panics and cycler errors, `null`
+    /// disables them
+    pub crash_capture: Option<crash_capture::Parameters>,
     pub microphones: microphones::Parameters,
     pub paths: Paths,
     pub speakers: speakers::Parameters,
     pub spl_network_ports: Ports,
+    /// JSON lines logs with cycler, node and game state context
+    pub structured_log: Option<RotationParameters>,
+    #[serde(default)]
+    pub watch_parameters: bool,
 }
 
 pub struct HardwareInterface {
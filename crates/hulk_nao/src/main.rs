@@ -1,15 +1,22 @@
 #![recursion_limit = "256"]
-use std::{env::args, fs::File, io::stdout, sync::Arc};
+use std::{
+    env::args,
+    fs::File,
+    io::{stdout, Write},
+    sync::Arc,
+};
 
 use color_eyre::{
     eyre::{Result, WrapErr},
     install,
 };
 use ctrlc::set_handler;
+use framework::crash_capture;
 use hardware::{IdInterface, PathsInterface};
 use hardware_interface::{HardwareInterface, Parameters};
 use hulk::run::run;
 use serde_json::from_reader;
+use structured_log::{LogRecord, RotatingWriter, RotationParameters};
 use tokio_util::sync::CancellationToken;
 
 mod audio_parameter_deserializers;
@@ -21,25 +28,38 @@ mod hula_wrapper;
 mod microphones;
 mod speakers;
 
-pub fn setup_logger() -> Result<(), fern::InitError> {
-    fern::Dispatch::new()
-        .format(|out, message, record| {
-            out.finish(format_args!(
-                "{}  {:<18}  {:>5}  {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                record.target(),
-                record.level(),
-                message
-            ))
-        })
-        .level(log::LevelFilter::Debug)
-        .chain(stdout())
-        .apply()?;
+pub fn setup_logger(structured_log: Option<RotationParameters>) -> Result<(), fern::InitError> {
+    let mut dispatch = fern::Dispatch::new().level(log::LevelFilter::Debug).chain(
+        fern::Dispatch::new()
+            .format(|out, message, record| {
+                out.finish(format_args!(
+                    "{}  {:<18}  {:>5}  {}",
+                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                    record.target(),
+                    record.level(),
+                    message
+                ))
+            })
+            .chain(stdout()),
+    );
+    if let Some(parameters) = structured_log {
+        let writer: Box<dyn Write + Send> = Box::new(RotatingWriter::new(parameters)?);
+        dispatch = dispatch.chain(
+            fern::Dispatch::new()
+                .format(|out, _message, record| {
+                    out.finish(format_args!(
+                        "{}",
+                        LogRecord::from_record(record).to_json_line()
+                    ))
+                })
+                .chain(writer),
+        );
+    }
+    dispatch.apply()?;
     Ok(())
 }
 
 fn main() -> Result<()> {
-    setup_logger()?;
     install()?;
     let hardware_parameters_path = args()
         .nth(1)
@@ -55,7 +75,14 @@ fn main() -> Result<()> {
         File::open(hardware_parameters_path).wrap_err("failed to open hardware parameters")?;
     let hardware_parameters: Parameters =
         from_reader(file).wrap_err("failed to parse hardware parameters")?;
+    setup_logger(hardware_parameters.structured_log.clone())?;
+    if let Some(parameters) = hardware_parameters.crash_capture.clone() {
+        crash_capture::enable(parameters);
+    }
     let communication_addresses = hardware_parameters.communication_addresses.clone();
+    let watch_parameters = hardware_parameters.watch_parameters;
+    let access_control = hardware_parameters.communication_access.clone();
+    let local_socket = hardware_parameters.communication_socket.clone();
     let hardware_interface = HardwareInterface::new(keep_running.clone(), hardware_parameters)
         .wrap_err("failed to create hardware interface")?;
     let ids = hardware_interface.get_ids();
@@ -64,6 +91,9 @@ fn main() -> Result<()> {
         Arc::new(hardware_interface),
         communication_addresses,
         paths.parameters,
+        watch_parameters,
+        access_control,
+        local_socket,
         ids.body_id,
         ids.head_id,
         keep_running,
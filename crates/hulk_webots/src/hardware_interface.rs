@@ -1,4 +1,5 @@
 use std::{
+    path::PathBuf,
     str::from_utf8,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -11,6 +12,7 @@ use color_eyre::{
     eyre::{bail, eyre, Error, WrapErr},
     Result,
 };
+use communication::server::AccessControl;
 use hardware::{
     ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
     PathsInterface, SensorInterface, SpeakerInterface, TimeInterface,
@@ -47,8 +49,13 @@ pub const SIMULATION_TIME_STEP: i32 = 10;
 #[derive(Clone, Debug, Deserialize)]
 pub struct Parameters {
     pub communication_addresses: Option<String>,
+    #[serde(default)]
+    pub communication_access: AccessControl,
+    pub communication_socket: Option<PathBuf>,
     pub paths: Paths,
     pub spl_network_ports: Ports,
+    #[serde(default)]
+    pub watch_parameters: bool,
 }
 
 pub struct HardwareInterface {
@@ -387,6 +394,7 @@ impl SensorInterface for HardwareInterface {
             force_sensitive_resistors,
             touch_sensors,
             temperature_sensors,
+            battery: None,
         })
     }
 }
@@ -55,6 +55,9 @@ fn main() -> Result<()> {
     let hardware_parameters: Parameters =
         from_reader(file).wrap_err("failed to parse hardware parameters")?;
     let communication_addresses = hardware_parameters.communication_addresses.clone();
+    let watch_parameters = hardware_parameters.watch_parameters;
+    let access_control = hardware_parameters.communication_access.clone();
+    let local_socket = hardware_parameters.communication_socket.clone();
     let hardware_interface = HardwareInterface::new(keep_running.clone(), hardware_parameters)
         .wrap_err("failed to create hardware interface")?;
     let ids = hardware_interface.get_ids();
@@ -63,6 +66,9 @@ fn main() -> Result<()> {
         Arc::new(hardware_interface),
         communication_addresses,
         paths.parameters,
+        watch_parameters,
+        access_control,
+        local_socket,
         ids.body_id,
         ids.head_id,
         keep_running,
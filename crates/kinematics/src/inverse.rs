@@ -1,7 +1,20 @@
 use std::f32::consts::PI;
 
-use nalgebra::{geometry::Isometry3, Rotation3, Translation3, Vector3};
-use types::{joints::LegJoints, robot_dimensions::RobotDimensions};
+use nalgebra::{geometry::Isometry3, Matrix3, Point3, Rotation3, Translation3, Vector3};
+use types::{
+    joints::{ArmJointLimits, ArmJoints, Joints, LegJoints},
+    robot_dimensions::RobotDimensions,
+    support_foot::Side,
+};
+
+use crate::{
+    jacobian::{arm_chain, jacobian},
+    whole_body::robot_kinematics,
+};
+
+const ARM_ITERATIONS: usize = 50;
+const ARM_DAMPING: f32 = 0.01;
+const ARM_TOLERANCE: f32 = 0.001;
 
 pub fn leg_angles(
     left_foot_to_torso: Isometry3<f32>,
@@ -123,3 +136,167 @@ pub fn leg_angles(
 
     (is_reachable, left_leg, right_leg)
 }
+
+/// Iteratively solves for arm angles placing the wrist at the target position using damped least
+/// squares on the arm jacobian, starting at the given angles. Each step is clamped to the limits,
+/// the wrist yaw is kept unchanged.
+pub fn arm_angles(
+    side: Side,
+    wrist_target_in_robot: Point3<f32>,
+    initial_angles: ArmJoints<f32>,
+    limits: &ArmJointLimits,
+) -> (bool, ArmJoints<f32>) {
+    let mut angles = initial_angles;
+    let mut error = Vector3::zeros();
+    for _ in 0..ARM_ITERATIONS {
+        let joints = match side {
+            Side::Left => Joints {
+                left_arm: angles,
+                ..Default::default()
+            },
+            Side::Right => Joints {
+                right_arm: angles,
+                ..Default::default()
+            },
+        };
+        let kinematics = robot_kinematics(&joints);
+        let chain = arm_chain(side, &kinematics);
+        let wrist = chain[4].joint_to_robot * Point3::origin();
+        error = wrist_target_in_robot - wrist;
+        if error.norm() < ARM_TOLERANCE {
+            break;
+        }
+        let positional_jacobian = jacobian(&chain[..4], wrist).fixed_rows::<3>(0).into_owned();
+        let damped = &positional_jacobian * positional_jacobian.transpose()
+            + Matrix3::identity() * ARM_DAMPING.powi(2);
+        let Some(damped_inverse) = damped.try_inverse() else {
+            break;
+        };
+        let step = positional_jacobian.transpose() * damped_inverse * error;
+        angles = limits.clamp(
+            side,
+            ArmJoints {
+                shoulder_pitch: angles.shoulder_pitch + step[0],
+                shoulder_roll: angles.shoulder_roll + step[1],
+                elbow_yaw: angles.elbow_yaw + step[2],
+                elbow_roll: angles.elbow_roll + step[3],
+                ..angles
+            },
+        );
+    }
+    (error.norm() < ARM_TOLERANCE, angles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ArmJointLimits {
+        ArmJointLimits {
+            minimum: ArmJoints {
+                shoulder_pitch: -2.0857,
+                shoulder_roll: -0.3142,
+                elbow_yaw: -2.0857,
+                elbow_roll: -1.5446,
+                wrist_yaw: -1.8238,
+                hand: 0.0,
+            },
+            maximum: ArmJoints {
+                shoulder_pitch: 2.0857,
+                shoulder_roll: 1.3265,
+                elbow_yaw: 2.0857,
+                elbow_roll: -0.0349,
+                wrist_yaw: 1.8238,
+                hand: 1.0,
+            },
+        }
+    }
+
+    fn wrist_position(side: Side, angles: ArmJoints<f32>) -> Point3<f32> {
+        match side {
+            Side::Left => {
+                robot_kinematics(&Joints {
+                    left_arm: angles,
+                    ..Default::default()
+                })
+                .left_wrist_to_robot
+                    * Point3::origin()
+            }
+            Side::Right => {
+                robot_kinematics(&Joints {
+                    right_arm: angles,
+                    ..Default::default()
+                })
+                .right_wrist_to_robot
+                    * Point3::origin()
+            }
+        }
+    }
+
+    #[test]
+    fn arm_angles_reach_forward_kinematics_wrist_position() {
+        let target_angles = ArmJoints {
+            shoulder_pitch: 0.5,
+            shoulder_roll: 0.3,
+            elbow_yaw: -1.0,
+            elbow_roll: -0.8,
+            wrist_yaw: 0.0,
+            hand: 0.0,
+        };
+        let initial_angles = ArmJoints {
+            shoulder_pitch: 1.5,
+            shoulder_roll: 0.1,
+            elbow_yaw: -1.5,
+            elbow_roll: -0.1,
+            wrist_yaw: 0.0,
+            hand: 0.0,
+        };
+
+        for side in [Side::Left, Side::Right] {
+            let (target_angles, initial_angles) = match side {
+                Side::Left => (target_angles, initial_angles),
+                Side::Right => (target_angles.mirrored(), initial_angles.mirrored()),
+            };
+            let wrist_target = wrist_position(side, target_angles);
+
+            let (is_reachable, angles) = arm_angles(side, wrist_target, initial_angles, &limits());
+
+            assert!(is_reachable);
+            assert!((wrist_position(side, angles) - wrist_target).norm() < ARM_TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn arm_angles_stay_within_limits_for_targets_beyond_them() {
+        let limits = limits();
+        // reaching across the body requires a shoulder roll beyond its limit
+        let wrist_target = wrist_position(
+            Side::Left,
+            ArmJoints {
+                shoulder_pitch: 0.0,
+                shoulder_roll: -1.0,
+                elbow_yaw: 0.0,
+                elbow_roll: -0.1,
+                wrist_yaw: 0.0,
+                hand: 0.0,
+            },
+        );
+
+        let (is_reachable, angles) =
+            arm_angles(Side::Left, wrist_target, ArmJoints::default(), &limits);
+
+        assert!(!is_reachable);
+        assert_eq!(limits.clamp(Side::Left, angles), angles);
+        assert!((angles.shoulder_roll - limits.minimum.shoulder_roll).abs() < 1e-3);
+
+        let (is_reachable, angles) = arm_angles(
+            Side::Right,
+            Point3::new(1.0, 0.0, 0.0),
+            ArmJoints::default(),
+            &limits,
+        );
+
+        assert!(!is_reachable);
+        assert_eq!(limits.clamp(Side::Right, angles), angles);
+    }
+}
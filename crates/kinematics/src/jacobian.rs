@@ -0,0 +1,157 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use nalgebra::{Isometry3, Matrix6xX, Point3, Unit, Vector3};
+use types::{robot_kinematics::RobotKinematics, support_foot::Side};
+
+#[derive(Clone, Copy, Debug)]
+pub struct RevoluteJoint {
+    /// frame of the joint after applying its rotation
+    pub joint_to_robot: Isometry3<f32>,
+    /// rotation axis in the joint frame
+    pub axis: Unit<Vector3<f32>>,
+}
+
+impl RevoluteJoint {
+    fn new(joint_to_robot: Isometry3<f32>, axis: Vector3<f32>) -> Self {
+        Self {
+            joint_to_robot,
+            axis: Unit::new_normalize(axis),
+        }
+    }
+}
+
+/// Computes the geometric jacobian of a chain of revolute joints for an end effector position.
+/// The first three rows contain the linear, the last three rows the angular velocity in the robot
+/// frame per unit joint velocity.
+pub fn jacobian(chain: &[RevoluteJoint], end_effector_in_robot: Point3<f32>) -> Matrix6xX<f32> {
+    let mut jacobian = Matrix6xX::zeros(chain.len());
+    for (mut column, joint) in jacobian.column_iter_mut().zip(chain) {
+        let axis = joint.joint_to_robot.rotation * joint.axis.into_inner();
+        let joint_to_end_effector =
+            end_effector_in_robot - Point3::from(joint.joint_to_robot.translation.vector);
+        column
+            .fixed_rows_mut::<3>(0)
+            .copy_from(&axis.cross(&joint_to_end_effector));
+        column.fixed_rows_mut::<3>(3).copy_from(&axis);
+    }
+    jacobian
+}
+
+pub fn head_chain(robot_kinematics: &RobotKinematics) -> [RevoluteJoint; 2] {
+    [
+        RevoluteJoint::new(robot_kinematics.neck_to_robot, Vector3::z()),
+        RevoluteJoint::new(robot_kinematics.head_to_robot, Vector3::y()),
+    ]
+}
+
+pub fn arm_chain(side: Side, robot_kinematics: &RobotKinematics) -> [RevoluteJoint; 5] {
+    let (shoulder, upper_arm, elbow, forearm, wrist) = match side {
+        Side::Left => (
+            robot_kinematics.left_shoulder_to_robot,
+            robot_kinematics.left_upper_arm_to_robot,
+            robot_kinematics.left_elbow_to_robot,
+            robot_kinematics.left_forearm_to_robot,
+            robot_kinematics.left_wrist_to_robot,
+        ),
+        Side::Right => (
+            robot_kinematics.right_shoulder_to_robot,
+            robot_kinematics.right_upper_arm_to_robot,
+            robot_kinematics.right_elbow_to_robot,
+            robot_kinematics.right_forearm_to_robot,
+            robot_kinematics.right_wrist_to_robot,
+        ),
+    };
+    [
+        RevoluteJoint::new(shoulder, Vector3::y()),
+        RevoluteJoint::new(upper_arm, Vector3::z()),
+        RevoluteJoint::new(elbow, Vector3::x()),
+        RevoluteJoint::new(forearm, Vector3::z()),
+        RevoluteJoint::new(wrist, Vector3::x()),
+    ]
+}
+
+pub fn leg_chain(side: Side, robot_kinematics: &RobotKinematics) -> [RevoluteJoint; 6] {
+    // the hip yaw pitch axis is tilted by 45 degrees in the y-z-plane, see forward::*_pelvis_to_robot
+    let (pelvis, hip, thigh, tibia, ankle, foot, hip_yaw_pitch_axis) = match side {
+        Side::Left => (
+            robot_kinematics.left_pelvis_to_robot,
+            robot_kinematics.left_hip_to_robot,
+            robot_kinematics.left_thigh_to_robot,
+            robot_kinematics.left_tibia_to_robot,
+            robot_kinematics.left_ankle_to_robot,
+            robot_kinematics.left_foot_to_robot,
+            Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        ),
+        Side::Right => (
+            robot_kinematics.right_pelvis_to_robot,
+            robot_kinematics.right_hip_to_robot,
+            robot_kinematics.right_thigh_to_robot,
+            robot_kinematics.right_tibia_to_robot,
+            robot_kinematics.right_ankle_to_robot,
+            robot_kinematics.right_foot_to_robot,
+            Vector3::new(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        ),
+    };
+    [
+        RevoluteJoint::new(pelvis, hip_yaw_pitch_axis),
+        RevoluteJoint::new(hip, Vector3::x()),
+        RevoluteJoint::new(thigh, Vector3::y()),
+        RevoluteJoint::new(tibia, Vector3::y()),
+        RevoluteJoint::new(ankle, Vector3::y()),
+        RevoluteJoint::new(foot, Vector3::x()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use types::joints::{Joints, LegJoints};
+
+    use crate::whole_body::robot_kinematics;
+
+    use super::*;
+
+    fn joints_with_left_leg(left_leg: LegJoints<f32>) -> Joints<f32> {
+        Joints {
+            left_leg,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn jacobian_matches_finite_differences_of_forward_kinematics() {
+        let left_leg = LegJoints {
+            hip_yaw_pitch: -0.2,
+            hip_roll: 0.1,
+            hip_pitch: -0.4,
+            knee_pitch: 0.8,
+            ankle_pitch: -0.4,
+            ankle_roll: -0.1,
+        };
+        let kinematics = robot_kinematics(&joints_with_left_leg(left_leg));
+        let sole = kinematics.left_sole_to_robot * Point3::origin();
+        let jacobian = jacobian(&leg_chain(Side::Left, &kinematics), sole);
+
+        let epsilon = 1e-3;
+        let perturbations: [fn(&mut LegJoints<f32>, f32); 6] = [
+            |leg, delta| leg.hip_yaw_pitch += delta,
+            |leg, delta| leg.hip_roll += delta,
+            |leg, delta| leg.hip_pitch += delta,
+            |leg, delta| leg.knee_pitch += delta,
+            |leg, delta| leg.ankle_pitch += delta,
+            |leg, delta| leg.ankle_roll += delta,
+        ];
+        for (index, perturb) in perturbations.iter().enumerate() {
+            let mut perturbed_leg = left_leg;
+            perturb(&mut perturbed_leg, epsilon);
+            let perturbed_sole = robot_kinematics(&joints_with_left_leg(perturbed_leg))
+                .left_sole_to_robot
+                * Point3::origin();
+            let finite_difference = (perturbed_sole - sole) / epsilon;
+            let linear = jacobian.fixed_view::<3, 1>(0, index);
+            assert!(
+                (finite_difference - linear).norm() < 1e-2,
+                "column {index}: {finite_difference} != {linear}"
+            );
+        }
+    }
+}
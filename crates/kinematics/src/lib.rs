@@ -1,5 +1,7 @@
 mod forward;
 mod inverse;
+mod jacobian;
+mod whole_body;
 
 pub use forward::{
     head_to_neck, left_ankle_to_left_tibia, left_elbow_to_left_upper_arm, left_foot_to_left_ankle,
@@ -11,4 +13,6 @@ pub use forward::{
     right_shoulder_to_robot, right_thigh_to_right_hip, right_tibia_to_right_thigh,
     right_upper_arm_to_right_shoulder, right_wrist_to_right_forearm,
 };
-pub use inverse::leg_angles;
+pub use inverse::{arm_angles, leg_angles};
+pub use jacobian::{arm_chain, head_chain, jacobian, leg_chain, RevoluteJoint};
+pub use whole_body::{center_of_mass, robot_kinematics};
@@ -0,0 +1,145 @@
+use nalgebra::{Isometry3, Point, Point3, Translation};
+use types::{
+    joints::Joints, robot_dimensions::RobotDimensions, robot_kinematics::RobotKinematics,
+    robot_masses::RobotMass,
+};
+
+use crate::forward::{
+    head_to_neck, left_ankle_to_left_tibia, left_elbow_to_left_upper_arm, left_foot_to_left_ankle,
+    left_forearm_to_left_elbow, left_hip_to_left_pelvis, left_pelvis_to_robot,
+    left_shoulder_to_robot, left_thigh_to_left_hip, left_tibia_to_left_thigh,
+    left_upper_arm_to_left_shoulder, left_wrist_to_left_forearm, neck_to_robot,
+    right_ankle_to_right_tibia, right_elbow_to_right_upper_arm, right_foot_to_right_ankle,
+    right_forearm_to_right_elbow, right_hip_to_right_pelvis, right_pelvis_to_robot,
+    right_shoulder_to_robot, right_thigh_to_right_hip, right_tibia_to_right_thigh,
+    right_upper_arm_to_right_shoulder, right_wrist_to_right_forearm,
+};
+
+/// Computes the poses of all links of the robot for the given joint angles
+pub fn robot_kinematics(joints: &Joints<f32>) -> RobotKinematics {
+    // head
+    let neck_to_robot = neck_to_robot(&joints.head);
+    let head_to_robot = neck_to_robot * head_to_neck(&joints.head);
+    // torso
+    let torso_to_robot = Isometry3::from(RobotDimensions::TORSO_TO_ROBOT);
+    // left arm
+    let left_shoulder_to_robot = left_shoulder_to_robot(&joints.left_arm);
+    let left_upper_arm_to_robot =
+        left_shoulder_to_robot * left_upper_arm_to_left_shoulder(&joints.left_arm);
+    let left_elbow_to_robot =
+        left_upper_arm_to_robot * left_elbow_to_left_upper_arm(&joints.left_arm);
+    let left_forearm_to_robot = left_elbow_to_robot * left_forearm_to_left_elbow(&joints.left_arm);
+    let left_wrist_to_robot = left_forearm_to_robot * left_wrist_to_left_forearm(&joints.left_arm);
+    // right arm
+    let right_shoulder_to_robot = right_shoulder_to_robot(&joints.right_arm);
+    let right_upper_arm_to_robot =
+        right_shoulder_to_robot * right_upper_arm_to_right_shoulder(&joints.right_arm);
+    let right_elbow_to_robot =
+        right_upper_arm_to_robot * right_elbow_to_right_upper_arm(&joints.right_arm);
+    let right_forearm_to_robot =
+        right_elbow_to_robot * right_forearm_to_right_elbow(&joints.right_arm);
+    let right_wrist_to_robot =
+        right_forearm_to_robot * right_wrist_to_right_forearm(&joints.right_arm);
+    // left leg
+    let left_pelvis_to_robot = left_pelvis_to_robot(&joints.left_leg);
+    let left_hip_to_robot = left_pelvis_to_robot * left_hip_to_left_pelvis(&joints.left_leg);
+    let left_thigh_to_robot = left_hip_to_robot * left_thigh_to_left_hip(&joints.left_leg);
+    let left_tibia_to_robot = left_thigh_to_robot * left_tibia_to_left_thigh(&joints.left_leg);
+    let left_ankle_to_robot = left_tibia_to_robot * left_ankle_to_left_tibia(&joints.left_leg);
+    let left_foot_to_robot = left_ankle_to_robot * left_foot_to_left_ankle(&joints.left_leg);
+    let left_sole_to_robot = left_foot_to_robot * Translation::from(RobotDimensions::ANKLE_TO_SOLE);
+    // right leg
+    let right_pelvis_to_robot = right_pelvis_to_robot(&joints.right_leg);
+    let right_hip_to_robot = right_pelvis_to_robot * right_hip_to_right_pelvis(&joints.right_leg);
+    let right_thigh_to_robot = right_hip_to_robot * right_thigh_to_right_hip(&joints.right_leg);
+    let right_tibia_to_robot = right_thigh_to_robot * right_tibia_to_right_thigh(&joints.right_leg);
+    let right_ankle_to_robot = right_tibia_to_robot * right_ankle_to_right_tibia(&joints.right_leg);
+    let right_foot_to_robot = right_ankle_to_robot * right_foot_to_right_ankle(&joints.right_leg);
+    let right_sole_to_robot =
+        right_foot_to_robot * Translation::from(RobotDimensions::ANKLE_TO_SOLE);
+    RobotKinematics {
+        neck_to_robot,
+        head_to_robot,
+        torso_to_robot,
+        left_shoulder_to_robot,
+        left_upper_arm_to_robot,
+        left_elbow_to_robot,
+        left_forearm_to_robot,
+        left_wrist_to_robot,
+        right_shoulder_to_robot,
+        right_upper_arm_to_robot,
+        right_elbow_to_robot,
+        right_forearm_to_robot,
+        right_wrist_to_robot,
+        left_pelvis_to_robot,
+        left_hip_to_robot,
+        left_thigh_to_robot,
+        left_tibia_to_robot,
+        left_ankle_to_robot,
+        left_foot_to_robot,
+        left_sole_to_robot,
+        right_pelvis_to_robot,
+        right_hip_to_robot,
+        right_thigh_to_robot,
+        right_tibia_to_robot,
+        right_ankle_to_robot,
+        right_foot_to_robot,
+        right_sole_to_robot,
+    }
+}
+
+/// Computes the mass-weighted center of all links in the robot frame
+pub fn center_of_mass(robot_kinematics: &RobotKinematics) -> Point3<f32> {
+    let center_of_mass = (RobotMass::TORSO.mass
+        * (robot_kinematics.torso_to_robot * RobotMass::TORSO.center).coords
+        + RobotMass::NECK.mass * (robot_kinematics.neck_to_robot * RobotMass::NECK.center).coords
+        + RobotMass::HEAD.mass * (robot_kinematics.head_to_robot * RobotMass::HEAD.center).coords
+        + RobotMass::LEFT_SHOULDER.mass
+            * (robot_kinematics.left_shoulder_to_robot * RobotMass::LEFT_SHOULDER.center).coords
+        + RobotMass::LEFT_UPPER_ARM.mass
+            * (robot_kinematics.left_upper_arm_to_robot * RobotMass::LEFT_UPPER_ARM.center).coords
+        + RobotMass::LEFT_ELBOW.mass
+            * (robot_kinematics.left_elbow_to_robot * RobotMass::LEFT_ELBOW.center).coords
+        + RobotMass::LEFT_FOREARM.mass
+            * (robot_kinematics.left_forearm_to_robot * RobotMass::LEFT_FOREARM.center).coords
+        + RobotMass::LEFT_WRIST.mass
+            * (robot_kinematics.left_wrist_to_robot * RobotMass::LEFT_WRIST.center).coords
+        + RobotMass::RIGHT_SHOULDER.mass
+            * (robot_kinematics.right_shoulder_to_robot * RobotMass::RIGHT_SHOULDER.center).coords
+        + RobotMass::RIGHT_UPPER_ARM.mass
+            * (robot_kinematics.right_upper_arm_to_robot * RobotMass::RIGHT_UPPER_ARM.center)
+                .coords
+        + RobotMass::RIGHT_ELBOW.mass
+            * (robot_kinematics.right_elbow_to_robot * RobotMass::RIGHT_ELBOW.center).coords
+        + RobotMass::RIGHT_FOREARM.mass
+            * (robot_kinematics.right_forearm_to_robot * RobotMass::RIGHT_FOREARM.center).coords
+        + RobotMass::RIGHT_WRIST.mass
+            * (robot_kinematics.right_wrist_to_robot * RobotMass::RIGHT_WRIST.center).coords
+        + RobotMass::LEFT_PELVIS.mass
+            * (robot_kinematics.left_pelvis_to_robot * RobotMass::LEFT_PELVIS.center).coords
+        + RobotMass::LEFT_HIP.mass
+            * (robot_kinematics.left_hip_to_robot * RobotMass::LEFT_HIP.center).coords
+        + RobotMass::LEFT_THIGH.mass
+            * (robot_kinematics.left_thigh_to_robot * RobotMass::LEFT_THIGH.center).coords
+        + RobotMass::LEFT_TIBIA.mass
+            * (robot_kinematics.left_tibia_to_robot * RobotMass::LEFT_TIBIA.center).coords
+        + RobotMass::LEFT_ANKLE.mass
+            * (robot_kinematics.left_ankle_to_robot * RobotMass::LEFT_ANKLE.center).coords
+        + RobotMass::LEFT_FOOT.mass
+            * (robot_kinematics.left_foot_to_robot * RobotMass::LEFT_FOOT.center).coords
+        + RobotMass::RIGHT_PELVIS.mass
+            * (robot_kinematics.right_pelvis_to_robot * RobotMass::RIGHT_PELVIS.center).coords
+        + RobotMass::RIGHT_HIP.mass
+            * (robot_kinematics.right_hip_to_robot * RobotMass::RIGHT_HIP.center).coords
+        + RobotMass::RIGHT_THIGH.mass
+            * (robot_kinematics.right_thigh_to_robot * RobotMass::RIGHT_THIGH.center).coords
+        + RobotMass::RIGHT_TIBIA.mass
+            * (robot_kinematics.right_tibia_to_robot * RobotMass::RIGHT_TIBIA.center).coords
+        + RobotMass::RIGHT_ANKLE.mass
+            * (robot_kinematics.right_ankle_to_robot * RobotMass::RIGHT_ANKLE.center).coords
+        + RobotMass::RIGHT_FOOT.mass
+            * (robot_kinematics.right_foot_to_robot * RobotMass::RIGHT_FOOT.center).coords)
+        / RobotMass::TOTAL_MASS;
+
+    Point::from(center_of_mass)
+}
@@ -1,24 +1,52 @@
 use std::{
+    env,
     fmt::{self, Display, Formatter},
     net::Ipv4Addr,
-    path::Path,
+    path::{Path, PathBuf},
+    process::Stdio,
 };
 
 use color_eyre::{
     eyre::{bail, eyre, WrapErr},
     Result,
 };
-use tokio::process::Command;
+use tokio::process::{Child, Command};
 
 pub const PING_TIMEOUT_SECONDS: u32 = 2;
+pub const DEPLOYMENT_DIRECTORY: &str = "hulk";
+pub const PREVIOUS_DEPLOYMENT_DIRECTORY: &str = "hulk.previous";
+const ROLLBACK_DIRECTORY: &str = "hulk.rollback";
+/// Directory containing the virtual NAOs (see the `virtual_nao` crate), defaults to a directory
+/// in the temporary directory
+pub const VIRTUAL_NAO_ROOT_VARIABLE: &str = "HULK_VIRTUAL_NAO_ROOT";
+
+pub fn virtual_nao_root() -> PathBuf {
+    env::var_os(VIRTUAL_NAO_ROOT_VARIABLE)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| env::temp_dir().join("hulk_virtual_naos"))
+}
 
+/// Loopback addresses select virtual NAOs, their commands are executed locally via the `ssh`
+/// stand-in in `<virtual NAO root>/bin/`, their file system is `<virtual NAO root>/<address>/`
 pub struct Nao {
     host: Ipv4Addr,
+    virtual_nao_root: Option<PathBuf>,
 }
 
 impl Nao {
     pub fn new(host: Ipv4Addr) -> Self {
-        Self { host }
+        Self {
+            host,
+            virtual_nao_root: host.is_loopback().then(virtual_nao_root),
+        }
+    }
+
+    /// Virtual NAO in `root` instead of the root from `HULK_VIRTUAL_NAO_ROOT`
+    pub fn new_virtual(host: Ipv4Addr, root: PathBuf) -> Self {
+        Self {
+            host,
+            virtual_nao_root: Some(root),
+        }
     }
 
     pub async fn try_new_with_ping(host: Ipv4Addr) -> Result<Self> {
@@ -29,6 +57,12 @@ impl Nao {
         host: Ipv4Addr,
         timeout_seconds: u32,
     ) -> Result<Self> {
+        if let Some(root) = Self::new(host).virtual_nao_root {
+            if !root.join(host.to_string()).is_dir() {
+                bail!("No virtual NAO {host} in {}", root.display());
+            }
+            return Ok(Self::new(host));
+        }
         match Command::new("ping")
             .arg("-c")
             .arg("1")
@@ -46,7 +80,7 @@ impl Nao {
     pub async fn get_os_version(&self) -> Result<String> {
         let output = self
             .ssh_to_nao()
-            .arg("cat /etc/os-release")
+            .arg(format!("cat {}", self.system_path("/etc/os-release")))
             .output()
             .await
             .wrap_err("failed to execute cat ssh command")?;
@@ -64,8 +98,23 @@ impl Nao {
         ]
     }
 
+    fn ssh_program(&self) -> String {
+        match &self.virtual_nao_root {
+            Some(root) => root.join("bin/ssh").display().to_string(),
+            None => "ssh".to_string(),
+        }
+    }
+
+    /// Absolute path on the NAO, virtual NAOs have their file system in their directory
+    fn system_path(&self, path: &str) -> String {
+        match &self.virtual_nao_root {
+            Some(root) => format!("{}{path}", root.join(self.host.to_string()).display()),
+            None => path.to_string(),
+        }
+    }
+
     fn ssh_to_nao(&self) -> Command {
-        let mut command = Command::new("ssh");
+        let mut command = Command::new(self.ssh_program());
         for flag in self.get_ssh_flags() {
             command.arg(flag);
         }
@@ -80,7 +129,7 @@ impl Nao {
             .arg("--compress")
             .arg("--recursive")
             .arg("--times")
-            .arg(format!("--rsh=ssh {ssh_flags}"));
+            .arg(format!("--rsh={} {ssh_flags}", self.ssh_program()));
         if mkpath {
             command.arg("--mkpath");
         }
@@ -140,7 +189,7 @@ impl Nao {
             .arg("rm")
             .arg("--recursive")
             .arg("--force")
-            .arg("/home/nao/hulk/logs/*")
+            .arg("hulk/logs/*")
             .status()
             .await
             .wrap_err("failed to remove the log directory")?;
@@ -155,7 +204,7 @@ impl Nao {
     pub async fn download_logs(&self, local_directory: impl AsRef<Path>) -> Result<()> {
         let status = self
             .ssh_to_nao()
-            .arg("dmesg > /home/nao/hulk/logs/kernel.log")
+            .arg("dmesg > hulk/logs/kernel.log")
             .status()
             .await
             .wrap_err("failed to write dmesg to kernel.log")?;
@@ -197,6 +246,78 @@ impl Nao {
         String::from_utf8(output.stdout).wrap_err("failed to decode UTF-8")
     }
 
+    /// Reads a file relative to the home directory, returns `None` if it does not exist
+    pub async fn read_file(&self, path: &str) -> Result<Option<String>> {
+        let output = self
+            .ssh_to_nao()
+            .arg("cat")
+            .arg(path)
+            .output()
+            .await
+            .wrap_err("failed to execute cat ssh command")?;
+
+        match output.status.code() {
+            Some(0) => {}
+            Some(255) | None => bail!("cat ssh command exited with {}", output.status),
+            Some(_) => return Ok(None),
+        }
+
+        String::from_utf8(output.stdout)
+            .wrap_err("failed to decode UTF-8")
+            .map(Some)
+    }
+
+    /// Streams all files matching the shell pattern to stdout of the returned process, the process
+    /// exits successfully without output if no file matches
+    pub fn stream_files(&self, pattern: &str) -> Result<Child> {
+        self.ssh_to_nao()
+            .arg(format!("cat {pattern} 2>/dev/null || true"))
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .wrap_err("failed to execute cat ssh command")
+    }
+
+    /// Copies the current deployment (without logs) to `hulk.previous/` for rolling back later
+    pub async fn backup_deployment(&self) -> Result<()> {
+        let status = self
+            .ssh_to_nao()
+            .arg(format!(
+                "test ! -d {DEPLOYMENT_DIRECTORY} || rsync --archive --delete --exclude=/logs {DEPLOYMENT_DIRECTORY}/ {PREVIOUS_DEPLOYMENT_DIRECTORY}/"
+            ))
+            .status()
+            .await
+            .wrap_err("failed to execute rsync ssh command")?;
+
+        if !status.success() {
+            bail!("rsync ssh command exited with {status}");
+        }
+
+        Ok(())
+    }
+
+    /// Swaps the current with the previous deployment, logs stay in place
+    pub async fn rollback_deployment(&self) -> Result<()> {
+        let status = self
+            .ssh_to_nao()
+            .arg(format!(
+                "test -d {PREVIOUS_DEPLOYMENT_DIRECTORY} \
+                && rsync --archive --delete --exclude=/logs {DEPLOYMENT_DIRECTORY}/ {ROLLBACK_DIRECTORY}/ \
+                && rsync --archive --delete --exclude=/logs {PREVIOUS_DEPLOYMENT_DIRECTORY}/ {DEPLOYMENT_DIRECTORY}/ \
+                && rm --recursive --force {PREVIOUS_DEPLOYMENT_DIRECTORY} \
+                && mv {ROLLBACK_DIRECTORY} {PREVIOUS_DEPLOYMENT_DIRECTORY}"
+            ))
+            .status()
+            .await
+            .wrap_err("failed to execute rollback ssh command")?;
+
+        if !status.success() {
+            bail!("rollback ssh command exited with {status} (is there a previous deployment?)");
+        }
+
+        Ok(())
+    }
+
     pub async fn power_off(&self) -> Result<()> {
         let status = self
             .ssh_to_nao()
@@ -342,7 +463,11 @@ impl Nao {
             .rsync_with_nao(false)
             .arg("--copy-links")
             .arg(image_path.as_ref().to_str().unwrap())
-            .arg(format!("{}:/data/.image/", self.host))
+            .arg(format!(
+                "{}:{}",
+                self.host,
+                self.system_path("/data/.image/")
+            ))
             .status()
             .await
             .wrap_err("failed to execute rsync command")?;
@@ -4,8 +4,11 @@ use std::{
 };
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_json::{error, from_str, from_value, to_string_pretty, to_value, Value};
-use tokio::fs::{read_to_string, write};
+use serde_json::{error, from_str, from_value, to_string, to_string_pretty, to_value, Value};
+use tokio::{
+    fs::{read_dir, read_to_string, write, OpenOptions},
+    io::AsyncWriteExt,
+};
 
 use super::json::{clone_nested_value, merge_json, prune_equal_branches};
 
@@ -29,6 +32,13 @@ pub enum DirectoryError {
     ParametersNotConvertedToJsonValue(#[source] error::Error),
     #[error("failed to set head parameters of location")]
     HeadParametersOfLocationNotSet(#[source] SerializationError),
+    #[error("failed to get parameters of profile {profile:?}")]
+    ProfileParametersNotGet {
+        source: SerializationError,
+        profile: String,
+    },
+    #[error("failed to list profiles in {path:?}")]
+    ProfilesNotListed { source: io::Error, path: PathBuf },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -52,60 +62,184 @@ pub async fn deserialize<Parameters>(
 where
     Parameters: DeserializeOwned,
 {
-    let default_file_path = parameters_root_path.as_ref().join("default.json");
-    let mut parameters = read_from_file(default_file_path)
-        .await
-        .map_err(DirectoryError::DefaultParametersNotGet)?;
+    deserialize_with_profiles(parameters_root_path, body_id, head_id, &[]).await
+}
 
-    let location_directory = parameters_root_path
-        .as_ref()
-        .join(location_directory_from_head_id(head_id));
+/// Like [`deserialize`] but additionally merges the given profiles (in this order) on top
+pub async fn deserialize_with_profiles<Parameters>(
+    parameters_root_path: impl AsRef<Path>,
+    body_id: &str,
+    head_id: &str,
+    profiles: &[String],
+) -> Result<Parameters, DirectoryError>
+where
+    Parameters: DeserializeOwned,
+{
+    let parameters = deserialize_to_value(parameters_root_path, body_id, head_id, profiles).await?;
+    from_value(parameters).map_err(DirectoryError::JsonValueNotConvertedToParameters)
+}
 
-    let location_default_file_path = location_directory.join("default.json");
-    if location_default_file_path.exists() {
-        let location_default_parameters = read_from_file(location_default_file_path)
-            .await
-            .map_err(DirectoryError::DefaultParametersOfLocationNotGet)?;
-        merge_json(&mut parameters, &location_default_parameters);
+async fn deserialize_to_value(
+    parameters_root_path: impl AsRef<Path>,
+    body_id: &str,
+    head_id: &str,
+    profiles: &[String],
+) -> Result<Value, DirectoryError> {
+    let layers = read_layers(parameters_root_path, None, body_id, head_id, profiles).await?;
+    let mut parameters = Value::Null;
+    for layer in layers {
+        merge_json(&mut parameters, &layer.parameters);
     }
+    Ok(parameters)
+}
 
-    let body_file_path = parameters_root_path
-        .as_ref()
-        .join(format!("body.{}.json", body_id));
-    if body_file_path.exists() {
-        let body_parameters = read_from_file(body_file_path)
-            .await
-            .map_err(DirectoryError::BodyParametersNotGet)?;
-        merge_json(&mut parameters, &body_parameters);
-    }
+/// Parameter file as merged by [`deserialize_with_profiles`]
+#[derive(Clone, Debug)]
+pub struct Layer {
+    pub path: PathBuf,
+    pub parameters: Value,
+}
 
-    let head_file_path = parameters_root_path
-        .as_ref()
-        .join(format!("head.{}.json", head_id));
-    if head_file_path.exists() {
-        let head_parameters = read_from_file(head_file_path)
+/// Reads the files merged by [`deserialize_with_profiles`] in merge order, starting with
+/// `default.json`. `location` replaces the location configured for the head (e.g. `nao_location`).
+pub async fn read_layers(
+    parameters_root_path: impl AsRef<Path>,
+    location: Option<&str>,
+    body_id: &str,
+    head_id: &str,
+    profiles: &[String],
+) -> Result<Vec<Layer>, DirectoryError> {
+    let default_file_path = parameters_root_path.as_ref().join("default.json");
+    let mut result = vec![Layer {
+        parameters: read_from_file(&default_file_path)
             .await
-            .map_err(DirectoryError::HeadParametersNotGet)?;
-        merge_json(&mut parameters, &head_parameters);
+            .map_err(DirectoryError::DefaultParametersNotGet)?,
+        path: default_file_path,
+    }];
+
+    for (file_path, into_error) in layers(&parameters_root_path, location, body_id, head_id) {
+        if file_path.exists() {
+            result.push(Layer {
+                parameters: read_from_file(&file_path).await.map_err(into_error)?,
+                path: file_path,
+            });
+        }
     }
 
-    let location_body_file_path = location_directory.join(format!("body.{}.json", body_id));
-    if location_body_file_path.exists() {
-        let location_body_parameters = read_from_file(location_body_file_path)
-            .await
-            .map_err(DirectoryError::BodyParametersOfLocationNotGet)?;
-        merge_json(&mut parameters, &location_body_parameters);
+    for profile in profiles {
+        let file_path = profile_file_path(&parameters_root_path, profile);
+        result.push(Layer {
+            parameters: read_from_file(&file_path).await.map_err(|source| {
+                DirectoryError::ProfileParametersNotGet {
+                    source,
+                    profile: profile.clone(),
+                }
+            })?,
+            path: file_path,
+        });
     }
 
-    let location_head_file_path = location_directory.join(format!("head.{}.json", head_id));
-    if location_head_file_path.exists() {
-        let location_head_parameters = read_from_file(location_head_file_path)
+    Ok(result)
+}
+
+type LayerErrorConstructor = fn(SerializationError) -> DirectoryError;
+
+/// Optional parameter files merged on top of `default.json`, in merge order
+fn layers(
+    parameters_root_path: impl AsRef<Path>,
+    location: Option<&str>,
+    body_id: &str,
+    head_id: &str,
+) -> [(PathBuf, LayerErrorConstructor); 5] {
+    let location_directory = parameters_root_path
+        .as_ref()
+        .join(location.unwrap_or_else(|| location_directory_from_head_id(head_id)));
+    [
+        (
+            location_directory.join("default.json"),
+            DirectoryError::DefaultParametersOfLocationNotGet,
+        ),
+        (
+            parameters_root_path
+                .as_ref()
+                .join(format!("body.{}.json", body_id)),
+            DirectoryError::BodyParametersNotGet,
+        ),
+        (
+            parameters_root_path
+                .as_ref()
+                .join(format!("head.{}.json", head_id)),
+            DirectoryError::HeadParametersNotGet,
+        ),
+        (
+            location_directory.join(format!("body.{}.json", body_id)),
+            DirectoryError::BodyParametersOfLocationNotGet,
+        ),
+        (
+            location_directory.join(format!("head.{}.json", head_id)),
+            DirectoryError::HeadParametersOfLocationNotGet,
+        ),
+    ]
+}
+
+/// Files which are merged when deserializing with the given profiles, in merge order
+pub fn merge_order(
+    parameters_root_path: impl AsRef<Path>,
+    body_id: &str,
+    head_id: &str,
+    profiles: &[String],
+) -> Vec<PathBuf> {
+    let layers = layers(&parameters_root_path, None, body_id, head_id)
+        .into_iter()
+        .map(|(file_path, _)| file_path)
+        .filter(|file_path| file_path.exists());
+    let profiles = profiles
+        .iter()
+        .map(|profile| profile_file_path(&parameters_root_path, profile));
+    [parameters_root_path.as_ref().join("default.json")]
+        .into_iter()
+        .chain(layers)
+        .chain(profiles)
+        .collect()
+}
+
+/// Names of the profiles available in the `profiles` directory
+pub async fn list_profiles(
+    parameters_root_path: impl AsRef<Path>,
+) -> Result<Vec<String>, DirectoryError> {
+    let profiles_directory = parameters_root_path.as_ref().join("profiles");
+    if !profiles_directory.exists() {
+        return Ok(vec![]);
+    }
+    let mut entries = read_dir(&profiles_directory).await.map_err(|source| {
+        DirectoryError::ProfilesNotListed {
+            source,
+            path: profiles_directory.clone(),
+        }
+    })?;
+    let mut profiles = vec![];
+    while let Some(entry) =
+        entries
+            .next_entry()
             .await
-            .map_err(DirectoryError::HeadParametersOfLocationNotGet)?;
-        merge_json(&mut parameters, &location_head_parameters);
+            .map_err(|source| DirectoryError::ProfilesNotListed {
+                source,
+                path: profiles_directory.clone(),
+            })?
+    {
+        let file_path = entry.path();
+        if file_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            == Some("json")
+        {
+            if let Some(name) = file_path.file_stem().and_then(|stem| stem.to_str()) {
+                profiles.push(name.to_string());
+            }
+        }
     }
-
-    from_value(parameters).map_err(DirectoryError::JsonValueNotConvertedToParameters)
+    profiles.sort();
+    Ok(profiles)
 }
 
 pub async fn serialize<Parameters>(
@@ -116,13 +250,38 @@ pub async fn serialize<Parameters>(
     body_id: &str,
     head_id: &str,
 ) -> Result<(), DirectoryError>
+where
+    Parameters: DeserializeOwned + Serialize,
+{
+    serialize_with_profiles(
+        parameters,
+        scope,
+        path,
+        parameters_root_path,
+        body_id,
+        head_id,
+        &[],
+    )
+    .await
+}
+
+/// Like [`serialize`] but values coming from the given profiles are not written to the scope
+pub async fn serialize_with_profiles<Parameters>(
+    parameters: &Parameters,
+    scope: Scope,
+    path: &str,
+    parameters_root_path: impl AsRef<Path>,
+    body_id: &str,
+    head_id: &str,
+    profiles: &[String],
+) -> Result<(), DirectoryError>
 where
     Parameters: DeserializeOwned + Serialize,
 {
     let mut parameters =
         to_value(parameters).map_err(DirectoryError::ParametersNotConvertedToJsonValue)?;
     let stored_parameters = to_value(
-        deserialize::<Parameters>(&parameters_root_path, body_id, head_id)
+        deserialize_with_profiles::<Parameters>(&parameters_root_path, body_id, head_id, profiles)
             .await
             .map_err(|error| {
                 println!("{:?}", error);
@@ -152,6 +311,33 @@ where
         .map_err(DirectoryError::HeadParametersOfLocationNotSet)
 }
 
+/// Appends one JSON line to the change log stored next to the parameter files
+pub async fn append_to_change_log(
+    parameters_root_path: impl AsRef<Path>,
+    entry: &impl Serialize,
+) -> Result<(), SerializationError> {
+    let file_path = parameters_root_path.as_ref().join("change_log.jsonl");
+    let line = to_string(entry).map_err(|source| SerializationError::FileNotSerialized {
+        source,
+        path: file_path.clone(),
+    })? + "\n";
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file_path)
+        .await
+        .map_err(|source| SerializationError::FileNotWritten {
+            source,
+            path: file_path.clone(),
+        })?;
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|source| SerializationError::FileNotWritten {
+            source,
+            path: file_path,
+        })
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Scope {
     pub location: Location,
@@ -190,6 +376,13 @@ fn file_path_from_scope(
     }
 }
 
+fn profile_file_path(parameters_root_path: impl AsRef<Path>, profile: &str) -> PathBuf {
+    parameters_root_path
+        .as_ref()
+        .join("profiles")
+        .join(format!("{profile}.json"))
+}
+
 fn location_directory_from_head_id(head_id: &str) -> &'static str {
     let webots_id_found = head_id.starts_with("webots");
     let behavior_simulator_id_found = head_id.starts_with("behavior_simulator");
@@ -232,3 +425,157 @@ async fn write_to_file(
             path: file_path.as_ref().to_path_buf(),
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use serde_json::json;
+    use tempfile::{tempdir, TempDir};
+
+    use super::*;
+
+    fn parameters_directory() -> TempDir {
+        let directory = tempdir().unwrap();
+        let root = directory.path();
+        create_dir_all(root.join("nao_location")).unwrap();
+        create_dir_all(root.join("profiles")).unwrap();
+        write(root.join("default.json"), r#"{"a":0,"b":0,"c":0,"d":0}"#).unwrap();
+        write(root.join("nao_location/default.json"), r#"{"a":1}"#).unwrap();
+        write(root.join("body.body.json"), r#"{"a":2,"b":2}"#).unwrap();
+        write(root.join("head.head.json"), r#"{"b":3}"#).unwrap();
+        write(root.join("profiles/penalty_shootout.json"), r#"{"c":4}"#).unwrap();
+        write(root.join("profiles/demo.json"), r#"{"c":5,"d":5}"#).unwrap();
+        write(root.join("profiles/notes.txt"), "not a profile").unwrap();
+        directory
+    }
+
+    #[test]
+    fn merge_order_lists_existing_layers_before_profiles() {
+        let directory = parameters_directory();
+        let root = directory.path();
+
+        let merge_order = merge_order(
+            root,
+            "body",
+            "head",
+            &["penalty_shootout".to_string(), "demo".to_string()],
+        );
+
+        assert_eq!(
+            merge_order,
+            vec![
+                root.join("default.json"),
+                root.join("nao_location/default.json"),
+                root.join("body.body.json"),
+                root.join("head.head.json"),
+                root.join("profiles/penalty_shootout.json"),
+                root.join("profiles/demo.json"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn profiles_are_merged_on_top_in_the_given_order() {
+        let directory = parameters_directory();
+
+        let without_profiles: Value =
+            deserialize_with_profiles(directory.path(), "body", "head", &[])
+                .await
+                .unwrap();
+        let with_profiles: Value = deserialize_with_profiles(
+            directory.path(),
+            "body",
+            "head",
+            &["penalty_shootout".to_string(), "demo".to_string()],
+        )
+        .await
+        .unwrap();
+        let with_reversed_profiles: Value = deserialize_with_profiles(
+            directory.path(),
+            "body",
+            "head",
+            &["demo".to_string(), "penalty_shootout".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(without_profiles, json!({"a":2,"b":3,"c":0,"d":0}));
+        assert_eq!(with_profiles, json!({"a":2,"b":3,"c":5,"d":5}));
+        assert_eq!(with_reversed_profiles, json!({"a":2,"b":3,"c":4,"d":5}));
+    }
+
+    #[tokio::test]
+    async fn locations_override_the_configured_location() {
+        let directory = parameters_directory();
+        let root = directory.path();
+        create_dir_all(root.join("field_a")).unwrap();
+        write(root.join("field_a/default.json"), r#"{"c":6}"#).unwrap();
+        write(root.join("field_a/head.head.json"), r#"{"a":7}"#).unwrap();
+
+        let configured_layers = read_layers(root, None, "body", "head", &[]).await.unwrap();
+        let overridden_layers = read_layers(root, Some("field_a"), "body", "head", &[])
+            .await
+            .unwrap();
+
+        let paths = |layers: &[Layer]| -> Vec<_> {
+            layers.iter().map(|layer| layer.path.clone()).collect()
+        };
+        assert_eq!(
+            paths(&configured_layers),
+            [
+                root.join("default.json"),
+                root.join("nao_location/default.json"),
+                root.join("body.body.json"),
+                root.join("head.head.json"),
+            ]
+        );
+        assert_eq!(
+            paths(&overridden_layers),
+            [
+                root.join("default.json"),
+                root.join("field_a/default.json"),
+                root.join("body.body.json"),
+                root.join("head.head.json"),
+                root.join("field_a/head.head.json"),
+            ]
+        );
+        let mut merged = Value::Null;
+        for layer in &overridden_layers {
+            merge_json(&mut merged, &layer.parameters);
+        }
+        assert_eq!(merged, json!({"a":7,"b":3,"c":6,"d":0}));
+    }
+
+    #[tokio::test]
+    async fn missing_profiles_are_errors() {
+        let directory = parameters_directory();
+
+        let result = deserialize_with_profiles::<Value>(
+            directory.path(),
+            "body",
+            "head",
+            &["missing".to_string()],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(DirectoryError::ProfileParametersNotGet { profile, .. }) if profile == "missing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_json_files_are_listed_as_profiles() {
+        let directory = parameters_directory();
+
+        assert_eq!(
+            list_profiles(directory.path()).await.unwrap(),
+            vec!["demo".to_string(), "penalty_shootout".to_string()]
+        );
+        assert!(list_profiles(directory.path().join("missing"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde_json::{json, Map, Value};
 
 pub fn merge_json(own: &mut Value, other: &Value) {
@@ -67,6 +69,45 @@ pub fn nest_value_at_path(path: &str, value: Value) -> Value {
         })
 }
 
+/// Replaces all non-object values by `leaf`. Merging the results with [`merge_json`] yields the
+/// same structure as merging the original values, which allows tracking where values come from.
+pub fn replace_leaves(value: &Value, leaf: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), replace_leaves(value, leaf)))
+                .collect(),
+        ),
+        _ => leaf.clone(),
+    }
+}
+
+/// Non-object values and empty objects by their dot separated paths
+pub fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    fn flatten_into(prefix: &str, value: &Value, leaves: &mut BTreeMap<String, Value>) {
+        match value {
+            Value::Object(object) if !object.is_empty() => {
+                for (key, value) in object {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    flatten_into(&path, value, leaves);
+                }
+            }
+            _ => {
+                leaves.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut leaves = BTreeMap::new();
+    flatten_into("", value, &mut leaves);
+    leaves
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +209,31 @@ mod tests {
             assert_eq!(nest_value_at_path(path, value), expected_output);
         }
     }
+
+    #[test]
+    fn sources_of_merged_leaves_are_tracked() {
+        let default = json!({"a":{"b":1,"c":[1,2]},"d":{"e":true}});
+        let location = json!({"a":{"c":[3]},"d":null});
+        let mut merged = default.clone();
+        merge_json(&mut merged, &location);
+        let mut sources = replace_leaves(&default, &json!("default"));
+        merge_json(&mut sources, &replace_leaves(&location, &json!("location")));
+
+        assert_eq!(
+            flatten(&merged),
+            BTreeMap::from([
+                ("a.b".to_string(), json!(1)),
+                ("a.c".to_string(), json!([3])),
+                ("d".to_string(), Value::Null),
+            ])
+        );
+        assert_eq!(
+            flatten(&sources),
+            BTreeMap::from([
+                ("a.b".to_string(), json!("default")),
+                ("a.c".to_string(), json!("location")),
+                ("d".to_string(), json!("location")),
+            ])
+        );
+    }
 }
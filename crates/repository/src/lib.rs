@@ -36,6 +36,10 @@ use tokio::{
 
 use spl_network_messages::PlayerNumber;
 
+use provenance::{Provenance, PROVENANCE_FILE_NAME};
+
+pub mod provenance;
+
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
@@ -294,9 +298,30 @@ impl Repository {
         .await
         .wrap_err("failed to link executable")?;
 
+        let location = self
+            .get_configured_locations()
+            .await
+            .wrap_err("failed to get configured locations")?
+            .remove("nao_location")
+            .flatten();
+        let provenance =
+            Provenance::collect(&self.root, &self.parameters_root(), profile, location)
+                .await
+                .wrap_err("failed to collect provenance")?;
+        write(
+            hulk_directory.join(PROVENANCE_FILE_NAME),
+            to_string_pretty(&provenance).wrap_err("failed to serialize provenance")? + "\n",
+        )
+        .await
+        .wrap_err("failed to write provenance")?;
+
         Ok((upload_directory, hulk_directory))
     }
 
+    pub async fn get_parameter_checksums(&self) -> Result<BTreeMap<String, String>> {
+        provenance::parameter_checksums(&self.parameters_root()).await
+    }
+
     pub async fn get_hardware_ids(&self) -> Result<HashMap<u8, HardwareIds>> {
         let hardware_ids_path = self.root.join("etc/parameters/hardware_ids.json");
         let mut hardware_ids = File::open(&hardware_ids_path)
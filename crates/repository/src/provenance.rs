@@ -0,0 +1,312 @@
+use std::{collections::BTreeMap, env, path::Path, process::Stdio};
+
+use chrono::Utc;
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use walkdir::WalkDir;
+
+/// File in the upload directory describing what has been uploaded
+pub const PROVENANCE_FILE_NAME: &str = "provenance.json";
+
+/// Record of what has been uploaded to a NAO
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Provenance {
+    /// `None` if the repository is not a git repository
+    pub git_revision: Option<String>,
+    /// Hash of `git diff HEAD` and the untracked files, `None` if there are no uncommitted changes
+    pub dirty_diff_hash: Option<String>,
+    pub profile: String,
+    pub location: Option<String>,
+    /// Git blob hashes of the node parameter files, keyed by their relative path
+    pub parameter_checksums: BTreeMap<String, String>,
+    pub uploaded_at: String,
+    pub uploaded_by: Option<String>,
+}
+
+impl Provenance {
+    pub async fn collect(
+        repository_root: &Path,
+        parameters_root: &Path,
+        profile: &str,
+        location: Option<String>,
+    ) -> Result<Self> {
+        let git_revision = git(repository_root, &["rev-parse", "HEAD"], None)
+            .await
+            .ok()
+            .map(|output| output.trim().to_string());
+        let dirty_diff_hash = match git_revision {
+            Some(_) => dirty_diff_hash(repository_root).await?,
+            None => None,
+        };
+        let parameter_checksums = parameter_checksums(parameters_root)
+            .await
+            .wrap_err("failed to hash parameters")?;
+
+        Ok(Self {
+            git_revision,
+            dirty_diff_hash,
+            profile: profile.to_string(),
+            location,
+            parameter_checksums,
+            uploaded_at: Utc::now().to_rfc3339(),
+            uploaded_by: env::var("USER").ok(),
+        })
+    }
+
+    /// Parameter files which are missing, added or changed in `checksums`
+    pub fn differing_parameters<'a>(
+        &'a self,
+        checksums: &'a BTreeMap<String, String>,
+    ) -> Vec<&'a str> {
+        let mut paths: Vec<_> = self
+            .parameter_checksums
+            .keys()
+            .chain(checksums.keys())
+            .filter(|path| self.parameter_checksums.get(*path) != checksums.get(*path))
+            .map(String::as_str)
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        paths
+    }
+}
+
+/// `git diff` does not contain untracked files, they are appended by their path and content hash
+async fn dirty_diff_hash(repository_root: &Path) -> Result<Option<String>> {
+    let diff = git(repository_root, &["diff", "HEAD", "--binary"], None)
+        .await
+        .wrap_err("failed to get uncommitted changes")?;
+    let untracked_paths = git(
+        repository_root,
+        &["ls-files", "--others", "--exclude-standard"],
+        None,
+    )
+    .await
+    .wrap_err("failed to list untracked files")?;
+    if diff.is_empty() && untracked_paths.is_empty() {
+        return Ok(None);
+    }
+    let untracked_hashes = if untracked_paths.is_empty() {
+        String::new()
+    } else {
+        git(
+            repository_root,
+            &["hash-object", "--stdin-paths"],
+            Some(untracked_paths.as_bytes()),
+        )
+        .await
+        .wrap_err("failed to hash untracked files")?
+    };
+    let mut changes = diff;
+    for (path, hash) in untracked_paths.lines().zip(untracked_hashes.lines()) {
+        changes.push_str(&format!("untracked {path} {hash}\n"));
+    }
+    let hash = git(
+        repository_root,
+        &["hash-object", "--stdin"],
+        Some(changes.as_bytes()),
+    )
+    .await
+    .wrap_err("failed to hash uncommitted changes")?;
+    Ok(Some(hash.trim().to_string()))
+}
+
+/// Locations are symlinked into the parameters directory, symlinks are skipped to hash every file
+/// only once. Only parameter files of the nodes are hashed, not the hardware configuration or
+/// the change log written by the communication server.
+pub async fn parameter_checksums(parameters_root: &Path) -> Result<BTreeMap<String, String>> {
+    let paths = WalkDir::new(parameters_root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Ok(entry) if entry.file_type().is_file() && is_node_parameter_file(entry.path()) => {
+                Some(Ok(entry.into_path()))
+            }
+            Ok(_) => None,
+            Err(error) => Some(Err(error)),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .wrap_err("failed to list parameter files")?;
+    if paths.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let mut arguments = vec!["hash-object", "--"];
+    arguments.extend(
+        paths
+            .iter()
+            .map(|path| {
+                path.to_str()
+                    .ok_or_else(|| eyre!("failed to convert {path:?} to UTF-8"))
+            })
+            .collect::<Result<Vec<_>>>()?,
+    );
+    let hashes = git(parameters_root, &arguments, None).await?;
+
+    paths
+        .iter()
+        .zip(hashes.lines())
+        .map(|(path, hash)| {
+            let relative_path = path
+                .strip_prefix(parameters_root)
+                .wrap_err("failed to get relative parameter path")?;
+            Ok((relative_path.display().to_string(), hash.to_string()))
+        })
+        .collect()
+}
+
+fn is_node_parameter_file(path: &Path) -> bool {
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension == "json");
+    let is_hardware = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("hardware"));
+    is_json && !is_hardware
+}
+
+async fn git(directory: &Path, arguments: &[&str], stdin: Option<&[u8]>) -> Result<String> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .wrap_err("failed to spawn git")?;
+    let mut child_stdin = child.stdin.take().expect("stdin should be piped");
+    if let Some(stdin) = stdin {
+        child_stdin
+            .write_all(stdin)
+            .await
+            .wrap_err("failed to write to git")?;
+    }
+    drop(child_stdin);
+    let output = child
+        .wait_with_output()
+        .await
+        .wrap_err("failed to wait for git")?;
+    if !output.status.success() {
+        bail!("git {} exited with {}", arguments.join(" "), output.status);
+    }
+    String::from_utf8(output.stdout).wrap_err("failed to decode UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn only_node_parameter_files_are_hashed() {
+        let parameters = tempdir().unwrap();
+        let root = parameters.path();
+        create_dir_all(root.join("field_a")).unwrap();
+        write(root.join("default.json"), "{}").unwrap();
+        write(root.join("head.P0000074A05S98U00019.json"), "{}").unwrap();
+        write(root.join("field_a/default.json"), "{\"a\": 1}").unwrap();
+        write(root.join("hardware.json"), "{}").unwrap();
+        write(root.join("hardware_ids.json"), "{}").unwrap();
+        write(root.join("change_log.jsonl"), "{}\n").unwrap();
+
+        let checksums = parameter_checksums(root).await.unwrap();
+
+        assert_eq!(
+            checksums.keys().collect::<Vec<_>>(),
+            [
+                "default.json",
+                "field_a/default.json",
+                "head.P0000074A05S98U00019.json"
+            ]
+        );
+        assert_eq!(
+            checksums["default.json"],
+            checksums["head.P0000074A05S98U00019.json"]
+        );
+        assert_ne!(checksums["default.json"], checksums["field_a/default.json"]);
+    }
+
+    #[tokio::test]
+    async fn untracked_files_make_the_tree_dirty() {
+        let repository = tempdir().unwrap();
+        let root = repository.path();
+        let git = |arguments: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args([
+                    "-c",
+                    "user.name=tester",
+                    "-c",
+                    "user.email=tester@localhost",
+                ])
+                .args(arguments)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {arguments:?} failed");
+        };
+        git(&["init", "--quiet"]);
+        write(root.join(".gitignore"), "target\n").unwrap();
+        git(&["add", "--all"]);
+        git(&["commit", "--quiet", "--message", "initial"]);
+        create_dir_all(root.join("target")).unwrap();
+        write(root.join("target/ignored"), "ignored").unwrap();
+
+        let clean_hash = dirty_diff_hash(root).await.unwrap();
+        write(root.join("new.rs"), "first").unwrap();
+        let untracked_hash = dirty_diff_hash(root).await.unwrap();
+        write(root.join("new.rs"), "second").unwrap();
+        let changed_untracked_hash = dirty_diff_hash(root).await.unwrap();
+
+        assert_eq!(clean_hash, None);
+        assert!(untracked_hash.is_some());
+        assert!(changed_untracked_hash.is_some());
+        assert_ne!(untracked_hash, changed_untracked_hash);
+    }
+
+    #[test]
+    fn missing_added_and_changed_parameter_files_differ() {
+        let checksums = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let provenance = Provenance {
+            git_revision: None,
+            dirty_diff_hash: None,
+            profile: "release".to_string(),
+            location: None,
+            parameter_checksums: checksums(&[
+                ("changed.json", "1"),
+                ("missing.json", "2"),
+                ("same.json", "3"),
+            ]),
+            uploaded_at: "2023-07-04T12:00:00+00:00".to_string(),
+            uploaded_by: None,
+        };
+        let local = checksums(&[
+            ("added.json", "4"),
+            ("changed.json", "5"),
+            ("same.json", "3"),
+        ]);
+
+        assert_eq!(
+            provenance.differing_parameters(&local),
+            ["added.json", "changed.json", "missing.json"]
+        );
+        assert!(provenance
+            .differing_parameters(&provenance.parameter_checksums)
+            .is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     ops::{Deref, Range},
     sync::Arc,
 };
@@ -7,7 +7,7 @@ use std::{
 use nalgebra::{ArrayStorage, Const, Matrix, Point, Scalar, U1};
 use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{error::Error, SerializeHierarchy};
+use crate::{error::Error, FieldMetadata, SerializeHierarchy};
 
 impl<T> SerializeHierarchy for Arc<T>
 where
@@ -37,6 +37,14 @@ where
     fn get_fields() -> BTreeSet<String> {
         T::get_fields()
     }
+
+    fn get_metadata() -> BTreeMap<String, FieldMetadata> {
+        T::get_metadata()
+    }
+
+    fn get_variants() -> Option<Vec<String>> {
+        T::get_variants()
+    }
 }
 
 impl<T> SerializeHierarchy for Option<T>
@@ -72,6 +80,14 @@ where
     fn get_fields() -> BTreeSet<String> {
         T::get_fields()
     }
+
+    fn get_metadata() -> BTreeMap<String, FieldMetadata> {
+        T::get_metadata()
+    }
+
+    fn get_variants() -> Option<Vec<String>> {
+        T::get_variants()
+    }
 }
 
 impl<T> SerializeHierarchy for Range<T>
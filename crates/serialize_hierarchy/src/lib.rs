@@ -1,9 +1,10 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub use bincode;
 pub use error::Error;
 
 pub use jpeg::{DecodeJpeg, EncodeJpeg};
+pub use metadata::{validate, FieldMetadata, ValidationError};
 use serde::{Deserializer, Serializer};
 pub use serde_json;
 pub use serialize_hierarchy_derive::SerializeHierarchy;
@@ -11,6 +12,7 @@ pub use serialize_hierarchy_derive::SerializeHierarchy;
 pub mod error;
 mod implementation;
 mod jpeg;
+mod metadata;
 mod not_supported;
 
 pub trait SerializeHierarchy {
@@ -29,6 +31,15 @@ pub trait SerializeHierarchy {
     fn exists(path: &str) -> bool;
 
     fn get_fields() -> BTreeSet<String>;
+
+    fn get_metadata() -> BTreeMap<String, FieldMetadata> {
+        BTreeMap::new()
+    }
+
+    /// Names of the unit variants if the type is an enum
+    fn get_variants() -> Option<Vec<String>> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +60,20 @@ mod tests {
         field: bool,
     }
 
+    #[derive(Deserialize, Serialize, SerializeHierarchy)]
+    struct Documented {
+        /// Height of the hip above the ground
+        #[serialize_hierarchy(unit = "m", minimum = 0.0, maximum = "0.5")]
+        height: f32,
+        mode: Mode,
+    }
+
+    #[derive(Deserialize, Serialize, SerializeHierarchy)]
+    enum Mode {
+        Walking,
+        Standing,
+    }
+
     #[test]
     fn primitive_fields_are_empty() {
         assert_eq!(bool::get_fields(), Default::default());
@@ -66,4 +91,42 @@ mod tests {
             ["inner".to_string(), "inner.field".to_string()].into()
         );
     }
+
+    #[test]
+    fn metadata_is_collected_from_attributes() {
+        let metadata = Documented::get_metadata();
+        assert_eq!(
+            metadata["height"],
+            FieldMetadata {
+                type_name: "f32".to_string(),
+                documentation: Some("Height of the hip above the ground".to_string()),
+                unit: Some("m".to_string()),
+                minimum: Some(0.0),
+                maximum: Some(0.5),
+                variants: None,
+            }
+        );
+        assert_eq!(
+            metadata["mode"].variants,
+            Some(vec!["Walking".to_string(), "Standing".to_string()])
+        );
+    }
+
+    #[test]
+    fn values_outside_of_range_or_variants_are_rejected() {
+        let metadata = Documented::get_metadata();
+        assert!(validate(&metadata, "height", &serde_json::json!(0.3)).is_ok());
+        assert!(matches!(
+            validate(&metadata, "height", &serde_json::json!(0.7)),
+            Err(ValidationError::AboveMaximum { .. })
+        ));
+        assert!(matches!(
+            validate(
+                &metadata,
+                "",
+                &serde_json::json!({"height": 0.3, "mode": "Running"})
+            ),
+            Err(ValidationError::UnknownVariant { .. })
+        ));
+    }
 }
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ValidationError {
+    #[error("{path}: {value} is below the minimum of {minimum}")]
+    BelowMinimum {
+        path: String,
+        value: f64,
+        minimum: f64,
+    },
+    #[error("{path}: {value} is above the maximum of {maximum}")]
+    AboveMaximum {
+        path: String,
+        value: f64,
+        maximum: f64,
+    },
+    #[error("{path}: {variant:?} is not one of {variants:?}")]
+    UnknownVariant {
+        path: String,
+        variant: String,
+        variants: Vec<String>,
+    },
+}
+
+/// Description of a field collected from its type, doc comments and `serialize_hierarchy`
+/// attributes (`unit = "..."`, `minimum = ...`, `maximum = ...`)
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FieldMetadata {
+    pub type_name: String,
+    pub documentation: Option<String>,
+    pub unit: Option<String>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    /// names of the unit variants if the field is an enum
+    pub variants: Option<Vec<String>>,
+}
+
+// the ranges are parsed from number literals and therefore never NaN
+impl Eq for FieldMetadata {}
+
+impl FieldMetadata {
+    /// Checks the range of numbers (applied to each component of arrays) and enum variants
+    pub fn validate(&self, path: &str, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Number(number) => {
+                let Some(value) = number.as_f64() else {
+                    return Ok(());
+                };
+                if let Some(minimum) = self.minimum.filter(|&minimum| value < minimum) {
+                    return Err(ValidationError::BelowMinimum {
+                        path: path.to_string(),
+                        value,
+                        minimum,
+                    });
+                }
+                if let Some(maximum) = self.maximum.filter(|&maximum| value > maximum) {
+                    return Err(ValidationError::AboveMaximum {
+                        path: path.to_string(),
+                        value,
+                        maximum,
+                    });
+                }
+                Ok(())
+            }
+            Value::Array(values) => values
+                .iter()
+                .try_for_each(|value| self.validate(path, value)),
+            Value::String(variant) => match &self.variants {
+                Some(variants) if !variants.contains(variant) => {
+                    Err(ValidationError::UnknownVariant {
+                        path: path.to_string(),
+                        variant: variant.clone(),
+                        variants: variants.clone(),
+                    })
+                }
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Validates a value that is about to be written to `path` against the metadata of the path
+/// itself and all of its children
+pub fn validate(
+    metadata: &BTreeMap<String, FieldMetadata>,
+    path: &str,
+    value: &Value,
+) -> Result<(), ValidationError> {
+    let prefix = if path.is_empty() {
+        String::new()
+    } else {
+        format!("{path}.")
+    };
+    metadata
+        .iter()
+        .filter_map(|(field_path, field_metadata)| {
+            if field_path == path {
+                return Some((field_path, field_metadata, value));
+            }
+            let suffix = field_path.strip_prefix(&prefix)?;
+            let field_value = suffix
+                .split('.')
+                .try_fold(value, |value, segment| value.get(segment))?;
+            Some((field_path, field_metadata, field_value))
+        })
+        .try_for_each(|(field_path, field_metadata, field_value)| {
+            field_metadata.validate(field_path, field_value)
+        })
+}
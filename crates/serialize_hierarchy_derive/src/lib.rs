@@ -4,8 +4,8 @@ use proc_macro2::TokenStream;
 use proc_macro_error::{abort, proc_macro_error};
 use quote::{quote, ToTokens};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, Data, DataStruct, DeriveInput, Generics, Ident, Lit,
-    Meta, MetaNameValue, NestedMeta, Token, Type, WherePredicate,
+    parse_macro_input, punctuated::Punctuated, Data, DataEnum, DataStruct, DeriveInput, Fields,
+    Generics, Ident, Lit, Meta, MetaNameValue, NestedMeta, Token, Type, WherePredicate,
 };
 
 #[proc_macro_derive(SerializeHierarchy, attributes(serialize_hierarchy))]
@@ -45,6 +45,12 @@ fn process_input(mut input: DeriveInput) -> TokenStream {
     let field_exists_getters = generate_field_exists_getters(&serializable_fields);
     let field_chains = generate_field_chains(&serializable_fields);
     let path_field_chains = generate_path_field_chains(&serializable_fields);
+    let field_metadata_chains = generate_field_metadata_chains(&serializable_fields);
+    let path_metadata_chains = generate_path_metadata_chains(&serializable_fields);
+    let variants_getter = match &input.data {
+        Data::Enum(data) => generate_variants_getter(data),
+        _ => Default::default(),
+    };
     let (jpeg_serialization, jpeg_exists_getter, jpeg_field_chain) = if contains_as_jpeg {
         (
             quote! {
@@ -142,6 +148,15 @@ fn process_input(mut input: DeriveInput) -> TokenStream {
                     #jpeg_field_chain
                     .collect()
             }
+
+            fn get_metadata() -> std::collections::BTreeMap<String, serialize_hierarchy::FieldMetadata> {
+                std::iter::empty::<(std::string::String, serialize_hierarchy::FieldMetadata)>()
+                    #(#field_metadata_chains)*
+                    #(#path_metadata_chains)*
+                    .collect()
+            }
+
+            #variants_getter
         }
     };
     implementation
@@ -273,6 +288,96 @@ fn generate_path_field_chains(fields: &[&Field]) -> Vec<TokenStream> {
         .collect()
 }
 
+fn generate_field_metadata_chains(fields: &[&Field]) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .map(|field| {
+            let name_string = field.identifier.to_string();
+            let ty = &field.ty;
+            let type_name = ty.to_token_stream().to_string().replace(' ', "");
+            let documentation = quote_option(
+                field
+                    .metadata
+                    .documentation
+                    .as_ref()
+                    .map(|documentation| quote! { #documentation.to_string() }),
+            );
+            let unit = quote_option(
+                field
+                    .metadata
+                    .unit
+                    .as_ref()
+                    .map(|unit| quote! { #unit.to_string() }),
+            );
+            let minimum = quote_option(field.metadata.minimum.map(|minimum| quote! { #minimum }));
+            let maximum = quote_option(field.metadata.maximum.map(|maximum| quote! { #maximum }));
+            let variants = if field.attributes.contains(&FieldAttribute::Leaf) {
+                quote! { None }
+            } else {
+                quote! { <#ty as serialize_hierarchy::SerializeHierarchy>::get_variants() }
+            };
+            quote! {
+                .chain(std::iter::once((
+                    #name_string.to_string(),
+                    serialize_hierarchy::FieldMetadata {
+                        type_name: #type_name.to_string(),
+                        documentation: #documentation,
+                        unit: #unit,
+                        minimum: #minimum,
+                        maximum: #maximum,
+                        variants: #variants,
+                    },
+                )))
+            }
+        })
+        .collect()
+}
+
+fn generate_path_metadata_chains(fields: &[&Field]) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .filter(|field| !field.attributes.contains(&FieldAttribute::Leaf))
+        .map(|field| {
+            let identifier = &field.identifier;
+            let pattern = format!("{identifier}.{{}}");
+            let ty = &field.ty;
+            quote! {
+                .chain(
+                    <#ty as serialize_hierarchy::SerializeHierarchy>::get_metadata()
+                        .into_iter()
+                        .map(|(name, metadata)| (format!(#pattern, name), metadata))
+                )
+            }
+        })
+        .collect()
+}
+
+fn generate_variants_getter(data: &DataEnum) -> TokenStream {
+    if !data
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+    {
+        return Default::default();
+    }
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| variant.ident.to_string());
+    quote! {
+        fn get_variants() -> Option<Vec<String>> {
+            Some(vec![#(#variants.to_string()),*])
+        }
+    }
+}
+
+fn quote_option(value: Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 enum TypeAttribute {
     AsJpeg,
@@ -326,9 +431,18 @@ enum FieldAttribute {
     Leaf,
 }
 
+#[derive(Debug, Default)]
+struct FieldMetadata {
+    documentation: Option<String>,
+    unit: Option<String>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+}
+
 #[derive(Debug)]
 struct Field {
     attributes: HashSet<FieldAttribute>,
+    metadata: FieldMetadata,
     identifier: Ident,
     ty: Type,
 }
@@ -349,16 +463,34 @@ fn read_fields(input: &DataStruct) -> Vec<Field> {
         .fields
         .iter()
         .map(|field| {
-            let attributes = field
-                .attrs
-                .iter()
-                .flat_map(parse_meta_items)
-                .map(|meta| match meta {
+            let mut attributes = HashSet::new();
+            let mut metadata = FieldMetadata {
+                documentation: read_documentation(&field.attrs),
+                ..Default::default()
+            };
+            for meta in field.attrs.iter().flat_map(parse_meta_items) {
+                match meta {
                     NestedMeta::Meta(Meta::Path(word)) if word.is_ident("skip") => {
-                        FieldAttribute::Skip
+                        attributes.insert(FieldAttribute::Skip);
                     }
                     NestedMeta::Meta(Meta::Path(word)) if word.is_ident("leaf") => {
-                        FieldAttribute::Leaf
+                        attributes.insert(FieldAttribute::Leaf);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path, lit: literal, ..
+                    })) if path.is_ident("unit") => match literal {
+                        Lit::Str(unit) => metadata.unit = Some(unit.value()),
+                        _ => abort!(literal, "expected unit to be a string: `unit = \"...\"`"),
+                    },
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path, lit: literal, ..
+                    })) if path.is_ident("minimum") => {
+                        metadata.minimum = Some(parse_number(&literal));
+                    }
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path, lit: literal, ..
+                    })) if path.is_ident("maximum") => {
+                        metadata.maximum = Some(parse_number(&literal));
                     }
                     NestedMeta::Meta(meta_item) => {
                         let path = meta_item
@@ -372,8 +504,8 @@ fn read_fields(input: &DataStruct) -> Vec<Field> {
                     NestedMeta::Lit(lit) => {
                         abort!(lit, "unexpected literal in attribute")
                     }
-                })
-                .collect();
+                }
+            }
             let identifier = field
                 .ident
                 .clone()
@@ -381,9 +513,39 @@ fn read_fields(input: &DataStruct) -> Vec<Field> {
             let ty = field.ty.clone();
             Field {
                 attributes,
+                metadata,
                 identifier,
                 ty,
             }
         })
         .collect()
 }
+
+fn read_documentation(attributes: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<_> = attributes
+        .iter()
+        .filter(|attribute| attribute.path.is_ident("doc"))
+        .filter_map(|attribute| match attribute.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(line),
+                ..
+            })) => Some(line.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Numbers are accepted as literals or strings, the latter allow negative values
+fn parse_number(literal: &Lit) -> f64 {
+    let number = match literal {
+        Lit::Float(float) => float.base10_parse(),
+        Lit::Int(int) => int.base10_parse(),
+        Lit::Str(string) => string
+            .value()
+            .parse()
+            .map_err(|_| syn::Error::new(string.span(), "expected a number")),
+        _ => abort!(literal, "expected a number"),
+    };
+    number.unwrap_or_else(|error| abort!(error.span(), error.to_string()))
+}
@@ -5,6 +5,7 @@ use std::{
 
 use log::warn;
 use serde::Deserialize;
+use spl_network_messages::TeamMessage;
 use thiserror::Error;
 use tokio::{net::UdpSocket, select, sync::Mutex};
 use types::messages::{IncomingMessage, OutgoingMessage};
@@ -69,9 +70,12 @@ impl Endpoint {
                 result = self.spl_socket.recv_from(&mut spl_buffer) => {
                     let (received_bytes, _address) = result.map_err(Error::ReadError)?;
                     match bincode::deserialize(&spl_buffer[0..received_bytes]) {
-                        Ok(parsed_message) => {
+                        Ok(TeamMessage::Hulk(parsed_message)) => {
                             break Ok(IncomingMessage::Spl(parsed_message));
                         }
+                        Ok(TeamMessage::Health(parsed_message)) => {
+                            break Ok(IncomingMessage::Health(parsed_message));
+                        }
                         Err(error) => {
                             warn!("Failed to parse SPL message (will be discarded): {error:?}");
                             continue;
@@ -89,23 +93,12 @@ impl Endpoint {
                 self.send_game_controller_visual_referee_message(message)
                     .await;
             }
-            OutgoingMessage::Spl(message) => match bincode::serialize(&message) {
-                Ok(message) => {
-                    if let Err(error) = self
-                        .spl_socket
-                        .send_to(
-                            message.as_slice(),
-                            SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.ports.spl),
-                        )
-                        .await
-                    {
-                        warn!("Failed to send UDP datagram via SPL socket: {error:?}")
-                    }
-                }
-                Err(error) => {
-                    warn!("Failed to serialize Hulk Message: {error:?}")
-                }
-            },
+            OutgoingMessage::Spl(message) => {
+                self.send_team_message(TeamMessage::Hulk(message)).await;
+            }
+            OutgoingMessage::Health(message) => {
+                self.send_team_message(TeamMessage::Health(message)).await;
+            }
             OutgoingMessage::VisualReferee(message) => {
                 let message: Vec<u8> = message.into();
                 self.send_game_controller_visual_referee_message(message)
@@ -114,6 +107,26 @@ impl Endpoint {
         };
     }
 
+    async fn send_team_message(&self, message: TeamMessage) {
+        match bincode::serialize(&message) {
+            Ok(message) => {
+                if let Err(error) = self
+                    .spl_socket
+                    .send_to(
+                        message.as_slice(),
+                        SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.ports.spl),
+                    )
+                    .await
+                {
+                    warn!("Failed to send UDP datagram via SPL socket: {error:?}")
+                }
+            }
+            Err(error) => {
+                warn!("Failed to serialize Hulk Message: {error:?}")
+            }
+        }
+    }
+
     async fn send_game_controller_visual_referee_message(&self, message: Vec<u8>) {
         let last_game_controller_address = *self.last_game_controller_address.lock().await;
         if let Some(last_game_controller_address) = last_game_controller_address {
@@ -26,6 +26,22 @@ pub struct HulkMessage {
     pub robot_to_field: Isometry2<f32>,
     pub ball_position: Option<BallPosition>,
     pub time_to_reach_kick_position: Option<Duration>,
+    pub pass_target: Option<PassTarget>,
+    pub requests_substitution: bool,
+}
+
+/// Health of a robot, sent separately from [`HulkMessage`]s since these claim the striker role
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct HealthMessage {
+    pub player_number: PlayerNumber,
+    pub requests_substitution: bool,
+}
+
+/// Wire format of all messages exchanged between HULKs robots
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum TeamMessage {
+    Hulk(HulkMessage),
+    Health(HealthMessage),
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -34,6 +50,12 @@ pub struct BallPosition {
     pub age: Duration,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PassTarget {
+    pub receiver: PlayerNumber,
+    pub target_in_field: Point2<f32>,
+}
+
 pub const HULKS_TEAM_NUMBER: u8 = 24;
 
 #[derive(
@@ -70,9 +92,9 @@ impl Display for PlayerNumber {
 mod tests {
     use std::time::Duration;
 
-    use nalgebra::Isometry2;
+    use nalgebra::{point, Isometry2};
 
-    use crate::{BallPosition, HulkMessage, PlayerNumber};
+    use crate::{BallPosition, HulkMessage, PassTarget, PlayerNumber, TeamMessage};
 
     #[test]
     fn maximum_hulk_message_size() {
@@ -85,7 +107,17 @@ mod tests {
                 age: Duration::MAX,
             }),
             time_to_reach_kick_position: Some(Duration::MAX),
+            pass_target: Some(PassTarget {
+                receiver: PlayerNumber::Seven,
+                target_in_field: point![0.0, 0.0],
+            }),
+            requests_substitution: true,
         };
-        assert!(bincode::serialize(&test_message).unwrap().len() <= 128)
+        assert!(
+            bincode::serialize(&TeamMessage::Hulk(test_message))
+                .unwrap()
+                .len()
+                <= 128
+        )
     }
 }
@@ -0,0 +1,221 @@
+use std::{
+    fs::{read_dir, remove_file, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use framework::log_context;
+use serde::{Deserialize, Serialize};
+
+pub const FILE_PREFIX: &str = "hulk.";
+pub const FILE_EXTENSION: &str = "jsonl";
+
+/// One line of a structured log file
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LogRecord {
+    /// RFC 3339 in UTC with milliseconds, lexicographically ordered
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub cycler: Option<String>,
+    pub node: Option<String>,
+    pub cycle: Option<u64>,
+    pub game_state: Option<String>,
+    pub message: String,
+}
+
+impl LogRecord {
+    pub fn from_record(record: &log::Record) -> Self {
+        let context = log_context::current();
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            cycler: context.cycler,
+            node: context.node.map(ToString::to_string),
+            cycle: context.cycle,
+            game_state: log_context::game_state(),
+            message: record.args().to_string(),
+        }
+    }
+
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).expect("log record should be serializable")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RotationParameters {
+    pub directory: PathBuf,
+    /// Size in bytes after which a new file is started
+    pub maximum_file_size: u64,
+    /// Older files are removed
+    pub maximum_files: usize,
+}
+
+/// Writes lines into `hulk.<unix milliseconds>.jsonl` files, starting a new file at a line
+/// boundary after the maximum file size is exceeded
+pub struct RotatingWriter {
+    parameters: RotationParameters,
+    file: File,
+    written: u64,
+    at_line_start: bool,
+}
+
+impl RotatingWriter {
+    pub fn new(parameters: RotationParameters) -> io::Result<Self> {
+        std::fs::create_dir_all(&parameters.directory)?;
+        // each start opens a new file, crash looping must not fill the disk
+        let file = create_file(&parameters)?;
+        remove_superfluous_files(&parameters)?;
+        Ok(Self {
+            parameters,
+            file,
+            written: 0,
+            at_line_start: true,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file = create_file(&self.parameters)?;
+        self.written = 0;
+        remove_superfluous_files(&self.parameters)
+    }
+}
+
+impl Write for RotatingWriter {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        if self.at_line_start && self.written >= self.parameters.maximum_file_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buffer)?;
+        self.written += written as u64;
+        if written > 0 {
+            self.at_line_start = buffer[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn create_file(parameters: &RotationParameters) -> io::Result<File> {
+    // the new file has to be the newest one even if files were created in the same millisecond
+    let newest_milliseconds = log_files(&parameters.directory)?
+        .last()
+        .and_then(|path| file_milliseconds(path));
+    let mut milliseconds = Utc::now()
+        .timestamp_millis()
+        .max(newest_milliseconds.map_or(i64::MIN, |milliseconds| milliseconds + 1));
+    loop {
+        let path = parameters
+            .directory
+            .join(format!("{FILE_PREFIX}{milliseconds}.{FILE_EXTENSION}"));
+        match File::options().write(true).create_new(true).open(path) {
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => milliseconds += 1,
+            result => return result,
+        }
+    }
+}
+
+fn remove_superfluous_files(parameters: &RotationParameters) -> io::Result<()> {
+    let mut files = log_files(&parameters.directory)?;
+    let superfluous_files = files.len().saturating_sub(parameters.maximum_files);
+    for path in files.drain(..superfluous_files) {
+        remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Structured log files in the directory, oldest first
+pub fn log_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in read_dir(directory)? {
+        let path = entry?.path();
+        let is_log_file = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.starts_with(FILE_PREFIX) && name.ends_with(&format!(".{FILE_EXTENSION}"))
+            });
+        if is_log_file {
+            files.push(path);
+        }
+    }
+    files.sort_by_key(|path| file_milliseconds(path));
+    Ok(files)
+}
+
+fn file_milliseconds(path: &Path) -> Option<i64> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.strip_prefix(FILE_PREFIX))
+        .and_then(|milliseconds| milliseconds.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn files_are_rotated_at_line_boundaries_and_old_files_removed() {
+        let directory = tempdir().unwrap();
+        let mut writer = RotatingWriter::new(RotationParameters {
+            directory: directory.path().to_path_buf(),
+            maximum_file_size: 10,
+            maximum_files: 2,
+        })
+        .unwrap();
+
+        for line in ["first", "second", "third"] {
+            // lines are written in pieces, they must not be split across files
+            writer.write_all(line.as_bytes()).unwrap();
+            writer.write_all(b" line is long\n").unwrap();
+        }
+        writer.flush().unwrap();
+
+        let files = log_files(directory.path()).unwrap();
+        let contents: Vec<_> = files
+            .iter()
+            .map(|path| read_to_string(path).unwrap())
+            .collect();
+        assert_eq!(contents, ["second line is long\n", "third line is long\n"]);
+    }
+
+    #[test]
+    fn restarting_keeps_at_most_maximum_files() {
+        let directory = tempdir().unwrap();
+        let parameters = RotationParameters {
+            directory: directory.path().to_path_buf(),
+            maximum_file_size: 1000,
+            maximum_files: 2,
+        };
+
+        for start in 0..5 {
+            let mut writer = RotatingWriter::new(parameters.clone()).unwrap();
+            writeln!(writer, "start {start}").unwrap();
+            writer.flush().unwrap();
+        }
+
+        let files = log_files(directory.path()).unwrap();
+        let contents: Vec<_> = files
+            .iter()
+            .map(|path| read_to_string(path).unwrap())
+            .collect();
+        assert_eq!(contents, ["start 3\n", "start 4\n"]);
+    }
+}
@@ -0,0 +1,19 @@
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BalanceControlState {
+    pub center_of_mass: Point2<f32>,
+    pub center_of_mass_height: f32,
+    pub center_of_mass_velocity: Vector2<f32>,
+    pub capture_point: Point2<f32>,
+    pub capture_point_error: Vector2<f32>,
+    pub zero_moment_point: Option<Point2<f32>>,
+    pub zero_moment_point_error: Vector2<f32>,
+    pub ankle_pitch_adjustment: f32,
+    pub ankle_roll_adjustment: f32,
+    pub step_placement_adjustment: Vector2<f32>,
+    pub step_duration_factor: f32,
+    pub is_disturbed: bool,
+}
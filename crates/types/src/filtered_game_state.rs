@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::Team;
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, SerializeHierarchy)]
 pub enum FilteredGameState {
     Initial,
     Ready { kicking_team: Team },
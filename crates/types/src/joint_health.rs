@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// File in the statistics directory the joint health of a body is saved to
+pub fn statistics_file_name(body_id: &str) -> String {
+    format!("joint_health_{body_id}.json")
+}
+
+/// Mean weighting all samples equally until the maximum number of samples is reached, older
+/// samples decay exponentially afterwards
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RunningMean {
+    pub mean: f32,
+    pub samples: usize,
+}
+
+impl RunningMean {
+    pub fn update(&mut self, value: f32, maximum_samples: usize) {
+        self.samples = (self.samples + 1).min(maximum_samples.max(1));
+        self.mean += (value - self.mean) / self.samples as f32;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct JointIssues {
+    pub calibration_drift: bool,
+    pub backlash: bool,
+    pub stiffness_anomaly: bool,
+}
+
+impl JointIssues {
+    pub fn any(&self) -> bool {
+        self.calibration_drift || self.backlash || self.stiffness_anomaly
+    }
+}
+
+/// Statistics of the difference between measured and commanded positions of a joint
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct JointHealth {
+    /// Measured minus commanded position after settling at a position approached from below
+    pub settled_error_from_below: RunningMean,
+    /// Measured minus commanded position after settling at a position approached from above
+    pub settled_error_from_above: RunningMean,
+    /// Absolute difference between measured and commanded position while moving
+    pub tracking_error: RunningMean,
+    pub issues: JointIssues,
+}
+
+impl JointHealth {
+    /// Settled error independent of the direction of approach, e.g. from drifted calibration
+    pub fn offset(&self) -> Option<f32> {
+        match (
+            self.settled_error_from_below.samples,
+            self.settled_error_from_above.samples,
+        ) {
+            (0, 0) => None,
+            (_, 0) => Some(self.settled_error_from_below.mean),
+            (0, _) => Some(self.settled_error_from_above.mean),
+            _ => Some(
+                (self.settled_error_from_below.mean + self.settled_error_from_above.mean) / 2.0,
+            ),
+        }
+    }
+
+    /// Play between both directions of approach
+    pub fn backlash(&self) -> Option<f32> {
+        if self.settled_error_from_below.samples == 0 || self.settled_error_from_above.samples == 0
+        {
+            return None;
+        }
+        Some((self.settled_error_from_above.mean - self.settled_error_from_below.mean).abs())
+    }
+}
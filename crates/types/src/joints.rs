@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use splines::impl_Interpolate;
 
+use crate::support_foot::Side;
+
 #[derive(
     Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize, SerializeHierarchy,
 )]
@@ -32,6 +34,10 @@ where
     pub fn as_vec(&self) -> Vec<T> {
         vec![self.yaw.clone(), self.pitch.clone()]
     }
+
+    pub fn as_vec_mut(&mut self) -> Vec<&mut T> {
+        vec![&mut self.yaw, &mut self.pitch]
+    }
 }
 
 impl<T> From<Joints<T>> for HeadJoints<T> {
@@ -153,6 +159,17 @@ where
             self.hand.clone(),
         ]
     }
+
+    pub fn as_vec_mut(&mut self) -> Vec<&mut T> {
+        vec![
+            &mut self.shoulder_pitch,
+            &mut self.shoulder_roll,
+            &mut self.elbow_yaw,
+            &mut self.elbow_roll,
+            &mut self.wrist_yaw,
+            &mut self.hand,
+        ]
+    }
 }
 
 impl<T> Add for ArmJoints<T>
@@ -253,6 +270,44 @@ impl ArmJoints<f32> {
     }
 }
 
+/// Angle limits of the left arm, the right arm is limited by the mirrored angles
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ArmJointLimits {
+    pub minimum: ArmJoints<f32>,
+    pub maximum: ArmJoints<f32>,
+}
+
+impl ArmJointLimits {
+    pub fn clamp(&self, side: Side, angles: ArmJoints<f32>) -> ArmJoints<f32> {
+        let left_angles = match side {
+            Side::Left => angles,
+            Side::Right => angles.mirrored(),
+        };
+        let clamped_angles = ArmJoints {
+            shoulder_pitch: left_angles
+                .shoulder_pitch
+                .clamp(self.minimum.shoulder_pitch, self.maximum.shoulder_pitch),
+            shoulder_roll: left_angles
+                .shoulder_roll
+                .clamp(self.minimum.shoulder_roll, self.maximum.shoulder_roll),
+            elbow_yaw: left_angles
+                .elbow_yaw
+                .clamp(self.minimum.elbow_yaw, self.maximum.elbow_yaw),
+            elbow_roll: left_angles
+                .elbow_roll
+                .clamp(self.minimum.elbow_roll, self.maximum.elbow_roll),
+            wrist_yaw: left_angles
+                .wrist_yaw
+                .clamp(self.minimum.wrist_yaw, self.maximum.wrist_yaw),
+            hand: left_angles.hand.clamp(self.minimum.hand, self.maximum.hand),
+        };
+        match side {
+            Side::Left => clamped_angles,
+            Side::Right => clamped_angles.mirrored(),
+        }
+    }
+}
+
 #[derive(
     Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize, SerializeHierarchy,
 )]
@@ -291,6 +346,17 @@ where
             self.ankle_roll.clone(),
         ]
     }
+
+    pub fn as_vec_mut(&mut self) -> Vec<&mut T> {
+        vec![
+            &mut self.hip_yaw_pitch,
+            &mut self.hip_roll,
+            &mut self.hip_pitch,
+            &mut self.knee_pitch,
+            &mut self.ankle_pitch,
+            &mut self.ankle_roll,
+        ]
+    }
 }
 
 impl<T> Add for LegJoints<T>
@@ -547,6 +613,16 @@ where
             self.right_leg.as_vec(),
         ]
     }
+
+    pub fn as_vec_mut(&mut self) -> Vec<Vec<&mut T>> {
+        vec![
+            self.head.as_vec_mut(),
+            self.left_arm.as_vec_mut(),
+            self.right_arm.as_vec_mut(),
+            self.left_leg.as_vec_mut(),
+            self.right_leg.as_vec_mut(),
+        ]
+    }
 }
 
 impl<T> Joints<T> {
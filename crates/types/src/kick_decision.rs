@@ -2,11 +2,20 @@ use nalgebra::Isometry2;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
-use crate::{motion_command::KickVariant, support_foot::Side};
+use crate::{
+    motion_command::{KickVariant, StandingKickVariant},
+    support_foot::Side,
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum KickKind {
+    InWalk(KickVariant),
+    Standing(StandingKickVariant),
+}
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, SerializeHierarchy)]
 pub struct KickDecision {
-    pub variant: KickVariant,
+    pub kind: KickKind,
     pub kicking_side: Side,
     pub kick_pose: Isometry2<f32>,
     pub strength: f32,
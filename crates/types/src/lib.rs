@@ -1,6 +1,7 @@
 #![recursion_limit = "256"]
 pub mod action;
 pub mod audio;
+pub mod balance_control;
 pub mod ball;
 pub mod ball_filter;
 pub mod ball_position;
@@ -29,6 +30,7 @@ pub mod image_segments;
 pub mod initial_look_around;
 pub mod initial_pose;
 pub mod interpolated;
+pub mod joint_health;
 pub mod joints;
 pub mod joints_velocity;
 pub mod kick_decision;
@@ -48,6 +50,7 @@ pub mod obstacle_filter;
 pub mod obstacles;
 pub mod orientation_filter;
 pub mod parameters;
+pub mod pass;
 pub mod path_obstacles;
 pub mod penalty_shot_direction;
 pub mod perspective_grid_candidates;
@@ -56,6 +59,7 @@ pub mod players;
 pub mod point_of_interest;
 pub mod primary_state;
 pub mod robot_dimensions;
+pub mod robot_health;
 pub mod robot_kinematics;
 pub mod robot_masses;
 pub mod roles;
@@ -68,6 +72,7 @@ pub mod sonar_values;
 pub mod step_adjustment;
 pub mod step_plan;
 pub mod support_foot;
+pub mod teammate;
 pub mod walk_command;
 pub mod whistle;
 pub mod world_state;
@@ -4,6 +4,15 @@ use serialize_hierarchy::SerializeHierarchy;
 
 use crate::multivariate_normal_distribution::MultivariateNormalDistribution;
 
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum LocalizationMode {
+    #[default]
+    Hypotheses,
+    ParticleFilter,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct Update {
     pub robot_to_field: Isometry2<f32>,
@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::{
-    GameControllerReturnMessage, GameControllerStateMessage, HulkMessage, VisualRefereeMessage,
+    GameControllerReturnMessage, GameControllerStateMessage, HealthMessage, HulkMessage,
+    VisualRefereeMessage,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum IncomingMessage {
     GameController(GameControllerStateMessage),
     Spl(HulkMessage),
+    Health(HealthMessage),
 }
 
 impl Default for IncomingMessage {
@@ -20,6 +22,7 @@ impl Default for IncomingMessage {
 pub enum OutgoingMessage {
     GameController(GameControllerReturnMessage),
     Spl(HulkMessage),
+    Health(HealthMessage),
     VisualReferee(VisualRefereeMessage),
 }
 
@@ -45,6 +45,11 @@ pub enum MotionCommand {
         kicking_side: Side,
         strength: f32,
     },
+    StandingKick {
+        head: HeadMotion,
+        kick: StandingKickVariant,
+        kicking_side: Side,
+    },
 }
 
 impl MotionCommand {
@@ -53,7 +58,8 @@ impl MotionCommand {
             MotionCommand::SitDown { head }
             | MotionCommand::Stand { head, .. }
             | MotionCommand::Walk { head, .. }
-            | MotionCommand::InWalkKick { head, .. } => Some(*head),
+            | MotionCommand::InWalkKick { head, .. }
+            | MotionCommand::StandingKick { head, .. } => Some(*head),
             MotionCommand::Penalized => Some(HeadMotion::ZeroAngles),
             MotionCommand::Unstiff => Some(HeadMotion::Unstiff),
             MotionCommand::ArmsUpSquat
@@ -101,6 +107,12 @@ pub enum KickVariant {
     Side,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum StandingKickVariant {
+    Straight,
+    Outer,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum Facing {
     Down,
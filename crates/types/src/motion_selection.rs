@@ -22,6 +22,7 @@ pub enum MotionType {
     Stand,
     StandUpBack,
     StandUpFront,
+    StandingKick,
     Unstiff,
     Walk,
 }
@@ -45,6 +46,7 @@ pub struct MotionSafeExits {
     stand_up_back: bool,
     stand_up_front: bool,
     stand: bool,
+    standing_kick: bool,
     unstiff: bool,
     walk: bool,
 }
@@ -63,6 +65,7 @@ impl Default for MotionSafeExits {
             stand_up_back: false,
             stand_up_front: false,
             stand: true,
+            standing_kick: false,
             unstiff: true,
             walk: false,
         }
@@ -85,6 +88,7 @@ impl Index<MotionType> for MotionSafeExits {
             MotionType::Stand => &self.stand,
             MotionType::StandUpBack => &self.stand_up_back,
             MotionType::StandUpFront => &self.stand_up_front,
+            MotionType::StandingKick => &self.standing_kick,
             MotionType::Unstiff => &self.unstiff,
             MotionType::Walk => &self.walk,
         }
@@ -105,6 +109,7 @@ impl IndexMut<MotionType> for MotionSafeExits {
             MotionType::Stand => &mut self.stand,
             MotionType::StandUpBack => &mut self.stand_up_back,
             MotionType::StandUpFront => &mut self.stand_up_front,
+            MotionType::StandingKick => &mut self.standing_kick,
             MotionType::Unstiff => &mut self.unstiff,
             MotionType::Walk => &mut self.walk,
         }
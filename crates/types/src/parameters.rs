@@ -7,9 +7,10 @@ use serialize_hierarchy::SerializeHierarchy;
 
 use crate::{
     initial_pose::InitialPose,
-    joints::{ArmJoints, HeadJoints, LegJoints},
+    joints::{ArmJointLimits, ArmJoints, HeadJoints, LegJoints},
     kick_step::KickStep,
-    motion_command::{KickVariant, MotionCommand},
+    localization::LocalizationMode,
+    motion_command::{KickVariant, MotionCommand, StandingKickVariant},
     players::Players,
     roles::Role,
     step_plan::Step,
@@ -48,6 +49,39 @@ pub struct LocalizationParameters {
     pub good_matching_threshold: f32,
     pub score_per_good_match: f32,
     pub hypothesis_score_base_increase: f32,
+    pub mode: LocalizationMode,
+    pub particle_filter: ParticleFilterParameters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ParticleFilterParameters {
+    /// Each update costs number of particles × number of lines × number of field marks line
+    /// matches, both particles and lines are bounded to keep this within the cycle time
+    #[serialize_hierarchy(minimum = 1, maximum = 1000)]
+    pub number_of_particles: usize,
+    /// Only the longest measured lines of a cycle are used for updating the particles
+    #[serialize_hierarchy(minimum = 1, maximum = 20)]
+    pub maximum_number_of_lines: usize,
+    /// Standard deviation of the odometry (x, y, angle) per cycle
+    #[serialize_hierarchy(minimum = 0.0)]
+    pub prediction_noise: Vector3<f32>,
+    #[serialize_hierarchy(unit = "m", minimum = 0.0)]
+    pub measurement_standard_deviation: f32,
+    #[serialize_hierarchy(unit = "m", minimum = 0.0)]
+    pub maximum_fit_error: f32,
+    #[serialize_hierarchy(minimum = 0.0, maximum = 1.0)]
+    pub short_term_fit_error_low_pass_factor: f32,
+    #[serialize_hierarchy(minimum = 0.0, maximum = 1.0)]
+    pub long_term_fit_error_low_pass_factor: f32,
+    #[serialize_hierarchy(unit = "m", minimum = 0.0)]
+    pub minimum_resetting_fit_error: f32,
+    /// Fraction of particles replaced by uniformly distributed ones during sensor resetting
+    #[serialize_hierarchy(minimum = 0.0, maximum = 1.0)]
+    pub maximum_injection_fraction: f32,
+    #[serialize_hierarchy(unit = "m", minimum = 0.0)]
+    pub estimate_cluster_distance: f32,
+    #[serialize_hierarchy(unit = "rad", minimum = 0.0)]
+    pub estimate_cluster_angle: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -89,6 +123,8 @@ pub struct BehaviorParameters {
     pub look_action: LookActionParameters,
     pub intercept_ball: InterceptBallParameters,
     pub initial_lookaround_duration: Duration,
+    /// Robots in critical health stay down instead of retrying to stand up after this duration
+    pub maximum_stand_up_duration_when_critical: Duration,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -140,6 +176,69 @@ pub struct FindKickTargetsParameters {
     pub ball_radius_for_kick_target_selection: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PassPlanningParameters {
+    pub enabled: bool,
+    pub minimum_pass_distance: f32,
+    pub maximum_pass_distance: f32,
+    pub ball_speed: f32,
+    pub opponent_speed: f32,
+    pub receiver_speed: f32,
+    pub interception_time_margin: f32,
+    pub reachability_time_margin: f32,
+    pub minimum_strength: f32,
+    pub minimum_forward_gain: f32,
+    pub minimum_score: f32,
+    /// Goal kicks are scored by the ball distance to the opponent goal, reaching zero at this
+    /// distance, and compete with passes by score
+    pub maximum_goal_kick_distance: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RobotHealthParameters {
+    pub degraded_temperature: f32,
+    pub critical_temperature: f32,
+    /// Robots with joints predicted to reach the critical temperature earlier are degraded
+    pub minimum_time_to_overheat: Duration,
+    pub degraded_battery_charge: f32,
+    pub critical_battery_charge: f32,
+    /// Temperatures are compared over this interval to estimate heating rates
+    pub heating_rate_interval: Duration,
+    pub heating_rate_smoothing_factor: f32,
+    /// Margins by which values have to recover before a state is left
+    pub temperature_hysteresis: f32,
+    pub battery_charge_hysteresis: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct JointHealthParameters {
+    /// Joints commanded with lower stiffness are not evaluated
+    pub minimum_stiffness: f32,
+    /// Commanded joints slower than this (in radians per second) hold their position
+    pub maximum_settled_velocity: f32,
+    pub minimum_moving_velocity: f32,
+    /// Time a joint has to hold its position before its error is considered settled
+    pub settling_duration: Duration,
+    pub maximum_number_of_samples: usize,
+    /// Issues are only reported for statistics with at least this many samples
+    pub minimum_number_of_samples: usize,
+    pub maximum_offset: f32,
+    pub maximum_backlash: f32,
+    pub maximum_tracking_error: f32,
+    /// Statistics are accumulated across runs in a file per body in this directory, joint wear
+    /// belongs to the body and not to the head
+    pub statistics_directory: PathBuf,
+    pub save_interval: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeammateFilterParameters {
+    pub maximum_message_age: Duration,
+    pub velocity_smoothing_factor: f32,
+    pub maximum_velocity: f32,
+    pub incoming_pass_timeout: Duration,
+}
+
 impl Index<KickVariant> for InWalkKicksParameters {
     type Output = InWalkKickInfoParameters;
 
@@ -161,6 +260,26 @@ pub struct InWalkKickInfoParameters {
     pub enabled: bool,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct StandingKicksParameters {
+    pub straight: InWalkKickInfoParameters,
+    pub outer: InWalkKickInfoParameters,
+    pub execution_time: Duration,
+    pub opponent_speed: f32,
+    pub stiffness: f32,
+}
+
+impl Index<StandingKickVariant> for StandingKicksParameters {
+    type Output = InWalkKickInfoParameters;
+
+    fn index(&self, variant: StandingKickVariant) -> &Self::Output {
+        match variant {
+            StandingKickVariant::Straight => &self.straight,
+            StandingKickVariant::Outer => &self.outer,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct DribblingParameters {
     pub hybrid_align_distance: f32,
@@ -218,6 +337,7 @@ pub struct WalkingEngineParameters {
     pub additional_kick_foot_lift: f32,
     pub arm_stiffness: f32,
     pub backward_foot_support_offset: f32,
+    pub balance_control: BalanceControlParameters,
     pub base_foot_lift: f32,
     pub base_step_duration: Duration,
     pub emergency_foot_lift: f32,
@@ -260,6 +380,31 @@ pub struct WalkingEngineParameters {
     pub walk_hip_height: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BalanceControlParameters {
+    pub enabled: bool,
+    #[serialize_hierarchy(minimum = 0.0, maximum = 1.0)]
+    pub center_of_mass_velocity_low_pass_factor: f32,
+    /// Capture point region relative to the left support sole, mirrored for the right sole
+    #[serialize_hierarchy(unit = "m")]
+    pub capture_point_region_minimum: Vector2<f32>,
+    #[serialize_hierarchy(unit = "m")]
+    pub capture_point_region_maximum: Vector2<f32>,
+    pub capture_point_ankle_gains: Vector2<f32>,
+    pub zero_moment_point_ankle_gains: Vector2<f32>,
+    #[serialize_hierarchy(unit = "rad", minimum = 0.0)]
+    pub maximum_ankle_adjustment: f32,
+    #[serialize_hierarchy(minimum = 0.0)]
+    pub step_placement_gain: f32,
+    #[serialize_hierarchy(unit = "m", minimum = 0.0)]
+    pub maximum_step_placement_adjustment: Vector2<f32>,
+    #[serialize_hierarchy(minimum = 0.0)]
+    pub step_timing_gain: f32,
+    /// Steps are at most sped up by the inverse of this factor
+    #[serialize_hierarchy(minimum = 0.1, maximum = 1.0)]
+    pub minimum_step_duration_factor: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SwingingArmsParameters {
     pub debug_pull_back: bool,
@@ -267,7 +412,10 @@ pub struct SwingingArmsParameters {
     pub roll_factor: f32,
     pub pitch_factor: f32,
     pub pull_back_joints: ArmJoints<f32>,
-    pub pull_tight_joints: ArmJoints<f32>,
+    /// Wrist position of the left arm in robot coordinates when pulled tight, mirrored for the
+    /// right arm
+    pub pull_tight_wrist_position: Point3<f32>,
+    pub arm_joint_limits: ArmJointLimits,
     pub pulling_back_duration: Duration,
     pub pulling_tight_duration: Duration,
     pub torso_tilt_compensation_factor: f32,
@@ -0,0 +1,28 @@
+use std::time::SystemTime;
+
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::PlayerNumber;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct PassDecision {
+    pub receiver: PlayerNumber,
+    pub target_in_ground: Point2<f32>,
+    pub target_in_field: Point2<f32>,
+    pub interception_risk: f32,
+    pub reachability: f32,
+    pub score: f32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct IncomingPass {
+    pub passer: PlayerNumber,
+    pub target_in_field: Point2<f32>,
+    pub announced_at: SystemTime,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+pub struct PlannedPass {
+    pub decision: Option<PassDecision>,
+}
@@ -1,4 +1,4 @@
-use nalgebra::{vector, Vector3};
+use nalgebra::{vector, Vector2, Vector3};
 
 #[derive(Debug)]
 pub struct RobotDimensions {}
@@ -18,4 +18,12 @@ impl RobotDimensions {
     pub const ELBOW_TO_WRIST: Vector3<f32> = vector![0.05595, 0.0, 0.0];
     pub const NECK_TO_TOP_CAMERA: Vector3<f32> = vector![0.05871, 0.0, 0.06364];
     pub const NECK_TO_BOTTOM_CAMERA: Vector3<f32> = vector![0.05071, 0.0, 0.01774];
+    pub const LEFT_SOLE_TO_FRONT_LEFT_FORCE_SENSOR: Vector2<f32> = vector![0.07025, 0.0299];
+    pub const LEFT_SOLE_TO_FRONT_RIGHT_FORCE_SENSOR: Vector2<f32> = vector![0.07025, -0.0231];
+    pub const LEFT_SOLE_TO_REAR_LEFT_FORCE_SENSOR: Vector2<f32> = vector![-0.03025, 0.0299];
+    pub const LEFT_SOLE_TO_REAR_RIGHT_FORCE_SENSOR: Vector2<f32> = vector![-0.02965, -0.0191];
+    pub const RIGHT_SOLE_TO_FRONT_LEFT_FORCE_SENSOR: Vector2<f32> = vector![0.07025, 0.0231];
+    pub const RIGHT_SOLE_TO_FRONT_RIGHT_FORCE_SENSOR: Vector2<f32> = vector![0.07025, -0.0299];
+    pub const RIGHT_SOLE_TO_REAR_LEFT_FORCE_SENSOR: Vector2<f32> = vector![-0.03025, 0.0191];
+    pub const RIGHT_SOLE_TO_REAR_RIGHT_FORCE_SENSOR: Vector2<f32> = vector![-0.02965, -0.0299];
 }
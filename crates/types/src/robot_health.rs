@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    SerializeHierarchy,
+)]
+pub enum HealthState {
+    #[default]
+    Healthy,
+    /// The robot should save energy and avoid unnecessary walking
+    Degraded,
+    /// The robot should be substituted
+    Critical,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RobotHealth {
+    pub state: HealthState,
+    pub maximum_joint_temperature: f32,
+    /// Earliest time a joint reaches the critical temperature at its current heating rate, `None`
+    /// if no joint heats up
+    pub time_to_overheat: Option<Duration>,
+    pub battery_charge: Option<f32>,
+}
@@ -1,8 +1,8 @@
-use nalgebra::{Vector2, Vector3};
+use nalgebra::{Point2, Vector2, Vector3};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
-use crate::joints::Joints;
+use crate::{joints::Joints, robot_dimensions::RobotDimensions, support_foot::Side};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct InertialMeasurementUnitData {
@@ -30,6 +30,39 @@ impl Foot {
     pub fn sum(&self) -> f32 {
         self.front_left + self.front_right + self.rear_left + self.rear_right
     }
+
+    pub fn center_of_pressure(&self, side: Side) -> Option<Point2<f32>> {
+        let sum = self.sum();
+        if sum <= 0.0 {
+            return None;
+        }
+        let sensor_positions = match side {
+            Side::Left => [
+                RobotDimensions::LEFT_SOLE_TO_FRONT_LEFT_FORCE_SENSOR,
+                RobotDimensions::LEFT_SOLE_TO_FRONT_RIGHT_FORCE_SENSOR,
+                RobotDimensions::LEFT_SOLE_TO_REAR_LEFT_FORCE_SENSOR,
+                RobotDimensions::LEFT_SOLE_TO_REAR_RIGHT_FORCE_SENSOR,
+            ],
+            Side::Right => [
+                RobotDimensions::RIGHT_SOLE_TO_FRONT_LEFT_FORCE_SENSOR,
+                RobotDimensions::RIGHT_SOLE_TO_FRONT_RIGHT_FORCE_SENSOR,
+                RobotDimensions::RIGHT_SOLE_TO_REAR_LEFT_FORCE_SENSOR,
+                RobotDimensions::RIGHT_SOLE_TO_REAR_RIGHT_FORCE_SENSOR,
+            ],
+        };
+        let pressures = [
+            self.front_left,
+            self.front_right,
+            self.rear_left,
+            self.rear_right,
+        ];
+        let weighted_sum: Vector2<f32> = sensor_positions
+            .iter()
+            .zip(pressures)
+            .map(|(position, pressure)| position * pressure)
+            .sum();
+        Some(Point2::from(weighted_sum / sum))
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -56,6 +89,15 @@ pub struct TouchSensors {
     pub right_hand_right: bool,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Battery {
+    /// Between 0.0 (empty) and 1.0 (full)
+    pub charge: f32,
+    pub status: f32,
+    pub current: f32,
+    pub temperature: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SensorData {
     pub positions: Joints<f32>,
@@ -64,4 +106,6 @@ pub struct SensorData {
     pub force_sensitive_resistors: ForceSensitiveResistors,
     pub touch_sensors: TouchSensors,
     pub temperature_sensors: Joints<f32>,
+    /// `None` if the hardware has no battery e.g. in simulation
+    pub battery: Option<Battery>,
 }
@@ -1,3 +1,4 @@
+use nalgebra::Point2;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -5,6 +6,8 @@ use serialize_hierarchy::SerializeHierarchy;
 pub struct SolePressure {
     pub left: f32,
     pub right: f32,
+    pub left_center_of_pressure: Option<Point2<f32>>,
+    pub right_center_of_pressure: Option<Point2<f32>>,
 }
 
 impl SolePressure {
@@ -0,0 +1,26 @@
+use std::time::SystemTime;
+
+use nalgebra::{Isometry2, Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::PlayerNumber;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct Teammate {
+    pub player_number: PlayerNumber,
+    pub robot_to_field: Isometry2<f32>,
+    pub velocity_in_field: Vector2<f32>,
+    pub fallen: bool,
+    pub requests_substitution: bool,
+    pub last_received: SystemTime,
+}
+
+impl Teammate {
+    pub fn predicted_position_in_field(&self, now: SystemTime) -> Point2<f32> {
+        let age = now
+            .duration_since(self.last_received)
+            .unwrap_or_default()
+            .as_secs_f32();
+        self.robot_to_field * Point2::origin() + self.velocity_in_field * age
+    }
+}
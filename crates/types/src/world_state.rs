@@ -8,8 +8,8 @@ use spl_network_messages::PlayerNumber;
 use crate::{
     fall_state::FallState, filtered_game_state::FilteredGameState,
     game_controller_state::GameControllerState, kick_decision::KickDecision, obstacles::Obstacle,
-    penalty_shot_direction::PenaltyShotDirection, primary_state::PrimaryState, roles::Role,
-    rule_obstacles::RuleObstacle, support_foot::Side,
+    pass::IncomingPass, penalty_shot_direction::PenaltyShotDirection, primary_state::PrimaryState,
+    robot_health::RobotHealth, roles::Role, rule_obstacles::RuleObstacle, support_foot::Side,
 };
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
@@ -23,6 +23,7 @@ pub struct WorldState {
     pub position_of_interest: Point2<f32>,
     pub kick_decisions: Option<Vec<KickDecision>>,
     pub instant_kick_decisions: Option<Vec<KickDecision>>,
+    pub incoming_pass: Option<IncomingPass>,
     pub robot: RobotState,
 }
 
@@ -57,4 +58,5 @@ pub struct RobotState {
     pub fall_state: FallState,
     pub has_ground_contact: bool,
     pub player_number: PlayerNumber,
+    pub health: RobotHealth,
 }
@@ -0,0 +1,222 @@
+use std::{
+    fs::{create_dir_all, read_dir, read_to_string, set_permissions, write, Permissions},
+    io,
+    net::{Ipv4Addr, SocketAddrV4},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use aliveness::{
+    service_manager::{ServiceState, SystemServices},
+    AlivenessState, Battery, BEACON_HEADER, BEACON_PORT,
+};
+use constants::OS_VERSION;
+use nao::virtual_nao_root;
+use tokio::{net::UdpSocket, select};
+use tokio_util::sync::CancellationToken;
+
+mod scripts;
+
+const SCRIPTS: &[(&str, &str)] = &[
+    ("ssh", scripts::SSH),
+    ("systemctl", scripts::SYSTEMCTL),
+    ("iwctl", scripts::IWCTL),
+    ("dmesg", scripts::DMESG),
+];
+const UNITS: &[&str] = &["hal", "hula", "hulk", "lola"];
+const NETWORKS: &[&str] = &[
+    "SPL_A",
+    "SPL_B",
+    "SPL_C",
+    "SPL_D",
+    "SPL_E",
+    "SPL_F",
+    "SPL_HULKs",
+];
+
+/// Directory based stand-in for a NAO, used by the `nao` crate for loopback addresses
+pub struct VirtualNao {
+    host: Ipv4Addr,
+    directory: PathBuf,
+}
+
+impl VirtualNao {
+    /// Creates the virtual NAO in the root directory used by the `nao` crate
+    pub fn create(host: Ipv4Addr) -> io::Result<Self> {
+        Self::create_in(&virtual_nao_root(), host)
+    }
+
+    /// Existing state of the virtual NAO is kept
+    pub fn create_in(root: &Path, host: Ipv4Addr) -> io::Result<Self> {
+        if !host.is_loopback() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("virtual NAOs need loopback addresses, got {host}"),
+            ));
+        }
+        let bin = root.join("bin");
+        create_dir_all(&bin)?;
+        for (name, script) in SCRIPTS {
+            let path = bin.join(name);
+            write(&path, script)?;
+            set_permissions(&path, Permissions::from_mode(0o755))?;
+        }
+
+        let directory = root.join(host.to_string());
+        for subdirectory in [
+            "data/.image",
+            "etc",
+            "home/nao/hulk/logs",
+            "state/known-networks",
+            "state/units",
+        ] {
+            create_dir_all(directory.join(subdirectory))?;
+        }
+        write(
+            directory.join("etc/os-release"),
+            format!("ID=hulks-os\nNAME=\"HULKs-OS\"\nVERSION_ID={OS_VERSION}\n"),
+        )?;
+        write(
+            directory.join("etc/hostname"),
+            format!("virtual-{}\n", host.octets()[3]),
+        )?;
+        for network in NETWORKS {
+            let path = directory.join("state/known-networks").join(network);
+            if !path.exists() {
+                write(path, "no\n")?;
+            }
+        }
+        for unit in UNITS {
+            let path = directory.join("state/units").join(unit);
+            if !path.exists() {
+                write(path, "active\n")?;
+            }
+        }
+
+        Ok(Self { host, directory })
+    }
+
+    pub fn host(&self) -> Ipv4Addr {
+        self.host
+    }
+
+    /// Root of the file system of the virtual NAO
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn home(&self) -> PathBuf {
+        self.directory.join("home/nao")
+    }
+
+    /// `active` or `inactive`, `None` for unknown units
+    pub fn unit_state(&self, unit: &str) -> Option<String> {
+        self.read_state(&format!("units/{unit}"))
+    }
+
+    /// Connected wireless network, e.g. `SPL_A`
+    pub fn network(&self) -> Option<String> {
+        self.read_state("network")
+    }
+
+    /// Whether the network is connected to automatically
+    pub fn auto_connects(&self, network: &str) -> bool {
+        self.read_state(&format!("known-networks/{network}"))
+            .is_some_and(|auto_connect| auto_connect == "yes")
+    }
+
+    /// `poweroff` and `reboot` requests, oldest first
+    pub fn power_requests(&self) -> Vec<String> {
+        self.read_state("power")
+            .map(|requests| requests.lines().map(ToString::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Images flashed onto the virtual NAO
+    pub fn images(&self) -> io::Result<Vec<PathBuf>> {
+        read_dir(self.directory.join("data/.image"))?
+            .map(|entry| Ok(entry?.path()))
+            .collect()
+    }
+
+    fn read_state(&self, path: &str) -> Option<String> {
+        read_to_string(self.directory.join("state").join(path))
+            .ok()
+            .map(|contents| contents.trim().to_string())
+    }
+
+    pub fn aliveness_state(&self) -> AlivenessState {
+        let service_state = |unit| {
+            self.unit_state(unit)
+                .map_or(ServiceState::NotLoaded, |state| state.as_str().into())
+        };
+        AlivenessState {
+            hostname: read_to_string(self.directory.join("etc/hostname"))
+                .map(|hostname| hostname.trim().to_string())
+                .unwrap_or_default(),
+            interface_name: "lo".to_string(),
+            system_services: SystemServices {
+                hal: service_state("hal"),
+                hula: service_state("hula"),
+                hulk: service_state("hulk"),
+                lola: service_state("lola"),
+            },
+            hulks_os_version: OS_VERSION.to_string(),
+            body_id: None,
+            head_id: None,
+            battery: Some(Battery {
+                charge: 1.0,
+                ..Default::default()
+            }),
+            network: self.network(),
+            temperature: None,
+        }
+    }
+
+    /// Answers unicast aliveness beacons sent to the address of the virtual NAO
+    pub async fn respond_to_beacons(&self, keep_running: CancellationToken) -> io::Result<()> {
+        let socket = UdpSocket::bind(SocketAddrV4::new(self.host, BEACON_PORT)).await?;
+        let mut receive_buffer = [0; BEACON_HEADER.len()];
+        loop {
+            let (size, peer) = select! {
+                result = socket.recv_from(&mut receive_buffer) => result?,
+                _ = keep_running.cancelled() => return Ok(()),
+            };
+            if &receive_buffer[..size] != BEACON_HEADER {
+                continue;
+            }
+            let response = serde_json::to_vec(&self.aliveness_state())?;
+            socket.send_to(&response, peer).await?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn commands_are_executed_in_the_home_directory_with_fake_tools() {
+        let root = tempdir().unwrap();
+        let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 2)).unwrap();
+
+        let output = Command::new(root.path().join("bin/ssh"))
+            .args(["-lnao", "-oLogLevel=quiet", "127.0.0.2"])
+            .arg("systemctl stop hulk && iwctl station wlan0 connect SPL_C && pwd")
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap().trim(),
+            nao.home().canonicalize().unwrap().display().to_string()
+        );
+        assert_eq!(nao.unit_state("hulk").as_deref(), Some("inactive"));
+        assert_eq!(nao.unit_state("lola").as_deref(), Some("active"));
+        assert_eq!(nao.network().as_deref(), Some("SPL_C"));
+    }
+}
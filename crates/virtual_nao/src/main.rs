@@ -0,0 +1,67 @@
+use std::net::Ipv4Addr;
+
+use clap::Parser;
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    install, Result,
+};
+use futures_util::future::try_join_all;
+use tokio::signal::ctrl_c;
+use tokio_util::sync::CancellationToken;
+
+use nao::virtual_nao_root;
+use virtual_nao::VirtualNao;
+
+/// Creates virtual NAOs usable with pepsi and answers their aliveness beacons until interrupted
+#[derive(Parser)]
+struct Arguments {
+    /// Loopback addresses of the virtual NAOs e.g. 127.0.0.2
+    #[arg(required = true)]
+    hosts: Vec<Ipv4Addr>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    install()?;
+    let arguments = Arguments::parse();
+
+    let naos = arguments
+        .hosts
+        .into_iter()
+        .map(|host| {
+            VirtualNao::create(host)
+                .wrap_err_with(|| format!("failed to create virtual NAO {host}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for nao in &naos {
+        println!(
+            "Virtual NAO {} in {}",
+            nao.host(),
+            nao.directory().display()
+        );
+    }
+    println!(
+        "Set {} to use another directory than {}",
+        nao::VIRTUAL_NAO_ROOT_VARIABLE,
+        virtual_nao_root().display()
+    );
+
+    let keep_running = CancellationToken::new();
+    let responders = try_join_all(naos.iter().map(|nao| {
+        let keep_running = keep_running.clone();
+        async move {
+            nao.respond_to_beacons(keep_running)
+                .await
+                .wrap_err_with(|| format!("failed to respond to beacons of {}", nao.host()))
+        }
+    }));
+    tokio::select! {
+        result = responders => {
+            result?;
+            bail!("responders exited unexpectedly");
+        }
+        result = ctrl_c() => result.wrap_err("failed to wait for interrupt")?,
+    }
+    keep_running.cancel();
+    Ok(())
+}
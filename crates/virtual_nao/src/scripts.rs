@@ -0,0 +1,72 @@
+/// Stand-in for `ssh`: skips the flags, then executes the command like the login shell of the NAO
+/// would, in the home directory of the virtual NAO. rsync uses it as remote shell as well.
+pub const SSH: &str = r#"#!/bin/sh
+root="$(dirname "$(dirname "$(readlink -f "$0")")")"
+while [ $# -gt 0 ]; do
+    case "$1" in
+        -*) shift ;;
+        *) break ;;
+    esac
+done
+host="$1"
+shift
+export VIRTUAL_NAO="$root/$host"
+export PATH="$root/bin:$PATH"
+cd "$VIRTUAL_NAO/home/nao" || exit 255
+if [ $# -eq 0 ]; then
+    exec bash --login
+fi
+exec bash -c "$*"
+"#;
+
+/// Stand-in for `systemctl`, unit states are files in `state/units/`
+pub const SYSTEMCTL: &str = r#"#!/bin/sh
+units="$VIRTUAL_NAO/state/units"
+mkdir -p "$units"
+action="$1"
+unit="${2%.service}"
+case "$action" in
+    start | restart) echo active > "$units/$unit" ;;
+    stop) echo inactive > "$units/$unit" ;;
+    enable | disable) echo "${action}d" > "$units/$unit.enablement" ;;
+    status)
+        state="$(cat "$units/$unit" 2> /dev/null || echo inactive)"
+        echo "* $unit.service - virtual $unit"
+        echo "     Active: $state"
+        [ "$state" = active ] || exit 3
+        ;;
+    poweroff | reboot) echo "$action" >> "$VIRTUAL_NAO/state/power" ;;
+    *)
+        echo "virtual systemctl does not support $action" >&2
+        exit 1
+        ;;
+esac
+"#;
+
+/// Stand-in for `iwctl`, the connected network is the file `state/network`
+pub const IWCTL: &str = r#"#!/bin/sh
+state="$VIRTUAL_NAO/state"
+mkdir -p "$state/known-networks"
+case "$1 $3" in
+    "known-networks set-property") echo "$5" > "$state/known-networks/$2" ;;
+    "station connect") echo "$4" > "$state/network" ;;
+    "station disconnect") rm -f "$state/network" ;;
+    "station show")
+        if [ -f "$state/network" ]; then
+            echo "State connected"
+            echo "Connected network $(cat "$state/network")"
+        else
+            echo "State disconnected"
+        fi
+        ;;
+    "station get-networks") ls "$state/known-networks" ;;
+    *)
+        echo "virtual iwctl does not support $*" >&2
+        exit 1
+        ;;
+esac
+"#;
+
+pub const DMESG: &str = r#"#!/bin/sh
+echo "[    0.000000] virtual NAO $(basename "$VIRTUAL_NAO")"
+"#;
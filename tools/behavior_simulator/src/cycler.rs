@@ -9,6 +9,7 @@ use control::{
     motion::look_around::LookAround,
     role_assignment::{self, RoleAssignment},
     rule_obstacle_composer::RuleObstacleComposer,
+    teammate_filter::{self, TeammateFilter},
     time_to_reach_kick_position::{self, TimeToReachKickPosition},
     world_state_composer::{self, WorldStateComposer},
 };
@@ -43,6 +44,7 @@ pub struct BehaviorCycler {
     look_around: LookAround,
     role_assignment: RoleAssignment,
     rule_obstacle_composer: RuleObstacleComposer,
+    teammate_filter: TeammateFilter,
     world_state_composer: WorldStateComposer,
     time_to_reach_kick_position: TimeToReachKickPosition,
 }
@@ -76,6 +78,8 @@ impl BehaviorCycler {
             control::rule_obstacle_composer::CreationContext {},
         )
         .wrap_err("failed to create node `RuleObstacleComposer`")?;
+        let teammate_filter = TeammateFilter::new(teammate_filter::CreationContext::new())
+            .wrap_err("failed to create node `TeammateFilter`")?;
         let world_state_composer =
             WorldStateComposer::new(world_state_composer::CreationContext::new())
                 .wrap_err("failed to create node `WorldStateComposer`")?;
@@ -92,6 +96,7 @@ impl BehaviorCycler {
             look_around,
             role_assignment,
             rule_obstacle_composer,
+            teammate_filter,
             world_state_composer,
         })
     }
@@ -139,13 +144,15 @@ impl BehaviorCycler {
                     &own_database.main_outputs.fall_state,
                     own_database.main_outputs.game_controller_state.as_ref(),
                     &own_database.main_outputs.primary_state,
+                    &own_database.main_outputs.robot_health,
                     own_database.main_outputs.robot_to_field.as_ref(),
                     &own_database.main_outputs.cycle_time,
                     PerceptionInput {
-                        persistent: incoming_messages,
+                        persistent: incoming_messages.clone(),
                         temporary: Default::default(),
                     },
                     &mut persistent_state.time_to_reach_kick_position,
+                    &mut persistent_state.planned_pass,
                     &parameters.field_dimensions,
                     parameters.role_assignment.forced_role.as_ref(),
                     &parameters
@@ -163,6 +170,22 @@ impl BehaviorCycler {
                 main_outputs.network_robot_obstacles.value;
             own_database.main_outputs.role = main_outputs.role.value;
         }
+        {
+            let main_outputs = self
+                .teammate_filter
+                .cycle(teammate_filter::CycleContext::new(
+                    &own_database.main_outputs.cycle_time,
+                    PerceptionInput {
+                        persistent: incoming_messages,
+                        temporary: Default::default(),
+                    },
+                    &parameters.player_number,
+                    &parameters.teammate_filter,
+                ))
+                .wrap_err("failed to execute cycle of node `TeammateFilter`")?;
+            own_database.main_outputs.teammates = main_outputs.teammates.value;
+            own_database.main_outputs.incoming_pass = main_outputs.incoming_pass.value;
+        }
         {
             let main_outputs = self
                 .ball_state_composer
@@ -208,8 +231,10 @@ impl BehaviorCycler {
                             own_database.main_outputs.robot_to_field.as_ref().unwrap(),
                             own_database.main_outputs.ball_state.as_ref().unwrap(),
                             &own_database.main_outputs.obstacles,
+                            &own_database.main_outputs.teammates,
                             &parameters.field_dimensions,
                             &parameters.in_walk_kicks,
+                            &parameters.standing_kicks,
                             &parameters.kick_selector.angle_distance_weight,
                             &parameters.kick_selector.max_kick_around_obstacle_angle,
                             &parameters.kick_selector.kick_pose_obstacle_radius,
@@ -220,6 +245,7 @@ impl BehaviorCycler {
                             &parameters.kick_selector.find_kick_targets,
                             &parameters.kick_selector.default_kick_strength,
                             &parameters.kick_selector.corner_kick_strength,
+                            &parameters.kick_selector.pass_planning,
                             framework::AdditionalOutput::new(
                                 true,
                                 &mut own_database.additional_outputs.kick_targets,
@@ -228,6 +254,11 @@ impl BehaviorCycler {
                                 true,
                                 &mut own_database.additional_outputs.instant_kick_targets,
                             ),
+                            framework::AdditionalOutput::new(
+                                true,
+                                &mut own_database.additional_outputs.pass_candidates,
+                            ),
+                            &mut persistent_state.planned_pass,
                         ))
                         .wrap_err("failed to execute cycle of node `KickSelector`")?
                 };
@@ -250,12 +281,14 @@ impl BehaviorCycler {
                     own_database.main_outputs.robot_to_field.as_ref(),
                     own_database.main_outputs.kick_decisions.as_ref(),
                     own_database.main_outputs.instant_kick_decisions.as_ref(),
+                    own_database.main_outputs.incoming_pass.as_ref(),
                     &parameters.player_number,
                     &own_database.main_outputs.fall_state,
                     &own_database.main_outputs.has_ground_contact,
                     &own_database.main_outputs.obstacles,
                     &own_database.main_outputs.rule_obstacles,
                     &own_database.main_outputs.primary_state,
+                    &own_database.main_outputs.robot_health,
                     &own_database.main_outputs.role,
                     &own_database.main_outputs.position_of_interest,
                 ))
@@ -280,6 +313,7 @@ impl BehaviorCycler {
                     &own_database.main_outputs.cycle_time,
                     &parameters.behavior,
                     &parameters.in_walk_kicks,
+                    &parameters.standing_kicks,
                     &parameters.field_dimensions,
                     &parameters.behavior.lost_ball,
                     &parameters.behavior.intercept_ball,
@@ -101,6 +101,9 @@ pub fn run(
         "behavior_simulator".to_string(),
         "behavior_simulator".to_string(),
         parameter_slots,
+        false,
+        Default::default(),
+        None,
         keep_running.clone(),
     )?;
 
@@ -21,7 +21,9 @@ use types::{
     primary_state::PrimaryState,
     support_foot::Side,
 };
-use types::{geometry::LineSegment, motion_command::KickVariant};
+use types::{
+    geometry::LineSegment, motion_command::KickVariant, parameters::InWalkKickInfoParameters,
+};
 
 use crate::{
     cycler::Database,
@@ -29,6 +31,9 @@ use crate::{
     structs::{control::AdditionalOutputs, Parameters},
 };
 
+/// Fraction of the ball velocity kept in each simulation step
+const BALL_VELOCITY_DECAY: f32 = 0.98;
+
 pub enum Event {
     Cycle,
     Goal,
@@ -166,10 +171,16 @@ impl State {
                             Side::Left => 1.0,
                             Side::Right => -1.0,
                         };
-
-                        // TODO: Check if ball is even in range
-                        // let kick_location = robot_to_field * ();
-                        if (self.time_elapsed - robot.last_kick_time).as_secs_f32() > 1.0 {
+                        let kick_info = &robot.parameters.in_walk_kicks[*kick];
+
+                        if (self.time_elapsed - robot.last_kick_time).as_secs_f32() > 1.0
+                            && is_ball_in_kick_range(
+                                *robot_to_field,
+                                ball.position,
+                                kick_info,
+                                *kicking_side,
+                            )
+                        {
                             let direction = match kick {
                                 KickVariant::Forward => vector![1.0, 0.0],
                                 KickVariant::Turn => vector![0.707, 0.707 * side],
@@ -181,6 +192,36 @@ impl State {
                     }
                     head
                 }
+                MotionCommand::StandingKick {
+                    head,
+                    kick,
+                    kicking_side,
+                } => {
+                    if let Some(ball) = self.ball.as_mut() {
+                        let kick_info = &robot.parameters.standing_kicks[*kick];
+                        let shot_angle = match kicking_side {
+                            Side::Left => UnitComplex::new(kick_info.shot_angle),
+                            Side::Right => UnitComplex::new(kick_info.shot_angle).inverse(),
+                        };
+                        let execution_time = robot.parameters.standing_kicks.execution_time;
+
+                        if self.time_elapsed - robot.last_kick_time > execution_time
+                            && is_ball_in_kick_range(
+                                *robot_to_field,
+                                ball.position,
+                                kick_info,
+                                *kicking_side,
+                            )
+                        {
+                            // choose the velocity such that the decelerating ball rolls shot_distance
+                            let speed = kick_info.shot_distance * (1.0 - BALL_VELOCITY_DECAY)
+                                / time_step.as_secs_f32();
+                            ball.velocity += *robot_to_field * (shot_angle * vector![speed, 0.0]);
+                            robot.last_kick_time = self.time_elapsed;
+                        };
+                    }
+                    head
+                }
                 MotionCommand::SitDown { head } => head,
                 MotionCommand::Stand {
                     head,
@@ -290,7 +331,7 @@ impl State {
         let mut events = Vec::new();
         if let Some(ball) = self.ball.as_mut() {
             ball.position += ball.velocity * time_step.as_secs_f32();
-            ball.velocity *= 0.98;
+            ball.velocity *= BALL_VELOCITY_DECAY;
 
             if ball.position.x.abs() > 4.5 && ball.position.y < 0.75 {
                 events.push(Event::Goal);
@@ -336,6 +377,26 @@ impl State {
     }
 }
 
+/// Whether the ball lies within the reached thresholds of the kick's ball position in front of
+/// the kicking foot
+fn is_ball_in_kick_range(
+    robot_to_field: Isometry2<f32>,
+    ball_position: Point2<f32>,
+    kick_info: &InWalkKickInfoParameters,
+    kicking_side: Side,
+) -> bool {
+    // the kick pose is offset from the ball, the ball is at the negated offset from the robot
+    let kick_ball_position = match kicking_side {
+        Side::Left => -kick_info.offset,
+        Side::Right => vector![-kick_info.offset.x, kick_info.offset.y],
+    };
+    let deviation = robot_to_field.inverse() * ball_position - Point2::from(kick_ball_position);
+    // the kick pose is reached with an orientation error, which moves the ball around the robot
+    let rotational_deviation = kick_info.offset.norm() * kick_info.reached_thresholds.z;
+    deviation.x.abs() < kick_info.reached_thresholds.x + rotational_deviation
+        && deviation.y.abs() < kick_info.reached_thresholds.y + rotational_deviation
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct LuaState {
     pub time_elapsed: f32,
@@ -0,0 +1,12 @@
+"""Client for the communication server of the HULK robotics stack.
+
+Blocking calls release the GIL, subscriptions can be iterated with `for` and `async for`:
+
+    communication = Communication("ws://10.1.24.42:1337")
+    async for ball_position in communication.subscribe_output("Control.main.ball_position"):
+        print(ball_position)
+"""
+
+from ._hulk_communication import Communication, Subscription
+
+__all__ = ["Communication", "Subscription"]
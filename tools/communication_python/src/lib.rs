@@ -0,0 +1,574 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex as BlockingMutex, OnceLock},
+    time::Duration,
+};
+
+use communication::{
+    client::{Communication, CyclerOutput, SubscriberMessage},
+    messages::{Format, SubscriptionOptions},
+};
+use image::{load_from_memory_with_format, ImageFormat};
+use numpy::{ndarray::Array3, IntoPyArray};
+use pyo3::{
+    exceptions::{
+        PyConnectionError, PyRuntimeError, PyStopAsyncIteration, PyTimeoutError, PyValueError,
+    },
+    prelude::*,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::{
+    runtime::{Builder, Runtime},
+    sync::{mpsc::Receiver, watch, Mutex},
+    task::AbortHandle,
+    time::timeout,
+};
+use uuid::Uuid;
+
+const DEFAULT_PARAMETER_TIMEOUT: f64 = 5.0;
+
+/// Runs the tasks of all clients, Python threads only block on it
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start tokio runtime")
+    })
+}
+
+#[pyclass(name = "Communication")]
+struct PyCommunication {
+    communication: Communication,
+    parameters: BlockingMutex<HashMap<String, CachedParameter>>,
+}
+
+#[pymethods]
+impl PyCommunication {
+    /// Connects to a communication server, e.g. `ws://10.1.24.42:1337`
+    #[new]
+    #[pyo3(signature = (address, token = None))]
+    fn new(address: String, token: Option<String>) -> Self {
+        let _guard = runtime().enter();
+        let communication = Communication::new(Some(address), false);
+        runtime().block_on(async {
+            communication.set_token(token).await;
+            communication.set_connect(true).await;
+        });
+        Self {
+            communication,
+            parameters: Default::default(),
+        }
+    }
+
+    /// Subscribes to an output like `Control.main.ball_position`, updates are JSON-like objects
+    fn subscribe_output(&self, py: Python<'_>, path: &str) -> PyResult<Subscription> {
+        let output = parse_output(path)?;
+        let (uuid, receiver) = py.detach(|| {
+            runtime().block_on(self.communication.subscribe_output(
+                output,
+                Format::Textual,
+                SubscriptionOptions::default(),
+            ))
+        });
+        Ok(Subscription::new(
+            self.communication.clone(),
+            Kind::Output { uuid },
+            Decoding::Json,
+            receiver,
+        ))
+    }
+
+    /// Subscribes to a JPEG encoded image like `VisionTop.main.image.jpeg`, updates are RGB numpy
+    /// arrays with shape (height, width, 3)
+    #[pyo3(signature = (path, maximum_rate = None))]
+    fn subscribe_image(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        maximum_rate: Option<f32>,
+    ) -> PyResult<Subscription> {
+        let output = parse_output(path)?;
+        let mut options = SubscriptionOptions::default();
+        if let Some(maximum_rate) = maximum_rate {
+            options = options.with_maximum_rate(maximum_rate);
+        }
+        let (uuid, receiver) = py.detach(|| {
+            runtime().block_on(
+                self.communication
+                    .subscribe_output(output, Format::Binary, options),
+            )
+        });
+        Ok(Subscription::new(
+            self.communication.clone(),
+            Kind::Output { uuid },
+            Decoding::Jpeg,
+            receiver,
+        ))
+    }
+
+    /// Subscribes to a parameter like `ball_filter.hypothesis_timeout`
+    fn subscribe_parameter(&self, py: Python<'_>, path: &str) -> Subscription {
+        let (uuid, receiver) = py.detach(|| {
+            runtime().block_on(self.communication.subscribe_parameter(path.to_string()))
+        });
+        Subscription::new(
+            self.communication.clone(),
+            Kind::Parameter { uuid },
+            Decoding::Json,
+            receiver,
+        )
+    }
+
+    /// Returns the current value of a parameter, the subscription is kept for later calls
+    #[pyo3(signature = (path, timeout = DEFAULT_PARAMETER_TIMEOUT))]
+    fn get_parameter(&self, py: Python<'_>, path: &str, timeout: f64) -> PyResult<Py<PyAny>> {
+        let mut latest = py.detach(|| {
+            let mut parameters = self.parameters.lock().unwrap();
+            match parameters.get(path) {
+                Some(parameter) => parameter.latest.clone(),
+                None => {
+                    let parameter = CachedParameter::subscribe(&self.communication, path);
+                    let latest = parameter.latest.clone();
+                    parameters.insert(path.to_string(), parameter);
+                    latest
+                }
+            }
+        });
+        let value = py.detach(|| {
+            runtime().block_on(async {
+                match tokio::time::timeout(
+                    Duration::from_secs_f64(timeout),
+                    latest.wait_for(Option::is_some),
+                )
+                .await
+                {
+                    Ok(Ok(value)) => Some(value.clone().expect("waited for a value")),
+                    Ok(Err(_)) => Some(Err("subscription ended".to_string())),
+                    Err(_) => None,
+                }
+            })
+        });
+        match value {
+            Some(Ok(value)) => to_python(py, &value),
+            Some(Err(info)) => {
+                // subscribe again with the next call
+                self.parameters.lock().unwrap().remove(path);
+                Err(PyRuntimeError::new_err(format!(
+                    "subscription failed: {info}"
+                )))
+            }
+            None => Err(PyTimeoutError::new_err("no update received in time")),
+        }
+    }
+
+    /// Updates a parameter with any JSON serializable value, raises `ConnectionError` if not
+    /// connected and `RuntimeError` if the server rejected the update
+    fn update_parameter(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let value: String = py
+            .import("json")?
+            .call_method1("dumps", (value,))?
+            .extract()?;
+        let value: Value = serde_json::from_str(&value)
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        let result = py.detach(|| {
+            runtime().block_on(
+                self.communication
+                    .update_parameter_value_confirmed(path, value),
+            )
+        });
+        match result {
+            Some(Ok(())) => Ok(()),
+            Some(Err(reason)) => Err(PyRuntimeError::new_err(format!(
+                "failed to update {path}: {reason}"
+            ))),
+            None => Err(PyConnectionError::new_err("not connected")),
+        }
+    }
+
+    /// Returns the output paths of all cycler instances
+    fn get_output_fields(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let fields = py.detach(|| runtime().block_on(self.communication.get_output_fields()));
+        to_python(py, &fields)
+    }
+
+    /// Returns the paths of all parameters
+    fn get_parameter_fields(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let fields = py.detach(|| runtime().block_on(self.communication.get_parameter_fields()));
+        to_python(py, &fields)
+    }
+}
+
+/// Latest value of a parameter which stays subscribed for repeated `get_parameter()` calls
+struct CachedParameter {
+    communication: Communication,
+    uuid: Uuid,
+    latest: watch::Receiver<Option<Result<Value, String>>>,
+    updater: AbortHandle,
+}
+
+impl CachedParameter {
+    fn subscribe(communication: &Communication, path: &str) -> Self {
+        let (uuid, mut receiver) =
+            runtime().block_on(communication.subscribe_parameter(path.to_string()));
+        let (latest_sender, latest) = watch::channel(None);
+        let updater = runtime().spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let latest = match message {
+                    SubscriberMessage::Update { value } => Ok(value),
+                    SubscriberMessage::SubscriptionFailure { info } => Err(info),
+                    SubscriberMessage::SubscriptionSuccess
+                    | SubscriberMessage::UpdateBinary { .. } => continue,
+                };
+                latest_sender.send_replace(Some(latest));
+            }
+        });
+        Self {
+            communication: communication.clone(),
+            uuid,
+            latest,
+            updater: updater.abort_handle(),
+        }
+    }
+}
+
+impl Drop for CachedParameter {
+    fn drop(&mut self) {
+        self.updater.abort();
+        let communication = self.communication.clone();
+        let uuid = self.uuid;
+        runtime().spawn(async move { communication.unsubscribe_parameter(uuid).await });
+    }
+}
+
+enum Kind {
+    Output { uuid: Uuid },
+    Parameter { uuid: Uuid },
+}
+
+#[derive(Clone, Copy)]
+enum Decoding {
+    Json,
+    Jpeg,
+}
+
+/// Updates of an output or parameter, iterable with `for` and `async for`
+#[pyclass]
+struct Subscription {
+    communication: Communication,
+    kind: Kind,
+    decoding: Decoding,
+    receiver: Arc<Mutex<Receiver<SubscriberMessage>>>,
+}
+
+impl Subscription {
+    fn new(
+        communication: Communication,
+        kind: Kind,
+        decoding: Decoding,
+        receiver: Receiver<SubscriberMessage>,
+    ) -> Self {
+        Self {
+            communication,
+            kind,
+            decoding,
+            receiver: Arc::new(Mutex::new(receiver)),
+        }
+    }
+
+    /// Returns `None` after the subscription ended
+    fn receive(&self, py: Python<'_>, timeout_seconds: Option<f64>) -> PyResult<Option<Py<PyAny>>> {
+        let message = py.detach(|| {
+            runtime().block_on(async {
+                match timeout_seconds {
+                    Some(seconds) => timeout(
+                        Duration::from_secs_f64(seconds),
+                        next_message(&self.receiver),
+                    )
+                    .await
+                    .map_err(|_| PyTimeoutError::new_err("no update received in time")),
+                    None => Ok(next_message(&self.receiver).await),
+                }
+            })
+        })?;
+        convert(py, self.decoding, message)
+    }
+}
+
+#[pymethods]
+impl Subscription {
+    /// Blocks until the next update, raises `TimeoutError` if none arrives within `timeout` seconds
+    #[pyo3(signature = (timeout = None))]
+    fn next(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Py<PyAny>> {
+        self.receive(py, timeout)?
+            .ok_or_else(|| PyRuntimeError::new_err("subscription ended"))
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        self.receive(py, None)
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Returns an `asyncio.Future` which is completed from the tokio runtime, no Python thread
+    /// blocks while waiting
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let future = event_loop.call_method0("create_future")?;
+        let complete_future = wrap_pyfunction!(complete_future, py)?.unbind();
+        let receiver = self.receiver.clone();
+        let decoding = self.decoding;
+        let (event_loop_handle, future_handle) = (event_loop.unbind(), future.clone().unbind());
+        let task = runtime().spawn(async move {
+            let message = next_message(&receiver).await;
+            Python::attach(|py| {
+                let (result, is_exception) = match convert(py, decoding, message) {
+                    Ok(Some(value)) => (value, false),
+                    Ok(None) => (
+                        PyStopAsyncIteration::new_err(()).into_value(py).into_any(),
+                        true,
+                    ),
+                    Err(error) => (error.into_value(py).into_any(), true),
+                };
+                // the event loop is gone if it was closed in the meantime, nobody awaits anymore
+                let _ = event_loop_handle.call_method1(
+                    py,
+                    "call_soon_threadsafe",
+                    (complete_future, future_handle, result, is_exception),
+                );
+            });
+        });
+        // receiving is cancel safe, no update is lost if the future is cancelled
+        future.call_method1(
+            "add_done_callback",
+            (AbortOnDone {
+                handle: task.abort_handle(),
+            },),
+        )?;
+        Ok(future)
+    }
+}
+
+#[pyclass]
+struct AbortOnDone {
+    handle: AbortHandle,
+}
+
+#[pymethods]
+impl AbortOnDone {
+    fn __call__(&self, _future: &Bound<'_, PyAny>) {
+        self.handle.abort();
+    }
+}
+
+/// Called in the event loop thread, the future may have been cancelled in the meantime
+#[pyfunction]
+fn complete_future(
+    future: &Bound<'_, PyAny>,
+    result: &Bound<'_, PyAny>,
+    is_exception: bool,
+) -> PyResult<()> {
+    if future.call_method0("done")?.extract()? {
+        return Ok(());
+    }
+    let method = if is_exception {
+        "set_exception"
+    } else {
+        "set_result"
+    };
+    future.call_method1(method, (result,))?;
+    Ok(())
+}
+
+async fn next_message(receiver: &Mutex<Receiver<SubscriberMessage>>) -> Option<SubscriberMessage> {
+    let mut receiver = receiver.lock().await;
+    loop {
+        match receiver.recv().await? {
+            SubscriberMessage::SubscriptionSuccess => continue,
+            message => return Some(message),
+        }
+    }
+}
+
+fn convert(
+    py: Python<'_>,
+    decoding: Decoding,
+    message: Option<SubscriberMessage>,
+) -> PyResult<Option<Py<PyAny>>> {
+    let value = match message {
+        Some(SubscriberMessage::Update { value }) => to_python(py, &value)?,
+        Some(SubscriberMessage::UpdateBinary { data }) => match decoding {
+            Decoding::Jpeg => decode_jpeg(py, &data)?,
+            Decoding::Json => {
+                return Err(PyRuntimeError::new_err(
+                    "got binary data for textual subscription",
+                ))
+            }
+        },
+        Some(SubscriberMessage::SubscriptionSuccess) => unreachable!("skipped while receiving"),
+        Some(SubscriberMessage::SubscriptionFailure { info }) => {
+            return Err(PyRuntimeError::new_err(format!(
+                "subscription failed: {info}"
+            )))
+        }
+        None => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn decode_jpeg(py: Python<'_>, data: &[u8]) -> PyResult<Py<PyAny>> {
+    let jpeg: Vec<u8> =
+        bincode::deserialize(data).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    let image = load_from_memory_with_format(&jpeg, ImageFormat::Jpeg)
+        .map_err(|error| PyValueError::new_err(error.to_string()))?
+        .to_rgb8();
+    let shape = (image.height() as usize, image.width() as usize, 3);
+    let array = Array3::from_shape_vec(shape, image.into_raw())
+        .expect("RGB image buffer should match its dimensions");
+    Ok(array.into_pyarray(py).into_any().unbind())
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let communication = self.communication.clone();
+        match self.kind {
+            Kind::Output { uuid } => {
+                runtime().spawn(async move { communication.unsubscribe_output(uuid).await });
+            }
+            Kind::Parameter { uuid } => {
+                runtime().spawn(async move { communication.unsubscribe_parameter(uuid).await });
+            }
+        }
+    }
+}
+
+fn parse_output(path: &str) -> PyResult<CyclerOutput> {
+    CyclerOutput::from_str(path).map_err(|error| PyValueError::new_err(error.to_string()))
+}
+
+fn to_python(py: Python<'_>, value: &impl Serialize) -> PyResult<Py<PyAny>> {
+    let value =
+        serde_json::to_string(value).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (value,))?.unbind())
+}
+
+#[pymodule]
+fn _hulk_communication(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyCommunication>()?;
+    module.add_class::<Subscription>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn disconnected() -> PyCommunication {
+        let _guard = runtime().enter();
+        PyCommunication {
+            communication: Communication::new(None, false),
+            parameters: Default::default(),
+        }
+    }
+
+    #[test]
+    fn repeated_parameter_reads_share_one_subscription() {
+        Python::initialize();
+        Python::attach(|py| {
+            let communication = disconnected();
+
+            let error = communication
+                .get_parameter(py, "walking_engine.step_height", 0.01)
+                .unwrap_err();
+            assert!(error.is_instance_of::<PyTimeoutError>(py));
+            let uuid = communication.parameters.lock().unwrap()["walking_engine.step_height"].uuid;
+
+            communication
+                .get_parameter(py, "walking_engine.step_height", 0.01)
+                .unwrap_err();
+            let parameters = communication.parameters.lock().unwrap();
+            assert_eq!(parameters.len(), 1);
+            assert_eq!(parameters["walking_engine.step_height"].uuid, uuid);
+        });
+    }
+
+    #[test]
+    fn updates_without_connection_raise() {
+        Python::initialize();
+        Python::attach(|py| {
+            let communication = disconnected();
+            let value = 0.05_f32.into_pyobject(py).unwrap().into_any();
+
+            let error = communication
+                .update_parameter(py, "walking_engine.step_height", &value)
+                .unwrap_err();
+
+            assert!(error.is_instance_of::<PyConnectionError>(py));
+        });
+    }
+
+    #[test]
+    fn messages_are_converted_to_values_or_exceptions() {
+        Python::initialize();
+        Python::attach(|py| {
+            let value = convert(
+                py,
+                Decoding::Json,
+                Some(SubscriberMessage::Update {
+                    value: json!({ "x": 1.5 }),
+                }),
+            )
+            .unwrap()
+            .unwrap();
+            let x: f64 = value.bind(py).get_item("x").unwrap().extract().unwrap();
+            assert_eq!(x, 1.5);
+
+            let failure = convert(
+                py,
+                Decoding::Json,
+                Some(SubscriberMessage::SubscriptionFailure {
+                    info: "unknown path".to_string(),
+                }),
+            )
+            .unwrap_err();
+            assert!(failure.is_instance_of::<PyRuntimeError>(py));
+
+            let binary = convert(
+                py,
+                Decoding::Json,
+                Some(SubscriberMessage::UpdateBinary { data: vec![] }),
+            )
+            .unwrap_err();
+            assert!(binary.is_instance_of::<PyRuntimeError>(py));
+
+            assert!(convert(py, Decoding::Json, None).unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn invalid_output_paths_raise_value_errors() {
+        Python::initialize();
+        Python::attach(|py| {
+            assert!(parse_output("Control.main.ball_position").is_ok());
+            assert!(parse_output("ball_position")
+                .unwrap_err()
+                .is_instance_of::<PyValueError>(py));
+        });
+    }
+}
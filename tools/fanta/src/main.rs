@@ -4,7 +4,7 @@ use clap::Parser;
 use color_eyre::{eyre::bail, Result};
 use communication::{
     client::{Communication, CyclerOutput, SubscriberMessage},
-    messages::Format,
+    messages::{Format, SubscriptionOptions},
 };
 use log::{error, info};
 
@@ -17,6 +17,15 @@ mod logging;
 struct CommandlineArguments {
     #[clap(short, long, default_value = "localhost")]
     address: String,
+    /// Token to authenticate at the communication server
+    #[clap(long)]
+    token: Option<String>,
+    /// Maximum number of printed updates per second
+    #[clap(short, long)]
+    rate: Option<f32>,
+    /// Only print updates if the value changed
+    #[clap(long)]
+    on_change: bool,
     path: String,
 }
 
@@ -26,9 +35,18 @@ async fn main() -> Result<()> {
 
     let arguments = CommandlineArguments::parse();
     let output_to_subscribe = CyclerOutput::from_str(&arguments.path)?;
-    let communication = Communication::new(Some(format!("ws://{}:1337", arguments.address)), true);
+    let communication = Communication::new(Some(format!("ws://{}:1337", arguments.address)), false);
+    communication.set_token(arguments.token).await;
+    communication.set_connect(true).await;
+    let mut options = SubscriptionOptions {
+        only_on_change: arguments.on_change,
+        ..Default::default()
+    };
+    if let Some(rate) = arguments.rate {
+        options = options.with_maximum_rate(rate);
+    }
     let (_uuid, mut receiver) = communication
-        .subscribe_output(output_to_subscribe, Format::Textual)
+        .subscribe_output(output_to_subscribe, Format::Textual, options)
         .await;
     while let Some(message) = receiver.recv().await {
         match message {
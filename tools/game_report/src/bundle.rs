@@ -0,0 +1,260 @@
+use std::{
+    fs::{read_dir, read_to_string, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use bincode::deserialize_from;
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::WrapErr, Result};
+use control::localization_recorder::RecordedCycleContext;
+use framework::crash_capture::{self, CrashBundle};
+use serde_json::from_str;
+use structured_log::{log_files, LogRecord};
+
+use crate::events::{
+    from_crash_bundle, from_kernel_line, from_log_record, from_text_line, Event, EventKind,
+};
+
+/// Pose changes between two recorded cycles larger than this are reported as localization resets
+const LOCALIZATION_JUMP_DISTANCE: f32 = 1.0;
+
+pub struct Robot {
+    pub name: String,
+    /// Dated events in chronological order followed by undated ones
+    pub events: Vec<Event>,
+}
+
+/// Loads a directory downloaded by `pepsi logs download` or `pepsi postgame`, containing one
+/// directory per robot, or the logs directory of a single robot
+pub fn load(bundle: &Path) -> Result<Vec<Robot>> {
+    if is_robot_directory(bundle)? {
+        return Ok(vec![load_robot(bundle)?]);
+    }
+    let mut directories = read_dir(bundle)
+        .wrap_err_with(|| format!("failed to read {}", bundle.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<PathBuf>>>()?;
+    directories.retain(|path| path.is_dir());
+    directories.sort();
+    directories
+        .iter()
+        .map(|directory| load_robot(directory))
+        .collect()
+}
+
+fn is_robot_directory(directory: &Path) -> Result<bool> {
+    Ok(!log_files(directory)
+        .wrap_err_with(|| format!("failed to read {}", directory.display()))?
+        .is_empty()
+        || directory.join("hulk.out").exists()
+        || directory.join("kernel.log").exists())
+}
+
+fn load_robot(directory: &Path) -> Result<Robot> {
+    let mut events = Vec::new();
+
+    let structured_logs =
+        log_files(directory).wrap_err_with(|| format!("failed to read {}", directory.display()))?;
+    let has_structured_logs = !structured_logs.is_empty();
+    for path in structured_logs {
+        let contents = read(&path)?;
+        events.extend(
+            contents
+                .lines()
+                // the last line may be incomplete if the robot crashed while writing
+                .filter_map(|line| from_str::<LogRecord>(line).ok())
+                .filter_map(|record| from_log_record(&record)),
+        );
+    }
+
+    let crash_bundles = files_with_prefix(
+        directory,
+        crash_capture::FILE_PREFIX,
+        crash_capture::FILE_EXTENSION,
+    )?;
+    let has_crash_bundles = !crash_bundles.is_empty();
+    for path in crash_bundles {
+        let bundle: CrashBundle = from_str(&read(&path)?)
+            .wrap_err_with(|| format!("failed to parse {}", path.display()))?;
+        events.push(from_crash_bundle(&bundle));
+    }
+
+    for name in ["hulk.out", "hulk.err"] {
+        let path = directory.join(name);
+        if path.exists() {
+            events.extend(
+                read(&path)?
+                    .lines()
+                    .filter_map(|line| from_text_line(line, !has_structured_logs))
+                    // panics are printed as well when writing crash bundles
+                    .filter(|event| {
+                        !has_crash_bundles || !matches!(event.kind, EventKind::Crash { .. })
+                    }),
+            );
+        }
+    }
+
+    let kernel_log = directory.join("kernel.log");
+    if kernel_log.exists() {
+        events.extend(read(&kernel_log)?.lines().filter_map(from_kernel_line));
+    }
+
+    // state changes are logged by the nodes, recordings are a fallback for logs without them
+    if !has_structured_logs {
+        events.extend(recording_events(directory)?);
+    }
+
+    // undated events are sorted last, the sort is stable to keep the order within a file
+    events.sort_by_key(|event| (event.time.is_none(), event.time));
+
+    Ok(Robot {
+        name: directory
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| directory.display().to_string()),
+        events,
+    })
+}
+
+fn read(path: &Path) -> Result<String> {
+    read_to_string(path).wrap_err_with(|| format!("failed to read {}", path.display()))
+}
+
+fn recording_events(directory: &Path) -> Result<Vec<Event>> {
+    let mut recordings = files_with_prefix(directory, "localization.", "bincode")?;
+    // file names contain the seconds since the epoch of the start of the recording
+    recordings.sort_by_key(|path| {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix("localization."))
+            .and_then(|seconds| seconds.parse::<u64>().ok())
+    });
+
+    let mut events = Vec::new();
+    let mut last: Option<RecordedCycleContext> = None;
+    for path in recordings {
+        let mut reader = BufReader::new(
+            File::open(&path).wrap_err_with(|| format!("failed to open {}", path.display()))?,
+        );
+        while let Ok(context) = deserialize_from::<_, RecordedCycleContext>(&mut reader) {
+            let Some(time) = recording_time(&context) else {
+                continue;
+            };
+            let game_state = context
+                .game_controller_state
+                .map(|game_controller_state| format!("{:?}", game_controller_state.game_state));
+            if let Some(last) = &last {
+                if last.primary_state != context.primary_state {
+                    events.push(Event {
+                        time: Some(time),
+                        game_state: game_state.clone(),
+                        kind: EventKind::PrimaryState {
+                            from: format!("{:?}", last.primary_state),
+                            to: format!("{:?}", context.primary_state),
+                        },
+                    });
+                }
+                if let (Some(last_pose), Some(pose)) = (last.robot_to_field, context.robot_to_field)
+                {
+                    let distance = (pose.translation.vector - last_pose.translation.vector).norm();
+                    if distance > LOCALIZATION_JUMP_DISTANCE {
+                        events.push(Event {
+                            time: Some(time),
+                            game_state,
+                            kind: EventKind::LocalizationReset {
+                                description: format!("pose jumped by {distance:.1} m"),
+                            },
+                        });
+                    }
+                }
+            }
+            last = Some(context);
+        }
+    }
+    Ok(events)
+}
+
+fn files_with_prefix(directory: &Path, prefix: &str, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut files = read_dir(directory)
+        .wrap_err_with(|| format!("failed to read {}", directory.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<PathBuf>>>()?;
+    files.retain(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.starts_with(prefix) && name.ends_with(&format!(".{extension}"))
+            })
+    });
+    files.sort();
+    Ok(files)
+}
+
+/// Recorded cycles have no timestamp of their own, the latest timestamp of their inputs is used
+fn recording_time(context: &RecordedCycleContext) -> Option<DateTime<Utc>> {
+    context
+        .current_odometry_to_last_odometry
+        .keys()
+        .chain(context.line_data_bottom_persistent.keys())
+        .chain(context.line_data_bottom_temporary.keys())
+        .chain(context.line_data_top_persistent.keys())
+        .chain(context.line_data_top_temporary.keys())
+        .max()
+        .map(|time| DateTime::from(*time))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir, write};
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn robots_are_loaded_with_sorted_events() {
+        let bundle = tempdir().unwrap();
+        let robot = bundle.path().join("10.1.24.22");
+        create_dir(&robot).unwrap();
+        write(
+            robot.join("hulk.1688652000000.jsonl"),
+            concat!(
+                r#"{"timestamp":"2023-07-06T14:00:02.000Z","level":"INFO","target":"control::whistle_filter","cycler":"Control","node":"WhistleFilter","cycle":2,"game_state":"Playing","message":"whistle detected"}"#,
+                "\n",
+                r#"{"timestamp":"2023-07-06T14:00:01.000Z","level":"INFO","target":"control::fall_state_estimation","cycler":"Control","node":"FallStateEstimation","cycle":1,"game_state":"Playing","message":"fallen facing Up"}"#,
+                "\n",
+                r#"{"timestamp":"2023-07-06T14:00:03.0"#,
+            ),
+        )
+        .unwrap();
+        // errors of text logs are contained in the structured logs as well
+        write(
+            robot.join("hulk.err"),
+            "2023-07-06 14:00:01  hulk::run  ERROR  cycler crashed\nthread 'control' panicked at 'oops'\n",
+        )
+        .unwrap();
+
+        let robots = load(bundle.path()).unwrap();
+
+        assert_eq!(robots.len(), 1);
+        assert_eq!(robots[0].name, "10.1.24.22");
+        let kinds: Vec<_> = robots[0]
+            .events
+            .iter()
+            .map(|event| event.kind.clone())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Fall {
+                    facing: "Up".to_string()
+                },
+                EventKind::Whistle,
+                EventKind::Crash {
+                    message: "thread 'control' panicked at 'oops'".to_string()
+                },
+            ]
+        );
+    }
+}
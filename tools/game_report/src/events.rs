@@ -0,0 +1,287 @@
+use std::fmt::{self, Display, Formatter};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use control::{
+    fall_state_estimation::FALLEN_FACING,
+    localization::HYPOTHESES_RESET,
+    primary_state_filter::{
+        PENALIZED, PRIMARY_STATE_CHANGED, PRIMARY_STATE_CHANGED_TO, UNPENALIZED,
+    },
+    whistle_filter::WHISTLE_DETECTED,
+};
+use framework::crash_capture::CrashBundle;
+use structured_log::LogRecord;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    /// `None` for sources without absolute timestamps, e.g. panics and kernel logs
+    pub time: Option<DateTime<Utc>>,
+    pub game_state: Option<String>,
+    pub kind: EventKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EventKind {
+    PrimaryState { from: String, to: String },
+    Fall { facing: String },
+    Penalty { penalty: String },
+    Unpenalized,
+    Whistle,
+    LocalizationReset { description: String },
+    Error { message: String },
+    Crash { message: String },
+}
+
+impl Display for EventKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            EventKind::PrimaryState { from, to } => {
+                write!(formatter, "primary state {from} → {to}")
+            }
+            EventKind::Fall { facing } => write!(formatter, "fell, facing {facing}"),
+            EventKind::Penalty { penalty } => write!(formatter, "penalized: {penalty}"),
+            EventKind::Unpenalized => write!(formatter, "unpenalized"),
+            EventKind::Whistle => write!(formatter, "whistle detected"),
+            EventKind::LocalizationReset { description } => {
+                write!(formatter, "localization reset: {description}")
+            }
+            EventKind::Error { message } => write!(formatter, "error: {message}"),
+            EventKind::Crash { message } => write!(formatter, "crash: {message}"),
+        }
+    }
+}
+
+/// Classifies the messages logged by the control nodes, the messages are shared as constants
+/// with the nodes, e.g. `PRIMARY_STATE_CHANGED`
+pub fn from_log_record(record: &LogRecord) -> Option<Event> {
+    let message = record.message.as_str();
+    let kind = match record.target.as_str() {
+        "control::primary_state_filter" => {
+            if let Some(states) = message.strip_prefix(PRIMARY_STATE_CHANGED) {
+                let (from, to) = states.split_once(PRIMARY_STATE_CHANGED_TO)?;
+                EventKind::PrimaryState {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                }
+            } else if let Some(penalty) = message.strip_prefix(PENALIZED) {
+                EventKind::Penalty {
+                    penalty: penalty_name(penalty).to_string(),
+                }
+            } else if message == UNPENALIZED {
+                EventKind::Unpenalized
+            } else {
+                return error(record);
+            }
+        }
+        "control::fall_state_estimation" => match message.strip_prefix(FALLEN_FACING) {
+            Some(facing) => EventKind::Fall {
+                facing: facing.to_string(),
+            },
+            None => return error(record),
+        },
+        "control::whistle_filter" if message == WHISTLE_DETECTED => EventKind::Whistle,
+        "control::localization" if message.starts_with(HYPOTHESES_RESET) => {
+            EventKind::LocalizationReset {
+                description: message.to_string(),
+            }
+        }
+        _ => return error(record),
+    };
+    Some(Event {
+        time: record.time(),
+        game_state: record.game_state.clone(),
+        kind,
+    })
+}
+
+fn error(record: &LogRecord) -> Option<Event> {
+    (record.level == "ERROR").then(|| Event {
+        time: record.time(),
+        game_state: record.game_state.clone(),
+        kind: EventKind::Error {
+            message: format!("{}: {}", record.target, record.message),
+        },
+    })
+}
+
+/// `IllegalBallContact { remaining: 45s }` becomes `IllegalBallContact`
+fn penalty_name(penalty: &str) -> &str {
+    penalty.split_once(' ').map_or(penalty, |(name, _)| name)
+}
+
+/// Parses a line of `hulk.out` or `hulk.err`, error lines are only classified if
+/// `include_errors` is set because they are contained in the structured logs as well
+pub fn from_text_line(line: &str, include_errors: bool) -> Option<Event> {
+    let line = line.trim();
+    if line.contains(" panicked at ") || line.starts_with("Error: ") {
+        return Some(Event {
+            time: None,
+            game_state: None,
+            kind: EventKind::Crash {
+                message: line.to_string(),
+            },
+        });
+    }
+    if !include_errors {
+        return None;
+    }
+    // lines are formatted as `<date> <time>  <target>  <level>  <message>` in the NAO's local time
+    // which is UTC
+    let mut parts = line.split_whitespace();
+    let (date, time, target, level) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if level != "ERROR" {
+        return None;
+    }
+    let time = NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M:%S")
+        .ok()?
+        .and_utc();
+    Some(Event {
+        time: Some(time),
+        game_state: None,
+        kind: EventKind::Error {
+            message: format!("{target}: {}", parts.collect::<Vec<_>>().join(" ")),
+        },
+    })
+}
+
+/// Crash bundles are written by the robot on panics and cycler errors
+pub fn from_crash_bundle(bundle: &CrashBundle) -> Event {
+    let location = match (&bundle.cycler, &bundle.node, bundle.cycle) {
+        (Some(cycler), Some(node), Some(cycle)) => format!(" in {cycler}/{node}#{cycle}"),
+        (Some(cycler), None, Some(cycle)) => format!(" in {cycler}#{cycle}"),
+        _ => String::new(),
+    };
+    Event {
+        time: DateTime::from_timestamp_millis(bundle.unix_milliseconds as i64),
+        game_state: None,
+        kind: EventKind::Crash {
+            message: format!(
+                "{}{location}",
+                bundle.reason.lines().next().unwrap_or_default()
+            ),
+        },
+    }
+}
+
+const KERNEL_PROBLEMS: &[&str] = &[
+    "Out of memory",
+    "oom-kill",
+    "Killed process",
+    "segfault",
+    "I/O error",
+];
+
+/// Kernel log timestamps are relative to the boot, problems are therefore undated
+pub fn from_kernel_line(line: &str) -> Option<Event> {
+    KERNEL_PROBLEMS
+        .iter()
+        .any(|problem| line.contains(problem))
+        .then(|| Event {
+            time: None,
+            game_state: None,
+            kind: EventKind::Error {
+                message: format!("kernel: {}", line.trim()),
+            },
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(target: &str, level: &str, message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: "2023-07-06T14:00:00.000Z".to_string(),
+            level: level.to_string(),
+            target: target.to_string(),
+            cycler: Some("Control".to_string()),
+            node: None,
+            cycle: Some(42),
+            game_state: Some("Playing".to_string()),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn log_records_of_control_nodes_are_classified() {
+        let kinds: Vec<_> = [
+            record(
+                "control::primary_state_filter",
+                "INFO",
+                "primary state changed from Set to Playing",
+            ),
+            record(
+                "control::primary_state_filter",
+                "INFO",
+                "penalized with PlayerPushing { remaining: 45s }",
+            ),
+            record("control::fall_state_estimation", "INFO", "falling Forward"),
+            record(
+                "control::fall_state_estimation",
+                "INFO",
+                "fallen facing Down",
+            ),
+            record("control::whistle_filter", "INFO", "whistle detected"),
+            record("control::walking_engine", "WARN", "Not reachable!"),
+            record("hulk::run", "ERROR", "cycler crashed"),
+        ]
+        .iter()
+        .filter_map(from_log_record)
+        .map(|event| event.kind)
+        .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                EventKind::PrimaryState {
+                    from: "Set".to_string(),
+                    to: "Playing".to_string()
+                },
+                EventKind::Penalty {
+                    penalty: "PlayerPushing".to_string()
+                },
+                EventKind::Fall {
+                    facing: "Down".to_string()
+                },
+                EventKind::Whistle,
+                EventKind::Error {
+                    message: "hulk::run: cycler crashed".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn text_lines_are_classified() {
+        assert_eq!(
+            from_text_line(
+                "2023-07-06 14:00:01  hulk::run           ERROR  cycler crashed",
+                true
+            ),
+            Some(Event {
+                time: "2023-07-06T14:00:01Z".parse().ok(),
+                game_state: None,
+                kind: EventKind::Error {
+                    message: "hulk::run: cycler crashed".to_string()
+                }
+            })
+        );
+        assert_eq!(
+            from_text_line(
+                "2023-07-06 14:00:01  hulk::run           ERROR  cycler crashed",
+                false
+            ),
+            None
+        );
+        assert!(matches!(
+            from_text_line(
+                "thread 'control' panicked at 'index out of bounds', src/lib.rs:1:1",
+                false
+            ),
+            Some(Event {
+                kind: EventKind::Crash { .. },
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,37 @@
+use std::{fs::write, path::PathBuf};
+
+use clap::Parser;
+use color_eyre::{eyre::WrapErr, install, Result};
+
+use report::{render, Format};
+
+mod bundle;
+mod events;
+mod report;
+
+/// Summarizes logs downloaded with `pepsi postgame` into a report of the game
+#[derive(Parser)]
+struct Arguments {
+    /// Directory of the downloaded logs, containing one directory per robot
+    bundle: PathBuf,
+    #[arg(long, value_enum, default_value = "markdown")]
+    format: Format,
+    /// Write the report into this file instead of printing it
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    install()?;
+    let arguments = Arguments::parse();
+
+    let robots = bundle::load(&arguments.bundle).wrap_err("failed to load bundle")?;
+    let report = render(&robots, arguments.format);
+    match arguments.output {
+        Some(path) => {
+            write(&path, report).wrap_err_with(|| format!("failed to write {}", path.display()))?
+        }
+        None => print!("{report}"),
+    }
+    Ok(())
+}
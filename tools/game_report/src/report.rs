@@ -0,0 +1,318 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use clap::ValueEnum;
+
+use crate::{
+    bundle::Robot,
+    events::{Event, EventKind},
+};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    falls: usize,
+    penalties: usize,
+    whistles: usize,
+    localization_resets: usize,
+    errors: usize,
+    crashes: usize,
+}
+
+impl Summary {
+    fn new(events: &[Event]) -> Self {
+        let mut summary = Self::default();
+        for event in events {
+            match event.kind {
+                EventKind::Fall { .. } => summary.falls += 1,
+                EventKind::Penalty { .. } => summary.penalties += 1,
+                EventKind::Whistle => summary.whistles += 1,
+                EventKind::LocalizationReset { .. } => summary.localization_resets += 1,
+                EventKind::Error { .. } => summary.errors += 1,
+                EventKind::Crash { .. } => summary.crashes += 1,
+                EventKind::PrimaryState { .. } | EventKind::Unpenalized => {}
+            }
+        }
+        summary
+    }
+
+    fn cells(&self) -> [usize; 6] {
+        [
+            self.falls,
+            self.penalties,
+            self.whistles,
+            self.localization_resets,
+            self.errors,
+            self.crashes,
+        ]
+    }
+}
+
+const SUMMARY_HEADER: [&str; 7] = [
+    "Robot",
+    "Falls",
+    "Penalties",
+    "Whistles",
+    "Localization resets",
+    "Errors",
+    "Crashes",
+];
+const TIMELINE_HEADER: [&str; 4] = ["Time", "Robot", "Game state", "Event"];
+
+/// Errors are only listed grouped by message, everything else is shown in the timeline
+fn timeline(robots: &[Robot]) -> Vec<(&Robot, &Event)> {
+    let mut timeline: Vec<_> = robots
+        .iter()
+        .flat_map(|robot| robot.events.iter().map(move |event| (robot, event)))
+        .filter(|(_, event)| event.time.is_some() && !matches!(event.kind, EventKind::Error { .. }))
+        .collect();
+    timeline.sort_by_key(|(_, event)| event.time);
+    timeline
+}
+
+/// Error and crash messages with their number of occurrences, most frequent first
+fn problems(robot: &Robot) -> Vec<(String, usize)> {
+    let mut counts = BTreeMap::<String, usize>::new();
+    for event in &robot.events {
+        if matches!(
+            event.kind,
+            EventKind::Error { .. } | EventKind::Crash { .. }
+        ) {
+            *counts.entry(event.kind.to_string()).or_default() += 1;
+        }
+    }
+    let mut problems: Vec<_> = counts.into_iter().collect();
+    problems.sort_by(|(_, left), (_, right)| right.cmp(left));
+    problems
+}
+
+fn time(event: &Event) -> String {
+    event
+        .time
+        .map(|time| time.format("%H:%M:%S%.3f").to_string())
+        .unwrap_or_default()
+}
+
+pub fn render(robots: &[Robot], format: Format) -> String {
+    match format {
+        Format::Markdown => render_markdown(robots),
+        Format::Html => render_html(robots),
+    }
+}
+
+fn render_markdown(robots: &[Robot]) -> String {
+    fn row(cells: impl IntoIterator<Item = String>) -> String {
+        let cells: Vec<_> = cells
+            .into_iter()
+            .map(|cell| cell.replace('|', "\\|"))
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    }
+    fn header(cells: &[&str]) -> String {
+        row(cells.iter().map(ToString::to_string)) + &row(cells.iter().map(|_| "---".to_string()))
+    }
+
+    let mut report = "# Game Report\n\n## Summary\n\n".to_string();
+    report += &header(&SUMMARY_HEADER);
+    for robot in robots {
+        let summary = Summary::new(&robot.events);
+        report += &row([robot.name.clone()]
+            .into_iter()
+            .chain(summary.cells().map(|cell| cell.to_string())));
+    }
+
+    report += "\n## Timeline\n\n";
+    report += &header(&TIMELINE_HEADER);
+    for (robot, event) in timeline(robots) {
+        report += &row([
+            time(event),
+            robot.name.clone(),
+            event.game_state.clone().unwrap_or_default(),
+            event.kind.to_string(),
+        ]);
+    }
+
+    report += "\n## Errors and Crashes\n";
+    for robot in robots {
+        let problems = problems(robot);
+        if problems.is_empty() {
+            continue;
+        }
+        let _ = write!(report, "\n### {}\n\n", robot.name);
+        for (problem, count) in problems {
+            let _ = writeln!(report, "- {count}× `{}`", problem.replace('`', "'"));
+        }
+    }
+    report
+}
+
+fn render_html(robots: &[Robot]) -> String {
+    fn row(tag: &str, cells: impl IntoIterator<Item = String>) -> String {
+        let cells: String = cells
+            .into_iter()
+            .map(|cell| format!("<{tag}>{}</{tag}>", escape(&cell)))
+            .collect();
+        format!("<tr>{cells}</tr>\n")
+    }
+    fn header(cells: &[&str]) -> String {
+        row("th", cells.iter().map(ToString::to_string))
+    }
+
+    let mut report = concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Game Report</title>\n",
+        "<style>table { border-collapse: collapse; } th, td { border: 1px solid #ccc; ",
+        "padding: 2px 8px; text-align: left; }</style>\n</head>\n<body>\n",
+        "<h1>Game Report</h1>\n<h2>Summary</h2>\n<table>\n"
+    )
+    .to_string();
+    report += &header(&SUMMARY_HEADER);
+    for robot in robots {
+        let summary = Summary::new(&robot.events);
+        report += &row(
+            "td",
+            [robot.name.clone()]
+                .into_iter()
+                .chain(summary.cells().map(|cell| cell.to_string())),
+        );
+    }
+
+    report += "</table>\n<h2>Timeline</h2>\n<table>\n";
+    report += &header(&TIMELINE_HEADER);
+    for (robot, event) in timeline(robots) {
+        report += &row(
+            "td",
+            [
+                time(event),
+                robot.name.clone(),
+                event.game_state.clone().unwrap_or_default(),
+                event.kind.to_string(),
+            ],
+        );
+    }
+
+    report += "</table>\n<h2>Errors and Crashes</h2>\n";
+    for robot in robots {
+        let problems = problems(robot);
+        if problems.is_empty() {
+            continue;
+        }
+        let _ = write!(report, "<h3>{}</h3>\n<ul>\n", escape(&robot.name));
+        for (problem, count) in problems {
+            let _ = writeln!(
+                report,
+                "<li>{count}× <code>{}</code></li>",
+                escape(&problem)
+            );
+        }
+        report += "</ul>\n";
+    }
+    report += "</body>\n</html>\n";
+    report
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(time: Option<&str>, kind: EventKind) -> Event {
+        Event {
+            time: time.map(|time| time.parse().unwrap()),
+            game_state: Some("Playing".to_string()),
+            kind,
+        }
+    }
+
+    fn robots() -> Vec<Robot> {
+        vec![
+            Robot {
+                name: "10.1.24.22".to_string(),
+                events: vec![
+                    event(
+                        Some("2023-07-06T14:00:02Z"),
+                        EventKind::Fall {
+                            facing: "Down".to_string(),
+                        },
+                    ),
+                    event(
+                        Some("2023-07-06T14:00:03Z"),
+                        EventKind::Error {
+                            message: "control::ball_filter: |x| < 0".to_string(),
+                        },
+                    ),
+                    event(
+                        Some("2023-07-06T14:00:04Z"),
+                        EventKind::Error {
+                            message: "control::ball_filter: |x| < 0".to_string(),
+                        },
+                    ),
+                    event(
+                        Some("2023-07-06T14:00:05Z"),
+                        EventKind::LocalizationReset {
+                            description: "hypotheses reset | penalized".to_string(),
+                        },
+                    ),
+                    event(
+                        None,
+                        EventKind::Crash {
+                            message: "thread 'control' panicked at `Vec<T>` & \"index\""
+                                .to_string(),
+                        },
+                    ),
+                ],
+            },
+            Robot {
+                name: "10.1.24.33".to_string(),
+                events: vec![event(Some("2023-07-06T14:00:01Z"), EventKind::Whistle)],
+            },
+        ]
+    }
+
+    #[test]
+    fn markdown_report_escapes_table_cells_and_code() {
+        let report = render(&robots(), Format::Markdown);
+
+        assert!(report.contains("| 10.1.24.22 | 1 | 0 | 0 | 1 | 2 | 1 |\n"));
+        assert!(report.contains("| 10.1.24.33 | 0 | 0 | 1 | 0 | 0 | 0 |\n"));
+        let whistle = report
+            .find("| 14:00:01.000 | 10.1.24.33 | Playing | whistle detected |")
+            .unwrap();
+        let fall = report
+            .find("| 14:00:02.000 | 10.1.24.22 | Playing | fell, facing Down |")
+            .unwrap();
+        assert!(whistle < fall);
+        assert!(!report.contains("| 14:00:03.000"));
+        assert!(report.contains("| localization reset: hypotheses reset \\| penalized |\n"));
+        assert!(report.contains("- 2× `error: control::ball_filter: |x| < 0`\n"));
+        assert!(
+            report.contains("- 1× `crash: thread 'control' panicked at 'Vec<T>' & \"index\"`\n")
+        );
+    }
+
+    #[test]
+    fn html_report_escapes_markup() {
+        let report = render(&robots(), Format::Html);
+
+        assert!(report.contains(
+            "<tr><td>10.1.24.22</td><td>1</td><td>0</td><td>0</td><td>1</td><td>2</td><td>1</td></tr>\n"
+        ));
+        assert!(
+            report.contains("<li>2× <code>error: control::ball_filter: |x| &lt; 0</code></li>\n")
+        );
+        assert!(report.contains(
+            "<li>1× <code>crash: thread 'control' panicked at `Vec&lt;T&gt;` &amp; &quot;index&quot;</code></li>\n"
+        ));
+        assert!(!report.contains("Vec<T>"));
+        assert!(report.ends_with("</body>\n</html>\n"));
+    }
+}
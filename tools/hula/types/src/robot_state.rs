@@ -178,6 +178,35 @@ pub struct JointsArray {
 }
 
 impl JointsArray {
+    /// Joint names in the order of [`JointsArray::into_lola`]
+    pub const NAMES: [&'static str; 25] = [
+        "head_yaw",
+        "head_pitch",
+        "left_shoulder_pitch",
+        "left_shoulder_roll",
+        "left_elbow_yaw",
+        "left_elbow_roll",
+        "left_wrist_yaw",
+        "left_hip_yaw_pitch",
+        "left_hip_roll",
+        "left_hip_pitch",
+        "left_knee_pitch",
+        "left_ankle_pitch",
+        "left_ankle_roll",
+        "right_hip_roll",
+        "right_hip_pitch",
+        "right_knee_pitch",
+        "right_ankle_pitch",
+        "right_ankle_roll",
+        "right_shoulder_pitch",
+        "right_shoulder_roll",
+        "right_elbow_yaw",
+        "right_elbow_roll",
+        "right_wrist_yaw",
+        "left_hand",
+        "right_hand",
+    ];
+
     pub fn into_lola(self) -> [f32; 25] {
         [
             self.head_yaw,
@@ -271,6 +271,9 @@ fn start_communication_server(
         "".to_string(),
         "".to_string(),
         parameter_slots,
+        false,
+        Default::default(),
+        None,
         keep_running.clone(),
     )?;
 
@@ -1,10 +1,18 @@
-use std::{collections::BTreeMap, net::IpAddr, num::ParseIntError, time::Duration};
+use std::{
+    collections::BTreeMap,
+    net::IpAddr,
+    num::ParseIntError,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use clap::{arg, Args};
 use color_eyre::owo_colors::OwoColorize;
+use serde::Serialize;
+use tokio::time::interval;
 
 use crate::parsers::NaoAddress;
 use aliveness::{
+    monitor::{Alert, History, Monitor, Thresholds},
     query_aliveness,
     service_manager::{ServiceState, SystemServices},
     AlivenessError, AlivenessState,
@@ -22,6 +30,27 @@ pub struct Arguments {
     /// Timeout in ms for waiting for responses
     #[arg(long, short = 't', value_parser = parse_duration, default_value = "200")]
     timeout: Duration,
+    /// Keep polling, show a table with trends and alerts (or a JSON line per poll with --json)
+    #[arg(long, short = 'm')]
+    monitor: bool,
+    /// Interval in ms between polls in monitor mode
+    #[arg(long, value_parser = parse_duration, default_value = "1000")]
+    interval: Duration,
+    /// Joint temperature in °C above which a joint is alerted as hot
+    #[arg(long, default_value = "70")]
+    maximum_temperature: f32,
+    /// Battery charge (0 to 1) below which a discharging battery is alerted
+    #[arg(long, default_value = "0.2")]
+    minimum_charge: f32,
+    /// Number of HULK service restarts during the history above which a crash loop is alerted
+    #[arg(long, default_value = "2")]
+    maximum_restarts: usize,
+    /// Time in ms without response after which a NAO is alerted as offline
+    #[arg(long, value_parser = parse_duration, default_value = "5000")]
+    offline_after: Duration,
+    /// Time in ms of history kept per NAO
+    #[arg(long, value_parser = parse_duration, default_value = "600000")]
+    history: Duration,
     /// The NAOs to show the aliveness information from, e.g. 20w or 10.1.24.22
     naos: Option<Vec<NaoAddress>>,
 }
@@ -42,6 +71,9 @@ pub enum Error {
 }
 
 pub async fn aliveness(arguments: Arguments) -> Result<(), Error> {
+    if arguments.monitor {
+        return monitor(&arguments).await;
+    }
     let states = query_aliveness_list(&arguments)
         .await
         .map_err(Error::QueryFailed)?;
@@ -223,6 +255,172 @@ fn print_verbose(states: &AlivenessList) {
     }
 }
 
+async fn monitor(arguments: &Arguments) -> Result<(), Error> {
+    let mut monitor = Monitor::new(Thresholds {
+        maximum_temperature: arguments.maximum_temperature,
+        minimum_charge: arguments.minimum_charge,
+        offline_after: arguments.offline_after,
+        maximum_restarts: arguments.maximum_restarts,
+        history: arguments.history,
+    });
+    let ips: Option<Vec<_>> = arguments
+        .naos
+        .as_ref()
+        .map(|naos| naos.iter().map(|nao| nao.ip).collect());
+    monitor.watch(ips.iter().flatten().copied().map(IpAddr::V4));
+    let mut interval = interval(arguments.interval);
+    loop {
+        interval.tick().await;
+        // a failed poll says nothing about the robots, monitoring continues with the next one
+        let query_error = match query_aliveness(arguments.timeout, ips.clone()).await {
+            Ok(responses) => {
+                monitor.update(SystemTime::now(), responses);
+                None
+            }
+            Err(error) => Some(error),
+        };
+        let now = SystemTime::now();
+        if arguments.json {
+            if let Some(error) = query_error {
+                eprintln!("failed to query aliveness: {error}");
+            }
+            print_monitor_json(&monitor, now)?;
+        } else {
+            print_monitor_table(&monitor, now, query_error);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct MonitorUpdate<'a> {
+    time: f64,
+    robots: BTreeMap<IpAddr, RobotUpdate<'a>>,
+}
+
+#[derive(Serialize)]
+struct RobotUpdate<'a> {
+    state: Option<&'a AlivenessState>,
+    last_seen: Option<f64>,
+    charge_trend_per_minute: Option<f32>,
+    hulk_restarts: usize,
+    alerts: Vec<Alert>,
+}
+
+fn unix_time(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn print_monitor_json(monitor: &Monitor, now: SystemTime) -> Result<(), Error> {
+    let update = MonitorUpdate {
+        time: unix_time(now),
+        robots: monitor
+            .robots()
+            .iter()
+            .map(|(ip, history)| {
+                (
+                    *ip,
+                    RobotUpdate {
+                        state: history.latest_state(),
+                        last_seen: history.last_seen().map(unix_time),
+                        charge_trend_per_minute: history.charge_trend(),
+                        hulk_restarts: history.hulk_restarts(),
+                        alerts: monitor.alerts(history, now),
+                    },
+                )
+            })
+            .collect(),
+    };
+    println!(
+        "{}",
+        serde_json::to_string(&update).map_err(Error::SerializeFailed)?
+    );
+    Ok(())
+}
+
+fn print_monitor_table(monitor: &Monitor, now: SystemTime, query_error: Option<AlivenessError>) {
+    const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+    print!("{CLEAR_SCREEN}");
+    println!(
+        "{:<6}{:<12}{:<22}{:<22}{:<16}{:<10}Alerts",
+        "NAO", "Network", "Battery", "Temperature", "HULK", "Seen"
+    );
+    for (ip, history) in monitor.robots() {
+        let id = match ip {
+            IpAddr::V4(ip) => ip.octets()[3],
+            IpAddr::V6(ip) => ip.octets()[15],
+        };
+        let alerts = monitor
+            .alerts(history, now)
+            .iter()
+            .map(describe_alert)
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "{:<6}{:<12}{:<22}{:<22}{:<16}{:<10}{}",
+            format!("[{id}]"),
+            history
+                .latest_state()
+                .and_then(|state| state.network.clone())
+                .unwrap_or_else(|| "None".to_string()),
+            describe_battery(history),
+            describe_temperature(history),
+            describe_hulk(history),
+            history
+                .last_seen()
+                .and_then(|last_seen| now.duration_since(last_seen).ok())
+                .map(|age| format!("{}s ago", age.as_secs()))
+                .unwrap_or_else(|| "never".to_string()),
+            alerts.red(),
+        );
+    }
+    if let Some(error) = query_error {
+        println!("{}", format!("failed to query aliveness: {error}").red());
+    }
+}
+
+fn describe_battery(history: &History) -> String {
+    let Some(battery) = history.latest_state().and_then(|state| state.battery) else {
+        return "?".to_string();
+    };
+    let charge = (battery.charge * 100.0) as u32;
+    match history.charge_trend() {
+        Some(trend) => format!("{charge}% ({:+.1}%/min)", trend * 100.0),
+        None => format!("{charge}%"),
+    }
+}
+
+fn describe_temperature(history: &History) -> String {
+    let Some(temperatures) = history.latest_state().and_then(|state| state.temperature) else {
+        return "?".to_string();
+    };
+    let temperatures = temperatures.into_lola();
+    let maximum = temperatures.iter().copied().fold(0.0, f32::max);
+    let mean = temperatures.iter().sum::<f32>() / temperatures.len() as f32;
+    format!("max {maximum}°C, mean {mean:.0}°C")
+}
+
+fn describe_hulk(history: &History) -> String {
+    let Some(state) = history.latest_state() else {
+        return "?".to_string();
+    };
+    match history.hulk_restarts() {
+        0 => state.system_services.hulk.to_string(),
+        restarts => format!("{} ({restarts}x)", state.system_services.hulk),
+    }
+}
+
+fn describe_alert(alert: &Alert) -> String {
+    match alert {
+        Alert::HotJoint { joint, temperature } => format!("hot {joint} {temperature}°C"),
+        Alert::LowBattery { charge } => format!("low battery {:.0}%", charge * 100.0),
+        Alert::CrashLoop { restarts } => format!("crash loop ({restarts} restarts)"),
+        Alert::Offline { seconds } => format!("offline for {seconds:.0}s"),
+    }
+}
+
 async fn query_aliveness_list(arguments: &Arguments) -> Result<AlivenessList, AlivenessError> {
     let ips = arguments
         .naos
@@ -72,10 +72,11 @@ fn dynamic_completions(shell: Shell, static_completions: String) {
         Shell::Fish => {
             print!("{static_completions}");
 
-            const COMPLETION_SUBCOMMANDS: [(&str, &str); 14] = [
+            const COMPLETION_SUBCOMMANDS: [(&str, &str); 15] = [
                 ("aliveness", ""),
                 ("gammaray", ""),
                 ("hulk", ""),
+                ("joints", ""),
                 ("logs", "delete"),
                 ("logs", "downloads"),
                 ("logs", "show"),
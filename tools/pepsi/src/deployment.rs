@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use clap::Subcommand;
+use color_eyre::{eyre::WrapErr, Result};
+use serde_json::from_str;
+
+use nao::{Nao, SystemctlAction, DEPLOYMENT_DIRECTORY, PREVIOUS_DEPLOYMENT_DIRECTORY};
+use repository::{
+    provenance::{Provenance, PROVENANCE_FILE_NAME},
+    Repository,
+};
+
+use crate::{parsers::NaoAddress, progress_indicator::ProgressIndicator};
+
+#[derive(Subcommand)]
+pub enum Arguments {
+    /// Show what is deployed on the NAOs (git revision, profile, location, parameters)
+    Status {
+        /// The NAOs to execute that command on e.g. 20w or 10.1.24.22
+        #[arg(required = true)]
+        naos: Vec<NaoAddress>,
+    },
+    /// Restore the previous deployment, rolling back twice restores the replaced deployment
+    Rollback {
+        /// Do not restart HULK service after rolling back
+        #[arg(long)]
+        no_restart: bool,
+        /// The NAOs to execute that command on e.g. 20w or 10.1.24.22
+        #[arg(required = true)]
+        naos: Vec<NaoAddress>,
+    },
+}
+
+pub async fn deployment(arguments: Arguments, repository: &Repository) -> Result<()> {
+    match arguments {
+        Arguments::Status { naos } => {
+            let local_checksums = repository
+                .get_parameter_checksums()
+                .await
+                .wrap_err("failed to get local parameter checksums")?;
+            let local_checksums = &local_checksums;
+            ProgressIndicator::map_tasks(
+                naos,
+                "Retrieving deployment...",
+                |nao_address| async move {
+                    let nao = Nao::try_new_with_ping(nao_address.ip).await?;
+                    let current = read_provenance(&nao, DEPLOYMENT_DIRECTORY)
+                        .await
+                        .wrap_err_with(|| format!("failed to get deployment of {nao_address}"))?;
+                    let previous = read_provenance(&nao, PREVIOUS_DEPLOYMENT_DIRECTORY)
+                        .await
+                        .wrap_err_with(|| {
+                            format!("failed to get previous deployment of {nao_address}")
+                        })?;
+                    Ok(format!(
+                        "  Deployed: {}\n  Previous: {}",
+                        describe(current.as_ref(), local_checksums),
+                        describe(previous.as_ref(), local_checksums),
+                    ))
+                },
+            )
+            .await;
+        }
+        Arguments::Rollback { no_restart, naos } => {
+            ProgressIndicator::map_tasks(naos, "Rolling back...", |nao_address| async move {
+                let nao = Nao::try_new_with_ping(nao_address.ip).await?;
+                nao.execute_systemctl(SystemctlAction::Stop, "hulk")
+                    .await
+                    .wrap_err_with(|| format!("failed to stop HULK service on {nao_address}"))?;
+                nao.rollback_deployment()
+                    .await
+                    .wrap_err_with(|| format!("failed to roll back {nao_address}"))?;
+                if !no_restart {
+                    nao.execute_systemctl(SystemctlAction::Start, "hulk")
+                        .await
+                        .wrap_err_with(|| {
+                            format!("failed to start HULK service on {nao_address}")
+                        })?;
+                }
+                Ok(())
+            })
+            .await;
+        }
+    }
+    Ok(())
+}
+
+/// Returns `None` for deployments without provenance (e.g. uploaded by older pepsi versions)
+pub async fn read_provenance(nao: &Nao, deployment_directory: &str) -> Result<Option<Provenance>> {
+    nao.read_file(&format!("{deployment_directory}/{PROVENANCE_FILE_NAME}"))
+        .await?
+        .map(|contents| from_str(&contents).wrap_err("failed to parse provenance"))
+        .transpose()
+}
+
+fn describe(provenance: Option<&Provenance>, local_checksums: &BTreeMap<String, String>) -> String {
+    let Some(provenance) = provenance else {
+        return "<UNKNOWN>".to_string();
+    };
+    let revision = match (&provenance.git_revision, &provenance.dirty_diff_hash) {
+        (Some(revision), Some(diff_hash)) => {
+            format!("{} (dirty {})", shorten(revision), shorten(diff_hash))
+        }
+        (Some(revision), None) => shorten(revision).to_string(),
+        (None, _) => "<NO GIT REVISION>".to_string(),
+    };
+    let differing_parameters = provenance.differing_parameters(local_checksums);
+    let parameters = if differing_parameters.is_empty() {
+        "parameters match local".to_string()
+    } else {
+        format!(
+            "{} parameter file(s) differ from local",
+            differing_parameters.len()
+        )
+    };
+    format!(
+        "{revision}, profile {}, location {}, {parameters}, uploaded {} by {}",
+        provenance.profile,
+        provenance.location.as_deref().unwrap_or("<NOT_CONFIGURED>"),
+        provenance.uploaded_at,
+        provenance.uploaded_by.as_deref().unwrap_or("<UNKNOWN>"),
+    )
+}
+
+fn shorten(hash: &str) -> &str {
+    &hash[..hash.len().min(8)]
+}
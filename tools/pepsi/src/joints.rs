@@ -0,0 +1,139 @@
+use clap::Args;
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use futures_util::future::try_join_all;
+use serde_json::{from_str, from_value, Value};
+
+use nao::Nao;
+use repository::Repository;
+use types::joint_health::{statistics_file_name, JointHealth};
+
+use crate::{
+    parameters::effective_parameters,
+    parsers::{NaoAddress, NaoNumber, NaoNumberAtLocation},
+};
+
+#[derive(Args)]
+pub struct Arguments {
+    /// Show all joints instead of only the ones with issues
+    #[arg(long)]
+    all: bool,
+    /// The NAOs to show the joint health of e.g. 20w or 10.1.24.22
+    #[arg(required = true)]
+    naos: Vec<NaoAddress>,
+}
+
+pub async fn joints(arguments: Arguments, repository: &Repository) -> Result<()> {
+    let reports = try_join_all(arguments.naos.into_iter().map(|nao_address| async move {
+        let statistics_path = statistics_path(repository, nao_address)
+            .await
+            .wrap_err_with(|| format!("failed to get statistics path of {nao_address}"))?;
+        let nao = Nao::try_new_with_ping(nao_address.ip).await?;
+        let contents = nao
+            .read_file(&statistics_path)
+            .await
+            .wrap_err_with(|| format!("failed to read joint health of {nao_address}"))?;
+        let joints = contents
+            .map(|contents| parse_statistics(&contents))
+            .transpose()
+            .wrap_err_with(|| format!("failed to parse joint health of {nao_address}"))?;
+        Ok::<_, color_eyre::Report>((nao_address, joints))
+    }))
+    .await?;
+
+    for (nao_address, joints) in reports {
+        println!("{nao_address}");
+        let Some(joints) = joints else {
+            println!("  no joint health statistics recorded yet");
+            continue;
+        };
+        let mut number_of_shown_joints = 0;
+        for (name, health) in &joints {
+            if !arguments.all && !health.issues.any() {
+                continue;
+            }
+            println!("  {name:<28} {}", describe(health));
+            number_of_shown_joints += 1;
+        }
+        if number_of_shown_joints == 0 {
+            println!("  no issues");
+        }
+    }
+    Ok(())
+}
+
+/// The statistics file of the body in the `joint_health.statistics_directory` effective on the NAO
+async fn statistics_path(repository: &Repository, nao_address: NaoAddress) -> Result<String> {
+    let nao = NaoNumberAtLocation {
+        nao_number: NaoNumber::try_from(nao_address)?,
+        location: None,
+    };
+    let hardware_ids = repository
+        .get_hardware_ids()
+        .await
+        .wrap_err("failed to get hardware IDs")?;
+    let body_id = &hardware_ids
+        .get(&nao.nao_number.number)
+        .ok_or_else(|| eyre!("no hardware IDs for NAO {}", nao.nao_number))?
+        .body_id;
+    let parameters = effective_parameters(repository, &nao, &[]).await?;
+    match parameters.get("joint_health.statistics_directory") {
+        Some((Value::String(statistics_directory), _)) => Ok(format!(
+            "{}/{}",
+            statistics_directory.trim_end_matches('/'),
+            statistics_file_name(body_id)
+        )),
+        Some((value, source)) => bail!("expected a directory, got {value} from {source}"),
+        None => bail!("joint_health.statistics_directory is not set"),
+    }
+}
+
+/// Joints by their path e.g. left_leg.knee_pitch
+fn parse_statistics(contents: &str) -> Result<Vec<(String, JointHealth)>> {
+    let statistics: Value = from_str(contents)?;
+    let limbs = statistics
+        .as_object()
+        .ok_or_else(|| eyre!("expected an object of limbs"))?;
+    let mut joints = Vec::new();
+    for (limb, limb_joints) in limbs {
+        let limb_joints = limb_joints
+            .as_object()
+            .ok_or_else(|| eyre!("expected an object of joints in {limb}"))?;
+        for (joint, health) in limb_joints {
+            let health = from_value(health.clone())
+                .wrap_err_with(|| format!("failed to parse {limb}.{joint}"))?;
+            joints.push((format!("{limb}.{joint}"), health));
+        }
+    }
+    Ok(joints)
+}
+
+fn describe(health: &JointHealth) -> String {
+    let degrees = |radians: Option<f32>| match radians {
+        Some(radians) => format!("{:+6.2}°", radians.to_degrees()),
+        None => format!("{:>7}", "-"),
+    };
+    let tracking_error = (health.tracking_error.samples > 0).then_some(health.tracking_error.mean);
+    let mut issues = Vec::new();
+    if health.issues.calibration_drift {
+        issues.push("calibration drift");
+    }
+    if health.issues.backlash {
+        issues.push("backlash");
+    }
+    if health.issues.stiffness_anomaly {
+        issues.push("stiffness anomaly");
+    }
+    format!(
+        "offset {}  backlash {}  tracking error {}  samples {}/{}/{}  {}",
+        degrees(health.offset()),
+        degrees(health.backlash()),
+        degrees(tracking_error),
+        health.settled_error_from_below.samples,
+        health.settled_error_from_above.samples,
+        health.tracking_error.samples,
+        issues.join(", ")
+    )
+}
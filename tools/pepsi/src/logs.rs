@@ -1,9 +1,22 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use clap::Subcommand;
-use color_eyre::{eyre::WrapErr, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use futures_util::future::try_join_all;
+use log::Level;
+use serde::Serialize;
+use serde_json::from_str;
+use tokio::{
+    fs::File,
+    io::{AsyncBufRead, AsyncBufReadExt, BufReader},
+};
 
-use nao::Nao;
+use nao::{Nao, DEPLOYMENT_DIRECTORY};
+use structured_log::{log_files, LogRecord, FILE_EXTENSION, FILE_PREFIX};
 
 use crate::{parsers::NaoAddress, progress_indicator::ProgressIndicator};
 
@@ -29,6 +42,81 @@ pub enum Arguments {
         #[arg(required = true)]
         naos: Vec<NaoAddress>,
     },
+    /// Filter and merge structured logs of several NAOs by time
+    Query {
+        #[command(flatten)]
+        filter: Filter,
+        /// Read logs previously downloaded with `pepsi logs download` into this directory instead
+        /// of reading them from the NAOs
+        #[arg(long)]
+        directory: Option<PathBuf>,
+        /// Output matching records as JSON lines
+        #[arg(long)]
+        json: bool,
+        /// The NAOs to query logs from e.g. 20w or 10.1.24.22
+        #[arg(required = true)]
+        naos: Vec<NaoAddress>,
+    },
+}
+
+#[derive(Args)]
+pub struct Filter {
+    /// Only records at or after this time (RFC 3339, e.g. 2023-07-06T14:00:00Z)
+    #[arg(long)]
+    since: Option<DateTime<Utc>>,
+    /// Only records before this time (RFC 3339)
+    #[arg(long)]
+    until: Option<DateTime<Utc>>,
+    /// Only records with at least this severity (error, warn, info, debug, trace)
+    #[arg(long, value_parser = parse_level)]
+    level: Option<Level>,
+    /// Only records logged by this cycler instance e.g. Control or VisionTop
+    #[arg(long)]
+    cycler: Option<String>,
+    /// Only records logged by this node e.g. BallFilter
+    #[arg(long)]
+    node: Option<String>,
+}
+
+fn parse_level(level: &str) -> Result<Level, String> {
+    level
+        .parse()
+        .map_err(|_| format!("unknown log level {level:?}"))
+}
+
+impl Filter {
+    fn matches(&self, record: &LogRecord) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Some(time) = record.time() else {
+                return false;
+            };
+            if self.since.is_some_and(|since| time < since)
+                || self.until.is_some_and(|until| time >= until)
+            {
+                return false;
+            }
+        }
+        if let Some(minimum_level) = self.level {
+            let level = record.level.parse::<Level>();
+            if !level.is_ok_and(|level| level <= minimum_level) {
+                return false;
+            }
+        }
+        if self.cycler.is_some() && record.cycler != self.cycler {
+            return false;
+        }
+        if self.node.is_some() && record.node != self.node {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Serialize)]
+struct RobotLogRecord {
+    nao: String,
+    #[serde(flatten)]
+    record: LogRecord,
 }
 
 pub async fn logs(arguments: Arguments) -> Result<()> {
@@ -66,7 +154,227 @@ pub async fn logs(arguments: Arguments) -> Result<()> {
             })
             .await
         }
+        Arguments::Query {
+            filter,
+            directory,
+            json,
+            naos,
+        } => query(filter, directory.as_deref(), json, naos).await?,
     }
 
     Ok(())
 }
+
+async fn query(
+    filter: Filter,
+    directory: Option<&Path>,
+    json: bool,
+    naos: Vec<NaoAddress>,
+) -> Result<()> {
+    let filter = &filter;
+    let logs = try_join_all(naos.into_iter().map(|nao_address| async move {
+        let records = match directory {
+            Some(directory) => {
+                read_downloaded_logs(&directory.join(nao_address.to_string()), filter)
+                    .await
+                    .wrap_err_with(|| format!("failed to read downloaded logs of {nao_address}"))?
+            }
+            None => read_remote_logs(nao_address, filter)
+                .await
+                .wrap_err_with(|| format!("failed to read logs from {nao_address}"))?,
+        };
+        Ok::<_, color_eyre::Report>((nao_address, records))
+    }))
+    .await?;
+
+    let mut records: Vec<_> = logs
+        .into_iter()
+        .flat_map(|(nao_address, records)| {
+            records.into_iter().map(move |record| RobotLogRecord {
+                nao: nao_address.to_string(),
+                record,
+            })
+        })
+        .collect();
+    // timestamps are RFC 3339 in UTC with fixed precision
+    records.sort_by(|left, right| left.record.timestamp.cmp(&right.record.timestamp));
+
+    for entry in &records {
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string(entry).wrap_err("failed to serialize log record")?
+            );
+            continue;
+        }
+        let RobotLogRecord { nao, record } = entry;
+        let context = match (&record.cycler, &record.node, record.cycle) {
+            (Some(cycler), Some(node), Some(cycle)) => format!("{cycler}/{node}#{cycle}"),
+            (Some(cycler), None, Some(cycle)) => format!("{cycler}#{cycle}"),
+            _ => record.target.clone(),
+        };
+        println!(
+            "{}  [{nao}]  {:>5}  {context:<32}  {:<24}  {}",
+            record.timestamp,
+            record.level,
+            record.game_state.as_deref().unwrap_or("-"),
+            record.message
+        );
+    }
+    Ok(())
+}
+
+/// Logs are streamed and filtered line by line, only matching records are kept in memory
+async fn read_remote_logs(nao_address: NaoAddress, filter: &Filter) -> Result<Vec<LogRecord>> {
+    let nao = Nao::try_new_with_ping(nao_address.ip).await?;
+    // the remote shell expands the pattern to all rotated files
+    let mut cat = nao.stream_files(&format!(
+        "{DEPLOYMENT_DIRECTORY}/logs/{FILE_PREFIX}*.{FILE_EXTENSION}"
+    ))?;
+    let stdout = cat.stdout.take().expect("stdout should be piped");
+    let records = matching_records(BufReader::new(stdout), filter).await?;
+    let status = cat.wait().await.wrap_err("failed to wait for cat")?;
+    if !status.success() {
+        bail!("cat ssh command exited with {status}");
+    }
+    Ok(records)
+}
+
+async fn read_downloaded_logs(directory: &Path, filter: &Filter) -> Result<Vec<LogRecord>> {
+    if !directory.exists() {
+        bail!("{} does not exist", directory.display());
+    }
+    let mut records = Vec::new();
+    for path in log_files(directory)? {
+        let file = File::open(&path)
+            .await
+            .wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        records.extend(
+            matching_records(BufReader::new(file), filter)
+                .await
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?,
+        );
+    }
+    Ok(records)
+}
+
+async fn matching_records(
+    reader: impl AsyncBufRead + Unpin,
+    filter: &Filter,
+) -> Result<Vec<LogRecord>> {
+    let mut lines = reader.lines();
+    let mut records = Vec::new();
+    while let Some(line) = lines.next_line().await.wrap_err("failed to read line")? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: LogRecord = match from_str(&line) {
+            Ok(record) => record,
+            // a line may be incomplete if the NAO is still writing or crashed while writing
+            Err(_) => continue,
+        };
+        if filter.matches(&record) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: &str, level: &str, node: Option<&str>) -> LogRecord {
+        LogRecord {
+            timestamp: timestamp.to_string(),
+            level: level.to_string(),
+            target: "control::ball_filter".to_string(),
+            cycler: Some("Control".to_string()),
+            node: node.map(ToString::to_string),
+            cycle: Some(42),
+            game_state: None,
+            message: "hypothesis removed".to_string(),
+        }
+    }
+
+    fn unfiltered() -> Filter {
+        Filter {
+            since: None,
+            until: None,
+            level: None,
+            cycler: None,
+            node: None,
+        }
+    }
+
+    #[test]
+    fn time_window_includes_since_and_excludes_until() {
+        let filter = Filter {
+            since: Some("2023-07-06T14:00:00Z".parse().unwrap()),
+            until: Some("2023-07-06T14:10:00Z".parse().unwrap()),
+            ..unfiltered()
+        };
+
+        assert!(!filter.matches(&record("2023-07-06T13:59:59.999Z", "INFO", None)));
+        assert!(filter.matches(&record("2023-07-06T14:00:00.000Z", "INFO", None)));
+        assert!(filter.matches(&record("2023-07-06T14:09:59.999Z", "INFO", None)));
+        assert!(!filter.matches(&record("2023-07-06T14:10:00.000Z", "INFO", None)));
+        assert!(!filter.matches(&record("yesterday", "INFO", None)));
+        assert!(unfiltered().matches(&record("yesterday", "INFO", None)));
+    }
+
+    #[test]
+    fn level_is_a_minimum_severity() {
+        let filter = Filter {
+            level: Some(Level::Warn),
+            ..unfiltered()
+        };
+
+        assert!(filter.matches(&record("2023-07-06T14:00:00.000Z", "ERROR", None)));
+        assert!(filter.matches(&record("2023-07-06T14:00:00.000Z", "WARN", None)));
+        assert!(!filter.matches(&record("2023-07-06T14:00:00.000Z", "INFO", None)));
+        assert!(!filter.matches(&record("2023-07-06T14:00:00.000Z", "LOUD", None)));
+    }
+
+    #[test]
+    fn cycler_and_node_have_to_match_exactly() {
+        let filter = Filter {
+            cycler: Some("Control".to_string()),
+            node: Some("BallFilter".to_string()),
+            ..unfiltered()
+        };
+
+        assert!(filter.matches(&record(
+            "2023-07-06T14:00:00.000Z",
+            "INFO",
+            Some("BallFilter")
+        )));
+        assert!(!filter.matches(&record(
+            "2023-07-06T14:00:00.000Z",
+            "INFO",
+            Some("BallDetection")
+        )));
+        assert!(!filter.matches(&record("2023-07-06T14:00:00.000Z", "INFO", None)));
+    }
+
+    #[tokio::test]
+    async fn incomplete_and_filtered_lines_are_skipped() {
+        let matching = record("2023-07-06T14:00:00.000Z", "ERROR", None);
+        let filtered = record("2023-07-06T14:00:01.000Z", "DEBUG", None);
+        let contents = format!(
+            "{}\n\n{}\n{{\"timestamp\": \"2023-07-06T14:00:02",
+            matching.to_json_line(),
+            filtered.to_json_line()
+        );
+        let filter = Filter {
+            level: Some(Level::Info),
+            ..unfiltered()
+        };
+
+        let records = matching_records(contents.as_bytes(), &filter)
+            .await
+            .unwrap();
+
+        assert_eq!(records, [matching]);
+    }
+}
@@ -8,10 +8,14 @@ use analyze::{analyze, Arguments as AnalyzeArguments};
 use cargo::{cargo, Arguments as CargoArguments, Command as CargoCommand};
 use communication::{communication, Arguments as CommunicationArguments};
 use completions::{completions, Arguments as CompletionArguments};
+use deployment::{deployment, Arguments as DeploymentArguments};
 use gammaray::{gammaray, Arguments as GammarayArguments};
 use hulk::{hulk, Arguments as HulkArguments};
+use joints::{joints, Arguments as JointsArguments};
 use location::{location, Arguments as LocationArguments};
 use logs::{logs, Arguments as LogsArguments};
+use manifest::{manifest, Arguments as ManifestArguments};
+use parameters::{parameters, Arguments as ParametersArguments};
 use ping::{ping, Arguments as PingArguments};
 use player_number::{player_number, Arguments as PlayerNumberArguments};
 use post_game::{post_game, Arguments as PostGameArguments};
@@ -29,10 +33,14 @@ mod analyze;
 mod cargo;
 mod communication;
 mod completions;
+mod deployment;
 mod gammaray;
 mod hulk;
+mod joints;
 mod location;
 mod logs;
+mod manifest;
+mod parameters;
 mod parsers;
 mod ping;
 mod player_number;
@@ -81,18 +89,30 @@ async fn main() -> Result<()> {
         Command::Completions(arguments) => completions(arguments, Arguments::command())
             .await
             .wrap_err("failed to execute completion command")?,
+        Command::Deployment(arguments) => deployment(arguments, &repository?)
+            .await
+            .wrap_err("failed to execute deployment command")?,
         Command::Gammaray(arguments) => gammaray(arguments)
             .await
             .wrap_err("failed to execute gammaray command")?,
         Command::Hulk(arguments) => hulk(arguments)
             .await
             .wrap_err("failed to execute hulk command")?,
+        Command::Joints(arguments) => joints(arguments, &repository?)
+            .await
+            .wrap_err("failed to execute joints command")?,
         Command::Location(arguments) => location(arguments, &repository?)
             .await
             .wrap_err("failed to execute location command")?,
         Command::Logs(arguments) => logs(arguments)
             .await
             .wrap_err("failed to execute logs command")?,
+        Command::Manifest(arguments) => manifest(arguments, &repository?)
+            .await
+            .wrap_err("failed to execute manifest command")?,
+        Command::Parameters(arguments) => parameters(arguments, &repository?)
+            .await
+            .wrap_err("failed to execute parameters command")?,
         Command::Ping(arguments) => ping(arguments).await,
         Command::Playernumber(arguments) => player_number(arguments, &repository?)
             .await
@@ -157,16 +177,27 @@ enum Command {
     Communication(CommunicationArguments),
     /// Generates shell completion files
     Completions(CompletionArguments),
+    /// Inspect and roll back what is deployed on NAOs
+    #[command(subcommand)]
+    Deployment(DeploymentArguments),
     /// Flash a HULKs-OS image to NAOs
     Gammaray(GammarayArguments),
     /// Control the HULK service
     Hulk(HulkArguments),
+    /// Show joint health statistics recorded on NAOs to find worn joints
+    Joints(JointsArguments),
     /// Control the configured location
     #[command(subcommand)]
     Location(LocationArguments),
     /// Logging on the NAO
     #[command(subcommand)]
     Logs(LogsArguments),
+    /// Validate, apply and diff game-day manifests (NAOs, player numbers, location, network)
+    #[command(subcommand)]
+    Manifest(ManifestArguments),
+    /// Show, diff and audit the merged parameters of NAOs at locations
+    #[command(subcommand)]
+    Parameters(ParametersArguments),
     /// Change player numbers of the NAOs in local parameters
     Playernumber(PlayerNumberArguments),
     /// Ping NAOs
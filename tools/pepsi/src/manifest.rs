@@ -0,0 +1,514 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use clap::{Args, Subcommand};
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_json::{from_str, Value};
+use tokio::fs::read_to_string;
+
+use aliveness::{query_aliveness, service_manager::ServiceState, AlivenessState};
+use constants::OS_VERSION;
+use nao::{Nao, Network, DEPLOYMENT_DIRECTORY};
+use repository::{HardwareIds, Repository};
+use spl_network_messages::{PlayerNumber, TeamColor};
+
+use crate::{
+    deployment::read_provenance,
+    parsers::{
+        number_to_ip, parse_network, Connection, NaoAddress, NaoAddressPlayerAssignment,
+        NaoNumberPlayerAssignment,
+    },
+    pre_game::{pre_game, Arguments as PreGameArguments},
+    progress_indicator::ProgressIndicator,
+};
+
+#[derive(Subcommand)]
+pub enum Arguments {
+    /// Check the manifest against hardware IDs, locations and aliveness information of the NAOs
+    Validate {
+        #[command(flatten)]
+        manifest: ManifestArguments,
+        /// Only check the manifest against the repository, do not query the NAOs
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Get the NAOs of the manifest ready for a game (like pregame)
+    Apply {
+        #[command(flatten)]
+        manifest: ManifestArguments,
+        /// Do not update nor install SDK
+        #[arg(long)]
+        no_sdk_installation: bool,
+        /// Do not build before uploading
+        #[arg(long)]
+        no_build: bool,
+        /// Do not restart HULK nor HULA service after uploading
+        #[arg(long)]
+        no_restart: bool,
+        /// Do not remove existing remote files during uploading
+        #[arg(long)]
+        no_clean: bool,
+        /// Enable communication
+        #[arg(long)]
+        with_communication: bool,
+        /// Apply even if the validation finds problems
+        #[arg(long)]
+        force: bool,
+        /// Use a remote machine for compilation, see ./scripts/remote for details
+        #[arg(long)]
+        remote: bool,
+    },
+    /// Show differences between the manifest and the state of the NAOs, fails if any NAO differs
+    Diff {
+        #[command(flatten)]
+        manifest: ManifestArguments,
+    },
+}
+
+#[derive(Args)]
+pub struct ManifestArguments {
+    /// Timeout in ms for waiting for aliveness responses
+    #[arg(long, short = 't', value_parser = parse_duration, default_value = "500")]
+    timeout: Duration,
+    /// The game-day manifest, see tools/pepsi/game_day.example.toml
+    path: PathBuf,
+}
+
+fn parse_duration(arg: &str) -> Result<Duration, ParseIntError> {
+    let milliseconds = arg.parse()?;
+    Ok(Duration::from_millis(milliseconds))
+}
+
+/// Declarative description of the NAOs playing a game
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub location: String,
+    #[serde(deserialize_with = "deserialize_network")]
+    pub network: Network,
+    #[serde(default = "default_profile")]
+    pub profile: String,
+    /// Only documents the jersey, the team colour is assigned by the GameController
+    pub jersey_color: Option<TeamColor>,
+    /// How pepsi reaches the NAOs while applying the manifest
+    #[serde(default)]
+    pub connection: Connection,
+    /// NAO numbers with their player numbers e.g. "20:2" (player numbers start from 1)
+    #[serde(deserialize_with = "deserialize_assignments")]
+    pub robots: Vec<NaoNumberPlayerAssignment>,
+}
+
+fn default_profile() -> String {
+    "release".to_string()
+}
+
+fn deserialize_network<'de, D>(deserializer: D) -> Result<Network, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let network = String::deserialize(deserializer)?;
+    parse_network(&network).map_err(D::Error::custom)
+}
+
+fn deserialize_assignments<'de, D>(
+    deserializer: D,
+) -> Result<Vec<NaoNumberPlayerAssignment>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|assignment| {
+            assignment
+                .parse()
+                .map_err(|error| D::Error::custom(format!("{assignment:?}: {error}")))
+        })
+        .collect()
+}
+
+impl Manifest {
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_to_string(path)
+            .await
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        toml::from_str(&contents).wrap_err_with(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn address(&self, assignment: &NaoNumberPlayerAssignment) -> Result<NaoAddress> {
+        Ok(NaoAddress {
+            ip: number_to_ip(assignment.nao_number.number, self.connection)?,
+        })
+    }
+
+    /// Problems which are detectable without talking to the NAOs
+    pub fn offline_problems(
+        &self,
+        available_locations: &HashSet<String>,
+        hardware_ids: &HashMap<u8, HardwareIds>,
+    ) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !available_locations.contains(&self.location) {
+            problems.push(format!("location {:?} does not exist", self.location));
+        }
+        let mut nao_numbers = HashSet::new();
+        let mut player_numbers = HashSet::new();
+        for assignment in &self.robots {
+            let nao_number = assignment.nao_number.number;
+            if !nao_numbers.insert(nao_number) {
+                problems.push(format!("NAO {nao_number} is listed multiple times"));
+            }
+            if !player_numbers.insert(assignment.player_number) {
+                problems.push(format!(
+                    "player number {} is assigned multiple times",
+                    assignment.player_number
+                ));
+            }
+            if !hardware_ids.contains_key(&nao_number) {
+                problems.push(format!(
+                    "NAO {nao_number} has no entry in hardware_ids.json"
+                ));
+            }
+        }
+        problems
+    }
+}
+
+pub async fn manifest(arguments: Arguments, repository: &Repository) -> Result<()> {
+    match arguments {
+        Arguments::Validate { manifest, offline } => {
+            let timeout = manifest.timeout;
+            let manifest = Manifest::load(&manifest.path).await?;
+            let problems = validate(&manifest, repository, (!offline).then_some(timeout)).await?;
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("✗ {problem}");
+                }
+                bail!("manifest has {} problem(s)", problems.len());
+            }
+            println!("✔ manifest is valid");
+            print_jersey_color(&manifest);
+        }
+        Arguments::Apply {
+            manifest,
+            no_sdk_installation,
+            no_build,
+            no_restart,
+            no_clean,
+            with_communication,
+            force,
+            remote,
+        } => {
+            let timeout = manifest.timeout;
+            let manifest = Manifest::load(&manifest.path).await?;
+            let problems = validate(&manifest, repository, Some(timeout)).await?;
+            for problem in &problems {
+                println!("✗ {problem}");
+            }
+            if !problems.is_empty() && !force {
+                bail!(
+                    "manifest has {} problem(s), use --force to apply anyway",
+                    problems.len()
+                );
+            }
+            print_jersey_color(&manifest);
+            let assignments = manifest
+                .robots
+                .iter()
+                .map(|assignment| {
+                    Ok(NaoAddressPlayerAssignment {
+                        nao_address: manifest.address(assignment)?,
+                        player_number: assignment.player_number,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            pre_game(
+                PreGameArguments {
+                    profile: manifest.profile,
+                    no_sdk_installation,
+                    no_build,
+                    no_restart,
+                    no_clean,
+                    with_communication,
+                    // the OS version is part of the validation
+                    skip_os_check: true,
+                    location: manifest.location,
+                    network: manifest.network,
+                    assignments,
+                    remote,
+                },
+                repository,
+            )
+            .await
+            .wrap_err("failed to apply manifest")?;
+        }
+        Arguments::Diff { manifest } => {
+            let timeout = manifest.timeout;
+            let manifest = Manifest::load(&manifest.path).await?;
+            diff(&manifest, repository, timeout).await?;
+        }
+    }
+    Ok(())
+}
+
+fn print_jersey_color(manifest: &Manifest) {
+    if let Some(jersey_color) = &manifest.jersey_color {
+        println!("Jersey colour: {jersey_color:?}");
+    }
+}
+
+/// Returns all problems found, the aliveness of the NAOs is only checked with a timeout
+async fn validate(
+    manifest: &Manifest,
+    repository: &Repository,
+    aliveness_timeout: Option<Duration>,
+) -> Result<Vec<String>> {
+    let available_locations = repository
+        .list_available_locations()
+        .await
+        .wrap_err("failed to list available locations")?
+        .into_iter()
+        .collect();
+    let hardware_ids = repository
+        .get_hardware_ids()
+        .await
+        .wrap_err("failed to get hardware IDs")?;
+    let mut problems = manifest.offline_problems(&available_locations, &hardware_ids);
+
+    let Some(timeout) = aliveness_timeout else {
+        return Ok(problems);
+    };
+    let states = query_states(manifest, timeout).await?;
+    for assignment in &manifest.robots {
+        let nao_number = assignment.nao_number.number;
+        let address = manifest.address(assignment)?;
+        let Some(state) = states.get(&IpAddr::V4(address.ip)) else {
+            problems.push(format!("NAO {nao_number} ({address}) is not alive"));
+            continue;
+        };
+        if state.hulks_os_version != OS_VERSION {
+            problems.push(format!(
+                "NAO {nao_number} runs HULKs-OS {} instead of {OS_VERSION}",
+                state.hulks_os_version
+            ));
+        }
+        if let Some(expected) = hardware_ids.get(&nao_number) {
+            if state.head_id.as_deref() != Some(expected.head_id.as_str()) {
+                problems.push(format!(
+                    "NAO {nao_number} reports head ID {:?} but hardware_ids.json expects {}",
+                    state.head_id, expected.head_id
+                ));
+            }
+            if state.body_id.as_deref() != Some(expected.body_id.as_str()) {
+                problems.push(format!(
+                    "NAO {nao_number} reports body ID {:?} but hardware_ids.json expects {}",
+                    state.body_id, expected.body_id
+                ));
+            }
+        }
+    }
+    Ok(problems)
+}
+
+async fn query_states(
+    manifest: &Manifest,
+    timeout: Duration,
+) -> Result<HashMap<IpAddr, AlivenessState>> {
+    let ips = manifest
+        .robots
+        .iter()
+        .map(|assignment| Ok(manifest.address(assignment)?.ip))
+        .collect::<Result<_>>()?;
+    Ok(query_aliveness(timeout, Some(ips))
+        .await
+        .wrap_err("failed to query aliveness")?
+        .into_iter()
+        .collect())
+}
+
+async fn diff(manifest: &Manifest, repository: &Repository, timeout: Duration) -> Result<()> {
+    let hardware_ids = repository
+        .get_hardware_ids()
+        .await
+        .wrap_err("failed to get hardware IDs")?;
+    let states = query_states(manifest, timeout).await?;
+    let hardware_ids = &hardware_ids;
+    let states = &states;
+    let differing_naos = &AtomicUsize::new(0);
+
+    ProgressIndicator::map_tasks(
+        manifest.robots.iter().copied(),
+        "Comparing deployed state...",
+        |assignment| async move {
+            let differences = differences(manifest, assignment, hardware_ids, states).await;
+            if differences.is_err() {
+                differing_naos.fetch_add(1, Ordering::Relaxed);
+            }
+            differences
+        },
+    )
+    .await;
+
+    let differing_naos = differing_naos.load(Ordering::Relaxed);
+    if differing_naos > 0 {
+        bail!("{differing_naos} NAO(s) differ from the manifest or could not be compared");
+    }
+    Ok(())
+}
+
+/// Fails with all differences of the NAO, the progress indicator shows them as error
+async fn differences(
+    manifest: &Manifest,
+    assignment: NaoNumberPlayerAssignment,
+    hardware_ids: &HashMap<u8, HardwareIds>,
+    states: &HashMap<IpAddr, AlivenessState>,
+) -> Result<String> {
+    let address = manifest.address(&assignment)?;
+    let state = states
+        .get(&IpAddr::V4(address.ip))
+        .ok_or_else(|| eyre!("{address} is not alive"))?;
+    let head_id = &hardware_ids
+        .get(&assignment.nao_number.number)
+        .ok_or_else(|| eyre!("no entry in hardware_ids.json"))?
+        .head_id;
+    let nao = Nao::new(address.ip);
+    let deployed_player_number = deployed_player_number(&nao, head_id).await?;
+    let provenance = read_provenance(&nao, DEPLOYMENT_DIRECTORY)
+        .await
+        .wrap_err("failed to get deployment")?;
+
+    let mut differences = Vec::new();
+    if deployed_player_number != Some(assignment.player_number) {
+        differences.push(format!(
+            "player number: deployed {}, manifest {}",
+            deployed_player_number
+                .map(|player_number| player_number.to_string())
+                .unwrap_or_else(|| "<DEFAULT>".to_string()),
+            assignment.player_number
+        ));
+    }
+    let network = manifest.network.to_string();
+    let deployed_network = state.network.as_deref().unwrap_or("None");
+    if deployed_network != network {
+        differences.push(format!(
+            "network: connected {deployed_network}, manifest {network}"
+        ));
+    }
+    match provenance {
+        Some(provenance) => {
+            if provenance.location.as_ref() != Some(&manifest.location) {
+                differences.push(format!(
+                    "location: deployed {}, manifest {}",
+                    provenance.location.as_deref().unwrap_or("<NOT_CONFIGURED>"),
+                    manifest.location
+                ));
+            }
+            if provenance.profile != manifest.profile {
+                differences.push(format!(
+                    "profile: deployed {}, manifest {}",
+                    provenance.profile, manifest.profile
+                ));
+            }
+        }
+        None => differences
+            .push("location and profile: unknown, deployment has no provenance".to_string()),
+    }
+    if !matches!(state.system_services.hulk, ServiceState::Active) {
+        differences.push(format!("HULK service: {}", state.system_services.hulk));
+    }
+    if differences.is_empty() {
+        return Ok(String::new());
+    }
+    bail!("{}", differences.join("\n"))
+}
+
+/// The player number is stored in the head parameters of all locations (see
+/// `Repository::set_player_number()`), `None` if it is not set there
+async fn deployed_player_number(nao: &Nao, head_id: &str) -> Result<Option<PlayerNumber>> {
+    let Some(contents) = nao
+        .read_file(&format!(
+            "{DEPLOYMENT_DIRECTORY}/etc/parameters/head.{head_id}.json"
+        ))
+        .await
+        .wrap_err("failed to read deployed head parameters")?
+    else {
+        return Ok(None);
+    };
+    let parameters: Value = from_str(&contents).wrap_err("failed to parse head parameters")?;
+    parameters
+        .get("player_number")
+        .map(|player_number| {
+            PlayerNumber::deserialize(player_number).wrap_err("failed to parse player number")
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn manifest_is_parsed_and_problems_are_found() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+                location = "smd"
+                network = "SPL_A"
+                jersey_color = "Blue"
+                robots = ["21:1", "22:2", "21:3", "99:2"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(manifest.network, Network::SplA);
+        assert_eq!(manifest.profile, "release");
+        assert_eq!(
+            manifest.address(&manifest.robots[0]).unwrap().ip,
+            Ipv4Addr::new(10, 1, 24, 21)
+        );
+
+        let available_locations = ["smd".to_string()].into();
+        let hardware_ids = [21, 22]
+            .into_iter()
+            .map(|nao_number| {
+                (
+                    nao_number,
+                    HardwareIds {
+                        body_id: String::new(),
+                        head_id: String::new(),
+                    },
+                )
+            })
+            .collect();
+        assert_eq!(
+            manifest.offline_problems(&available_locations, &hardware_ids),
+            [
+                "NAO 21 is listed multiple times",
+                "player number 2 is assigned multiple times",
+                "NAO 99 has no entry in hardware_ids.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_networks_are_rejected() {
+        let result = toml::from_str::<Manifest>(
+            r#"
+                location = "smd"
+                network = "SPL_Z"
+                robots = []
+            "#,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use serde_json::{from_str, Value};
+use tokio::fs::{read_dir, read_to_string};
+
+use hulk_manifest::collect_hulk_cyclers_in;
+use parameters::{
+    directory::read_layers,
+    json::{flatten, merge_json, replace_leaves},
+};
+use repository::Repository;
+use source_analyzer::{struct_hierarchy::StructHierarchy, structs::Structs};
+
+use crate::parsers::NaoNumberAtLocation;
+
+#[derive(Subcommand)]
+pub enum Arguments {
+    /// Show the effective parameters of a NAO and the file each value comes from
+    Show {
+        /// The NAO with an optional location e.g. 22 or 22@field_a (defaults to nao_location)
+        nao: NaoNumberAtLocation,
+        /// Only show parameters below this path e.g. ball_filter
+        path: Option<String>,
+        /// Profiles merged on top in this order
+        #[arg(long = "profile")]
+        profiles: Vec<String>,
+    },
+    /// Show parameters differing between two NAOs or locations
+    Diff {
+        /// The first NAO with an optional location e.g. 22 or 22@field_a
+        left: NaoNumberAtLocation,
+        /// The second NAO with an optional location e.g. 23 or 22@field_b
+        right: NaoNumberAtLocation,
+        /// Only compare parameters below this path e.g. ball_filter
+        path: Option<String>,
+        /// Profiles merged on top in this order
+        #[arg(long = "profile")]
+        profiles: Vec<String>,
+    },
+    /// List keys in parameter files which do not exist in the parameters of the nodes
+    Audit,
+}
+
+pub async fn parameters(arguments: Arguments, repository: &Repository) -> Result<()> {
+    match arguments {
+        Arguments::Show {
+            nao,
+            path,
+            profiles,
+        } => {
+            let parameters = effective_parameters(repository, &nao, &profiles).await?;
+            for (parameter_path, (value, source)) in below(&parameters, path.as_deref()) {
+                println!("{parameter_path} = {value}  ({source})");
+            }
+        }
+        Arguments::Diff {
+            left,
+            right,
+            path,
+            profiles,
+        } => {
+            let left_parameters = effective_parameters(repository, &left, &profiles).await?;
+            let right_parameters = effective_parameters(repository, &right, &profiles).await?;
+            let left_parameters: BTreeMap<_, _> =
+                below(&left_parameters, path.as_deref()).collect();
+            let right_parameters: BTreeMap<_, _> =
+                below(&right_parameters, path.as_deref()).collect();
+            let mut paths: Vec<_> = left_parameters
+                .keys()
+                .chain(right_parameters.keys())
+                .collect();
+            paths.sort();
+            paths.dedup();
+            for parameter_path in paths {
+                let left_value = left_parameters.get(parameter_path);
+                let right_value = right_parameters.get(parameter_path);
+                if left_value.map(|(value, _)| value) == right_value.map(|(value, _)| value) {
+                    continue;
+                }
+                println!("{parameter_path}");
+                println!("  {left}: {}", describe(left_value));
+                println!("  {right}: {}", describe(right_value));
+            }
+        }
+        Arguments::Audit => audit(repository).await?,
+    }
+    Ok(())
+}
+
+/// Merged values by their paths together with the file they come from, relative to the
+/// parameters directory
+pub async fn effective_parameters(
+    repository: &Repository,
+    nao: &NaoNumberAtLocation,
+    profiles: &[String],
+) -> Result<BTreeMap<String, (Value, String)>> {
+    let hardware_ids = repository
+        .get_hardware_ids()
+        .await
+        .wrap_err("failed to get hardware IDs")?;
+    let ids = hardware_ids
+        .get(&nao.nao_number.number)
+        .ok_or_else(|| eyre!("no hardware IDs for NAO {}", nao.nao_number))?;
+    let parameters_root = repository.parameters_root();
+    if let Some(location) = &nao.location {
+        let locations = repository
+            .list_available_locations()
+            .await
+            .wrap_err("failed to list available locations")?;
+        if !locations.contains(location) {
+            bail!("unknown location {location}, available locations: {locations:?}");
+        }
+    }
+
+    let layers = read_layers(
+        &parameters_root,
+        nao.location.as_deref(),
+        &ids.body_id,
+        &ids.head_id,
+        profiles,
+    )
+    .await
+    .wrap_err_with(|| format!("failed to read parameters of {nao}"))?;
+    let mut parameters = Value::Null;
+    let mut sources = Value::Null;
+    for layer in layers {
+        let source = layer
+            .path
+            .strip_prefix(&parameters_root)
+            .unwrap_or(&layer.path)
+            .display()
+            .to_string();
+        merge_json(&mut parameters, &layer.parameters);
+        merge_json(
+            &mut sources,
+            &replace_leaves(&layer.parameters, &Value::String(source)),
+        );
+    }
+
+    let sources = flatten(&sources);
+    Ok(flatten(&parameters)
+        .into_iter()
+        .map(|(path, value)| {
+            let source = match sources.get(&path) {
+                Some(Value::String(source)) => source.clone(),
+                _ => "-".to_string(),
+            };
+            (path, (value, source))
+        })
+        .collect())
+}
+
+fn below<'a>(
+    parameters: &'a BTreeMap<String, (Value, String)>,
+    prefix: Option<&'a str>,
+) -> impl Iterator<Item = (&'a String, &'a (Value, String))> {
+    parameters.iter().filter(move |(path, _)| {
+        prefix.is_none_or(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    })
+}
+
+fn describe(parameter: Option<&&(Value, String)>) -> String {
+    match parameter {
+        Some((value, source)) => format!("{value}  ({source})"),
+        None => "<missing>".to_string(),
+    }
+}
+
+async fn audit(repository: &Repository) -> Result<()> {
+    let cyclers = collect_hulk_cyclers_in(repository.crates_directory())
+        .wrap_err("failed to collect cyclers")?;
+    let structs = Structs::try_from_cyclers(&cyclers).wrap_err("failed to collect structs")?;
+    let parameters_root = repository.parameters_root();
+
+    let mut number_of_unknown_keys = 0;
+    for file_path in parameter_files(&parameters_root).await? {
+        let contents = read_to_string(&file_path)
+            .await
+            .wrap_err_with(|| format!("failed to read {}", file_path.display()))?;
+        let value: Value = from_str(&contents)
+            .wrap_err_with(|| format!("failed to parse {}", file_path.display()))?;
+        let mut unknown_keys = Vec::new();
+        collect_unknown_keys(&structs.parameters, &value, "", &mut unknown_keys);
+        if unknown_keys.is_empty() {
+            continue;
+        }
+        println!(
+            "{}",
+            file_path
+                .strip_prefix(&parameters_root)
+                .unwrap_or(&file_path)
+                .display()
+        );
+        for key in &unknown_keys {
+            println!("  {key}");
+        }
+        number_of_unknown_keys += unknown_keys.len();
+    }
+
+    if number_of_unknown_keys > 0 {
+        bail!("found {number_of_unknown_keys} unknown parameter keys");
+    }
+    println!("All parameter keys exist in the parameters of the nodes");
+    Ok(())
+}
+
+/// Parameter files in the parameters directory, its locations and profiles, except hardware
+/// parameters which are not node parameters
+async fn parameter_files(parameters_root: &Path) -> Result<Vec<PathBuf>> {
+    let mut directories = vec![parameters_root.to_path_buf()];
+    let mut files = Vec::new();
+    while let Some(directory) = directories.pop() {
+        let mut entries = read_dir(&directory)
+            .await
+            .wrap_err_with(|| format!("failed to read {}", directory.display()))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .wrap_err_with(|| format!("failed to read {}", directory.display()))?
+        {
+            let path = entry.path();
+            // location symlinks point to directories which are visited anyway
+            if path.is_symlink() {
+                continue;
+            }
+            if path.is_dir() {
+                directories.push(path);
+                continue;
+            }
+            let is_node_parameter_file = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(".json") && !name.starts_with("hardware"));
+            if is_node_parameter_file {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_unknown_keys(
+    hierarchy: &StructHierarchy,
+    value: &Value,
+    prefix: &str,
+    unknown_keys: &mut Vec<String>,
+) {
+    match (hierarchy, value) {
+        (StructHierarchy::Struct { fields }, Value::Object(object)) => {
+            for (key, value) in object {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                match fields.get(key) {
+                    Some(field) => collect_unknown_keys(field, value, &path, unknown_keys),
+                    None => unknown_keys.push(path),
+                }
+            }
+        }
+        (StructHierarchy::Optional { child }, value) if !value.is_null() => {
+            collect_unknown_keys(child, value, prefix, unknown_keys)
+        }
+        // fields of data types are checked by deserialization
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use syn::parse_str;
+
+    use super::*;
+
+    fn field() -> StructHierarchy {
+        StructHierarchy::Field {
+            data_type: parse_str("f32").unwrap(),
+        }
+    }
+
+    fn structure(
+        fields: impl IntoIterator<Item = (&'static str, StructHierarchy)>,
+    ) -> StructHierarchy {
+        StructHierarchy::Struct {
+            fields: fields
+                .into_iter()
+                .map(|(name, field)| (name.to_string(), field))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn keys_missing_in_the_node_parameters_are_unknown() {
+        let hierarchy = structure([
+            (
+                "ball_filter",
+                structure([("noise", field()), ("hypothesis_timeout", field())]),
+            ),
+            (
+                "kick_target",
+                StructHierarchy::Optional {
+                    child: Box::new(structure([("distance", field())])),
+                },
+            ),
+            ("field_dimensions", field()),
+        ]);
+        let parameters = json!({
+            "ball_filter": {"noise": 0.1, "hypothesis_timeot": 1.0},
+            "kick_target": {"distance": 1.0, "angle": 0.5},
+            "field_dimensions": {"length": 9.0},
+            "removed_node": {"gain": 2.0},
+        });
+
+        let mut unknown_keys = Vec::new();
+        collect_unknown_keys(&hierarchy, &parameters, "", &mut unknown_keys);
+
+        assert_eq!(
+            unknown_keys,
+            [
+                "ball_filter.hypothesis_timeot",
+                "kick_target.angle",
+                "removed_node"
+            ]
+        );
+    }
+
+    #[test]
+    fn unset_optional_parameters_have_no_unknown_keys() {
+        let hierarchy = structure([(
+            "kick_target",
+            StructHierarchy::Optional {
+                child: Box::new(structure([("distance", field())])),
+            },
+        )]);
+
+        let mut unknown_keys = Vec::new();
+        collect_unknown_keys(
+            &hierarchy,
+            &json!({"kick_target": null}),
+            "",
+            &mut unknown_keys,
+        );
+
+        assert!(unknown_keys.is_empty());
+    }
+}
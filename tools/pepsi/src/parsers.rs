@@ -9,6 +9,7 @@ use color_eyre::{
     Report, Result,
 };
 use regex::Regex;
+use serde::Deserialize;
 
 use nao::{Network, SystemctlAction};
 use spl_network_messages::PlayerNumber;
@@ -93,9 +94,11 @@ impl Display for NaoAddress {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Connection {
     Wireless,
+    #[default]
     Wired,
 }
 
@@ -190,6 +193,38 @@ impl Display for NaoNumberPlayerAssignment {
     }
 }
 
+/// A NAO with an optional location overriding the configured one e.g. 22 or 22@field_a
+#[derive(Clone, Debug)]
+pub struct NaoNumberAtLocation {
+    pub nao_number: NaoNumber,
+    pub location: Option<String>,
+}
+
+impl FromStr for NaoNumberAtLocation {
+    type Err = Report;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let (nao_number, location) = match input.split_once('@') {
+            Some((_, "")) => bail!("missing location after `@`"),
+            Some((nao_number, location)) => (nao_number, Some(location.to_string())),
+            None => (input, None),
+        };
+        Ok(Self {
+            nao_number: nao_number.parse()?,
+            location,
+        })
+    }
+}
+
+impl Display for NaoNumberAtLocation {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(formatter, "{}@{location}", self.nao_number),
+            None => self.nao_number.fmt(formatter),
+        }
+    }
+}
+
 fn parse_assignment(input: &str) -> Result<(&str, PlayerNumber)> {
     let (prefix, player_number) = input.rsplit_once(':').ok_or_else(|| eyre!("missing `:`"))?;
     let player_number = match player_number {
@@ -218,3 +253,24 @@ impl TryFrom<NaoAddressPlayerAssignment> for NaoNumberPlayerAssignment {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nao_numbers_are_parsed_with_optional_locations() {
+        let nao: NaoNumberAtLocation = "22".parse().unwrap();
+        assert_eq!(nao.nao_number.number, 22);
+        assert_eq!(nao.location, None);
+        assert_eq!(nao.to_string(), "22");
+
+        let nao: NaoNumberAtLocation = "22@field_a".parse().unwrap();
+        assert_eq!(nao.nao_number.number, 22);
+        assert_eq!(nao.location.as_deref(), Some("field_a"));
+        assert_eq!(nao.to_string(), "22@field_a");
+
+        assert!("22@".parse::<NaoNumberAtLocation>().is_err());
+        assert!("field_a@22".parse::<NaoNumberAtLocation>().is_err());
+    }
+}
@@ -23,7 +23,8 @@ pub struct Arguments {
             .map(|s| parse_network(&s).unwrap()))
     ]
     pub network: Network,
-    /// Directory where to store the downloaded logs (will be created if not existing)
+    /// Directory where to store the downloaded logs (will be created if not existing), summarize
+    /// them with `cargo run --package game_report -- <LOG_DIRECTORY>`
     pub log_directory: PathBuf,
     /// The NAOs to execute that command on e.g. 20w or 10.1.24.22
     #[arg(required = true)]
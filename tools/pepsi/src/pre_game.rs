@@ -37,13 +37,13 @@ pub struct Arguments {
     #[arg(long)]
     pub skip_os_check: bool,
     /// The location to use for parameters
-    location: String,
+    pub location: String,
     /// The network to connect the wireless device to (None disconnects from anything)
     #[arg(
         value_parser = PossibleValuesParser::new(NETWORK_POSSIBLE_VALUES)
             .map(|s| parse_network(&s).unwrap()))
     ]
-    network: Network,
+    pub network: Network,
     /// The NAOs to upload to with player number assignments e.g. 20w:2 or 10.1.24.22:5 (player numbers start from 1)
     #[arg(required = true)]
     pub assignments: Vec<NaoAddressPlayerAssignment>,
@@ -87,6 +87,7 @@ pub async fn pre_game(arguments: Arguments, repository: &Repository) -> Result<(
             no_restart: arguments.no_restart,
             no_clean: arguments.no_clean,
             no_communication: !arguments.with_communication,
+            no_backup: false,
             skip_os_check: arguments.skip_os_check,
             naos: naos.clone(),
             remote: arguments.remote,
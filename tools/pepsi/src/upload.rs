@@ -37,6 +37,9 @@ pub struct Arguments {
     /// Do not enable communication
     #[arg(long)]
     pub no_communication: bool,
+    /// Do not keep the previous deployment for `pepsi deployment rollback`
+    #[arg(long)]
+    pub no_backup: bool,
     /// Skip the OS version check
     #[arg(long)]
     pub skip_os_check: bool,
@@ -73,6 +76,13 @@ async fn upload_with_progress(
         .await
         .wrap_err_with(|| format!("failed to stop HULK service on {nao_address}"))?;
 
+    if !arguments.no_backup {
+        progress.set_message("Keeping previous deployment...");
+        nao.backup_deployment()
+            .await
+            .wrap_err_with(|| format!("failed to keep previous deployment on {nao_address}"))?;
+    }
+
     progress.set_message("Uploading...");
     nao.upload(hulk_directory, !arguments.no_clean)
         .await
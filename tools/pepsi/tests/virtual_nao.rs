@@ -0,0 +1,247 @@
+// each virtual NAO listens on its own loopback address, only Linux routes 127.0.0.0/8 without
+// configuring aliases
+#![cfg(target_os = "linux")]
+// transfers are executed by rsync on both ends like on real NAOs, tests transferring files are
+// ignored by default and run with `cargo test -- --ignored` where rsync is installed
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    net::Ipv4Addr,
+    path::Path,
+    process::Output,
+};
+
+use nao::{Nao, DEPLOYMENT_DIRECTORY, VIRTUAL_NAO_ROOT_VARIABLE};
+use repository::provenance::{parameter_checksums, Provenance, PROVENANCE_FILE_NAME};
+use serde_json::Value;
+use tempfile::tempdir;
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+use virtual_nao::VirtualNao;
+
+/// Progress of tasks is only shown on terminals, the resulting state of the virtual NAOs is
+/// checked instead
+async fn pepsi(root: &Path, arguments: &[&str]) -> String {
+    let Output { stdout, .. } = Command::new(env!("CARGO_BIN_EXE_pepsi"))
+        .env(VIRTUAL_NAO_ROOT_VARIABLE, root)
+        .args(arguments)
+        .output()
+        .await
+        .unwrap();
+    String::from_utf8(stdout).unwrap()
+}
+
+#[tokio::test]
+async fn hulk_service_is_stopped_and_started() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 2)).unwrap();
+
+    pepsi(root.path(), &["hulk", "stop", "127.0.0.2"]).await;
+    assert_eq!(nao.unit_state("hulk").as_deref(), Some("inactive"));
+
+    pepsi(root.path(), &["hulk", "start", "127.0.0.2"]).await;
+    assert_eq!(nao.unit_state("hulk").as_deref(), Some("active"));
+}
+
+#[tokio::test]
+async fn wireless_network_is_set_and_shown() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 3)).unwrap();
+
+    pepsi(root.path(), &["wireless", "set", "SPL_B", "127.0.0.3"]).await;
+    assert_eq!(nao.network().as_deref(), Some("SPL_B"));
+    assert!(nao.auto_connects("SPL_B"));
+    assert!(!nao.auto_connects("SPL_A"));
+
+    pepsi(root.path(), &["wireless", "set", "None", "127.0.0.3"]).await;
+    assert_eq!(nao.network(), None);
+}
+
+#[tokio::test]
+async fn power_off_and_reboot_are_requested() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 4)).unwrap();
+
+    pepsi(root.path(), &["reboot", "127.0.0.4"]).await;
+    pepsi(root.path(), &["poweroff", "127.0.0.4"]).await;
+
+    assert_eq!(nao.power_requests(), ["reboot", "poweroff"]);
+}
+
+#[tokio::test]
+async fn aliveness_is_reported() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 6)).unwrap();
+    let keep_running = CancellationToken::new();
+    let responder = nao.respond_to_beacons(keep_running.clone());
+    let query = async {
+        let output = pepsi(root.path(), &["aliveness", "--json", "127.0.0.6"]).await;
+        keep_running.cancel();
+        output
+    };
+
+    let (responder, output) = tokio::join!(responder, query);
+
+    responder.unwrap();
+    let states: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(states["127.0.0.6"]["hostname"], "virtual-6");
+    assert_eq!(states["127.0.0.6"]["system_services"]["hulk"], "Active");
+}
+
+#[tokio::test]
+#[ignore = "requires rsync"]
+async fn logs_are_downloaded_and_deleted() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 7)).unwrap();
+    write(
+        nao.home().join("hulk/logs/hulk.out"),
+        "Hello from the NAO\n",
+    )
+    .unwrap();
+    let download = tempdir().unwrap();
+
+    pepsi(
+        root.path(),
+        &[
+            "logs",
+            "download",
+            download.path().to_str().unwrap(),
+            "127.0.0.7",
+        ],
+    )
+    .await;
+    let downloaded = download.path().join("127.0.0.7");
+    assert_eq!(
+        read_to_string(downloaded.join("hulk.out")).unwrap(),
+        "Hello from the NAO\n"
+    );
+    assert!(read_to_string(downloaded.join("kernel.log"))
+        .unwrap()
+        .contains("virtual NAO"));
+
+    pepsi(root.path(), &["logs", "delete", "127.0.0.7"]).await;
+    assert!(!nao.home().join("hulk/logs/hulk.out").exists());
+}
+
+#[tokio::test]
+#[ignore = "requires rsync"]
+async fn deployment_is_rolled_back() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 8)).unwrap();
+    create_dir_all(nao.home().join("hulk.previous")).unwrap();
+    write(nao.home().join("hulk/version"), "new").unwrap();
+    write(nao.home().join("hulk.previous/version"), "old").unwrap();
+
+    pepsi(root.path(), &["deployment", "rollback", "127.0.0.8"]).await;
+
+    assert_eq!(
+        read_to_string(nao.home().join("hulk/version")).unwrap(),
+        "old"
+    );
+    assert_eq!(
+        read_to_string(nao.home().join("hulk.previous/version")).unwrap(),
+        "new"
+    );
+    assert_eq!(nao.unit_state("hulk").as_deref(), Some("active"));
+}
+
+#[tokio::test]
+#[ignore = "requires rsync"]
+async fn deployment_is_backed_up_without_logs() {
+    let root = tempdir().unwrap();
+    let virtual_nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 9)).unwrap();
+    write(virtual_nao.home().join("hulk/version"), "current").unwrap();
+    write(virtual_nao.home().join("hulk/logs/hulk.out"), "log").unwrap();
+    let nao = Nao::new_virtual(virtual_nao.host(), root.path().to_path_buf());
+
+    nao.backup_deployment().await.unwrap();
+
+    assert_eq!(
+        read_to_string(virtual_nao.home().join("hulk.previous/version")).unwrap(),
+        "current"
+    );
+    assert!(!virtual_nao.home().join("hulk.previous/logs").exists());
+    assert!(virtual_nao.home().join("hulk/logs/hulk.out").exists());
+}
+
+#[tokio::test]
+async fn provenance_is_read_from_the_deployment() {
+    let root = tempdir().unwrap();
+    let virtual_nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 10)).unwrap();
+    let parameters = tempdir().unwrap();
+    write(parameters.path().join("default.json"), "{}").unwrap();
+    write(parameters.path().join("hardware.json"), "{}").unwrap();
+    let provenance = Provenance {
+        git_revision: Some("0123456789abcdef".to_string()),
+        dirty_diff_hash: None,
+        profile: "release".to_string(),
+        location: Some("field_a".to_string()),
+        parameter_checksums: parameter_checksums(parameters.path()).await.unwrap(),
+        uploaded_at: "2023-07-04T12:00:00+00:00".to_string(),
+        uploaded_by: Some("nao".to_string()),
+    };
+    write(
+        virtual_nao
+            .home()
+            .join(DEPLOYMENT_DIRECTORY)
+            .join(PROVENANCE_FILE_NAME),
+        serde_json::to_string(&provenance).unwrap(),
+    )
+    .unwrap();
+    let nao = Nao::new_virtual(virtual_nao.host(), root.path().to_path_buf());
+
+    let deployed = nao
+        .read_file(&format!("{DEPLOYMENT_DIRECTORY}/{PROVENANCE_FILE_NAME}"))
+        .await
+        .unwrap()
+        .unwrap();
+    let deployed: Provenance = serde_json::from_str(&deployed).unwrap();
+
+    assert_eq!(deployed, provenance);
+    assert!(deployed
+        .differing_parameters(&parameter_checksums(parameters.path()).await.unwrap())
+        .is_empty());
+    assert_eq!(
+        nao.read_file("hulk.previous/provenance.json")
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn structured_logs_are_queried_from_the_nao() {
+    let root = tempdir().unwrap();
+    let nao = VirtualNao::create_in(root.path(), Ipv4Addr::new(127, 0, 0, 11)).unwrap();
+    write(
+        nao.home().join("hulk/logs/hulk.1688652000000.jsonl"),
+        concat!(
+            r#"{"timestamp":"2023-07-06T14:00:00.000Z","level":"WARN","target":"control","cycler":"Control","node":"BallFilter","cycle":1,"game_state":null,"message":"first"}"#,
+            "\n",
+            r#"{"timestamp":"2023-07-06T14:00:01.000Z","level":"DEBUG","target":"control","cycler":"Control","node":"BallFilter","cycle":2,"game_state":null,"message":"filtered"}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+    write(
+        nao.home().join("hulk/logs/hulk.1688652002000.jsonl"),
+        concat!(
+            r#"{"timestamp":"2023-07-06T14:00:02.000Z","level":"ERROR","target":"control","cycler":"Control","node":"BallFilter","cycle":3,"game_state":null,"message":"second"}"#,
+            "\n",
+            r#"{"timestamp":"2023-07-06T14:00:03"#,
+        ),
+    )
+    .unwrap();
+
+    let output = pepsi(
+        root.path(),
+        &["logs", "query", "--level", "info", "--json", "127.0.0.11"],
+    )
+    .await;
+
+    let messages: Vec<_> = output
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["message"].clone())
+        .collect();
+    assert_eq!(messages, ["first", "second"]);
+}
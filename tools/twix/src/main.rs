@@ -1,4 +1,5 @@
 use std::{
+    env,
     fmt::{self, Display, Formatter},
     str::FromStr,
     sync::Arc,
@@ -10,11 +11,16 @@ use color_eyre::{
     Result,
 };
 
-use communication::client::ConnectionStatus;
+use communication::{
+    client::ConnectionStatus,
+    messages::{Compression, TransportOptions},
+    transport::MAXIMUM_ZSTD_LEVEL,
+};
 use completion_edit::CompletionEdit;
 use eframe::{
     egui::{
-        CentralPanel, Context, Id, Key, Layout, Modifiers, TopBottomPanel, Ui, Widget, WidgetText,
+        CentralPanel, Context, Id, Key, Layout, Modifiers, Slider, TextEdit, TopBottomPanel, Ui,
+        Widget, WidgetText,
     },
     emath::Align,
     epaint::Color32,
@@ -46,6 +52,11 @@ mod twix_painter;
 mod value_buffer;
 pub mod visuals;
 
+const DEFAULT_TRANSPORT_OPTIONS: TransportOptions = TransportOptions {
+    compression: Compression::Zstd { level: 3 },
+    delta_encoding: true,
+};
+
 fn setup_logger() -> Result<(), InitError> {
     Dispatch::new()
         .format(|out, message, record| {
@@ -95,6 +106,8 @@ struct TwixApp {
     connection_status: ConnectionStatus,
     connection_receiver: mpsc::Receiver<ConnectionStatus>,
     visual: Visuals,
+    transport_options: TransportOptions,
+    token: String,
 }
 
 impl TwixApp {
@@ -113,7 +126,21 @@ impl TwixApp {
             })
             .unwrap_or(false);
 
-        let nao = Arc::new(Nao::new(ip_address.clone(), connection_intent));
+        let transport_options = creation_context
+            .storage
+            .and_then(|storage| storage.get_string("transport_options"))
+            .and_then(|string| from_str(&string).ok())
+            .unwrap_or(DEFAULT_TRANSPORT_OPTIONS);
+
+        // tokens are secrets and therefore not persisted in the storage
+        let token = env::var("HULK_COMMUNICATION_TOKEN").unwrap_or_default();
+
+        let nao = Arc::new(Nao::new(
+            ip_address.clone(),
+            connection_intent,
+            transport_options,
+            (!token.is_empty()).then(|| token.clone()),
+        ));
 
         let tree: Option<Tree<Value>> = creation_context
             .storage
@@ -157,6 +184,8 @@ impl TwixApp {
             connection_status,
             connection_receiver,
             visual,
+            transport_options,
+            token,
         }
     }
 }
@@ -244,6 +273,12 @@ impl App for TwixApp {
                                 }
                             })
                         });
+                        ui.menu_button("Transport", |ui| {
+                            self.transport_options_ui(ui);
+                        });
+                        ui.menu_button("Token", |ui| {
+                            self.token_ui(ui);
+                        });
                     })
                 });
             })
@@ -284,10 +319,47 @@ impl App for TwixApp {
             .to_string(),
         );
         storage.set_string("style", self.visual.to_string());
+        storage.set_string(
+            "transport_options",
+            to_string(&self.transport_options).unwrap(),
+        );
     }
 }
 
 impl TwixApp {
+    fn transport_options_ui(&mut self, ui: &mut Ui) {
+        let mut is_compressed =
+            matches!(self.transport_options.compression, Compression::Zstd { .. });
+        if ui
+            .checkbox(&mut is_compressed, "Zstd compression")
+            .changed()
+        {
+            self.transport_options.compression = if is_compressed {
+                DEFAULT_TRANSPORT_OPTIONS.compression
+            } else {
+                Compression::None
+            };
+        }
+        if let Compression::Zstd { level } = &mut self.transport_options.compression {
+            ui.add(Slider::new(level, 1..=MAXIMUM_ZSTD_LEVEL).text("Level"));
+        }
+        ui.checkbox(&mut self.transport_options.delta_encoding, "Delta encoding");
+        ui.label("Applied when Twix is restarted");
+    }
+
+    fn token_ui(&mut self, ui: &mut Ui) {
+        let token_input = ui.add(
+            TextEdit::singleline(&mut self.token)
+                .password(true)
+                .hint_text("HULK_COMMUNICATION_TOKEN"),
+        );
+        if token_input.changed() {
+            self.nao
+                .set_token((!self.token.is_empty()).then(|| self.token.clone()));
+        }
+        ui.label("Sent when connecting the next time");
+    }
+
     fn active_panel(&mut self) -> Option<&mut SelectablePanel> {
         let (_viewport, tab) = self.tree.find_active_focused()?;
         Some(&mut tab.panel)
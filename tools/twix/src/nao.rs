@@ -1,15 +1,26 @@
-use std::{collections::BTreeSet, sync::Mutex};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+    time::Duration,
+};
 
 use communication::{
     client::{Communication, ConnectionStatus, CyclerOutput},
-    messages::{Fields, Path},
+    messages::{Fields, Path, SubscriptionOptions, TransportOptions},
 };
 
 use serde_json::Value;
-use tokio::runtime::{Builder, Runtime};
+use serialize_hierarchy::FieldMetadata;
+use tokio::{
+    runtime::{Builder, Runtime},
+    sync::oneshot,
+    time::sleep,
+};
 
 use crate::{image_buffer::ImageBuffer, value_buffer::ValueBuffer};
 
+const SCHEMA_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct Nao {
     communication: Communication,
     runtime: Runtime,
@@ -17,15 +28,25 @@ pub struct Nao {
 }
 
 impl Nao {
-    pub fn new(address: Option<String>, connect: bool) -> Self {
+    pub fn new(
+        address: Option<String>,
+        connect: bool,
+        transport_options: TransportOptions,
+        token: Option<String>,
+    ) -> Self {
         let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
         let _guard = runtime.enter();
-        let communication = Communication::new(
+        let communication = Communication::new_with_transport_options(
             address
                 .as_ref()
                 .map(|ip_address| ip_address_to_communication_url(ip_address)),
-            connect,
+            false,
+            transport_options,
         );
+        runtime.block_on(async {
+            communication.set_token(token).await;
+            communication.set_connect(connect).await;
+        });
         Self {
             communication,
             runtime,
@@ -38,6 +59,11 @@ impl Nao {
             .block_on(self.communication.set_connect(connect))
     }
 
+    /// The token is sent when connecting the next time
+    pub fn set_token(&self, token: Option<String>) {
+        self.runtime.block_on(self.communication.set_token(token))
+    }
+
     pub fn set_address(&self, address: &str) {
         {
             let mut current_address = self.address.lock().unwrap();
@@ -50,13 +76,25 @@ impl Nao {
     }
 
     pub fn subscribe_output(&self, output: CyclerOutput) -> ValueBuffer {
+        self.subscribe_output_with_options(output, SubscriptionOptions::default())
+    }
+
+    pub fn subscribe_output_with_options(
+        &self,
+        output: CyclerOutput,
+        options: SubscriptionOptions,
+    ) -> ValueBuffer {
         let _guard = self.runtime.enter();
-        ValueBuffer::output(self.communication.clone(), output)
+        ValueBuffer::output(self.communication.clone(), output, options)
     }
 
-    pub fn subscribe_image(&self, output: CyclerOutput) -> ImageBuffer {
+    pub fn subscribe_image(
+        &self,
+        output: CyclerOutput,
+        options: SubscriptionOptions,
+    ) -> ImageBuffer {
         let _guard = self.runtime.enter();
-        ImageBuffer::new(self.communication.clone(), output)
+        ImageBuffer::new(self.communication.clone(), output, options)
     }
 
     pub fn subscribe_parameter(&self, path: &str) -> ValueBuffer {
@@ -84,6 +122,22 @@ impl Nao {
             .block_on(self.communication.get_parameter_fields())
     }
 
+    /// The schema is delivered through the receiver once the server provided it
+    pub fn request_parameter_schema(&self) -> oneshot::Receiver<BTreeMap<Path, FieldMetadata>> {
+        let (schema_sender, schema_receiver) = oneshot::channel();
+        let communication = self.communication.clone();
+        self.runtime.spawn(async move {
+            while !schema_sender.is_closed() {
+                if let Some(schema) = communication.get_parameter_schema().await {
+                    let _ = schema_sender.send(schema);
+                    return;
+                }
+                sleep(SCHEMA_POLL_INTERVAL).await;
+            }
+        });
+        schema_receiver
+    }
+
     pub fn update_parameter_value(&self, path: &str, value: Value) {
         self.runtime
             .block_on(self.communication.update_parameter_value(path, value));
@@ -1,9 +1,12 @@
 use std::{str::FromStr, sync::Arc};
 
 use color_eyre::{eyre::eyre, Result};
-use communication::client::{Cycler, CyclerOutput, Output};
+use communication::{
+    client::{Cycler, CyclerOutput, Output},
+    messages::SubscriptionOptions,
+};
 use eframe::{
-    egui::{ComboBox, Response, TextureOptions, Ui, Widget},
+    egui::{ComboBox, DragValue, Response, TextureOptions, Ui, Widget},
     emath::Rect,
 };
 use egui_extras::RetainedImage;
@@ -50,6 +53,7 @@ pub struct ImagePanel {
     cycler_selector: VisionCyclerSelector,
     overlays: Overlays,
     image_kind: ImageKind,
+    maximum_rate: f32,
 }
 
 impl Panel for ImagePanel {
@@ -76,11 +80,15 @@ impl Panel for ImagePanel {
             .and_then(|value| value.get("image_kind"))
             .and_then(|value| from_value(value.clone()).ok())
             .unwrap_or(ImageKind::YCbCr422);
+        let maximum_rate = value
+            .and_then(|value| value.get("maximum_rate"))
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as f32;
         let output = CyclerOutput {
             cycler,
             output: image_kind.as_output(),
         };
-        let image_buffer = nao.subscribe_image(output);
+        let image_buffer = nao.subscribe_image(output, subscription_options(maximum_rate));
         let cycler_selector = VisionCyclerSelector::new(cycler);
         let overlays = Overlays::new(
             nao.clone(),
@@ -93,6 +101,7 @@ impl Panel for ImagePanel {
             cycler_selector,
             overlays,
             image_kind,
+            maximum_rate,
         }
     }
 
@@ -105,6 +114,7 @@ impl Panel for ImagePanel {
             "cycler": cycler.to_string(),
             "overlays": overlays,
             "image_kind": image_kind,
+            "maximum_rate": self.maximum_rate,
         })
     }
 }
@@ -117,7 +127,9 @@ impl Widget for &mut ImagePanel {
                     cycler: self.cycler_selector.selected_cycler(),
                     output: self.image_kind.as_output(),
                 };
-                self.image_buffer = self.nao.subscribe_image(output);
+                self.image_buffer = self
+                    .nao
+                    .subscribe_image(output, self.subscription_options());
                 self.overlays
                     .update_cycler(self.cycler_selector.selected_cycler());
             }
@@ -143,10 +155,29 @@ impl Widget for &mut ImagePanel {
                     cycler: self.cycler_selector.selected_cycler(),
                     output: self.image_kind.as_output(),
                 };
-                self.image_buffer = self.nao.subscribe_image(output);
+                self.image_buffer = self
+                    .nao
+                    .subscribe_image(output, self.subscription_options());
                 self.overlays
                     .update_cycler(self.cycler_selector.selected_cycler());
             }
+            if ui
+                .add(
+                    DragValue::new(&mut self.maximum_rate)
+                        .clamp_range(0.0..=100.0)
+                        .suffix(" Hz"),
+                )
+                .on_hover_text("Maximum image rate, 0 means every image")
+                .changed()
+            {
+                let output = CyclerOutput {
+                    cycler: self.cycler_selector.selected_cycler(),
+                    output: self.image_kind.as_output(),
+                };
+                self.image_buffer = self
+                    .nao
+                    .subscribe_image(output, self.subscription_options());
+            }
             self.overlays
                 .combo_box(ui, self.cycler_selector.selected_cycler());
         });
@@ -159,6 +190,10 @@ impl Widget for &mut ImagePanel {
 }
 
 impl ImagePanel {
+    fn subscription_options(&self) -> SubscriptionOptions {
+        subscription_options(self.maximum_rate)
+    }
+
     fn show_image(&self, ui: &mut Ui) -> Result<Response> {
         let image_data = self
             .image_buffer
@@ -184,3 +219,7 @@ impl ImagePanel {
         Ok(image_response)
     }
 }
+
+fn subscription_options(maximum_rate: f32) -> SubscriptionOptions {
+    SubscriptionOptions::default().with_maximum_rate(maximum_rate)
+}
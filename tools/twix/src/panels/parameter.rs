@@ -1,14 +1,15 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use crate::{
     completion_edit::CompletionEdit, nao::Nao, panel::Panel,
     repository_parameters::RepositoryParameters, value_buffer::ValueBuffer,
 };
 use color_eyre::{eyre::Context, Result};
-use eframe::egui::{Response, ScrollArea, TextEdit, Ui, Widget};
+use eframe::egui::{ComboBox, Response, ScrollArea, Slider, TextEdit, Ui, Widget};
 use log::error;
 use serde_json::{json, Value};
-use tokio::sync::mpsc;
+use serialize_hierarchy::FieldMetadata;
+use tokio::sync::{mpsc, oneshot};
 
 pub struct ParameterPanel {
     nao: Arc<Nao>,
@@ -16,6 +17,10 @@ pub struct ParameterPanel {
     repository_parameters: Result<RepositoryParameters>,
     value_buffer: Option<ValueBuffer>,
     parameter_value: String,
+    schema: Option<BTreeMap<String, FieldMetadata>>,
+    schema_receiver: oneshot::Receiver<BTreeMap<String, FieldMetadata>>,
+    /// Value of a widget which is still being dragged and not sent yet
+    pending_value: Option<Value>,
     update_notify_sender: mpsc::Sender<()>,
     update_notify_receiver: mpsc::Receiver<()>,
 }
@@ -45,6 +50,7 @@ impl Panel for ParameterPanel {
 
         let (update_notify_sender, update_notify_receiver) = mpsc::channel(1);
         let value_buffer = subscribe(nao.clone(), &path, update_notify_sender.clone());
+        let schema_receiver = nao.request_parameter_schema();
 
         Self {
             nao,
@@ -52,6 +58,9 @@ impl Panel for ParameterPanel {
             repository_parameters: RepositoryParameters::try_new(),
             value_buffer,
             parameter_value: String::new(),
+            schema: None,
+            schema_receiver,
+            pending_value: None,
             update_notify_sender,
             update_notify_receiver,
         }
@@ -70,6 +79,7 @@ impl Widget for &mut ParameterPanel {
                 let path_edit =
                     CompletionEdit::parameters(&mut self.path, self.nao.as_ref()).ui(ui);
                 if path_edit.changed() {
+                    self.pending_value = None;
                     self.value_buffer = subscribe(
                         self.nao.clone(),
                         &self.path,
@@ -109,12 +119,28 @@ impl Widget for &mut ParameterPanel {
                 }
             });
 
+            if self.schema.is_none() {
+                self.schema = self.schema_receiver.try_recv().ok();
+            }
+            let metadata = self
+                .schema
+                .as_ref()
+                .and_then(|schema| schema.get(&self.path));
+
             if let Some(buffer) = &self.value_buffer {
                 match buffer.get_latest() {
                     Ok(value) => {
                         if self.update_notify_receiver.try_recv().is_ok() {
                             self.parameter_value = serde_json::to_string_pretty(&value).unwrap();
                         }
+                        if let Some(metadata) = metadata {
+                            show_metadata(ui, metadata);
+                            if let Some(new_value) =
+                                add_typed_widget(ui, metadata, &value, &mut self.pending_value)
+                            {
+                                self.nao.update_parameter_value(&self.path, new_value);
+                            }
+                        }
                         ScrollArea::vertical().show(ui, |ui| {
                             ui.add(
                                 TextEdit::multiline(&mut self.parameter_value)
@@ -133,6 +159,83 @@ impl Widget for &mut ParameterPanel {
     }
 }
 
+fn show_metadata(ui: &mut Ui, metadata: &FieldMetadata) {
+    if let Some(documentation) = &metadata.documentation {
+        ui.label(documentation);
+    }
+    let mut details = vec![metadata.type_name.clone()];
+    if let Some(unit) = &metadata.unit {
+        details.push(format!("unit: {unit}"));
+    }
+    match (metadata.minimum, metadata.maximum) {
+        (Some(minimum), Some(maximum)) => details.push(format!("range: [{minimum}, {maximum}]")),
+        (Some(minimum), None) => details.push(format!("minimum: {minimum}")),
+        (None, Some(maximum)) => details.push(format!("maximum: {maximum}")),
+        (None, None) => {}
+    }
+    ui.weak(details.join(", "));
+}
+
+/// Returns the new value if the widget has been changed, dragged values are only returned once
+/// they are released
+fn add_typed_widget(
+    ui: &mut Ui,
+    metadata: &FieldMetadata,
+    value: &Value,
+    pending_value: &mut Option<Value>,
+) -> Option<Value> {
+    match value {
+        Value::Bool(current) => {
+            let mut checked = *current;
+            ui.checkbox(&mut checked, "")
+                .changed()
+                .then_some(Value::Bool(checked))
+        }
+        Value::Number(number) => {
+            let (Some(minimum), Some(maximum)) = (metadata.minimum, metadata.maximum) else {
+                return None;
+            };
+            let mut current = pending_value
+                .as_ref()
+                .and_then(Value::as_f64)
+                .or_else(|| number.as_f64())?;
+            let is_integer = number.is_i64() || number.is_u64();
+            let mut slider = Slider::new(&mut current, minimum..=maximum);
+            if is_integer {
+                slider = slider.integer();
+            }
+            if let Some(unit) = &metadata.unit {
+                slider = slider.suffix(format!(" {unit}"));
+            }
+            let response = ui.add(slider);
+            let new_value = if is_integer {
+                json!(current.round() as i64)
+            } else {
+                json!(current)
+            };
+            if response.dragged() {
+                *pending_value = Some(new_value);
+                return None;
+            }
+            let is_released = pending_value.take().is_some() && response.drag_released();
+            (is_released || response.changed()).then_some(new_value)
+        }
+        Value::String(current) => {
+            let variants = metadata.variants.as_ref()?;
+            let mut selected = current.clone();
+            ComboBox::from_id_source("parameter_variant")
+                .selected_text(selected.clone())
+                .show_ui(ui, |ui| {
+                    for variant in variants {
+                        ui.selectable_value(&mut selected, variant.clone(), variant);
+                    }
+                });
+            (selected != *current).then_some(Value::String(selected))
+        }
+        _ => None,
+    }
+}
+
 pub fn add_save_button<SerdesJsonValueProvider>(
     ui: &mut Ui,
     parameter_path: &str,
@@ -1,7 +1,7 @@
 use std::{str::FromStr, sync::Arc};
 
-use communication::client::CyclerOutput;
-use eframe::egui::{Label, ScrollArea, Sense, Widget};
+use communication::{client::CyclerOutput, messages::SubscriptionOptions};
+use eframe::egui::{DragValue, Label, ScrollArea, Sense, Widget};
 use log::error;
 use serde_json::{json, Value};
 
@@ -11,6 +11,8 @@ pub struct TextPanel {
     nao: Arc<Nao>,
     output: String,
     values: Option<ValueBuffer>,
+    maximum_rate: f32,
+    only_on_change: bool,
 }
 
 impl Panel for TextPanel {
@@ -21,10 +23,21 @@ impl Panel for TextPanel {
             Some(Value::String(string)) => string.to_string(),
             _ => String::new(),
         };
+        let maximum_rate = value
+            .and_then(|value| value.get("maximum_rate"))
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0) as f32;
+        let only_on_change = value
+            .and_then(|value| value.get("only_on_change"))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
         let values = if !output.is_empty() {
             let output = CyclerOutput::from_str(&output);
             match output {
-                Ok(output) => Some(nao.subscribe_output(output)),
+                Ok(output) => Some(nao.subscribe_output_with_options(
+                    output,
+                    subscription_options(maximum_rate, only_on_change),
+                )),
                 Err(error) => {
                     error!("Failed to subscribe: {error:?}");
                     None
@@ -37,29 +50,45 @@ impl Panel for TextPanel {
             nao,
             output,
             values,
+            maximum_rate,
+            only_on_change,
         }
     }
 
     fn save(&self) -> Value {
         json!({
-            "subscribe_key": self.output.clone()
+            "subscribe_key": self.output.clone(),
+            "maximum_rate": self.maximum_rate,
+            "only_on_change": self.only_on_change,
         })
     }
 }
 
 impl Widget for &mut TextPanel {
     fn ui(self, ui: &mut eframe::egui::Ui) -> eframe::egui::Response {
-        let edit_response = ui.add(CompletionEdit::outputs(&mut self.output, self.nao.as_ref()));
-        if edit_response.changed() {
-            match CyclerOutput::from_str(&self.output) {
-                Ok(output) => {
-                    self.values = Some(self.nao.subscribe_output(output));
+        let edit_response = ui
+            .horizontal(|ui| {
+                let edit_response =
+                    ui.add(CompletionEdit::outputs(&mut self.output, self.nao.as_ref()));
+                let rate_response = ui
+                    .add(
+                        DragValue::new(&mut self.maximum_rate)
+                            .clamp_range(0.0..=100.0)
+                            .suffix(" Hz"),
+                    )
+                    .on_hover_text("Maximum update rate, 0 means every cycle");
+                let on_change_response = ui
+                    .checkbox(&mut self.only_on_change, "On change")
+                    .on_hover_text("Only receive values which differ from the last one");
+                if edit_response.changed()
+                    || rate_response.changed()
+                    || on_change_response.changed()
+                {
+                    self.resubscribe();
                 }
-                Err(error) => {
-                    error!("Failed to subscribe: {error:#?}");
-                }
-            }
-        }
+                edit_response
+            })
+            .inner;
         let scroll_area = ScrollArea::vertical()
             .auto_shrink([false, false])
             .show(ui, |ui| {
@@ -89,3 +118,27 @@ impl Widget for &mut TextPanel {
         }
     }
 }
+
+impl TextPanel {
+    fn resubscribe(&mut self) {
+        match CyclerOutput::from_str(&self.output) {
+            Ok(output) => {
+                self.values = Some(self.nao.subscribe_output_with_options(
+                    output,
+                    subscription_options(self.maximum_rate, self.only_on_change),
+                ));
+            }
+            Err(error) => {
+                error!("Failed to subscribe: {error:#?}");
+            }
+        }
+    }
+}
+
+fn subscription_options(maximum_rate: f32, only_on_change: bool) -> SubscriptionOptions {
+    SubscriptionOptions {
+        only_on_change,
+        ..Default::default()
+    }
+    .with_maximum_rate(maximum_rate)
+}